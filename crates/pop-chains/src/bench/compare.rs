@@ -0,0 +1,593 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::Error;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+	path::Path,
+	sync::LazyLock,
+};
+
+/// A function returning a weight in a generated weight file, e.g. `fn transfer(n: u32, ) ->
+/// Weight {`.
+static WEIGHT_FN: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"fn\s+(\w+)\s*\([^)]*\)\s*->\s*Weight\s*\{").expect("Valid weight function regex")
+});
+/// The base weight of a function, on a line of its own.
+static BASE_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"(?m)^\s*Weight::from_parts\(([\d_]+),\s*([\d_]+)\)\s*$")
+		.expect("Valid base weight regex")
+});
+/// The weight per unit of a component of a function.
+static COMPONENT_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(
+		r"Weight::from_parts\(([\d_]+),\s*([\d_]+)\)\s*\.saturating_mul\(\s*(\w+)\.into\(\)\s*\)",
+	)
+	.expect("Valid component weight regex")
+});
+/// The database reads or writes of a function, e.g. `T::DbWeight::get().reads(1_u64)`.
+static DB_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"DbWeight::get\(\)\.(reads|writes)\(([\d_]+?)(?:_u64)?\)")
+		.expect("Valid database weight regex")
+});
+/// The database reads or writes per unit of a component of a function, e.g.
+/// `T::DbWeight::get().reads((1_u64).saturating_mul(u.into()))`.
+static DB_COMPONENT_WEIGHT: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(
+		r"DbWeight::get\(\)\.(reads|writes)\(\(([\d_]+?)(?:_u64)?\)\.saturating_mul\(\s*(\w+)\.into\(\)\s*\)\)",
+	)
+	.expect("Valid component database weight regex")
+});
+/// The pallet a weight file is generated for, e.g. `impl<T: frame_system::Config>
+/// pallet_balances::WeightInfo for WeightInfo<T>`.
+static WEIGHT_IMPL: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r"impl<[^>]*>\s+(\w+)::WeightInfo\s+for").expect("Valid weight impl regex")
+});
+
+/// The weights of benchmarks, keyed by pallet and extrinsic.
+pub type BenchmarkWeights = BTreeMap<BenchmarkId, BenchmarkWeight>;
+
+/// Identifies a benchmark.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct BenchmarkId {
+	/// The name of the pallet.
+	pub pallet: String,
+	/// The name of the extrinsic.
+	pub extrinsic: String,
+}
+
+impl Display for BenchmarkId {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}::{}", self.pallet, self.extrinsic)
+	}
+}
+
+/// A weight, as the execution time in picoseconds, the proof size in bytes and the number of
+/// database reads and writes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct Weight {
+	/// The execution time, in picoseconds.
+	pub ref_time: f64,
+	/// The proof size, in bytes.
+	pub proof_size: f64,
+	/// The number of database reads.
+	pub reads: f64,
+	/// The number of database writes.
+	pub writes: f64,
+}
+
+/// The weight of a benchmark, as a base weight and a weight per unit of each of its components.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BenchmarkWeight {
+	/// The weight regardless of the components.
+	pub base: Weight,
+	/// The weight per unit of each component.
+	pub components: BTreeMap<String, Weight>,
+}
+
+/// A dimension of a weight.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WeightMetric {
+	/// The execution time.
+	RefTime,
+	/// The proof size.
+	ProofSize,
+	/// The number of database reads.
+	Reads,
+	/// The number of database writes.
+	Writes,
+}
+
+impl Display for WeightMetric {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			WeightMetric::RefTime => write!(f, "ref time"),
+			WeightMetric::ProofSize => write!(f, "proof size"),
+			WeightMetric::Reads => write!(f, "reads"),
+			WeightMetric::Writes => write!(f, "writes"),
+		}
+	}
+}
+
+/// A change in a weight of a benchmark between two runs.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct WeightChange {
+	/// The benchmark.
+	#[serde(flatten)]
+	pub benchmark: BenchmarkId,
+	/// The component, or `None` for the base weight.
+	pub component: Option<String>,
+	/// The dimension of the weight.
+	pub metric: WeightMetric,
+	/// The weight of the old run.
+	pub old: f64,
+	/// The weight of the new run.
+	pub new: f64,
+	/// Whether the increase exceeds the threshold.
+	pub regression: bool,
+}
+
+impl WeightChange {
+	/// The change relative to the old weight, as a percentage. `None` if the old weight is zero.
+	pub fn delta(&self) -> Option<f64> {
+		(self.old != 0.0).then(|| (self.new - self.old) / self.old * 100.0)
+	}
+}
+
+/// The differences between the benchmarks of two runs.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct BenchmarkComparison {
+	/// The weights which changed between the runs.
+	pub changes: Vec<WeightChange>,
+	/// The benchmarks only present in the new run.
+	pub added: Vec<BenchmarkId>,
+	/// The benchmarks only present in the old run.
+	pub removed: Vec<BenchmarkId>,
+}
+
+impl BenchmarkComparison {
+	/// The changes exceeding the threshold.
+	pub fn regressions(&self) -> impl Iterator<Item = &WeightChange> {
+		self.changes.iter().filter(|change| change.regression)
+	}
+}
+
+/// Loads the weights of benchmarks from the raw JSON output of `pop bench pallet` (see
+/// `--json-file`), or from a directory (or single file) of generated weights.
+///
+/// # Arguments
+/// * `path` - The path to the results.
+pub fn load_benchmark_weights(path: &Path) -> Result<BenchmarkWeights, Error> {
+	if path.is_dir() {
+		let mut files: Vec<_> = std::fs::read_dir(path)?
+			.map(|entry| entry.map(|entry| entry.path()))
+			.collect::<Result<_, _>>()?;
+		files.retain(|file| file.extension().is_some_and(|ext| ext == "rs"));
+		files.sort();
+		let mut weights = BenchmarkWeights::new();
+		for file in files {
+			weights.extend(parse_weight_file(&file, &std::fs::read_to_string(&file)?));
+		}
+		if weights.is_empty() {
+			return Err(Error::BenchmarkingError(format!(
+				"no weight files found in {}",
+				path.display()
+			)));
+		}
+		return Ok(weights);
+	}
+	let contents = std::fs::read_to_string(path)?;
+	match path.extension().and_then(|ext| ext.to_str()) {
+		Some("rs") => Ok(parse_weight_file(path, &contents)),
+		_ => parse_raw_results(&contents),
+	}
+}
+
+/// Compares the weights of benchmarks between two runs, matching benchmarks by pallet and
+/// extrinsic.
+///
+/// # Arguments
+/// * `old` - The weights of the old run.
+/// * `new` - The weights of the new run.
+/// * `threshold` - The percentage by which a weight may increase before it is considered a
+///   regression.
+pub fn compare_benchmark_weights(
+	old: &BenchmarkWeights,
+	new: &BenchmarkWeights,
+	threshold: f64,
+) -> BenchmarkComparison {
+	let mut comparison = BenchmarkComparison {
+		added: new.keys().filter(|id| !old.contains_key(id)).cloned().collect(),
+		removed: old.keys().filter(|id| !new.contains_key(id)).cloned().collect(),
+		..Default::default()
+	};
+	for (id, old) in old {
+		let Some(new) = new.get(id) else { continue };
+		let mut components: Vec<_> = old.components.keys().chain(new.components.keys()).collect();
+		components.sort();
+		components.dedup();
+		let weights = std::iter::once((None, old.base, new.base)).chain(
+			components.into_iter().map(|component| {
+				let weight = |weight: &BenchmarkWeight| {
+					weight.components.get(component).copied().unwrap_or_default()
+				};
+				(Some(component.clone()), weight(old), weight(new))
+			}),
+		);
+		for (component, old, new) in weights {
+			for (metric, old, new) in [
+				(WeightMetric::RefTime, old.ref_time, new.ref_time),
+				(WeightMetric::ProofSize, old.proof_size, new.proof_size),
+				(WeightMetric::Reads, old.reads, new.reads),
+				(WeightMetric::Writes, old.writes, new.writes),
+			] {
+				if old == new {
+					continue;
+				}
+				comparison.changes.push(WeightChange {
+					benchmark: id.clone(),
+					component: component.clone(),
+					metric,
+					old,
+					new,
+					regression: new > old && (old == 0.0 || (new - old) / old * 100.0 > threshold),
+				});
+			}
+		}
+	}
+	comparison
+}
+
+// Parses the weights of a generated weight file. The pallet is taken from the `WeightInfo`
+// implementation, falling back to the name of the file. Only the first implementation of each
+// function is used, as weight files of pallets also implement `WeightInfo` for `()`.
+fn parse_weight_file(path: &Path, contents: &str) -> BenchmarkWeights {
+	let pallet = WEIGHT_IMPL
+		.captures(contents)
+		.map(|captures| captures[1].to_string())
+		.or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().into_owned()))
+		.unwrap_or_default();
+	let functions: Vec<_> = WEIGHT_FN.captures_iter(contents).collect();
+	let mut weights = BenchmarkWeights::new();
+	for (i, function) in functions.iter().enumerate() {
+		let start = function.get(0).expect("match expected").end();
+		let end = functions
+			.get(i + 1)
+			.map(|next| next.get(0).expect("match expected").start())
+			.unwrap_or(contents.len());
+		let body = &contents[start..end];
+		let mut weight = BenchmarkWeight::default();
+		if let Some(base) = BASE_WEIGHT.captures(body) {
+			weight.base.ref_time = number(&base[1]);
+			weight.base.proof_size = number(&base[2]);
+		}
+		for component in COMPONENT_WEIGHT.captures_iter(body) {
+			let entry = weight.components.entry(component[3].to_string()).or_default();
+			entry.ref_time += number(&component[1]);
+			entry.proof_size += number(&component[2]);
+		}
+		for db in DB_WEIGHT.captures_iter(body) {
+			*db_operations(&mut weight.base, &db[1]) += number(&db[2]);
+		}
+		for db in DB_COMPONENT_WEIGHT.captures_iter(body) {
+			let entry = weight.components.entry(db[3].to_string()).or_default();
+			*db_operations(entry, &db[1]) += number(&db[2]);
+		}
+		let id = BenchmarkId { pallet: pallet.clone(), extrinsic: function[1].to_string() };
+		weights.entry(id).or_insert(weight);
+	}
+	weights
+}
+
+// The database reads or writes of a weight.
+fn db_operations<'a>(weight: &'a mut Weight, operation: &str) -> &'a mut f64 {
+	match operation {
+		"reads" => &mut weight.reads,
+		_ => &mut weight.writes,
+	}
+}
+
+// Parses a number of a weight file, e.g. `46_000_000`.
+fn number(value: &str) -> f64 {
+	value.replace('_', "").parse().unwrap_or_default()
+}
+
+/// A batch of raw benchmark results, as output by `frame-benchmarking-cli`.
+#[derive(Deserialize)]
+struct RawBatch {
+	pallet: String,
+	benchmark: String,
+	time_results: Vec<RawResult>,
+	db_results: Vec<RawResult>,
+}
+
+/// A raw benchmark result.
+#[derive(Deserialize)]
+struct RawResult {
+	components: Vec<(String, u32)>,
+	/// The execution time, in nanoseconds.
+	extrinsic_time: u128,
+	proof_size: u32,
+	reads: u32,
+	writes: u32,
+}
+
+// Parses raw benchmark results, fitting a weight to the results of each benchmark. Execution
+// times are fitted to the timing results, and proof sizes and database operations to the storage
+// results, as done when generating weight files.
+fn parse_raw_results(contents: &str) -> Result<BenchmarkWeights, Error> {
+	let batches: Vec<RawBatch> = serde_json::from_str(contents)
+		.map_err(|e| Error::BenchmarkingError(format!("invalid raw benchmark results: {e}")))?;
+	let mut weights = BenchmarkWeights::new();
+	for batch in batches {
+		let ref_time = fit(&batch.time_results, |result| result.extrinsic_time as f64 * 1_000.0);
+		let proof_results =
+			if batch.db_results.is_empty() { &batch.time_results } else { &batch.db_results };
+		let proof_size = fit(proof_results, |result| result.proof_size as f64);
+		let reads = fit(proof_results, |result| result.reads as f64);
+		let writes = fit(proof_results, |result| result.writes as f64);
+		let mut weight = BenchmarkWeight {
+			base: Weight {
+				ref_time: ref_time.0,
+				proof_size: proof_size.0,
+				reads: reads.0,
+				writes: writes.0,
+			},
+			..Default::default()
+		};
+		for (component, slope) in ref_time.1 {
+			weight.components.entry(component).or_default().ref_time = slope;
+		}
+		for (component, slope) in proof_size.1 {
+			weight.components.entry(component).or_default().proof_size = slope;
+		}
+		for (component, slope) in reads.1 {
+			weight.components.entry(component).or_default().reads = slope;
+		}
+		for (component, slope) in writes.1 {
+			weight.components.entry(component).or_default().writes = slope;
+		}
+		weights.insert(BenchmarkId { pallet: batch.pallet, extrinsic: batch.benchmark }, weight);
+	}
+	Ok(weights)
+}
+
+// Fits a base value and a slope per component to the results. Each component is varied while the
+// others are at their maximum, so the slope of a component is fitted with least squares to the
+// results where the other components are at their maximum. Negative values are clamped to zero.
+fn fit(results: &[RawResult], value: impl Fn(&RawResult) -> f64) -> (f64, BTreeMap<String, f64>) {
+	let mut max = BTreeMap::<&str, u32>::new();
+	for result in results {
+		for (component, amount) in &result.components {
+			let entry = max.entry(component.as_str()).or_default();
+			*entry = (*entry).max(*amount);
+		}
+	}
+	let at_max = |result: &RawResult, except: Option<&str>| {
+		result.components.iter().all(|(component, amount)| {
+			Some(component.as_str()) == except || max[component.as_str()] == *amount
+		})
+	};
+
+	let mut slopes = BTreeMap::new();
+	for component in max.keys() {
+		let points: Vec<_> = results
+			.iter()
+			.filter(|result| at_max(result, Some(*component)))
+			.filter_map(|result| {
+				let (_, amount) =
+					result.components.iter().find(|(c, _)| c.as_str() == *component)?;
+				Some((*amount as f64, value(result)))
+			})
+			.collect();
+		slopes.insert(component.to_string(), least_squares_slope(&points).max(0.0));
+	}
+
+	let peak: Vec<_> = results.iter().filter(|result| at_max(result, None)).map(&value).collect();
+	let peak = if peak.is_empty() { 0.0 } else { peak.iter().sum::<f64>() / peak.len() as f64 };
+	let base = peak -
+		slopes
+			.iter()
+			.map(|(component, slope)| slope * max[component.as_str()] as f64)
+			.sum::<f64>();
+	(base.max(0.0), slopes)
+}
+
+// The slope of the line fitted to the points with least squares.
+fn least_squares_slope(points: &[(f64, f64)]) -> f64 {
+	if points.is_empty() {
+		return 0.0;
+	}
+	let n = points.len() as f64;
+	let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+	let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+	let covariance: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+	let variance: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+	if variance == 0.0 { 0.0 } else { covariance / variance }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	const WEIGHTS: &str = r#"
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_balances::WeightInfo for WeightInfo<T> {
+	/// Storage: `System::Account` (r:1 w:1)
+	fn transfer_allow_death() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `3593`
+		// Minimum execution time: 45_000_000 picoseconds.
+		Weight::from_parts(46_000_000, 3593)
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `u` is `[1, 1000]`.
+	fn upgrade_accounts(u: u32, ) -> Weight {
+		Weight::from_parts(16_000_000, 990)
+			// Standard Error: 12_000
+			.saturating_add(Weight::from_parts(13_000_000, 0).saturating_mul(u.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(u.into())))
+			.saturating_add(Weight::from_parts(0, 2603).saturating_mul(u.into()))
+	}
+}
+"#;
+
+	fn weight(ref_time: f64, proof_size: f64) -> Weight {
+		Weight { ref_time, proof_size, ..Default::default() }
+	}
+
+	fn id(pallet: &str, extrinsic: &str) -> BenchmarkId {
+		BenchmarkId { pallet: pallet.into(), extrinsic: extrinsic.into() }
+	}
+
+	#[test]
+	fn parse_weight_file_works() {
+		let weights = parse_weight_file(Path::new("balances.rs"), WEIGHTS);
+		assert_eq!(
+			weights,
+			BenchmarkWeights::from([
+				(
+					id("pallet_balances", "transfer_allow_death"),
+					BenchmarkWeight {
+						base: Weight { reads: 1.0, writes: 1.0, ..weight(46_000_000.0, 3593.0) },
+						..Default::default()
+					}
+				),
+				(
+					id("pallet_balances", "upgrade_accounts"),
+					BenchmarkWeight {
+						base: weight(16_000_000.0, 990.0),
+						components: BTreeMap::from([(
+							"u".to_string(),
+							Weight { reads: 1.0, ..weight(13_000_000.0, 2603.0) }
+						)]),
+					}
+				),
+			])
+		);
+	}
+
+	#[test]
+	fn parse_weight_file_uses_file_name_and_first_implementation() {
+		let contents = r#"
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn do_something() -> Weight {
+		Weight::from_parts(9_000_000, 0)
+	}
+}
+impl WeightInfo for () {
+	fn do_something() -> Weight {
+		Weight::from_parts(1_000_000, 0)
+	}
+}
+"#;
+		let weights = parse_weight_file(Path::new("weights/pallet_template.rs"), contents);
+		assert_eq!(weights.len(), 1);
+		assert_eq!(weights[&id("pallet_template", "do_something")].base, weight(9_000_000.0, 0.0));
+	}
+
+	#[test]
+	fn parse_raw_results_works() -> Result<()> {
+		let result = |n: u32, m: u32, time: u128, proof: u32| {
+			let (reads, writes) = (1 + n, m);
+			format!(
+				r#"{{"components":[["n",{n}],["m",{m}]],"extrinsic_time":{time},"storage_root_time":0,"reads":{reads},"repeat_reads":0,"writes":{writes},"repeat_writes":0,"proof_size":{proof},"keys":[]}}"#
+			)
+		};
+		// time = 10 + 2n + 3m (ns), proof = 100 + 5n, reads = 1 + n, writes = m.
+		let results = [(0, 10), (5, 10), (10, 10), (10, 0), (10, 5)]
+			.map(|(n, m)| result(n, m, 10 + 2 * n as u128 + 3 * m as u128, 100 + 5 * n))
+			.join(",");
+		let contents = format!(
+			r#"[{{"pallet":"pallet_test","instance":"Pallet","benchmark":"call","time_results":[{results}],"db_results":[{results}]}}]"#
+		);
+		let weights = parse_raw_results(&contents)?;
+		let call = &weights[&id("pallet_test", "call")];
+		assert_eq!(call.base, Weight { reads: 1.0, ..weight(10_000.0, 100.0) });
+		assert_eq!(call.components["n"], Weight { reads: 1.0, ..weight(2_000.0, 5.0) });
+		assert_eq!(call.components["m"], Weight { writes: 1.0, ..weight(3_000.0, 0.0) });
+
+		assert!(matches!(parse_raw_results("{}"), Err(Error::BenchmarkingError(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn least_squares_slope_works() {
+		assert_eq!(least_squares_slope(&[]), 0.0);
+		assert_eq!(least_squares_slope(&[(1.0, 5.0), (1.0, 7.0)]), 0.0);
+		assert_eq!(least_squares_slope(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]), 2.0);
+	}
+
+	#[test]
+	fn compare_benchmark_weights_works() {
+		let old = BenchmarkWeights::from([
+			(
+				id("pallet_a", "kept"),
+				BenchmarkWeight {
+					base: weight(100.0, 10.0),
+					components: BTreeMap::from([("n".to_string(), weight(10.0, 0.0))]),
+				},
+			),
+			(id("pallet_a", "removed"), BenchmarkWeight::default()),
+		]);
+		let new = BenchmarkWeights::from([
+			(
+				id("pallet_a", "kept"),
+				BenchmarkWeight {
+					base: Weight { reads: 2.0, ..weight(104.0, 20.0) },
+					components: BTreeMap::from([("m".to_string(), weight(1.0, 0.0))]),
+				},
+			),
+			(id("pallet_b", "added"), BenchmarkWeight::default()),
+		]);
+		let comparison = compare_benchmark_weights(&old, &new, 5.0);
+		assert_eq!(comparison.added, vec![id("pallet_b", "added")]);
+		assert_eq!(comparison.removed, vec![id("pallet_a", "removed")]);
+		let changes: Vec<_> = comparison
+			.changes
+			.iter()
+			.map(|change| {
+				(
+					change.component.as_deref(),
+					change.metric,
+					change.old,
+					change.new,
+					change.regression,
+				)
+			})
+			.collect();
+		assert_eq!(
+			changes,
+			vec![
+				(None, WeightMetric::RefTime, 100.0, 104.0, false),
+				(None, WeightMetric::ProofSize, 10.0, 20.0, true),
+				(None, WeightMetric::Reads, 0.0, 2.0, true),
+				(Some("m"), WeightMetric::RefTime, 0.0, 1.0, true),
+				(Some("n"), WeightMetric::RefTime, 10.0, 0.0, false),
+			]
+		);
+		assert_eq!(comparison.regressions().count(), 3);
+		assert_eq!(comparison.changes[0].delta(), Some(4.0));
+		assert_eq!(comparison.changes[3].delta(), None);
+	}
+
+	#[test]
+	fn load_benchmark_weights_works() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		std::fs::write(temp.path().join("pallet_balances.rs"), WEIGHTS)?;
+		std::fs::write(temp.path().join("mod.txt"), "ignored")?;
+		let weights = load_benchmark_weights(temp.path())?;
+		assert_eq!(weights.len(), 2);
+		assert_eq!(load_benchmark_weights(&temp.path().join("pallet_balances.rs"))?, weights);
+
+		let empty = tempfile::tempdir()?;
+		assert!(matches!(load_benchmark_weights(empty.path()), Err(Error::BenchmarkingError(..))));
+		Ok(())
+	}
+}
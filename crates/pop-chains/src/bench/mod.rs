@@ -18,6 +18,8 @@ use tempfile::NamedTempFile;
 
 /// Provides functionality for sourcing binaries of the benchmarking CLI.
 pub mod binary;
+/// Provides functionality for comparing the weights of benchmark runs.
+pub mod compare;
 
 /// The default `development` preset used to communicate with the runtime via
 /// [`GenesisBuilder`](https://docs.rs/sp-genesis-builder/latest/sp_genesis_builder/trait.GenesisBuilder.html) interface.
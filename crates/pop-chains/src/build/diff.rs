@@ -0,0 +1,308 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	ChangeKind, DecodedStorage, DecodedValue, StorageKeyPart, decode::decode_storage_entry,
+	raw_value_to_string,
+};
+use serde::Serialize;
+use serde_json::Value;
+use sp_core::{blake2_256, bytes::to_hex};
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+};
+use subxt::Metadata;
+
+/// The well-known storage key of the runtime code.
+pub(super) const CODE_KEY: &[u8] = b":code";
+
+/// A change between two chain specifications.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ChainSpecChange {
+	/// The kind of change.
+	pub change: ChangeKind,
+	/// The path of the entry: a JSON path such as `genesis.runtimeGenesis.patch.sudo.key`, or
+	/// a decoded storage key such as `System::Account(5GrwvaEF...)` for raw specifications.
+	pub path: String,
+	/// The value in the old chain specification.
+	pub old: Option<String>,
+	/// The value in the new chain specification.
+	pub new: Option<String>,
+}
+
+impl Display for ChainSpecChange {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match (self.change, &self.old, &self.new) {
+			(ChangeKind::Changed, Some(old), Some(new)) =>
+				write!(f, "~ {}: {old} -> {new}", self.path),
+			(ChangeKind::Removed, Some(old), _) => write!(f, "- {}: {old}", self.path),
+			(_, _, Some(new)) => write!(f, "+ {}: {new}", self.path),
+			_ => write!(f, "~ {}", self.path),
+		}
+	}
+}
+
+/// The differences between two chain specifications.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct ChainSpecDiff {
+	/// The changes: the fields of the specifications first, then their genesis storage.
+	pub changes: Vec<ChainSpecChange>,
+}
+
+impl ChainSpecDiff {
+	/// Whether the two chain specifications are equivalent.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// Renders the changes in a human-readable format, one change per line.
+	pub fn render(&self) -> String {
+		self.changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+	}
+}
+
+/// Compares two JSON values structurally, reporting the entries of objects and arrays which were
+/// added, removed or changed.
+///
+/// # Arguments
+/// * `path` - The path of the values.
+/// * `old` - The old value.
+/// * `new` - The new value.
+/// * `changes` - The changes found so far.
+pub(super) fn diff_json(path: &str, old: &Value, new: &Value, changes: &mut Vec<ChainSpecChange>) {
+	match (old, new) {
+		(Value::Object(old), Value::Object(new)) => {
+			let key_path =
+				|key: &str| if path.is_empty() { key.to_string() } else { format!("{path}.{key}") };
+			for (key, old_value) in old {
+				match new.get(key) {
+					Some(new_value) => diff_json(&key_path(key), old_value, new_value, changes),
+					None => changes.push(removed(key_path(key), old_value.to_string())),
+				}
+			}
+			for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+				changes.push(added(key_path(key), new_value.to_string()));
+			}
+		},
+		(Value::Array(old), Value::Array(new)) =>
+			for i in 0..old.len().max(new.len()) {
+				let item_path = format!("{path}[{i}]");
+				match (old.get(i), new.get(i)) {
+					(Some(old), Some(new)) => diff_json(&item_path, old, new, changes),
+					(Some(old), None) => changes.push(removed(item_path, old.to_string())),
+					(None, Some(new)) => changes.push(added(item_path, new.to_string())),
+					(None, None) => {},
+				}
+			},
+		(old, new) if old != new =>
+			changes.push(changed(path.to_string(), old.to_string(), new.to_string())),
+		_ => {},
+	}
+}
+
+/// Compares the genesis storage of two raw chain specifications, decoding the entries with the
+/// metadata of their runtimes where possible.
+///
+/// # Arguments
+/// * `old` - The genesis storage of the old chain specification.
+/// * `new` - The genesis storage of the new chain specification.
+/// * `old_metadata` - The metadata of the runtime of the old chain specification.
+/// * `new_metadata` - The metadata of the runtime of the new chain specification.
+pub(super) fn diff_storage(
+	old: &BTreeMap<Vec<u8>, Vec<u8>>,
+	new: &BTreeMap<Vec<u8>, Vec<u8>>,
+	old_metadata: Option<&Metadata>,
+	new_metadata: Option<&Metadata>,
+) -> Vec<ChainSpecChange> {
+	let mut changes = Vec::new();
+	for (key, old_value) in old {
+		match new.get(key) {
+			Some(new_value) if new_value != old_value => {
+				let (_, old_value) = describe_storage(old_metadata, key, old_value);
+				let (path, new_value) = describe_storage(new_metadata, key, new_value);
+				changes.push(changed(path, old_value, new_value));
+			},
+			Some(_) => {},
+			None => {
+				let (path, old_value) = describe_storage(old_metadata, key, old_value);
+				changes.push(removed(path, old_value));
+			},
+		}
+	}
+	for (key, new_value) in new.iter().filter(|(key, _)| !old.contains_key(*key)) {
+		let (path, new_value) = describe_storage(new_metadata, key, new_value);
+		changes.push(added(path, new_value));
+	}
+	// Group the entries of each storage item, which are ordered by hashed key otherwise.
+	changes.sort_by(|a, b| a.path.cmp(&b.path));
+	changes
+}
+
+/// Summarizes runtime code, which is too large to be displayed.
+///
+/// # Arguments
+/// * `code` - The runtime code.
+pub(super) fn describe_code(code: &[u8]) -> String {
+	format!("<{} bytes, hash {}>", code.len(), to_hex(&blake2_256(code), false))
+}
+
+// Describes a storage entry by its decoded key and value, falling back to hex for entries which
+// are not described by the metadata.
+fn describe_storage(metadata: Option<&Metadata>, key: &[u8], value: &[u8]) -> (String, String) {
+	if key == CODE_KEY {
+		return (":code".to_string(), describe_code(value));
+	}
+	// Well-known keys, such as `:heappages`, are readable as is.
+	if key.starts_with(b":") &&
+		let Ok(key) = std::str::from_utf8(key)
+	{
+		return (key.to_string(), to_hex(value, false));
+	}
+	let Some(metadata) = metadata else {
+		return (to_hex(key, false), to_hex(value, false));
+	};
+	if let Ok(decoded) = decode_storage_entry(metadata, key, Some(value)) &&
+		let Some(value) = decoded.value.as_ref().and_then(render_value)
+	{
+		return (storage_path(&decoded), value);
+	}
+	// The value may not match the type of the storage item, while the key still does.
+	match decode_storage_entry(metadata, key, None) {
+		Ok(decoded) => (storage_path(&decoded), to_hex(value, false)),
+		Err(_) => (to_hex(key, false), to_hex(value, false)),
+	}
+}
+
+// The path of a decoded storage entry, e.g. `System::Account(5GrwvaEF...)`.
+fn storage_path(storage: &DecodedStorage) -> String {
+	let path = format!("{}::{}", storage.pallet, storage.entry);
+	if storage.keys.is_empty() {
+		return path;
+	}
+	let keys: Vec<_> = storage
+		.keys
+		.iter()
+		.map(|key| match key {
+			StorageKeyPart::Value(value) => render_value(value).unwrap_or_default(),
+			StorageKeyPart::Hash(hash) => hash.clone(),
+		})
+		.collect();
+	format!("{path}({})", keys.join(", "))
+}
+
+// Renders a decoded value on a single line.
+fn render_value(value: &DecodedValue) -> Option<String> {
+	let rendered = raw_value_to_string(value, "").ok()?;
+	Some(rendered.lines().map(str::trim).collect::<Vec<_>>().join(" "))
+}
+
+fn added(path: String, new: String) -> ChainSpecChange {
+	ChainSpecChange { change: ChangeKind::Added, path, old: None, new: Some(new) }
+}
+
+fn removed(path: String, old: String) -> ChainSpecChange {
+	ChainSpecChange { change: ChangeKind::Removed, path, old: Some(old), new: None }
+}
+
+fn changed(path: String, old: String, new: String) -> ChainSpecChange {
+	ChainSpecChange { change: ChangeKind::Changed, path, old: Some(old), new: Some(new) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use scale::Encode;
+	use serde_json::json;
+	use sp_core::twox_128;
+
+	#[test]
+	fn diff_json_works() {
+		let old = json!({
+			"name": "Development",
+			"bootNodes": ["a", "b"],
+			"properties": { "tokenSymbol": "UNIT", "decimals": 12 },
+		});
+		let new = json!({
+			"name": "Local",
+			"bootNodes": ["a"],
+			"properties": { "tokenSymbol": "UNIT", "ss58Format": 42 },
+		});
+		let mut changes = Vec::new();
+		diff_json("", &old, &new, &mut changes);
+		assert_eq!(
+			changes,
+			vec![
+				changed("name".into(), "\"Development\"".into(), "\"Local\"".into()),
+				removed("bootNodes[1]".into(), "\"b\"".into()),
+				removed("properties.decimals".into(), "12".into()),
+				added("properties.ss58Format".into(), "42".into()),
+			]
+		);
+
+		changes.clear();
+		diff_json("", &old, &old, &mut changes);
+		assert!(changes.is_empty());
+	}
+
+	#[test]
+	fn render_works() {
+		let diff = ChainSpecDiff {
+			changes: vec![
+				changed("name".into(), "\"Development\"".into(), "\"Local\"".into()),
+				removed("bootNodes[1]".into(), "\"b\"".into()),
+				added(":heappages".into(), "0x08".into()),
+			],
+		};
+		assert_eq!(
+			diff.render(),
+			"~ name: \"Development\" -> \"Local\"\n- bootNodes[1]: \"b\"\n+ :heappages: 0x08"
+		);
+		assert!(!diff.is_empty());
+		assert!(ChainSpecDiff::default().is_empty());
+	}
+
+	#[test]
+	fn diff_storage_without_metadata_works() {
+		let old = BTreeMap::from([
+			(CODE_KEY.to_vec(), vec![1, 2, 3]),
+			(vec![0xaa], vec![1]),
+			(vec![0xbb], vec![2]),
+		]);
+		let new = BTreeMap::from([
+			(CODE_KEY.to_vec(), vec![1, 2, 3, 4]),
+			(vec![0xaa], vec![1]),
+			(b":heappages".to_vec(), vec![8]),
+		]);
+		assert_eq!(
+			diff_storage(&old, &new, None, None),
+			vec![
+				removed("0xbb".into(), "0x02".into()),
+				changed(":code".into(), describe_code(&[1, 2, 3]), describe_code(&[1, 2, 3, 4])),
+				added(":heappages".into(), "0x08".into()),
+			]
+		);
+	}
+
+	#[tokio::test]
+	async fn diff_storage_decodes_entries() -> Result<()> {
+		let metadata = set_up_client(&shared_substrate_ws_url().await).await?.metadata();
+		let key = [twox_128(b"System"), twox_128(b"Number")].concat();
+		let old = BTreeMap::from([(key.clone(), 1u32.encode())]);
+		let new = BTreeMap::from([(key.clone(), 2u32.encode())]);
+		assert_eq!(
+			diff_storage(&old, &new, Some(&metadata), Some(&metadata)),
+			vec![changed("System::Number".into(), "1".into(), "2".into())]
+		);
+
+		// The key is still decoded when the value does not match the storage item.
+		let new = BTreeMap::from([(key, vec![2])]);
+		assert_eq!(
+			diff_storage(&old, &new, Some(&metadata), Some(&metadata)),
+			vec![changed("System::Number".into(), "1".into(), "0x02".into())]
+		);
+		Ok(())
+	}
+}
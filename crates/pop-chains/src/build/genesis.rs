@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use regex::Regex;
+use serde_json::Value;
+use std::{fmt, fs, path::Path, sync::LazyLock};
+
+/// Matches the location appended by `serde_json` to its errors.
+static ERROR_LOCATION: LazyLock<Regex> = LazyLock::new(|| {
+	Regex::new(r" at line (\d+) column \d+$").expect("the location pattern is valid")
+});
+
+/// The genesis config of a runtime, as held by a plain chain specification.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuntimeGenesis {
+	/// A complete genesis config.
+	Config(Value),
+	/// A patch of the default genesis config of the runtime.
+	Patch(Value),
+}
+
+/// Applies a JSON merge patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)) to a value.
+///
+/// Objects are merged recursively, a `null` removes the key and any other value replaces the
+/// target.
+///
+/// # Arguments
+/// * `target` - The value to patch.
+/// * `patch` - The merge patch.
+pub fn merge_patch(target: &mut Value, patch: Value) {
+	match patch {
+		Value::Object(patch) => {
+			if !target.is_object() {
+				*target = Value::Object(Default::default());
+			}
+			let target = target.as_object_mut().expect("target was just made an object");
+			for (key, value) in patch {
+				if value.is_null() {
+					target.remove(&key);
+				} else {
+					merge_patch(target.entry(key).or_insert(Value::Null), value);
+				}
+			}
+		},
+		patch => *target = patch,
+	}
+}
+
+/// Loads a genesis patch, provided either as inline JSON or as the path to a JSON file.
+///
+/// # Arguments
+/// * `source` - The JSON object, or the path of a file holding it.
+pub fn load_genesis_patch(source: &str) -> Result<Value, Error> {
+	let json = if source.trim_start().starts_with('{') {
+		source.to_string()
+	} else {
+		let path = Path::new(source);
+		if !path.is_file() {
+			return Err(Error::GenesisBuilderError(format!(
+				"the genesis patch is neither a JSON object nor an existing file: {source}"
+			)));
+		}
+		fs::read_to_string(path)?
+	};
+	let patch: Value = serde_json::from_str(&json)?;
+	if !patch.is_object() {
+		return Err(Error::GenesisBuilderError("the genesis patch must be a JSON object".into()));
+	}
+	Ok(patch)
+}
+
+/// A genesis config rendered as pretty JSON, whose lines can be traced back to the config.
+///
+/// Runtimes report genesis configs which fail to deserialize with the line of the JSON at fault,
+/// which is mapped back to the path of the offending value.
+pub struct GenesisJson {
+	json: String,
+	// The path of the value at each line of the JSON.
+	paths: Vec<String>,
+}
+
+impl GenesisJson {
+	/// Renders a genesis config.
+	///
+	/// # Arguments
+	/// * `config` - The genesis config.
+	pub fn new(config: &Value) -> Self {
+		let mut json = Self { json: String::new(), paths: vec![String::new()] };
+		json.render(config, String::new(), 0);
+		json
+	}
+
+	/// The rendered JSON.
+	pub fn as_bytes(&self) -> &[u8] {
+		self.json.as_bytes()
+	}
+
+	/// Locates an error reported by the runtime for the rendered JSON.
+	///
+	/// # Arguments
+	/// * `error` - The error reported by the runtime.
+	pub fn error(&self, error: &str) -> GenesisError {
+		let Some(captures) = ERROR_LOCATION.captures(error) else {
+			return GenesisError { path: None, message: error.to_string() };
+		};
+		let path = captures[1]
+			.parse::<usize>()
+			.ok()
+			.and_then(|line| self.paths.get(line.checked_sub(1)?))
+			.filter(|path| !path.is_empty())
+			.cloned();
+		let message = error[..captures.get(0).expect("the match is captured").start()].to_string();
+		GenesisError { path, message }
+	}
+
+	// Renders a value at the current line, in the same format as `serde_json::to_string_pretty`.
+	fn render(&mut self, value: &Value, path: String, depth: usize) {
+		let indent = "  ".repeat(depth + 1);
+		match value {
+			Value::Object(map) if !map.is_empty() => {
+				self.json.push_str("{\n");
+				for (i, (key, value)) in map.iter().enumerate() {
+					let key_path =
+						if path.is_empty() { key.clone() } else { format!("{path}.{key}") };
+					self.paths.push(key_path.clone());
+					self.json.push_str(&format!("{indent}{}: ", Value::String(key.clone())));
+					self.render(value, key_path, depth + 1);
+					self.end_item(i + 1 < map.len());
+				}
+				self.close('}', path, depth);
+			},
+			Value::Array(items) if !items.is_empty() => {
+				self.json.push_str("[\n");
+				for (i, value) in items.iter().enumerate() {
+					let item_path = format!("{path}[{i}]");
+					self.paths.push(item_path.clone());
+					self.json.push_str(&indent);
+					self.render(value, item_path, depth + 1);
+					self.end_item(i + 1 < items.len());
+				}
+				self.close(']', path, depth);
+			},
+			value => self.json.push_str(&value.to_string()),
+		}
+	}
+
+	fn end_item(&mut self, more: bool) {
+		self.json.push_str(if more { ",\n" } else { "\n" });
+	}
+
+	// Closes an object or array on its own line, attributed to the object or array itself.
+	fn close(&mut self, bracket: char, path: String, depth: usize) {
+		self.paths.push(path);
+		self.json.push_str(&"  ".repeat(depth));
+		self.json.push(bracket);
+	}
+}
+
+/// An error reported by a runtime while building the genesis state.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GenesisError {
+	/// The path of the value at fault within the genesis config, if known.
+	pub path: Option<String>,
+	/// The error message.
+	pub message: String,
+}
+
+impl fmt::Display for GenesisError {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		match &self.path {
+			Some(path) => write!(f, "`{path}`: {}", self.message),
+			None => write!(f, "{}", self.message),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use serde_json::json;
+
+	#[test]
+	fn merge_patch_works() {
+		let mut target = json!({
+			"balances": { "balances": [["alice", 1]] },
+			"sudo": { "key": "alice" },
+			"system": {}
+		});
+		merge_patch(
+			&mut target,
+			json!({
+				"balances": { "balances": [["bob", 2]] },
+				"sudo": null,
+				"parachainInfo": { "parachainId": 2000 }
+			}),
+		);
+		assert_eq!(
+			target,
+			json!({
+				"balances": { "balances": [["bob", 2]] },
+				"system": {},
+				"parachainInfo": { "parachainId": 2000 }
+			})
+		);
+
+		let mut target = json!("value");
+		merge_patch(&mut target, json!({ "key": 1, "removed": null }));
+		assert_eq!(target, json!({ "key": 1 }));
+	}
+
+	#[test]
+	fn load_genesis_patch_works() -> anyhow::Result<()> {
+		let patch = json!({ "sudo": { "key": "alice" } });
+		assert_eq!(load_genesis_patch(&patch.to_string())?, patch);
+
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("patch.json");
+		fs::write(&path, patch.to_string())?;
+		assert_eq!(load_genesis_patch(path.to_str().unwrap())?, patch);
+
+		fs::write(&path, "[1, 2]")?;
+		assert!(matches!(
+			load_genesis_patch(path.to_str().unwrap()),
+			Err(Error::GenesisBuilderError(message)) if message.contains("JSON object")
+		));
+		assert!(matches!(load_genesis_patch("{ invalid"), Err(Error::JsonError(_))));
+		assert!(matches!(
+			load_genesis_patch("missing.json"),
+			Err(Error::GenesisBuilderError(message)) if message.contains("missing.json")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn genesis_json_matches_serde_json() {
+		let config = json!({
+			"balances": { "balances": [["alice", 1], ["bob", 2]], "devAccounts": null },
+			"session": { "keys": [] },
+			"system": {},
+			"\"quoted\"": "value"
+		});
+		let json = GenesisJson::new(&config);
+		assert_eq!(
+			String::from_utf8(json.as_bytes().to_vec()).unwrap(),
+			serde_json::to_string_pretty(&config).unwrap()
+		);
+		assert_eq!(json.paths.len(), json.json.lines().count());
+	}
+
+	#[test]
+	fn genesis_json_locates_errors() {
+		let config = json!({
+			"balances": { "balances": [["alice", 1]] },
+			"sudo": { "kye": "alice" }
+		});
+		let json = GenesisJson::new(&config);
+		// {
+		//   "balances": {
+		//     "balances": [
+		//       [
+		//         "alice",
+		//         1
+		//       ]
+		//     ]
+		//   },
+		//   "sudo": {
+		//     "kye": "alice"
+		//   }
+		// }
+		let error = json
+			.error("Invalid JSON blob: unknown field `kye`, expected `key` at line 11 column 10");
+		assert_eq!(error.path.as_deref(), Some("sudo.kye"));
+		assert_eq!(error.message, "Invalid JSON blob: unknown field `kye`, expected `key`");
+		assert_eq!(
+			error.to_string(),
+			"`sudo.kye`: Invalid JSON blob: unknown field `kye`, expected `key`"
+		);
+
+		assert_eq!(
+			json.error("invalid type at line 6 column 9").path.as_deref(),
+			Some("balances.balances[0][1]")
+		);
+		assert_eq!(
+			json.error("missing field at line 7 column 7").path.as_deref(),
+			Some("balances.balances[0]")
+		);
+		assert_eq!(json.error("missing field at line 13 column 1").path, None);
+		assert_eq!(
+			json.error("the runtime panicked"),
+			GenesisError { path: None, message: "the runtime panicked".into() }
+		);
+	}
+}
@@ -6,14 +6,24 @@ use duct::cmd;
 use pop_common::{Profile, account_id::convert_to_evm_accounts, manifest::from_path};
 use sc_chain_spec::{GenericChainSpec, NoExtension};
 use serde_json::{Value, json};
-use sp_core::bytes::to_hex;
+use sp_core::bytes::{from_hex, to_hex};
 use std::{
+	collections::BTreeMap,
 	fs,
 	io::Write,
 	path::{Path, PathBuf},
 	str::FromStr,
 };
+use subxt::Metadata;
 
+use diff::{CODE_KEY, describe_code, diff_json, diff_storage};
+pub use diff::{ChainSpecChange, ChainSpecDiff};
+pub use genesis::{GenesisError, GenesisJson, RuntimeGenesis, load_genesis_patch, merge_patch};
+
+/// Comparison of chain specifications.
+mod diff;
+/// Patching and validation of genesis configs.
+mod genesis;
 /// Build the deterministic runtime.
 pub mod runtime;
 
@@ -822,6 +832,141 @@ impl ChainSpec {
 		*code = json!(hex);
 		Ok(())
 	}
+
+	/// Applies a JSON merge patch to the genesis config patch of the chain specification.
+	///
+	/// # Arguments
+	/// * `patch` - The merge patch for `genesis.runtimeGenesis.patch`.
+	pub fn apply_genesis_patch(&mut self, patch: Value) -> Result<(), Error> {
+		// Patch `genesis.runtimeGenesis.patch`
+		let genesis_patch = self
+			.0
+			.get_mut("genesis")
+			.ok_or_else(|| Error::Config("expected `genesis`".into()))?
+			.get_mut("runtimeGenesis")
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis`".into()))?
+			.get_mut("patch")
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis.patch`".into()))?;
+		merge_patch(genesis_patch, patch);
+		Ok(())
+	}
+
+	/// Get the runtime code from the chain specification, plain or raw.
+	pub fn runtime_code(&self) -> Result<Vec<u8>, Error> {
+		if self.is_raw() {
+			return self
+				.raw_storage()?
+				.remove(CODE_KEY)
+				.ok_or_else(|| Error::Config("expected `:code` in `genesis.raw.top`".into()));
+		}
+		let code = self
+			.0
+			.pointer("/genesis/runtimeGenesis/code")
+			.and_then(|code| code.as_str())
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis.code`".into()))?;
+		from_hex(code).map_err(|e| Error::Config(format!("invalid `runtimeGenesis.code`: {e}")))
+	}
+
+	/// Whether the chain specification is raw, holding the genesis storage rather than a genesis
+	/// config.
+	pub fn is_raw(&self) -> bool {
+		self.0.pointer("/genesis/raw").is_some()
+	}
+
+	/// Get the genesis storage of a raw chain specification, by storage key.
+	pub fn raw_storage(&self) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, Error> {
+		let top = self
+			.0
+			.pointer("/genesis/raw/top")
+			.and_then(|top| top.as_object())
+			.ok_or_else(|| Error::Config("expected `genesis.raw.top`".into()))?;
+		top.iter()
+			.map(|(key, value)| {
+				let invalid =
+					|e: String| Error::Config(format!("invalid storage entry `{key}`: {e}"));
+				let value = value.as_str().ok_or_else(|| invalid("expected a hex value".into()))?;
+				Ok((
+					from_hex(key).map_err(|e| invalid(e.to_string()))?,
+					from_hex(value).map_err(|e| invalid(e.to_string()))?,
+				))
+			})
+			.collect()
+	}
+
+	/// Compares the chain specification with a newer one.
+	///
+	/// Plain chain specifications are compared structurally. Raw ones are compared by genesis
+	/// storage entry, decoded with the metadata of their runtimes where provided.
+	///
+	/// # Arguments
+	/// * `new` - The newer chain specification.
+	/// * `old_metadata` - The metadata of the runtime of this chain specification.
+	/// * `new_metadata` - The metadata of the runtime of the newer chain specification.
+	pub fn diff(
+		&self,
+		new: &ChainSpec,
+		old_metadata: Option<&Metadata>,
+		new_metadata: Option<&Metadata>,
+	) -> Result<ChainSpecDiff, Error> {
+		if self.is_raw() != new.is_raw() {
+			return Err(Error::Config(
+				"cannot compare a plain chain specification with a raw one".into(),
+			));
+		}
+		let mut changes = Vec::new();
+		if self.is_raw() {
+			// The genesis storage is compared by entry, everything else structurally.
+			let without_storage = |spec: &ChainSpec| {
+				let mut json = spec.0.clone();
+				if let Some(raw) =
+					json.pointer_mut("/genesis/raw").and_then(|raw| raw.as_object_mut())
+				{
+					raw.remove("top");
+				}
+				json
+			};
+			diff_json("", &without_storage(self), &without_storage(new), &mut changes);
+			changes.extend(diff_storage(
+				&self.raw_storage()?,
+				&new.raw_storage()?,
+				old_metadata,
+				new_metadata,
+			));
+		} else {
+			// The runtime code is summarized rather than compared as a hex string.
+			let with_code_summary = |spec: &ChainSpec| {
+				let mut json = spec.0.clone();
+				if let Ok(code) = spec.runtime_code() &&
+					let Some(summary) = json.pointer_mut("/genesis/runtimeGenesis/code")
+				{
+					*summary = json!(describe_code(&code));
+				}
+				json
+			};
+			diff_json("", &with_code_summary(self), &with_code_summary(new), &mut changes);
+		}
+		Ok(ChainSpecDiff { changes })
+	}
+
+	/// Get the genesis config of the runtime from the chain specification.
+	///
+	/// Only plain chain specifications hold a genesis config, raw ones holding the resulting
+	/// storage instead.
+	pub fn runtime_genesis(&self) -> Result<RuntimeGenesis, Error> {
+		let runtime_genesis = self
+			.0
+			.get("genesis")
+			.ok_or_else(|| Error::Config("expected `genesis`".into()))?
+			.get("runtimeGenesis")
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis`".into()))?;
+		if let Some(patch) = runtime_genesis.get("patch") {
+			return Ok(RuntimeGenesis::Patch(patch.clone()));
+		}
+		runtime_genesis
+			.get("config")
+			.map(|config| RuntimeGenesis::Config(config.clone()))
+			.ok_or_else(|| Error::Config("expected `runtimeGenesis.patch` or `config`".into()))
+	}
 }
 
 #[cfg(test)]
@@ -836,7 +981,6 @@ mod tests {
 		manifest::{Dependency, add_feature},
 		set_executable_permission,
 	};
-	use sp_core::bytes::from_hex;
 	use std::{
 		fs::{self, write},
 		io::Write,
@@ -1632,6 +1776,126 @@ edition = "2021"
 		Ok(())
 	}
 
+	#[test]
+	fn apply_genesis_patch_works() -> Result<()> {
+		let mut chain_spec = ChainSpec(json!({"genesis": {"runtimeGenesis": {
+			"code": "0x00",
+			"patch": { "parachainInfo": { "parachainId": 1000 }, "sudo": { "key": "alice" } }
+		}}}));
+		chain_spec.apply_genesis_patch(json!({
+			"balances": { "balances": [["bob", 1]] },
+			"parachainInfo": { "parachainId": 2000 },
+			"sudo": null
+		}))?;
+		assert_eq!(
+			chain_spec.runtime_genesis()?,
+			RuntimeGenesis::Patch(json!({
+				"parachainInfo": { "parachainId": 2000 },
+				"balances": { "balances": [["bob", 1]] }
+			}))
+		);
+
+		chain_spec = ChainSpec(json!({"genesis": {"raw": { "top": {} }}}));
+		assert!(
+			matches!(chain_spec.apply_genesis_patch(json!({})), Err(Error::Config(error)) if error == "expected `runtimeGenesis`")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn runtime_genesis_works() -> Result<()> {
+		let chain_spec = ChainSpec(json!({"genesis": {"runtimeGenesis": {
+			"code": "0x1234",
+			"config": { "system": {} }
+		}}}));
+		assert_eq!(chain_spec.runtime_code()?, vec![0x12, 0x34]);
+		assert_eq!(chain_spec.runtime_genesis()?, RuntimeGenesis::Config(json!({ "system": {} })));
+
+		let chain_spec = ChainSpec(json!({"genesis": {"raw": { "top": {} }}}));
+		assert!(
+			matches!(chain_spec.runtime_genesis(), Err(Error::Config(error)) if error == "expected `runtimeGenesis`")
+		);
+		assert!(
+			matches!(chain_spec.runtime_code(), Err(Error::Config(error)) if error == "expected `:code` in `genesis.raw.top`")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn raw_storage_works() -> Result<()> {
+		let chain_spec = ChainSpec(json!({"genesis": {"raw": { "top": {
+			"0x3a636f6465": "0x1234",
+			"0xaabb": "0x01"
+		}}}}));
+		assert!(chain_spec.is_raw());
+		assert_eq!(
+			chain_spec.raw_storage()?,
+			BTreeMap::from([(b":code".to_vec(), vec![0x12, 0x34]), (vec![0xaa, 0xbb], vec![1])])
+		);
+		assert_eq!(chain_spec.runtime_code()?, vec![0x12, 0x34]);
+
+		let chain_spec = ChainSpec(json!({"genesis": {"raw": { "top": { "0xaabb": 1 }}}}));
+		assert!(
+			matches!(chain_spec.raw_storage(), Err(Error::Config(error)) if error == "invalid storage entry `0xaabb`: expected a hex value")
+		);
+		assert!(!ChainSpec(json!({"genesis": {"runtimeGenesis": {}}})).is_raw());
+		Ok(())
+	}
+
+	#[test]
+	fn diff_plain_chain_specs_works() -> Result<()> {
+		let old = ChainSpec(json!({
+			"name": "Development",
+			"genesis": {"runtimeGenesis": {
+				"code": "0x1234",
+				"patch": { "sudo": { "key": "alice" } }
+			}}
+		}));
+		let new = ChainSpec(json!({
+			"name": "Development",
+			"genesis": {"runtimeGenesis": {
+				"code": "0x123456",
+				"patch": { "sudo": { "key": "bob" }, "parachainInfo": { "parachainId": 2000 } }
+			}}
+		}));
+		let diff = old.diff(&new, None, None)?;
+		assert_eq!(
+			diff.render(),
+			format!(
+				"~ genesis.runtimeGenesis.code: \"{}\" -> \"{}\"
+~ genesis.runtimeGenesis.patch.sudo.key: \"alice\" -> \"bob\"
++ genesis.runtimeGenesis.patch.parachainInfo: {{\"parachainId\":2000}}",
+				describe_code(&[0x12, 0x34]),
+				describe_code(&[0x12, 0x34, 0x56])
+			)
+		);
+		assert!(old.diff(&old, None, None)?.is_empty());
+		Ok(())
+	}
+
+	#[test]
+	fn diff_raw_chain_specs_works() -> Result<()> {
+		let old = ChainSpec(json!({
+			"name": "Development",
+			"genesis": {"raw": { "top": { "0xaa": "0x01", "0xbb": "0x02" }, "childrenDefault": {} }}
+		}));
+		let new = ChainSpec(json!({
+			"name": "Local",
+			"genesis": {"raw": { "top": { "0xaa": "0x03", "0xcc": "0x04" }, "childrenDefault": {} }}
+		}));
+		assert_eq!(
+			old.diff(&new, None, None)?.render(),
+			"~ name: \"Development\" -> \"Local\"
+~ 0xaa: 0x01 -> 0x03
+- 0xbb: 0x02
++ 0xcc: 0x04"
+		);
+
+		let plain = ChainSpec(json!({"genesis": {"runtimeGenesis": { "patch": {} }}}));
+		assert!(matches!(old.diff(&plain, None, None), Err(Error::Config(_))));
+		Ok(())
+	}
+
 	#[test]
 	fn check_command_exists_fails() -> Result<()> {
 		let binary_path = PathBuf::from("/bin");
@@ -9,7 +9,7 @@ use subxt::{
 	dynamic::Value,
 	ext::futures::TryStreamExt,
 	metadata::types::{PalletMetadata, StorageEntryType},
-	utils::to_hex,
+	utils::{H256, to_hex},
 };
 
 pub mod action;
@@ -121,6 +121,8 @@ pub enum CallItem {
 	Constant(Constant),
 	/// A storage item that can be queried.
 	Storage(Storage),
+	/// A runtime API method that can be called.
+	RuntimeApi(RuntimeApi),
 }
 
 impl Default for CallItem {
@@ -135,6 +137,7 @@ impl Display for CallItem {
 			CallItem::Function(function) => function.fmt(f),
 			CallItem::Constant(constant) => constant.fmt(f),
 			CallItem::Storage(storage) => storage.fmt(f),
+			CallItem::RuntimeApi(api) => api.fmt(f),
 		}
 	}
 }
@@ -164,12 +167,21 @@ impl CallItem {
 		}
 	}
 
+	/// Returns a reference to the [`RuntimeApi`] if this is a runtime API call item.
+	pub fn as_runtime_api(&self) -> Option<&RuntimeApi> {
+		match self {
+			CallItem::RuntimeApi(api) => Some(api),
+			_ => None,
+		}
+	}
+
 	/// Returns the name of this call item.
 	pub fn name(&self) -> &str {
 		match self {
 			CallItem::Function(function) => &function.name,
 			CallItem::Constant(constant) => &constant.name,
 			CallItem::Storage(storage) => &storage.name,
+			CallItem::RuntimeApi(api) => &api.name,
 		}
 	}
 	/// Returns a descriptive hint string indicating the type of this call item.
@@ -178,6 +190,7 @@ impl CallItem {
 			CallItem::Function(_) => "📝 [EXTRINSIC]",
 			CallItem::Constant(_) => "[CONSTANT]",
 			CallItem::Storage(_) => "[STORAGE]",
+			CallItem::RuntimeApi(_) => "[RUNTIME API]",
 		}
 	}
 
@@ -187,6 +200,7 @@ impl CallItem {
 			CallItem::Function(function) => &function.docs,
 			CallItem::Constant(constant) => &constant.docs,
 			CallItem::Storage(storage) => &storage.docs,
+			CallItem::RuntimeApi(api) => &api.docs,
 		}
 	}
 
@@ -196,6 +210,7 @@ impl CallItem {
 			CallItem::Function(function) => &function.pallet,
 			CallItem::Constant(constant) => &constant.pallet,
 			CallItem::Storage(storage) => &storage.pallet,
+			CallItem::RuntimeApi(api) => &api.api,
 		}
 	}
 }
@@ -215,6 +230,9 @@ pub struct Pallet {
 	pub constants: Vec<Constant>,
 	/// The storage items of the pallet.
 	pub state: Vec<Storage>,
+	/// The methods of a runtime API. Runtime APIs are listed alongside the pallets, with the name
+	/// of the API as the pallet name.
+	pub runtime_apis: Vec<RuntimeApi>,
 }
 
 impl Display for Pallet {
@@ -224,13 +242,14 @@ impl Display for Pallet {
 }
 
 impl Pallet {
-	/// Returns a vector containing all callable items (functions, constants, and storage) defined
-	/// in this pallet.
+	/// Returns a vector containing all callable items (functions, constants, storage and runtime
+	/// API methods) defined in this pallet.
 	///
 	/// This method collects and returns all available callable items from the pallet:
 	/// - Dispatchable functions (extrinsics)
 	/// - Constants
 	/// - Storage items
+	/// - Runtime API methods
 	///
 	/// # Returns
 	/// A `Vec<CallItem>` containing all callable items from this pallet.
@@ -245,8 +264,16 @@ impl Pallet {
 		for storage in &self.state {
 			callables.push(CallItem::Storage(storage.clone()));
 		}
+		for api in &self.runtime_apis {
+			callables.push(CallItem::RuntimeApi(api.clone()));
+		}
 		callables
 	}
+
+	/// Whether this entry represents a runtime API rather than a pallet.
+	pub fn is_runtime_api(&self) -> bool {
+		!self.runtime_apis.is_empty()
+	}
 }
 
 /// Represents a dispatchable function.
@@ -366,20 +393,91 @@ impl Storage {
 		&self,
 		client: &OnlineClient<SubstrateConfig>,
 		keys: Vec<Value>,
+	) -> Result<Option<RawValue>, Error> {
+		let storage =
+			client.storage().at_latest().await.map_err(|e| {
+				Error::MetadataParsingError(format!("Failed to get storage: {}", e))
+			})?;
+		self.fetch(client, storage, keys).await
+	}
+
+	/// Query the storage value from the chain at the given block.
+	///
+	/// # Arguments
+	/// * `client` - The client to interact with the chain.
+	/// * `keys` - Optional storage keys for map-type storage items.
+	/// * `block_hash` - The hash of the block to query the storage at.
+	pub async fn query_at(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		keys: Vec<Value>,
+		block_hash: H256,
+	) -> Result<Option<RawValue>, Error> {
+		self.fetch(client, client.storage().at(block_hash), keys).await
+	}
+
+	/// Parses the keys of a storage map, provided as strings.
+	///
+	/// The keys of a map with several hashers, such as a double map or an N-map, are parsed
+	/// individually, which allows providing only the first keys to iterate the entries under
+	/// them.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the chain.
+	/// * `args` - The keys of the storage item, encoded as strings.
+	pub fn parse_keys(&self, metadata: &Metadata, args: Vec<String>) -> Result<Vec<Value>, Error> {
+		let Some(key_ty) = self.key_id.filter(|_| !args.is_empty()) else {
+			return Ok(vec![]);
+		};
+		let registry = metadata.types();
+		let name = registry
+			.resolve(key_ty)
+			.and_then(|ty| ty.path.segments.last().cloned())
+			.unwrap_or_default();
+		let key_param = params::type_to_param(&name, registry, key_ty)?;
+		let hashers = metadata
+			.pallet_by_name(&self.pallet)
+			.and_then(|pallet| pallet.storage())
+			.and_then(|storage| storage.entry_by_name(&self.name))
+			.map(|entry| match entry.entry_type() {
+				StorageEntryType::Map { hashers, .. } => hashers.len(),
+				StorageEntryType::Plain(_) => 0,
+			})
+			.unwrap_or_default();
+		if hashers < 2 {
+			return parse_dispatchable_arguments(&[key_param], args);
+		}
+		// A tuple of all the keys, e.g. "(A, B)".
+		if args.len() == 1 && args[0].trim_start().starts_with('(') {
+			let keys = parse_dispatchable_arguments(&[key_param], args)?;
+			return Ok(match keys.into_iter().next().map(|key| key.value) {
+				Some(ValueDef::Composite(composite)) => composite.into_values().collect(),
+				other => other.map(|value| vec![Value { value, context: () }]).unwrap_or_default(),
+			});
+		}
+		if args.len() > key_param.sub_params.len() {
+			return Err(Error::ParamParsingError(format!(
+				"Expected at most {} keys for `{}`, but received {}",
+				key_param.sub_params.len(),
+				self.name,
+				args.len()
+			)));
+		}
+		parse_dispatchable_arguments(&key_param.sub_params[..args.len()], args)
+	}
+
+	async fn fetch(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		storage: subxt::storage::Storage<SubstrateConfig, OnlineClient<SubstrateConfig>>,
+		keys: Vec<Value>,
 	) -> Result<Option<RawValue>, Error> {
 		let metadata = client.metadata();
 		let types = metadata.types();
 		let storage_address = subxt::dynamic::storage(&self.pallet, &self.name, keys);
-		let storage_data = client
-			.storage()
-			.at_latest()
-			.await
-			.map_err(|e| Error::MetadataParsingError(format!("Failed to get storage: {}", e)))?
-			.fetch(&storage_address)
-			.await
-			.map_err(|e| {
-				Error::MetadataParsingError(format!("Failed to fetch storage value: {}", e))
-			})?;
+		let storage_data = storage.fetch(&storage_address).await.map_err(|e| {
+			Error::MetadataParsingError(format!("Failed to fetch storage value: {}", e))
+		})?;
 
 		// Decode the value if it exists
 		match storage_data {
@@ -402,6 +500,63 @@ impl Storage {
 	}
 }
 
+/// Represents a runtime API method.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RuntimeApi {
+	/// The runtime API containing the method.
+	pub api: String,
+	/// The name of the method.
+	pub name: String,
+	/// The documentation of the method.
+	pub docs: String,
+	/// The parameters of the method.
+	pub params: Vec<Param>,
+	/// The type ID for decoding the result of the method.
+	pub output_type_id: u32,
+	/// Whether this method is supported (no recursive or unsupported parameter types).
+	pub is_supported: bool,
+}
+
+impl Display for RuntimeApi {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", self.name)
+	}
+}
+
+impl RuntimeApi {
+	/// Calls the runtime API method at the latest block and returns the decoded result.
+	///
+	/// # Arguments
+	/// * `client` - The client to interact with the chain.
+	/// * `args` - The method arguments, encoded as strings.
+	pub async fn call(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		args: Vec<String>,
+	) -> Result<RawValue, Error> {
+		if args.len() != self.params.len() {
+			return Err(Error::ParamParsingError(format!(
+				"Expected {} arguments for `{}`, but received {}",
+				self.params.len(),
+				self.name,
+				args.len()
+			)));
+		}
+		let args = parse_dispatchable_arguments(&self.params, args)?;
+		let payload = subxt::dynamic::runtime_api_call(&self.api, &self.name, args);
+		client
+			.runtime_api()
+			.at_latest()
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.call(payload)
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.to_value()
+			.map_err(|e| Error::SubXtError(Box::new(e.into())))
+	}
+}
+
 fn extract_chain_state_from_pallet_metadata(
 	pallet: &PalletMetadata,
 ) -> anyhow::Result<Vec<Storage>> {
@@ -536,28 +691,89 @@ fn extract_functions_from_pallet_metadata(
 		.map_err(|e| anyhow::Error::msg(e.to_string()))
 }
 
+fn extract_runtime_apis_from_metadata(metadata: &Metadata) -> Vec<Pallet> {
+	let types = metadata.types();
+	metadata
+		.runtime_api_traits()
+		.map(|api| {
+			let runtime_apis = api
+				.methods()
+				.map(|method| {
+					// Mark the method as unsupported rather than error if a parameter cannot be
+					// parsed.
+					let params = method
+						.inputs()
+						.map(|input| params::type_to_param(&input.name, types, input.ty))
+						.collect::<Result<Vec<_>, _>>();
+					let is_supported = params.is_ok();
+					RuntimeApi {
+						api: api.name().to_string(),
+						name: method.name().to_string(),
+						docs: if is_supported {
+							method
+								.docs()
+								.iter()
+								.filter(|l| !l.is_empty())
+								.cloned()
+								.collect::<Vec<_>>()
+								.join(" ")
+								.trim()
+								.to_string()
+						} else {
+							"Function Not Supported".to_string()
+						},
+						params: params.unwrap_or_default(),
+						output_type_id: method.output_ty(),
+						is_supported,
+					}
+				})
+				.collect();
+			Pallet {
+				name: api.name().to_string(),
+				docs: api.docs().join(" ").trim().to_string(),
+				runtime_apis,
+				..Default::default()
+			}
+		})
+		.filter(Pallet::is_runtime_api)
+		.collect()
+}
+
 /// Parses the chain metadata to extract information about pallets and their dispatchable functions.
 ///
 /// # Arguments
 /// * `client`: The client to interact with the chain.
 ///
-/// NOTE: pallets are ordered by their index within the runtime by default.
+/// NOTE: pallets are ordered by their index within the runtime by default, followed by the runtime
+/// APIs.
 pub fn parse_chain_metadata(client: &OnlineClient<SubstrateConfig>) -> Result<Vec<Pallet>, Error> {
-	let metadata: Metadata = client.metadata();
+	parse_metadata(&client.metadata())
+}
 
-	let pallets = metadata
+/// Parses runtime metadata to extract information about pallets and their dispatchable functions,
+/// e.g. metadata loaded from a file rather than fetched from a chain.
+///
+/// # Arguments
+/// * `metadata`: The metadata of the runtime.
+///
+/// NOTE: pallets are ordered by their index within the runtime by default, followed by the runtime
+/// APIs.
+pub fn parse_metadata(metadata: &Metadata) -> Result<Vec<Pallet>, Error> {
+	let mut pallets = metadata
 		.pallets()
 		.map(|pallet| {
 			Ok(Pallet {
 				name: pallet.name().to_string(),
 				index: pallet.index(),
 				docs: pallet.docs().join("").trim().to_string(),
-				functions: extract_functions_from_pallet_metadata(&pallet, &metadata)?,
-				constants: extract_constants_from_pallet_metadata(&pallet, &metadata)?,
+				functions: extract_functions_from_pallet_metadata(&pallet, metadata)?,
+				constants: extract_constants_from_pallet_metadata(&pallet, metadata)?,
 				state: extract_chain_state_from_pallet_metadata(&pallet)?,
+				runtime_apis: vec![],
 			})
 		})
 		.collect::<Result<Vec<Pallet>, Error>>()?;
+	pallets.extend(extract_runtime_apis_from_metadata(metadata));
 
 	Ok(pallets)
 }
@@ -599,8 +815,11 @@ pub fn find_callable_by_name(
 	if let Some(storage) = pallet.state.iter().find(|&e| e.name == function_name) {
 		return Ok(CallItem::Storage(storage.clone()));
 	}
+	if let Some(api) = pallet.runtime_apis.iter().find(|&e| e.name == function_name) {
+		return Ok(CallItem::RuntimeApi(api.clone()));
+	}
 	Err(Error::FunctionNotFound(format!(
-		"Could not find a function, constant or storage with the name \"{function_name}\""
+		"Could not find a function, constant, storage or runtime API with the name \"{function_name}\""
 	)))
 }
 
@@ -883,6 +1102,7 @@ mod tests {
 				key_id: None,
 				query_all: false,
 			}],
+			runtime_apis: vec![],
 		};
 		assert_eq!(pallet.constants.len(), 1);
 		assert_eq!(pallet.state.len(), 1);
@@ -1088,6 +1308,14 @@ mod tests {
 		};
 		let item = CallItem::Storage(storage);
 		assert_eq!(item.hint(), "[STORAGE]");
+
+		let api = RuntimeApi {
+			api: "AccountNonceApi".to_string(),
+			name: "account_nonce".to_string(),
+			..Default::default()
+		};
+		let item = CallItem::RuntimeApi(api);
+		assert_eq!(item.hint(), "[RUNTIME API]");
 	}
 
 	#[test]
@@ -1151,6 +1379,17 @@ mod tests {
 		};
 		let item = CallItem::Storage(storage);
 		assert_eq!(item.pallet(), "Timestamp");
+
+		let api = RuntimeApi {
+			api: "AccountNonceApi".to_string(),
+			name: "account_nonce".to_string(),
+			..Default::default()
+		};
+		let item = CallItem::RuntimeApi(api);
+		assert_eq!(item.pallet(), "AccountNonceApi");
+		assert_eq!(item.name(), "account_nonce");
+		assert!(item.as_runtime_api().is_some());
+		assert!(item.as_function().is_none());
 	}
 
 	#[test]
@@ -1182,6 +1421,7 @@ mod tests {
 			functions: vec![function.clone()],
 			constants: vec![constant.clone()],
 			state: vec![storage.clone()],
+			runtime_apis: vec![],
 		};
 
 		let callables = pallet.get_all_callables();
@@ -1231,6 +1471,7 @@ mod tests {
 			functions: vec![function.clone()],
 			constants: vec![constant.clone()],
 			state: vec![storage.clone()],
+			runtime_apis: vec![],
 		}];
 
 		// Test finding a function
@@ -1254,6 +1495,25 @@ mod tests {
 			assert_eq!(s.name, "Account");
 		}
 
+		// Test finding a runtime API method
+		let api = RuntimeApi {
+			api: "AccountNonceApi".to_string(),
+			name: "account_nonce".to_string(),
+			..Default::default()
+		};
+		let mut pallets = pallets;
+		pallets.push(Pallet {
+			name: "AccountNonceApi".to_string(),
+			runtime_apis: vec![api.clone()],
+			..Default::default()
+		});
+		assert!(pallets[1].is_runtime_api());
+		assert!(!pallets[0].is_runtime_api());
+		assert_eq!(
+			find_callable_by_name(&pallets, "AccountNonceApi", "account_nonce").unwrap(),
+			CallItem::RuntimeApi(api)
+		);
+
 		// Test not finding a callable
 		let result = find_callable_by_name(&pallets, "System", "NonExistent");
 		assert!(result.is_err());
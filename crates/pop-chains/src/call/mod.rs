@@ -1,6 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use crate::{Function, Pallet, Param, errors::Error, find_callable_by_name};
+use options::{TxOptions, sign_extrinsic_with_options};
 use pop_common::{
 	call::{DefaultEnvironment, DisplayEvents, TokenMetadata, Verbosity},
 	create_signer,
@@ -9,10 +10,18 @@ use sp_core::bytes::{from_hex, to_hex};
 use subxt::{
 	OnlineClient, SubstrateConfig,
 	blocks::ExtrinsicEvents,
+	config::DefaultExtrinsicParamsBuilder,
 	dynamic::Value,
-	tx::{DynamicPayload, Payload, SubmittableTransaction, TxStatus},
+	error::DispatchError,
+	events::EventDetails,
+	tx::{DynamicPayload, Payload, Signer, SubmittableTransaction, TxStatus},
 };
 pub mod metadata;
+pub mod multisig;
+pub mod offline;
+pub mod options;
+pub mod script;
+pub mod storage;
 
 /// Sets up an [OnlineClient] instance for connecting to a blockchain.
 ///
@@ -91,9 +100,126 @@ pub async fn sign_and_submit_extrinsic<Xt: Payload>(
 	suri: &str,
 ) -> Result<String, Error> {
 	let signer = create_signer(suri)?;
-	let mut tx = client
-		.tx()
-		.sign_and_submit_then_watch_default(&xt, &signer)
+	sign_and_submit_extrinsic_with_signer(client, url, xt, &signer).await
+}
+
+/// Signs and submits a call dispatched with `Root` origin through the `Sudo` pallet, failing with
+/// the dispatch error of the call if it did not succeed.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node.
+/// * `call` - The call to be dispatched with `Root` origin.
+/// * `suri` - The secret URI of the sudo account.
+pub async fn sign_and_submit_sudo_extrinsic(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	call: DynamicPayload,
+	suri: &str,
+) -> Result<String, Error> {
+	let signer = create_signer(suri)?;
+	let events = script::submit_extrinsic(client, &construct_sudo_extrinsic(call), &signer).await?;
+	ensure_dispatched(client, &events)?;
+	parse_and_format_events(client, url, &events).await
+}
+
+/// Ensures the calls dispatched on behalf of an extrinsic succeeded. `Sudo::sudo`,
+/// `Proxy::proxy` and `Utility::dispatch_as` succeed whatever the outcome of the call they
+/// dispatch, which is only reported by the events they emit.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `events` - The events of the extrinsic.
+pub fn ensure_dispatched(
+	client: &OnlineClient<SubstrateConfig>,
+	events: &ExtrinsicEvents<SubstrateConfig>,
+) -> Result<(), Error> {
+	for event in events.iter() {
+		let event = event.map_err(|e| Error::SubXtError(e.into()))?;
+		if let Some(error) = dispatch_error(client, &event)? {
+			return Err(Error::ExtrinsicSubmissionError(error));
+		}
+	}
+	Ok(())
+}
+
+/// The failure reported by an event announcing the outcome of a call dispatched on behalf of
+/// another origin, such as `Sudo::Sudid`, if the call failed.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `event` - The event emitted.
+pub fn dispatch_error(
+	client: &OnlineClient<SubstrateConfig>,
+	event: &EventDetails<SubstrateConfig>,
+) -> Result<Option<String>, Error> {
+	let dispatched = matches!(
+		(event.pallet_name(), event.variant_name()),
+		("Sudo", "Sudid" | "SudoAsDone") | ("Proxy", "ProxyExecuted") | ("Utility", "DispatchedAs")
+	);
+	// The only field of these events is the `Result<(), DispatchError>` of the call.
+	let (true, [1, error @ ..]) = (dispatched, event.field_bytes()) else {
+		return Ok(None);
+	};
+	let error = DispatchError::decode_from(error, client.metadata())
+		.map_err(|e| Error::DecodingError(e.to_string()))?;
+	Ok(Some(format!(
+		"{}::{} reported the call failed: {error}",
+		event.pallet_name(),
+		event.variant_name()
+	)))
+}
+
+/// Signs and submits a given extrinsic with the provided signer, which allows signing with
+/// keys of any scheme (e.g. a [`pop_common::AccountSigner`]).
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node.
+/// * `xt` - The (encoded) extrinsic to be signed and submitted.
+/// * `signer` - The signer of the extrinsic.
+pub async fn sign_and_submit_extrinsic_with_signer<Xt: Payload, S: Signer<SubstrateConfig>>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	xt: Xt,
+	signer: &S,
+) -> Result<String, Error> {
+	sign_and_submit_extrinsic_with_options(client, url, xt, signer, &TxOptions::default()).await
+}
+
+/// Signs and submits a given extrinsic with the provided signer and transaction options, such as
+/// a tip, an explicit nonce, a mortal era or an asset to pay the fees in.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node.
+/// * `xt` - The (encoded) extrinsic to be signed and submitted.
+/// * `signer` - The signer of the extrinsic.
+/// * `options` - The options of the transaction.
+pub async fn sign_and_submit_extrinsic_with_options<Xt: Payload, S: Signer<SubstrateConfig>>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	xt: Xt,
+	signer: &S,
+	options: &TxOptions,
+) -> Result<String, Error> {
+	let extrinsic = sign_extrinsic_with_options(client, url, &xt, signer, options).await?;
+	submit_and_watch_extrinsic(client, url, extrinsic).await
+}
+
+/// Submits an encoded signed extrinsic and waits for it to be included in a block.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node.
+/// * `extrinsic` - The encoded signed extrinsic.
+pub async fn submit_and_watch_extrinsic(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	extrinsic: Vec<u8>,
+) -> Result<String, Error> {
+	let mut tx = SubmittableTransaction::from_bytes(client.clone(), extrinsic)
+		.submit_and_watch()
 		.await
 		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{:?}", e)))?;
 
@@ -145,6 +271,31 @@ pub async fn sign_and_submit_extrinsic<Xt: Payload>(
 	))
 }
 
+/// Signs an extrinsic with an explicit account nonce, without submitting it.
+///
+/// The nonce is not queried from the chain, which allows signing an extrinsic that is executed
+/// against different state, such as a local fork of the chain.
+///
+/// # Arguments
+/// * `client` - The client providing the metadata, genesis hash and runtime version.
+/// * `xt` - The extrinsic to sign.
+/// * `signer` - The signer of the extrinsic.
+/// * `nonce` - The account nonce to sign with.
+pub fn sign_extrinsic_with_nonce<Xt: Payload, S: Signer<SubstrateConfig>>(
+	client: &OnlineClient<SubstrateConfig>,
+	xt: &Xt,
+	signer: &S,
+	nonce: u64,
+) -> Result<Vec<u8>, Error> {
+	let params = DefaultExtrinsicParamsBuilder::<SubstrateConfig>::new().nonce(nonce).build();
+	let tx = client
+		.tx()
+		.create_partial_offline(xt, params)
+		.map_err(|e| Error::SubXtError(Box::new(e)))?
+		.sign(signer);
+	Ok(tx.into_encoded())
+}
+
 /// Parses and formats the events from the extrinsic result.
 ///
 /// # Arguments
@@ -0,0 +1,398 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use scale::{Decode, Encode};
+use serde::Serialize;
+use sp_core::{blake2_256, bytes::to_hex};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	dynamic::{DecodedValueThunk, Value},
+	ext::{futures::TryStreamExt, scale_decode::DecodeAsType},
+	tx::DynamicPayload,
+	utils::AccountId32,
+};
+
+/// The prefix used by `pallet_multisig` to derive multisig accounts.
+const MULTISIG_PREFIX: &[u8; 16] = b"modlpy/utilisuba";
+
+/// The block height and extrinsic index of the first approval of a multisig operation.
+#[derive(Clone, Copy, Debug, Decode, DecodeAsType, Encode, PartialEq, Eq, Serialize)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+pub struct Timepoint {
+	/// The height of the block.
+	pub height: u32,
+	/// The index of the extrinsic within the block.
+	pub index: u32,
+}
+
+impl From<Timepoint> for Value {
+	fn from(timepoint: Timepoint) -> Self {
+		Value::named_composite([
+			("height", Value::u128(timepoint.height as u128)),
+			("index", Value::u128(timepoint.index as u128)),
+		])
+	}
+}
+
+/// A multisig operation as stored in `Multisig::Multisigs`.
+#[derive(DecodeAsType)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+struct MultisigInfo {
+	when: Timepoint,
+	deposit: u128,
+	depositor: AccountId32,
+	approvals: Vec<AccountId32>,
+}
+
+/// A multisig operation which has been opened but not yet executed or cancelled.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MultisigOperation {
+	/// The hex-encoded hash of the call to be dispatched.
+	pub call_hash: String,
+	/// When the operation was opened.
+	pub when: Timepoint,
+	/// The amount held from the depositor until the operation is executed or cancelled.
+	pub deposit: u128,
+	/// The account which opened the operation.
+	pub depositor: String,
+	/// The signatories which have approved the operation.
+	pub approvals: Vec<String>,
+}
+
+impl MultisigOperation {
+	fn new(call_hash: &[u8], info: MultisigInfo) -> Self {
+		Self {
+			call_hash: to_hex(call_hash, false),
+			when: info.when,
+			deposit: info.deposit,
+			depositor: info.depositor.to_string(),
+			approvals: info.approvals.iter().map(ToString::to_string).collect(),
+		}
+	}
+}
+
+/// A call wrapped for approval by a signatory of a multisig account.
+pub struct MultisigApproval {
+	/// The `Multisig` extrinsic to be signed by the signatory.
+	pub xt: DynamicPayload,
+	/// The hex-encoded hash of the wrapped call.
+	pub call_hash: String,
+	/// When the operation was opened, if it has already been approved by another signatory.
+	pub timepoint: Option<Timepoint>,
+	/// The number of approvals once the extrinsic has been executed.
+	pub approvals: usize,
+	/// Whether the extrinsic reaches the threshold and dispatches the wrapped call.
+	pub executes: bool,
+}
+
+/// A multisig account of `pallet_multisig`: a set of signatories and the number of approvals
+/// required to dispatch a call on behalf of the account.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Multisig {
+	threshold: u16,
+	signatories: Vec<AccountId32>,
+}
+
+impl Multisig {
+	/// Creates a multisig account.
+	///
+	/// # Arguments
+	/// * `threshold` - The number of approvals required to dispatch a call.
+	/// * `signatories` - The accounts which can approve calls, in any order.
+	pub fn new(threshold: u16, mut signatories: Vec<AccountId32>) -> Result<Self, Error> {
+		signatories.sort();
+		signatories.dedup();
+		if signatories.len() < 2 {
+			return Err(Error::MultisigError(
+				"A multisig account requires at least two distinct signatories".into(),
+			));
+		}
+		if threshold == 0 || threshold as usize > signatories.len() {
+			return Err(Error::MultisigError(format!(
+				"The threshold must be between 1 and the number of signatories ({})",
+				signatories.len()
+			)));
+		}
+		Ok(Self { threshold, signatories })
+	}
+
+	/// The number of approvals required to dispatch a call.
+	pub fn threshold(&self) -> u16 {
+		self.threshold
+	}
+
+	/// The signatories of the multisig account, sorted.
+	pub fn signatories(&self) -> &[AccountId32] {
+		&self.signatories
+	}
+
+	/// The account derived from the signatories and the threshold.
+	pub fn account(&self) -> AccountId32 {
+		let entropy =
+			(MULTISIG_PREFIX, &self.signatories, self.threshold).using_encoded(blake2_256);
+		AccountId32(entropy)
+	}
+
+	/// The signatories other than `signatory`, as required by the `Multisig` calls.
+	///
+	/// # Arguments
+	/// * `signatory` - The signatory approving a call.
+	pub fn other_signatories(&self, signatory: &AccountId32) -> Result<Vec<AccountId32>, Error> {
+		if !self.signatories.contains(signatory) {
+			return Err(Error::MultisigError(format!(
+				"{signatory} is not a signatory of the multisig account {}",
+				self.account()
+			)));
+		}
+		Ok(self.signatories.iter().filter(|s| *s != signatory).cloned().collect())
+	}
+
+	/// Queries the operations of the multisig account which are awaiting approvals.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub async fn pending_operations(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+	) -> Result<Vec<MultisigOperation>, Error> {
+		let address = subxt::dynamic::storage(
+			"Multisig",
+			"Multisigs",
+			vec![Value::from_bytes(self.account().0)],
+		);
+		let mut stream = client
+			.storage()
+			.at_latest()
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.iter(address)
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?;
+		let mut operations = Vec::new();
+		while let Some(entry) = stream.try_next().await.map_err(|e| Error::SubXtError(e.into()))? {
+			// The call hash is the last key, hashed with `Blake2_128Concat`.
+			let call_hash = &entry.key_bytes[entry.key_bytes.len().saturating_sub(32)..];
+			operations.push(MultisigOperation::new(call_hash, decode_info(entry.value)?));
+		}
+		operations.sort_by_key(|operation| (operation.when.height, operation.when.index));
+		Ok(operations)
+	}
+
+	/// Queries an operation of the multisig account which is awaiting approvals.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `call_hash` - The hash of the call of the operation.
+	pub async fn operation(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		call_hash: [u8; 32],
+	) -> Result<Option<MultisigOperation>, Error> {
+		let address = subxt::dynamic::storage(
+			"Multisig",
+			"Multisigs",
+			vec![Value::from_bytes(self.account().0), Value::from_bytes(call_hash)],
+		);
+		client
+			.storage()
+			.at_latest()
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.fetch(&address)
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.map(|value| Ok(MultisigOperation::new(&call_hash, decode_info(value)?)))
+			.transpose()
+	}
+
+	/// Wraps a call for approval by a signatory of the multisig account.
+	///
+	/// The operation of the call is looked up in `Multisig::Multisigs`. The call is wrapped with
+	/// `as_multi` and the weight of the call if the approval reaches the threshold, or with
+	/// `approve_as_multi` otherwise. Subsequent approvals reference the timepoint of the first
+	/// one.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `signatory` - The signatory approving the call.
+	/// * `xt` - The call to be dispatched on behalf of the multisig account.
+	pub async fn approve(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		signatory: &AccountId32,
+		xt: DynamicPayload,
+	) -> Result<MultisigApproval, Error> {
+		let other_signatories = self.other_signatories(signatory)?;
+		let others =
+			Value::unnamed_composite(other_signatories.iter().map(|a| Value::from_bytes(a.0)));
+		let call_data = client
+			.tx()
+			.call_data(&xt)
+			.map_err(|e| Error::CallDataEncodingError(e.to_string()))?;
+		let call_hash = blake2_256(&call_data);
+
+		if self.threshold == 1 {
+			return Ok(MultisigApproval {
+				xt: subxt::dynamic::tx(
+					"Multisig",
+					"as_multi_threshold_1",
+					vec![others, xt.into_value()],
+				),
+				call_hash: to_hex(&call_hash, false),
+				timepoint: None,
+				approvals: 1,
+				executes: true,
+			});
+		}
+
+		let operation = self.operation(client, call_hash).await?;
+		if let Some(operation) = &operation &&
+			operation.approvals.contains(&signatory.to_string())
+		{
+			return Err(Error::MultisigError(format!(
+				"{signatory} has already approved the operation {}",
+				operation.call_hash
+			)));
+		}
+		let timepoint = operation.as_ref().map(|operation| operation.when);
+		let approvals = operation.map_or(0, |operation| operation.approvals.len()) + 1;
+		let executes = approvals >= self.threshold as usize;
+		let maybe_timepoint = match timepoint {
+			Some(timepoint) => Value::unnamed_variant("Some", [timepoint.into()]),
+			None => Value::unnamed_variant("None", []),
+		};
+		let threshold = Value::u128(self.threshold as u128);
+
+		let xt = if executes {
+			let max_weight = query_call_weight(client, &call_data).await?;
+			subxt::dynamic::tx(
+				"Multisig",
+				"as_multi",
+				vec![threshold, others, maybe_timepoint, xt.into_value(), max_weight],
+			)
+		} else {
+			let max_weight = Weight::default().into();
+			subxt::dynamic::tx(
+				"Multisig",
+				"approve_as_multi",
+				vec![threshold, others, maybe_timepoint, Value::from_bytes(call_hash), max_weight],
+			)
+		};
+		Ok(MultisigApproval {
+			xt,
+			call_hash: to_hex(&call_hash, false),
+			timepoint,
+			approvals,
+			executes,
+		})
+	}
+}
+
+/// The weight of a call, as returned by `TransactionPaymentCallApi_query_call_info`.
+#[derive(Debug, Decode, Default, PartialEq)]
+pub(crate) struct Weight {
+	#[codec(compact)]
+	pub(crate) ref_time: u64,
+	#[codec(compact)]
+	pub(crate) proof_size: u64,
+}
+
+impl From<Weight> for Value {
+	fn from(weight: Weight) -> Self {
+		Value::named_composite([
+			("ref_time", Value::u128(weight.ref_time as u128)),
+			("proof_size", Value::u128(weight.proof_size as u128)),
+		])
+	}
+}
+
+// Queries the weight of the call, which is the first field of the `RuntimeDispatchInfo` returned.
+async fn query_call_weight(
+	client: &OnlineClient<SubstrateConfig>,
+	call_data: &[u8],
+) -> Result<Value, Error> {
+	let mut params = call_data.to_vec();
+	(call_data.len() as u32).encode_to(&mut params);
+	let info = client
+		.runtime_api()
+		.at_latest()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.call_raw("TransactionPaymentCallApi_query_call_info", Some(&params))
+		.await
+		.map_err(|e| {
+			Error::MultisigError(format!("Failed to query the weight of the call: {e}"))
+		})?;
+	let weight = Weight::decode(&mut &info[..]).map_err(|e| {
+		Error::MultisigError(format!("Failed to decode the weight of the call: {e}"))
+	})?;
+	Ok(weight.into())
+}
+
+fn decode_info(value: DecodedValueThunk) -> Result<MultisigInfo, Error> {
+	value
+		.as_type::<MultisigInfo>()
+		.map_err(|e| Error::MultisigError(format!("Failed to decode the operation: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use subxt_signer::sr25519::dev;
+
+	fn accounts() -> Vec<AccountId32> {
+		[dev::alice(), dev::bob(), dev::charlie()]
+			.iter()
+			.map(|pair| pair.public_key().to_account_id())
+			.collect()
+	}
+
+	#[test]
+	fn account_is_independent_of_signatory_order() -> Result<(), Error> {
+		let mut reversed = accounts();
+		reversed.reverse();
+		assert_eq!(Multisig::new(2, accounts())?.account(), Multisig::new(2, reversed)?.account());
+		assert_ne!(
+			Multisig::new(2, accounts())?.account(),
+			Multisig::new(3, accounts())?.account()
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn new_validates_threshold_and_signatories() {
+		let alice = accounts()[0].clone();
+		assert!(matches!(
+			Multisig::new(1, vec![alice.clone(), alice]),
+			Err(Error::MultisigError(e)) if e.contains("two distinct signatories")
+		));
+		assert!(matches!(Multisig::new(0, accounts()), Err(Error::MultisigError(_))));
+		assert!(matches!(Multisig::new(4, accounts()), Err(Error::MultisigError(_))));
+	}
+
+	#[test]
+	fn other_signatories_works() -> Result<(), Error> {
+		let multisig = Multisig::new(2, accounts())?;
+		let alice = &accounts()[0];
+		let others = multisig.other_signatories(alice)?;
+		assert_eq!(others.len(), 2);
+		assert!(!others.contains(alice));
+		assert!(others.windows(2).all(|w| w[0] < w[1]));
+
+		let dave = dev::dave().public_key().to_account_id();
+		assert!(matches!(multisig.other_signatories(&dave), Err(Error::MultisigError(_))));
+		Ok(())
+	}
+
+	#[test]
+	fn weight_decodes_from_dispatch_info() {
+		// `RuntimeDispatchInfo { weight, class: Normal, partial_fee: 1_000 }`.
+		let mut encoded = (scale::Compact(1_000_000u64), scale::Compact(2_048u64)).encode();
+		encoded.push(0);
+		encoded.extend(1_000u128.encode());
+		assert_eq!(
+			Weight::decode(&mut &encoded[..]).unwrap(),
+			Weight { ref_time: 1_000_000, proof_size: 2_048 }
+		);
+	}
+}
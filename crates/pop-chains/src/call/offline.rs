@@ -0,0 +1,488 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	CallData, DecodedValue,
+	call::options::{TxOptions, checkpoint},
+	decode_call,
+	errors::Error,
+};
+use frame_metadata::RuntimeMetadataPrefixed;
+use merkleized_metadata::{ExtraInfo, generate_metadata_digest};
+use pop_common::call::TokenMetadata;
+use scale::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::bytes::{from_hex, to_hex};
+use std::{fs, path::Path};
+use subxt::{
+	Metadata, OfflineClient, OnlineClient, SubstrateConfig,
+	client::{OfflineClientT, RuntimeVersion},
+	config::{DefaultExtrinsicParamsBuilder, substrate::H256},
+	tx::{PartialTransaction, Payload, Signer},
+	utils::{AccountId32, MultiSignature},
+};
+use url::Url;
+
+/// The oldest version of the metadata which can be bundled with a transaction.
+const METADATA_V14: u32 = 14;
+/// The version of the metadata the metadata hash is computed from.
+const METADATA_V15: u32 = 15;
+/// The latest version of the metadata which can be bundled with a transaction.
+const METADATA_LATEST: u32 = 16;
+
+/// Validity period of a transaction.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Era {
+	/// The transaction is valid forever.
+	#[default]
+	Immortal,
+	/// The transaction is valid for `period` blocks from the checkpoint block.
+	Mortal {
+		/// Number of blocks the transaction is valid for.
+		period: u64,
+		/// Number of the checkpoint block.
+		block_number: u64,
+		/// Hex-encoded hash of the checkpoint block.
+		block_hash: String,
+	},
+}
+
+/// A transaction prepared for signing on another, possibly air-gapped, machine.
+///
+/// The file contains everything needed to sign the transaction without access to the chain: the
+/// payload to sign, the parameters it was built with and the metadata of the runtime. The payload
+/// is rebuilt from the parameters before signing, and again to assemble the signed extrinsic
+/// before it is submitted.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OfflineTransaction {
+	/// Hex-encoded call data.
+	pub call_data: String,
+	/// SS58 address of the account expected to sign the transaction.
+	pub signer: String,
+	/// Nonce of the signer.
+	pub nonce: u64,
+	/// Validity period of the transaction.
+	pub era: Era,
+	/// Tip paid to the block author.
+	pub tip: u128,
+	/// Hex-encoded genesis hash of the chain.
+	pub genesis_hash: String,
+	/// Spec version of the runtime the transaction was built for.
+	pub spec_version: u32,
+	/// Transaction version of the runtime the transaction was built for.
+	pub transaction_version: u32,
+	/// Hex-encoded metadata of the runtime the transaction was built for, used to decode and check
+	/// the transaction offline. The latest version provided by the runtime is used.
+	pub metadata: String,
+	/// Hex-encoded hash of the metadata, as checked by the `CheckMetadataHash` extension, for
+	/// signers able to verify the metadata against it. Absent if the runtime does not provide
+	/// version 15 of its metadata, from which the hash is computed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub metadata_hash: Option<String>,
+	/// Hex-encoded payload to sign. Payloads longer than 256 bytes are already hashed.
+	pub signer_payload: String,
+	/// Hex-encoded SCALE `MultiSignature` of the signer payload, once signed.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub signature: Option<String>,
+}
+
+impl OfflineTransaction {
+	/// Prepare a transaction for offline signing, using the current nonce of the signer unless a
+	/// nonce is provided.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `url` - Endpoint of the node, used to query the token of the chain.
+	/// * `xt` - The extrinsic to sign.
+	/// * `signer` - The account expected to sign the transaction.
+	/// * `options` - The tip, nonce and mortality of the transaction.
+	pub async fn new<Xt: Payload>(
+		client: &OnlineClient<SubstrateConfig>,
+		url: &Url,
+		xt: &Xt,
+		signer: AccountId32,
+		options: &TxOptions,
+	) -> Result<Self, Error> {
+		if options.fee_asset.is_some() {
+			return Err(Error::OfflineSigningError(
+				"paying fees in an asset is not supported for offline signing".to_string(),
+			));
+		}
+		let nonce = match options.nonce {
+			Some(nonce) => nonce,
+			None => client
+				.tx()
+				.account_nonce(&signer)
+				.await
+				.map_err(|e| Error::SubXtError(Box::new(e)))?,
+		};
+		let era = match options.mortality {
+			Some(period) => {
+				let (block_number, block_hash) = checkpoint(client).await?;
+				Era::Mortal {
+					period,
+					block_number,
+					block_hash: to_hex(block_hash.as_bytes(), false),
+				}
+			},
+			None => Era::Immortal,
+		};
+		let versions = metadata_versions(client).await?;
+		let version = (METADATA_V14..=METADATA_LATEST)
+			.rev()
+			.find(|version| versions.contains(version))
+			.ok_or_else(|| {
+				Error::OfflineSigningError(format!(
+					"the runtime does not provide a supported version of its metadata: {versions:?}"
+				))
+			})?;
+		let metadata = runtime_metadata(client, version).await?;
+		let metadata_hash = if versions.contains(&METADATA_V15) {
+			let metadata = match version {
+				METADATA_V15 => metadata.clone(),
+				_ => runtime_metadata(client, METADATA_V15).await?,
+			};
+			Some(to_hex(&metadata_hash(client, url, &metadata).await?, false))
+		} else {
+			None
+		};
+		let mut transaction = Self {
+			call_data: to_hex(
+				&client
+					.tx()
+					.call_data(xt)
+					.map_err(|e| Error::CallDataEncodingError(e.to_string()))?,
+				false,
+			),
+			signer: signer.to_string(),
+			nonce,
+			era,
+			tip: options.tip,
+			genesis_hash: to_hex(client.genesis_hash().as_bytes(), false),
+			spec_version: client.runtime_version().spec_version,
+			transaction_version: client.runtime_version().transaction_version,
+			metadata: to_hex(&metadata, false),
+			metadata_hash,
+			signer_payload: String::new(),
+			signature: None,
+		};
+		transaction.signer_payload = to_hex(&transaction.build_signer_payload(client)?, false);
+		Ok(transaction)
+	}
+
+	/// Read a transaction from a JSON file.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+	}
+
+	/// Write the transaction to a JSON file.
+	pub fn save(&self, path: &Path) -> Result<(), Error> {
+		fs::write(path, serde_json::to_string_pretty(self)?)?;
+		Ok(())
+	}
+
+	/// Decode the call of the transaction with the bundled metadata, so it can be reviewed before
+	/// signing. Does not require access to the chain.
+	pub fn decoded_call(&self) -> Result<DecodedValue, Error> {
+		decode_call(&self.metadata()?, &self.call_data)
+	}
+
+	/// Sign the payload of the transaction. Does not require access to the chain.
+	///
+	/// The payload is rebuilt from the parameters of the transaction and the bundled metadata, so
+	/// that only the transaction described by the file can be signed.
+	///
+	/// # Arguments
+	/// * `signer` - The signer, which must be the account the transaction was prepared for.
+	pub fn sign<S: Signer<SubstrateConfig>>(&mut self, signer: &S) -> Result<(), Error> {
+		let account = signer.account_id();
+		if account.to_string() != self.signer {
+			return Err(Error::OfflineSigningError(format!(
+				"the transaction must be signed by {}, not {account}",
+				self.signer
+			)));
+		}
+		let client = OfflineClient::<SubstrateConfig>::new(
+			decode_hash("genesis hash", &self.genesis_hash)?,
+			RuntimeVersion {
+				spec_version: self.spec_version,
+				transaction_version: self.transaction_version,
+			},
+			self.metadata()?,
+		);
+		let payload = self.build_signer_payload(&client)?;
+		if to_hex(&payload, false) != self.signer_payload {
+			return Err(Error::OfflineSigningError(
+				"the signer payload does not match the transaction parameters".to_string(),
+			));
+		}
+		self.signature = Some(to_hex(&signer.sign(&payload).encode(), false));
+		Ok(())
+	}
+
+	/// Assemble the signed extrinsic, ready to be submitted.
+	///
+	/// The transaction is rebuilt with the runtime of the chain, which must be the one the
+	/// transaction was prepared for.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	pub fn signed_extrinsic(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+	) -> Result<Vec<u8>, Error> {
+		let signature = self.signature.as_deref().ok_or_else(|| {
+			Error::OfflineSigningError("the transaction has not been signed".to_string())
+		})?;
+		let signature = MultiSignature::decode(&mut &decode_hex("signature", signature)?[..])
+			.map_err(|e| Error::OfflineSigningError(format!("invalid signature: {e}")))?;
+
+		if to_hex(client.genesis_hash().as_bytes(), false) != self.genesis_hash {
+			return Err(Error::OfflineSigningError(
+				"the transaction was prepared for another chain".to_string(),
+			));
+		}
+		let version = client.runtime_version();
+		if version.spec_version != self.spec_version ||
+			version.transaction_version != self.transaction_version
+		{
+			return Err(Error::OfflineSigningError(format!(
+				"the transaction was prepared for spec version {}, but the chain runs {}",
+				self.spec_version, version.spec_version
+			)));
+		}
+		if to_hex(&self.build_signer_payload(client)?, false) != self.signer_payload {
+			return Err(Error::OfflineSigningError(
+				"the signer payload does not match the transaction parameters".to_string(),
+			));
+		}
+
+		let account: AccountId32 = self
+			.signer
+			.parse()
+			.map_err(|e| Error::OfflineSigningError(format!("invalid signer address: {e:?}")))?;
+		Ok(self
+			.partial_transaction(client)?
+			.sign_with_account_and_signature(&account, &signature)
+			.into_encoded())
+	}
+
+	fn metadata(&self) -> Result<Metadata, Error> {
+		Metadata::decode(&mut &decode_hex("metadata", &self.metadata)?[..])
+			.map_err(|e| Error::OfflineSigningError(format!("invalid metadata: {e}")))
+	}
+
+	fn partial_transaction<C: OfflineClientT<SubstrateConfig>>(
+		&self,
+		client: &C,
+	) -> Result<PartialTransaction<SubstrateConfig, C>, Error> {
+		let builder = DefaultExtrinsicParamsBuilder::<SubstrateConfig>::new()
+			.nonce(self.nonce)
+			.tip(self.tip);
+		let builder = match &self.era {
+			Era::Immortal => builder,
+			Era::Mortal { period, block_number, block_hash } => builder.mortal_from_unchecked(
+				*period,
+				*block_number,
+				decode_hash("era block hash", block_hash)?,
+			),
+		};
+		let call_data = CallData::new(decode_hex("call data", &self.call_data)?);
+		client
+			.tx()
+			.create_partial_offline(&call_data, builder.build())
+			.map_err(|e| Error::SubXtError(Box::new(e)))
+	}
+
+	fn build_signer_payload<C: OfflineClientT<SubstrateConfig>>(
+		&self,
+		client: &C,
+	) -> Result<Vec<u8>, Error> {
+		Ok(self.partial_transaction(client)?.signer_payload())
+	}
+}
+
+// Calls a runtime API at the latest block, decoding its result.
+async fn call_runtime_api<T: Decode>(
+	client: &OnlineClient<SubstrateConfig>,
+	function: &str,
+	params: Option<&[u8]>,
+) -> Result<T, Error> {
+	let result = client
+		.runtime_api()
+		.at_latest()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.call_raw(function, params)
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?;
+	T::decode(&mut &result[..])
+		.map_err(|e| Error::OfflineSigningError(format!("invalid result of {function}: {e}")))
+}
+
+// The versions of the metadata provided by the runtime.
+async fn metadata_versions(client: &OnlineClient<SubstrateConfig>) -> Result<Vec<u32>, Error> {
+	call_runtime_api(client, "Metadata_metadata_versions", None).await
+}
+
+// Fetches a version of the SCALE-encoded metadata of the runtime.
+async fn runtime_metadata(
+	client: &OnlineClient<SubstrateConfig>,
+	version: u32,
+) -> Result<Vec<u8>, Error> {
+	let metadata: Option<Vec<u8>> =
+		call_runtime_api(client, "Metadata_metadata_at_version", Some(&version.encode())).await?;
+	metadata.ok_or_else(|| {
+		Error::OfflineSigningError(format!(
+			"the runtime does not provide version {version} of its metadata"
+		))
+	})
+}
+
+// Computes the hash checked by the `CheckMetadataHash` extension from version 15 of the metadata
+// and the details of the chain and its token.
+async fn metadata_hash(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &Url,
+	metadata: &[u8],
+) -> Result<[u8; 32], Error> {
+	let RuntimeMetadataPrefixed(_, metadata) = RuntimeMetadataPrefixed::decode(&mut &metadata[..])
+		.map_err(|e| Error::OfflineSigningError(format!("invalid metadata: {e}")))?;
+	// The spec name is the first field of the runtime version.
+	let spec_name: String = call_runtime_api(client, "Core_version", None).await?;
+	let base58_prefix = client
+		.metadata()
+		.pallet_by_name("System")
+		.and_then(|pallet| pallet.constant_by_name("SS58Prefix"))
+		.map(|constant| u16::decode(&mut constant.value()))
+		.transpose()
+		.map_err(|e| Error::OfflineSigningError(format!("invalid SS58 prefix: {e}")))?
+		.ok_or_else(|| {
+			Error::OfflineSigningError("the runtime does not define its SS58 prefix".to_string())
+		})?;
+	let token = TokenMetadata::query::<SubstrateConfig>(url).await?;
+	let decimals = u8::try_from(token.token_decimals).map_err(|_| {
+		Error::OfflineSigningError(format!("invalid token decimals: {}", token.token_decimals))
+	})?;
+	let extra_info = ExtraInfo {
+		spec_version: client.runtime_version().spec_version,
+		spec_name,
+		base58_prefix,
+		decimals,
+		token_symbol: token.symbol,
+	};
+	Ok(generate_metadata_digest(&metadata, extra_info)
+		.map_err(|e| {
+			Error::OfflineSigningError(format!("failed to compute the metadata hash: {e}"))
+		})?
+		.hash())
+}
+
+fn decode_hex(field: &str, value: &str) -> Result<Vec<u8>, Error> {
+	from_hex(value).map_err(|e| Error::OfflineSigningError(format!("invalid {field}: {e}")))
+}
+
+fn decode_hash(field: &str, value: &str) -> Result<H256, Error> {
+	let hash = decode_hex(field, value)?;
+	if hash.len() != 32 {
+		return Err(Error::OfflineSigningError(format!("invalid {field}")));
+	}
+	Ok(H256::from_slice(&hash))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{raw_value_to_string, set_up_client};
+	use pop_common::{create_signer, test_env::shared_substrate_ws_url};
+	use subxt::dynamic::{self, Value};
+
+	// Prepares `System::remark(0x11)` for Alice on the test chain.
+	async fn transaction() -> anyhow::Result<OfflineTransaction> {
+		let url: Url = shared_substrate_ws_url().await.parse()?;
+		let client = set_up_client(url.as_str()).await?;
+		let xt = dynamic::tx("System", "remark", vec![Value::from_bytes([0x11])]);
+		let alice: AccountId32 = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".parse()?;
+		let options = TxOptions { nonce: Some(3), ..Default::default() };
+		Ok(OfflineTransaction::new(&client, &url, &xt, alice, &options).await?)
+	}
+
+	#[tokio::test]
+	async fn new_bundles_latest_metadata_and_its_hash() -> anyhow::Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		let versions = metadata_versions(&client).await?;
+		let transaction = transaction().await?;
+		let metadata = RuntimeMetadataPrefixed::decode(
+			&mut &decode_hex("metadata", &transaction.metadata)?[..],
+		)?;
+		let latest = versions.into_iter().filter(|version| *version <= METADATA_LATEST).max();
+		assert_eq!(Some(metadata.1.version()), latest);
+		let hash = decode_hex("metadata hash", transaction.metadata_hash.as_ref().unwrap())?;
+		assert_eq!(hash.len(), 32);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn sign_works() -> anyhow::Result<()> {
+		let signer = create_signer("//Alice")?;
+		let mut transaction = transaction().await?;
+		transaction.sign(&signer)?;
+		let signature = decode_hex("signature", transaction.signature.as_ref().unwrap())?;
+		let MultiSignature::Sr25519(signature) = MultiSignature::decode(&mut &signature[..])?
+		else {
+			panic!("expected an sr25519 signature");
+		};
+		assert!(subxt_signer::sr25519::verify(
+			&subxt_signer::sr25519::Signature(signature),
+			decode_hex("signer payload", &transaction.signer_payload)?,
+			&signer.public_key()
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn sign_rejects_other_signer() -> anyhow::Result<()> {
+		let mut transaction = transaction().await?;
+		let err = transaction.sign(&create_signer("//Bob")?).unwrap_err();
+		assert!(err.to_string().contains("must be signed by"), "{err}");
+		assert!(transaction.signature.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn sign_rejects_tampered_payload() -> anyhow::Result<()> {
+		let signer = create_signer("//Alice")?;
+		// A payload which does not belong to the call shown to the signer.
+		let mut tampered = transaction().await?;
+		tampered.signer_payload = "0x00000411".to_string();
+		let err = tampered.sign(&signer).unwrap_err();
+		assert!(err.to_string().contains("does not match"), "{err}");
+
+		// Parameters which do not match the payload.
+		let mut tampered = OfflineTransaction { nonce: 4, ..transaction().await? };
+		assert!(tampered.sign(&signer).is_err());
+		assert!(tampered.signature.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decoded_call_works() -> anyhow::Result<()> {
+		let call = transaction().await?.decoded_call()?;
+		let rendered = raw_value_to_string(&call, "")?;
+		assert!(rendered.contains("System") && rendered.contains("remark"), "{rendered}");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn save_and_load_roundtrip() -> anyhow::Result<()> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("tx.json");
+		let mut transaction = transaction().await?;
+		transaction.era =
+			Era::Mortal { period: 64, block_number: 10, block_hash: to_hex(&[3; 32], false) };
+		transaction.save(&path)?;
+		assert_eq!(OfflineTransaction::load(&path)?, transaction);
+		assert!(!fs::read_to_string(&path)?.contains("signature"));
+		Ok(())
+	}
+}
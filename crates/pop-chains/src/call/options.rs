@@ -0,0 +1,578 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{call::multisig::Weight, errors::Error};
+use frame_decode::extrinsics::ExtrinsicTypeInfo;
+use pop_common::{AccountSigner, EthereumConfig, KeyScheme, create_ethereum_signer};
+use scale::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use std::{
+	fmt::{Display, Formatter},
+	str::FromStr,
+};
+use subxt::{
+	Config, OnlineClient, SubstrateConfig,
+	config::{DefaultExtrinsicParams, DefaultExtrinsicParamsBuilder, substrate::H256},
+	ext::{scale_decode::DecodeAsType, scale_encode::EncodeAsType},
+	tx::{Payload, Signer},
+	utils::{AccountId32, MultiSignature},
+};
+
+/// The pallet whose assets can be selected by id to pay fees.
+const ASSETS_PALLET: &str = "Assets";
+/// The signed extension which pays transaction fees in an asset.
+const CHARGE_ASSET_TX_PAYMENT: &str = "ChargeAssetTxPayment";
+/// The name of the account id type of chains with Ethereum accounts.
+const ACCOUNT_ID_20: &str = "AccountId20";
+
+/// Options of a transaction, applied through its signed extensions.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TxOptions {
+	/// Tip paid to the block author, in the smallest unit of the fee token.
+	pub tip: u128,
+	/// Nonce to sign with, instead of the next nonce of the signer.
+	pub nonce: Option<u64>,
+	/// Number of blocks the transaction is valid for. The transaction is immortal if not set.
+	pub mortality: Option<u64>,
+	/// The asset to pay the fees in, instead of the native token.
+	pub fee_asset: Option<FeeAsset>,
+}
+
+impl TxOptions {
+	// Applies the nonce and mortality to the builder of the extrinsic parameters.
+	async fn apply<T: Config<Hasher = <SubstrateConfig as Config>::Hasher>>(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		mut builder: DefaultExtrinsicParamsBuilder<T>,
+	) -> Result<DefaultExtrinsicParamsBuilder<T>, Error> {
+		if let Some(nonce) = self.nonce {
+			builder = builder.nonce(nonce);
+		}
+		if let Some(period) = self.mortality {
+			let (number, hash) = checkpoint(client).await?;
+			builder = builder.mortal_from_unchecked(period, number, hash);
+		}
+		Ok(builder)
+	}
+}
+
+/// An estimate of the fee of an extrinsic, as returned by `TransactionPaymentApi_query_info`.
+///
+/// The fee is always denominated in the native token, also when it is paid in an asset.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct FeeEstimate {
+	/// The fee, excluding the tip, in the smallest unit of the native token.
+	pub partial_fee: u128,
+	/// The computation time of the extrinsic.
+	pub ref_time: u64,
+	/// The size of the proof of the extrinsic.
+	pub proof_size: u64,
+}
+
+impl Display for FeeEstimate {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"Estimated fee: {} in the native token (weight: ref_time {}, proof_size {})",
+			self.partial_fee, self.ref_time, self.proof_size
+		)
+	}
+}
+
+/// The dispatch info of an extrinsic, as returned by `TransactionPaymentApi_query_info`.
+#[derive(Decode)]
+struct RuntimeDispatchInfo {
+	weight: Weight,
+	_class: u8,
+	partial_fee: u128,
+}
+
+/// A location-based asset id, as used by Asset Hub to pay fees in assets. Only the junctions used
+/// to identify assets are supported.
+#[derive(
+	Clone, Debug, Decode, DecodeAsType, Deserialize, Encode, EncodeAsType, PartialEq, Eq, Serialize,
+)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub struct Location {
+	/// The number of parent consensus systems.
+	pub parents: u8,
+	/// The path from the parent to the asset.
+	pub interior: Junctions,
+}
+
+/// The interior of a [`Location`].
+#[allow(missing_docs)]
+#[derive(
+	Clone, Debug, Decode, DecodeAsType, Deserialize, Encode, EncodeAsType, PartialEq, Eq, Serialize,
+)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub enum Junctions {
+	Here,
+	X1([Junction; 1]),
+	X2([Junction; 2]),
+	X3([Junction; 3]),
+	X4([Junction; 4]),
+}
+
+/// A junction of a [`Location`].
+#[allow(missing_docs)]
+#[derive(
+	Clone, Debug, Decode, DecodeAsType, Deserialize, Encode, EncodeAsType, PartialEq, Eq, Serialize,
+)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub enum Junction {
+	#[codec(index = 0)]
+	Parachain(#[codec(compact)] u32),
+	#[codec(index = 1)]
+	AccountId32 { network: Option<NetworkId>, id: [u8; 32] },
+	#[codec(index = 3)]
+	AccountKey20 { network: Option<NetworkId>, key: [u8; 20] },
+	#[codec(index = 4)]
+	PalletInstance(u8),
+	#[codec(index = 5)]
+	GeneralIndex(#[codec(compact)] u128),
+	#[codec(index = 6)]
+	GeneralKey { length: u8, data: [u8; 32] },
+	#[codec(index = 9)]
+	GlobalConsensus(NetworkId),
+}
+
+/// A global consensus system, e.g. of a bridged asset.
+#[allow(missing_docs)]
+#[derive(
+	Clone, Debug, Decode, DecodeAsType, Deserialize, Encode, EncodeAsType, PartialEq, Eq, Serialize,
+)]
+#[decode_as_type(crate_path = "subxt::ext::scale_decode")]
+#[encode_as_type(crate_path = "subxt::ext::scale_encode")]
+pub enum NetworkId {
+	#[codec(index = 0)]
+	ByGenesis([u8; 32]),
+	#[codec(index = 2)]
+	Polkadot,
+	#[codec(index = 3)]
+	Kusama,
+	#[codec(index = 7)]
+	Ethereum {
+		#[codec(compact)]
+		chain_id: u64,
+	},
+}
+
+impl Location {
+	/// The location of an asset of the pallet at `pallet_index`, e.g. 1984 for USDT in the
+	/// `Assets` pallet of Asset Hub.
+	///
+	/// # Arguments
+	/// * `pallet_index` - The index of the pallet holding the asset.
+	/// * `id` - The id of the asset.
+	pub fn asset(pallet_index: u8, id: u128) -> Self {
+		Self {
+			parents: 0,
+			interior: Junctions::X2([
+				Junction::PalletInstance(pallet_index),
+				Junction::GeneralIndex(id),
+			]),
+		}
+	}
+}
+
+impl Display for Location {
+	/// Formats the location as JSON, as accepted by [`FeeAsset::from_str`].
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}", serde_json::to_string(self).map_err(|_| std::fmt::Error)?)
+	}
+}
+
+/// The asset to pay transaction fees in. Serialized as the id or the location of the asset.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(untagged)]
+pub enum FeeAsset {
+	/// The id of an asset of the `Assets` pallet, located with the index of the pallet in the
+	/// metadata of the chain.
+	Id(u128),
+	/// The location of an asset.
+	Location(Box<Location>),
+}
+
+impl FeeAsset {
+	/// Resolves the location of the asset on a chain.
+	///
+	/// # Arguments
+	/// * `metadata` - The metadata of the chain.
+	pub fn location(&self, metadata: &subxt::Metadata) -> Result<Location, Error> {
+		match self {
+			Self::Id(id) => {
+				let pallet = metadata.pallet_by_name(ASSETS_PALLET).ok_or_else(|| {
+					Error::TransactionOptionsError(format!(
+						"the chain has no {ASSETS_PALLET} pallet to locate asset {id}, use a JSON \
+						 location instead"
+					))
+				})?;
+				Ok(Location::asset(pallet.index(), *id))
+			},
+			Self::Location(location) => Ok(location.as_ref().clone()),
+		}
+	}
+}
+
+impl Display for FeeAsset {
+	/// Formats the asset as accepted by [`FeeAsset::from_str`].
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Id(id) => write!(f, "{id}"),
+			Self::Location(location) => write!(f, "{location}"),
+		}
+	}
+}
+
+impl FromStr for FeeAsset {
+	type Err = Error;
+
+	/// Parses the id of an asset of the `Assets` pallet, e.g. `1984`, or a JSON location, e.g.
+	/// `{"parents":1,"interior":{"X1":[{"Parachain":2011}]}}`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().parse::<u128>() {
+			Ok(id) => Ok(Self::Id(id)),
+			Err(_) => serde_json::from_str(s)
+				.map(|location| Self::Location(Box::new(location)))
+				.map_err(|e| {
+					Error::TransactionOptionsError(format!("invalid fee asset location: {e}"))
+				}),
+		}
+	}
+}
+
+/// A configuration of the chain which pays transaction fees with a location-based asset id, as
+/// Asset Hub does.
+enum AssetFeeConfig {}
+
+impl Config for AssetFeeConfig {
+	type AccountId = <SubstrateConfig as Config>::AccountId;
+	type Address = <SubstrateConfig as Config>::Address;
+	type Signature = <SubstrateConfig as Config>::Signature;
+	type Hasher = <SubstrateConfig as Config>::Hasher;
+	type Header = <SubstrateConfig as Config>::Header;
+	type ExtrinsicParams = DefaultExtrinsicParams<Self>;
+	type AssetId = Location;
+}
+
+/// Signs for [`AssetFeeConfig`] with a signer of [`SubstrateConfig`], which uses the same
+/// account and signature types.
+struct AssetFeeSigner<'a, S>(&'a S);
+
+impl<S: Signer<SubstrateConfig>> Signer<AssetFeeConfig> for AssetFeeSigner<'_, S> {
+	fn account_id(&self) -> AccountId32 {
+		self.0.account_id()
+	}
+
+	fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+		self.0.sign(signer_payload)
+	}
+}
+
+/// Signs an extrinsic with the provided options, without submitting it.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node, used to connect with the configuration paying fees in an asset.
+/// * `xt` - The extrinsic to sign.
+/// * `signer` - The signer of the extrinsic.
+/// * `options` - The options of the transaction.
+pub async fn sign_extrinsic_with_options<Xt: Payload, S: Signer<SubstrateConfig>>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	xt: &Xt,
+	signer: &S,
+	options: &TxOptions,
+) -> Result<Vec<u8>, Error> {
+	let Some(asset) = &options.fee_asset else {
+		let builder = DefaultExtrinsicParamsBuilder::<SubstrateConfig>::new().tip(options.tip);
+		let params = options.apply(client, builder).await?.build();
+		let tx = client
+			.tx()
+			.create_signed(xt, signer, params)
+			.await
+			.map_err(|e| Error::SubXtError(Box::new(e)))?;
+		return Ok(tx.into_encoded());
+	};
+
+	// Without the extension, the asset would be silently ignored and the fees paid in the native
+	// token.
+	if !client
+		.metadata()
+		.extrinsic()
+		.transaction_extensions_by_version(0)
+		.into_iter()
+		.flatten()
+		.any(|extension| extension.identifier() == CHARGE_ASSET_TX_PAYMENT)
+	{
+		return Err(Error::TransactionOptionsError(
+			"the chain does not support paying fees in assets".to_string(),
+		));
+	}
+	let asset = asset.location(&client.metadata())?;
+	let asset_client = OnlineClient::<AssetFeeConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let builder = DefaultExtrinsicParamsBuilder::<AssetFeeConfig>::new().tip_of(options.tip, asset);
+	let params = options.apply(client, builder).await?.build();
+	let tx = asset_client
+		.tx()
+		.create_signed(xt, &AssetFeeSigner(signer), params)
+		.await
+		.map_err(|e| {
+			Error::TransactionOptionsError(format!("failed to sign the extrinsic: {e}"))
+		})?;
+	Ok(tx.into_encoded())
+}
+
+/// Signs an extrinsic with a secret key URI of any scheme and the provided options, without
+/// submitting it. Ethereum keys sign extrinsics of chains with Ethereum accounts.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node, used to connect with the configuration of the key.
+/// * `xt` - The extrinsic to sign.
+/// * `suri` - The secret key URI of the signer.
+/// * `scheme` - The signature scheme of the key.
+/// * `options` - The options of the transaction.
+pub async fn sign_extrinsic_with_suri<Xt: Payload>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	xt: &Xt,
+	suri: &str,
+	scheme: KeyScheme,
+	options: &TxOptions,
+) -> Result<Vec<u8>, Error> {
+	match scheme {
+		KeyScheme::Ethereum => {
+			let signer = create_ethereum_signer(suri)?;
+			sign_extrinsic_with_ethereum_key(client, url, xt, &signer, options).await
+		},
+		scheme => {
+			let signer = AccountSigner::from_uri(suri, scheme)?;
+			sign_extrinsic_with_options(client, url, xt, &signer, options).await
+		},
+	}
+}
+
+/// Signs an extrinsic of a chain with Ethereum accounts with the provided options, without
+/// submitting it.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node, used to connect with the configuration of Ethereum accounts.
+/// * `xt` - The extrinsic to sign.
+/// * `signer` - The Ethereum key signing the extrinsic.
+/// * `options` - The options of the transaction.
+pub async fn sign_extrinsic_with_ethereum_key<Xt: Payload>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	xt: &Xt,
+	signer: &subxt_signer::eth::Keypair,
+	options: &TxOptions,
+) -> Result<Vec<u8>, Error> {
+	// Signing with the wrong account type would produce an extrinsic the chain cannot decode.
+	let metadata = client.metadata();
+	let address_ty = metadata
+		.get_signature_info()
+		.map_err(|e| Error::TransactionOptionsError(e.to_string()))?
+		.address_id;
+	if metadata
+		.types()
+		.resolve(address_ty)
+		.and_then(|ty| ty.path.ident())
+		.is_none_or(|ident| ident != ACCOUNT_ID_20)
+	{
+		return Err(Error::TransactionOptionsError(
+			"Ethereum keys can only sign extrinsics of chains with Ethereum accounts".to_string(),
+		));
+	}
+	if options.fee_asset.is_some() {
+		return Err(Error::TransactionOptionsError(
+			"paying fees in an asset is not supported with Ethereum keys".to_string(),
+		));
+	}
+	let ethereum_client = OnlineClient::<EthereumConfig>::from_url(url.as_str())
+		.await
+		.map_err(|e| Error::ConnectionFailure(e.to_string()))?;
+	let builder = DefaultExtrinsicParamsBuilder::<EthereumConfig>::new().tip(options.tip);
+	let params = options.apply(client, builder).await?.build();
+	let tx = ethereum_client
+		.tx()
+		.create_signed(xt, signer, params)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e)))?;
+	Ok(tx.into_encoded())
+}
+
+/// Estimates the fee and weight of a signed extrinsic.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `extrinsic` - The encoded signed extrinsic.
+pub async fn estimate_fee(
+	client: &OnlineClient<SubstrateConfig>,
+	extrinsic: &[u8],
+) -> Result<FeeEstimate, Error> {
+	let mut params = extrinsic.to_vec();
+	(extrinsic.len() as u32).encode_to(&mut params);
+	let info = client
+		.runtime_api()
+		.at_latest()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.call_raw("TransactionPaymentApi_query_info", Some(&params))
+		.await
+		.map_err(|e| Error::FeeEstimationError(e.to_string()))?;
+	let info = RuntimeDispatchInfo::decode(&mut &info[..])
+		.map_err(|e| Error::FeeEstimationError(e.to_string()))?;
+	Ok(FeeEstimate {
+		partial_fee: info.partial_fee,
+		ref_time: info.weight.ref_time,
+		proof_size: info.weight.proof_size,
+	})
+}
+
+/// The block the mortality of a transaction starts from: the latest block.
+pub(crate) async fn checkpoint(
+	client: &OnlineClient<SubstrateConfig>,
+) -> Result<(u64, H256), Error> {
+	let block = client.blocks().at_latest().await.map_err(|e| Error::SubXtError(Box::new(e)))?;
+	Ok((block.number().into(), block.hash()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::{create_signer, test_env::shared_substrate_ws_url};
+	use subxt::tx::SubmittableTransaction;
+
+	#[test]
+	fn fee_asset_from_str_works() -> Result<()> {
+		assert_eq!("1984".parse::<FeeAsset>()?, FeeAsset::Id(1984));
+		assert_eq!(
+			r#"{"parents":1,"interior":{"X1":[{"Parachain":2011}]}}"#.parse::<FeeAsset>()?,
+			FeeAsset::Location(Box::new(Location {
+				parents: 1,
+				interior: Junctions::X1([Junction::Parachain(2011)])
+			}))
+		);
+		assert!(matches!(
+			"usdt".parse::<FeeAsset>(),
+			Err(Error::TransactionOptionsError(e)) if e.starts_with("invalid fee asset location")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn fee_asset_display_works() -> Result<()> {
+		assert_eq!(FeeAsset::Id(1984).to_string(), "1984");
+		let location = r#"{"parents":1,"interior":{"X1":[{"Parachain":2011}]}}"#;
+		assert_eq!(location.parse::<FeeAsset>()?.to_string(), location);
+		Ok(())
+	}
+
+	#[test]
+	fn location_encoding_works() {
+		// `{ parents: 0, interior: X2(PalletInstance(50), GeneralIndex(1984)) }`.
+		assert_eq!(Location::asset(50, 1984).encode(), vec![0, 2, 4, 50, 5, 0x01, 0x1f]);
+	}
+
+	#[tokio::test]
+	async fn sign_extrinsic_with_options_and_estimate_fee_works() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let url = url::Url::parse(&node_url)?;
+		let client = set_up_client(&node_url).await?;
+		let signer = create_signer("//Alice")?;
+		let xt =
+			subxt::dynamic::tx("System", "remark", vec![subxt::dynamic::Value::from_bytes(b"pop")]);
+		let options =
+			TxOptions { tip: 1, nonce: Some(1_000), mortality: Some(64), fee_asset: None };
+		let extrinsic = sign_extrinsic_with_options(&client, &url, &xt, &signer, &options).await?;
+
+		let fee = estimate_fee(&client, &extrinsic).await?;
+		assert!(fee.partial_fee > 0);
+		assert!(fee.ref_time > 0);
+		// The signed extensions are accepted, with the future nonce queued until it is reached.
+		let validation = SubmittableTransaction::from_bytes(client, extrinsic).validate().await?;
+		assert!(validation.is_valid());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn fee_asset_location_uses_assets_pallet_index() -> Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		let metadata = client.metadata();
+		let location =
+			FeeAsset::Location(Box::new(Location { parents: 1, interior: Junctions::Here }));
+		assert_eq!(
+			location.location(&metadata)?,
+			Location { parents: 1, interior: Junctions::Here }
+		);
+		match metadata.pallet_by_name(ASSETS_PALLET) {
+			Some(pallet) => assert_eq!(
+				FeeAsset::Id(1984).location(&metadata)?,
+				Location::asset(pallet.index(), 1984)
+			),
+			None => assert!(matches!(
+				FeeAsset::Id(1984).location(&metadata),
+				Err(Error::TransactionOptionsError(e)) if e.contains("no Assets pallet")
+			)),
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn paying_fees_in_assets_requires_extension() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let url = url::Url::parse(&node_url)?;
+		let client = set_up_client(&node_url).await?;
+		let has_extension = client
+			.metadata()
+			.extrinsic()
+			.transaction_extensions_by_version(0)
+			.into_iter()
+			.flatten()
+			.any(|extension| extension.identifier() == CHARGE_ASSET_TX_PAYMENT);
+		if has_extension {
+			return Ok(());
+		}
+		let xt =
+			subxt::dynamic::tx("System", "remark", vec![subxt::dynamic::Value::from_bytes(b"pop")]);
+		let options = TxOptions { fee_asset: Some(FeeAsset::Id(1984)), ..Default::default() };
+		assert!(matches!(
+			sign_extrinsic_with_options(&client, &url, &xt, &create_signer("//Alice")?, &options)
+				.await,
+			Err(Error::TransactionOptionsError(_))
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn sign_extrinsic_with_suri_works() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let url = url::Url::parse(&node_url)?;
+		let client = set_up_client(&node_url).await?;
+		let xt =
+			subxt::dynamic::tx("System", "remark", vec![subxt::dynamic::Value::from_bytes(b"pop")]);
+		let options = TxOptions::default();
+		let extrinsic =
+			sign_extrinsic_with_suri(&client, &url, &xt, "//Bob", KeyScheme::Ed25519, &options)
+				.await?;
+		assert!(estimate_fee(&client, &extrinsic).await.is_ok());
+
+		// The test chain has 32-byte accounts.
+		let private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+		assert!(matches!(
+			sign_extrinsic_with_suri(&client, &url, &xt, private_key, KeyScheme::Ethereum, &options)
+				.await,
+			Err(Error::TransactionOptionsError(e)) if e.contains("Ethereum accounts")
+		));
+		Ok(())
+	}
+}
@@ -0,0 +1,465 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	Pallet, construct_extrinsic, construct_proxy_extrinsic, construct_sudo_extrinsic,
+	dispatch_error, errors::Error, find_callable_by_name,
+};
+use pop_common::KeyScheme;
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	blocks::ExtrinsicEvents,
+	dynamic::Value,
+	tx::{DynamicPayload, Signer},
+};
+
+/// A script of chain calls, executed in order.
+///
+/// Scripts are written in YAML or JSON, e.g.
+/// ```yaml
+/// signer: //Alice
+/// scheme: sr25519
+/// batch: true
+/// steps:
+///   - pallet: Assets
+///     function: create
+///     args: ["1", "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", "1"]
+///     expect_events: [Assets.Created]
+///   - pallet: Assets
+///     function: force_create
+///     args: ["2", "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", "true", "1"]
+///     sudo: true
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallScript {
+	/// Secret key URI of the signer of steps which do not specify one.
+	#[serde(default)]
+	pub signer: Option<String>,
+	/// Signature scheme of the signer of steps which do not specify one.
+	#[serde(default)]
+	pub scheme: Option<KeyScheme>,
+	/// Whether consecutive steps of the same signer are combined into a single
+	/// `Utility::batch_all` extrinsic.
+	#[serde(default)]
+	pub batch: bool,
+	/// The calls to execute.
+	pub steps: Vec<CallStep>,
+}
+
+/// A call within a [CallScript].
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct CallStep {
+	/// An optional name of the step, used when reporting results.
+	#[serde(default)]
+	pub name: Option<String>,
+	/// The pallet containing the dispatchable function.
+	pub pallet: String,
+	/// The dispatchable function to execute.
+	pub function: String,
+	/// The dispatchable function arguments, encoded as strings.
+	#[serde(default, deserialize_with = "deserialize_args")]
+	pub args: Vec<String>,
+	/// Secret key URI of the signer of the step.
+	#[serde(default)]
+	pub signer: Option<String>,
+	/// Signature scheme of the signer of the step.
+	#[serde(default)]
+	pub scheme: Option<KeyScheme>,
+	/// Whether to dispatch the call with `Root` origin.
+	#[serde(default)]
+	pub sudo: bool,
+	/// The account on whose behalf the call is dispatched using a proxy.
+	#[serde(default)]
+	pub proxy: Option<String>,
+	/// Events which must be emitted by the call of the step, as `Pallet.Event`.
+	#[serde(default)]
+	pub expect_events: Vec<String>,
+}
+
+impl CallStep {
+	/// A label identifying the step.
+	pub fn label(&self) -> String {
+		self.name
+			.clone()
+			.unwrap_or_else(|| format!("{}.{}", self.pallet, self.function))
+	}
+
+	/// Constructs the extrinsic of the step, wrapped in a sudo or proxy call if required.
+	///
+	/// # Arguments
+	/// * `pallets` - List of pallets available within the chain's runtime.
+	pub fn construct_extrinsic(&self, pallets: &[Pallet]) -> Result<DynamicPayload, Error> {
+		let callable = find_callable_by_name(pallets, &self.pallet, &self.function)?;
+		let function = callable.as_function().ok_or(Error::CallableNotSupported)?;
+		let xt = construct_extrinsic(function, self.args.clone())?;
+		let xt = if self.sudo { construct_sudo_extrinsic(xt) } else { xt };
+		match &self.proxy {
+			Some(real) => construct_proxy_extrinsic(pallets, real.clone(), xt),
+			None => Ok(xt),
+		}
+	}
+}
+
+/// An extrinsic executing one or more steps of a [CallScript].
+pub struct ScriptExtrinsic {
+	/// The indices of the steps executed by the extrinsic.
+	pub steps: Vec<usize>,
+	/// Secret key URI of the signer, if specified by the script.
+	pub signer: Option<String>,
+	/// Signature scheme of the signer, if specified by the script.
+	pub scheme: Option<KeyScheme>,
+	/// The extrinsic.
+	pub xt: DynamicPayload,
+}
+
+impl CallScript {
+	/// Reads a script from a file. Files with a `.json` extension are parsed as JSON, any other
+	/// file as YAML.
+	///
+	/// # Arguments
+	/// * `path` - The path of the script.
+	pub fn load(path: &Path) -> Result<Self, Error> {
+		let contents = fs::read_to_string(path)?;
+		let script: Self = if path.extension().is_some_and(|ext| ext == "json") {
+			serde_json::from_str(&contents)?
+		} else {
+			serde_norway::from_str(&contents)
+				.map_err(|e| Error::ScriptError(format!("{}: {e}", path.display())))?
+		};
+		if script.steps.is_empty() {
+			return Err(Error::ScriptError(format!("{} has no steps", path.display())));
+		}
+		Ok(script)
+	}
+
+	/// Constructs the extrinsics executing the steps, in order. When batching, consecutive steps
+	/// of the same signer and scheme are combined into a `Utility::batch_all` extrinsic.
+	///
+	/// # Arguments
+	/// * `pallets` - List of pallets available within the chain's runtime.
+	pub fn extrinsics(&self, pallets: &[Pallet]) -> Result<Vec<ScriptExtrinsic>, Error> {
+		type StepSigner = (Option<String>, Option<KeyScheme>);
+		let mut groups: Vec<(StepSigner, Vec<usize>)> = Vec::new();
+		for (index, step) in self.steps.iter().enumerate() {
+			let signer =
+				(step.signer.clone().or_else(|| self.signer.clone()), step.scheme.or(self.scheme));
+			match groups.last_mut() {
+				Some((last, steps)) if self.batch && *last == signer => steps.push(index),
+				_ => groups.push((signer, vec![index])),
+			}
+		}
+
+		groups
+			.into_iter()
+			.map(|((signer, scheme), steps)| {
+				let mut calls = steps
+					.iter()
+					.map(|&index| {
+						self.steps[index].construct_extrinsic(pallets).map_err(|e| {
+							Error::ScriptError(format!("{}: {e}", self.steps[index].label()))
+						})
+					})
+					.collect::<Result<Vec<_>, _>>()?;
+				let xt = if calls.len() == 1 {
+					calls.remove(0)
+				} else {
+					find_callable_by_name(pallets, "Utility", "batch_all")?;
+					construct_batch_extrinsic(calls)
+				};
+				Ok(ScriptExtrinsic { steps, signer, scheme, xt })
+			})
+			.collect()
+	}
+}
+
+/// Constructs a `Utility::batch_all` extrinsic, dispatching all calls or none of them.
+///
+/// # Arguments
+/// * `calls` - The calls to dispatch.
+pub fn construct_batch_extrinsic(calls: Vec<DynamicPayload>) -> DynamicPayload {
+	subxt::dynamic::tx(
+		"Utility",
+		"batch_all",
+		vec![Value::unnamed_composite(calls.into_iter().map(DynamicPayload::into_value))],
+	)
+}
+
+/// Signs and submits an extrinsic, returning its events once it has been finalized
+/// successfully. The outcome of calls dispatched on behalf of another origin is not checked, see
+/// [`step_dispatch_errors`].
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `xt` - The extrinsic to be signed and submitted.
+/// * `signer` - The signer of the extrinsic.
+pub async fn submit_extrinsic<S: Signer<SubstrateConfig>>(
+	client: &OnlineClient<SubstrateConfig>,
+	xt: &DynamicPayload,
+	signer: &S,
+) -> Result<ExtrinsicEvents<SubstrateConfig>, Error> {
+	client
+		.tx()
+		.sign_and_submit_then_watch_default(xt, signer)
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{:?}", e)))?
+		.wait_for_finalized_success()
+		.await
+		.map_err(|e| Error::ExtrinsicSubmissionError(format!("{:?}", e)))
+}
+
+/// The names of the events emitted by an extrinsic, as `Pallet.Event`.
+///
+/// # Arguments
+/// * `events` - The events of the extrinsic.
+pub fn event_names(events: &ExtrinsicEvents<SubstrateConfig>) -> Result<Vec<String>, Error> {
+	events
+		.iter()
+		.map(|event| {
+			let event = event.map_err(|e| Error::SubXtError(e.into()))?;
+			Ok(format!("{}.{}", event.pallet_name(), event.variant_name()))
+		})
+		.collect()
+}
+
+/// The events emitted by each of the steps executed by an extrinsic, as `Pallet.Event`. The calls
+/// of a `Utility::batch_all` extrinsic are delimited by `Utility.ItemCompleted` events: events
+/// emitted before the calls, such as the withdrawal of fees, are attributed to the first step and
+/// events emitted after them to the last step.
+///
+/// # Arguments
+/// * `events` - The events emitted by the extrinsic, as `Pallet.Event`.
+/// * `steps` - The number of steps executed by the extrinsic.
+pub fn step_events(events: &[String], steps: usize) -> Vec<Vec<String>> {
+	let mut step_events = vec![Vec::new(); steps];
+	let mut step = 0;
+	for event in events {
+		if event == "Utility.ItemCompleted" {
+			step += 1;
+		} else if let Some(events) = step_events.get_mut(step.min(steps.saturating_sub(1))) {
+			events.push(event.clone());
+		}
+	}
+	step_events
+}
+
+/// The failure of each of the steps executed by an extrinsic, for calls dispatched through
+/// `Sudo` or `Proxy` which failed although the extrinsic succeeded. A `Utility::batch_all`
+/// extrinsic is not reverted in that case. Steps are delimited as by [`step_events`].
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `events` - The events of the extrinsic.
+/// * `steps` - The number of steps executed by the extrinsic.
+pub fn step_dispatch_errors(
+	client: &OnlineClient<SubstrateConfig>,
+	events: &ExtrinsicEvents<SubstrateConfig>,
+	steps: usize,
+) -> Result<Vec<Option<String>>, Error> {
+	let mut errors = vec![None; steps];
+	let mut step = 0;
+	for event in events.iter() {
+		let event = event.map_err(|e| Error::SubXtError(e.into()))?;
+		if (event.pallet_name(), event.variant_name()) == ("Utility", "ItemCompleted") {
+			step += 1;
+		} else if let Some(error) = dispatch_error(client, &event)? &&
+			let Some(step_error) = errors.get_mut(step.min(steps.saturating_sub(1)))
+		{
+			// The first failure of a step is the one which matters.
+			step_error.get_or_insert(error);
+		}
+	}
+	Ok(errors)
+}
+
+/// The expected events which were not emitted.
+///
+/// # Arguments
+/// * `expected` - The expected events, as `Pallet.Event`.
+/// * `emitted` - The emitted events, as `Pallet.Event`.
+pub fn missing_events(expected: &[String], emitted: &[String]) -> Vec<String> {
+	expected
+		.iter()
+		.filter(|event| !emitted.iter().any(|e| e == event.trim()))
+		.cloned()
+		.collect()
+}
+
+// Accepts scalar arguments, so numbers and booleans do not need to be quoted in YAML.
+fn deserialize_args<'de, D: serde::Deserializer<'de>>(
+	deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+	#[derive(Deserialize)]
+	#[serde(untagged)]
+	enum Arg {
+		String(String),
+		Bool(bool),
+		Number(serde_json::Number),
+	}
+
+	Ok(Vec::<Arg>::deserialize(deserializer)?
+		.into_iter()
+		.map(|arg| match arg {
+			Arg::String(value) => value,
+			Arg::Bool(value) => value.to_string(),
+			Arg::Number(value) => value.to_string(),
+		})
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{parse_chain_metadata, set_up_client};
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use tempfile::tempdir;
+
+	const SCRIPT: &str = r#"
+signer: //Alice
+batch: true
+steps:
+  - pallet: Assets
+    function: create
+    args: [1, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1]
+    expect_events: [Assets.Created]
+  - name: force create
+    pallet: Assets
+    function: force_create
+    args: [2, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", true, 1]
+    sudo: true
+  - pallet: System
+    function: remark
+    args: ["0x11"]
+    signer: //Bob
+    scheme: ed25519
+"#;
+
+	#[test]
+	fn load_yaml_works() -> Result<()> {
+		let dir = tempdir()?;
+		let path = dir.path().join("calls.yaml");
+		fs::write(&path, SCRIPT)?;
+		let script = CallScript::load(&path)?;
+		assert_eq!(script.signer.as_deref(), Some("//Alice"));
+		assert!(script.batch);
+		assert_eq!(script.steps.len(), 3);
+		assert_eq!(
+			script.steps[0],
+			CallStep {
+				pallet: "Assets".into(),
+				function: "create".into(),
+				args: vec![
+					"1".into(),
+					"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into(),
+					"1".into()
+				],
+				expect_events: vec!["Assets.Created".into()],
+				..Default::default()
+			}
+		);
+		assert_eq!(script.steps[1].args[2], "true");
+		assert!(script.steps[1].sudo);
+		assert_eq!(script.steps[1].label(), "force create");
+		assert_eq!(script.steps[2].label(), "System.remark");
+		assert_eq!(script.steps[2].signer.as_deref(), Some("//Bob"));
+		assert_eq!(script.steps[2].scheme, Some(KeyScheme::Ed25519));
+		assert_eq!(script.scheme, None);
+		Ok(())
+	}
+
+	#[test]
+	fn load_json_works() -> Result<()> {
+		let dir = tempdir()?;
+		let path = dir.path().join("calls.json");
+		fs::write(
+			&path,
+			r#"{"steps": [{"pallet": "System", "function": "remark", "args": ["0x11"]}]}"#,
+		)?;
+		let script = CallScript::load(&path)?;
+		assert!(!script.batch);
+		assert_eq!(script.steps[0].args, vec!["0x11".to_string()]);
+		Ok(())
+	}
+
+	#[test]
+	fn load_fails_on_invalid_scripts() -> Result<()> {
+		let dir = tempdir()?;
+		let path = dir.path().join("calls.yaml");
+		fs::write(&path, "steps: []")?;
+		assert!(
+			matches!(CallScript::load(&path), Err(Error::ScriptError(e)) if e.contains("no steps"))
+		);
+		fs::write(&path, "steps:\n  - pallet: System\n    function: remark\n    unknown: 1")?;
+		assert!(matches!(CallScript::load(&path), Err(Error::ScriptError(_))));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn extrinsics_groups_steps_by_signer() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let client = set_up_client(&node_url).await?;
+		let pallets = parse_chain_metadata(&client)?;
+		let mut script: CallScript = serde_norway::from_str(SCRIPT)?;
+
+		let extrinsics = script.extrinsics(&pallets)?;
+		assert_eq!(extrinsics.len(), 2);
+		assert_eq!(extrinsics[0].steps, vec![0, 1]);
+		assert_eq!(extrinsics[0].signer.as_deref(), Some("//Alice"));
+		assert_eq!(extrinsics[0].scheme, None);
+		assert_eq!(extrinsics[0].xt.call_name(), "batch_all");
+		assert_eq!(extrinsics[1].steps, vec![2]);
+		assert_eq!(extrinsics[1].signer.as_deref(), Some("//Bob"));
+		assert_eq!(extrinsics[1].scheme, Some(KeyScheme::Ed25519));
+		assert_eq!(extrinsics[1].xt.call_name(), "remark");
+
+		script.batch = false;
+		assert_eq!(script.extrinsics(&pallets)?.len(), 3);
+
+		script.steps[0].function = "unknown".into();
+		assert!(matches!(
+			script.extrinsics(&pallets),
+			Err(Error::ScriptError(e)) if e.starts_with("Assets.unknown")
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn missing_events_works() {
+		let emitted = vec!["Assets.Created".to_string(), "System.ExtrinsicSuccess".to_string()];
+		assert!(missing_events(&["Assets.Created".to_string()], &emitted).is_empty());
+		assert_eq!(
+			missing_events(&["Assets.Created".to_string(), "Assets.Issued".to_string()], &emitted),
+			vec!["Assets.Issued".to_string()]
+		);
+	}
+
+	#[test]
+	fn step_events_works() {
+		let events = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+		let emitted = events(&[
+			"Balances.Withdraw",
+			"Assets.Created",
+			"Utility.ItemCompleted",
+			"Assets.ForceCreated",
+			"Utility.ItemCompleted",
+			"Utility.BatchCompleted",
+			"System.ExtrinsicSuccess",
+		]);
+		assert_eq!(
+			step_events(&emitted, 2),
+			vec![
+				events(&["Balances.Withdraw", "Assets.Created"]),
+				events(&[
+					"Assets.ForceCreated",
+					"Utility.BatchCompleted",
+					"System.ExtrinsicSuccess"
+				]),
+			]
+		);
+		let emitted = events(&["Assets.Created", "System.ExtrinsicSuccess"]);
+		assert_eq!(step_events(&emitted, 1), vec![emitted.clone()]);
+	}
+}
@@ -0,0 +1,346 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	DecodedValue, Storage, StorageKeyPart, decode::decode_storage_entry, errors::Error,
+	raw_value_to_string,
+};
+use std::{
+	fmt::{Display, Formatter},
+	io::Write,
+	path::Path,
+};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	dynamic::Value,
+	ext::futures::{StreamExt, TryStreamExt, stream},
+	utils::{H256, to_hex},
+};
+
+/// The number of keys fetched per request when iterating without a limit.
+const PAGE_SIZE: u32 = 1_000;
+/// The maximum number of storage values fetched concurrently.
+const MAX_CONCURRENT_FETCHES: usize = 16;
+
+/// Which entries of a storage item to iterate.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StoragePage {
+	/// Only return the entries after this storage key, e.g. the last key of the previous page.
+	pub start_key: Option<Vec<u8>>,
+	/// The maximum number of entries to return.
+	pub limit: Option<u32>,
+	/// The number of the block to query the storage at. Defaults to the latest block.
+	pub at: Option<u32>,
+}
+
+/// An entry of a storage item, with its decoded keys and value.
+#[derive(Debug)]
+pub struct StorageEntry {
+	/// The storage key.
+	pub key: Vec<u8>,
+	/// The keys of the storage map, decoded from the storage key.
+	pub keys: Vec<StorageKeyPart>,
+	/// The decoded value.
+	pub value: Option<DecodedValue>,
+}
+
+/// The entries of a storage item returned by [`iter_storage`].
+#[derive(Debug)]
+pub struct StorageEntries {
+	/// The hash of the block the storage was queried at.
+	pub block_hash: H256,
+	/// The entries.
+	pub entries: Vec<StorageEntry>,
+	/// The storage key to continue from when the limit was reached.
+	pub next_start_key: Option<Vec<u8>>,
+}
+
+/// The format of exported storage entries.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExportFormat {
+	/// Comma-separated values, with a row per entry.
+	Csv,
+	/// A JSON object per line.
+	#[default]
+	JsonLines,
+}
+
+impl ExportFormat {
+	/// The format of a file, based on its extension: CSV for `.csv` files, otherwise JSON lines.
+	///
+	/// # Arguments
+	/// * `path` - The path of the file.
+	pub fn from_path(path: &Path) -> Self {
+		match path.extension().and_then(|e| e.to_str()) {
+			Some(extension) if extension.eq_ignore_ascii_case("csv") => Self::Csv,
+			_ => Self::JsonLines,
+		}
+	}
+}
+
+impl Display for ExportFormat {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Csv => write!(f, "CSV"),
+			Self::JsonLines => write!(f, "JSON lines"),
+		}
+	}
+}
+
+/// Iterates the entries of a storage item, optionally under the first keys of a map.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the chain.
+/// * `url` - Endpoint of the node, used to page through the storage keys.
+/// * `storage` - The storage item.
+/// * `keys` - The first keys of the storage map to iterate the entries under, if any.
+/// * `page` - Which entries to return.
+pub async fn iter_storage(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	storage: &Storage,
+	keys: Vec<Value>,
+	page: &StoragePage,
+) -> Result<StorageEntries, Error> {
+	let rpc = LegacyRpcMethods::<SubstrateConfig>::new(
+		RpcClient::from_url(url.as_str())
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+	);
+	let block_hash = match page.at {
+		Some(number) => rpc
+			.chain_get_block_hash(Some((number as u64).into()))
+			.await
+			.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+			.ok_or_else(|| Error::ParamParsingError(format!("block #{number} not found")))?,
+		None => client
+			.blocks()
+			.at_latest()
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.hash(),
+	};
+	let prefix = client
+		.storage()
+		.address_bytes(&subxt::dynamic::storage(&storage.pallet, &storage.name, keys))
+		.map_err(|e| Error::ParamParsingError(format!("invalid storage keys: {e}")))?;
+
+	// Page through the keys under the prefix, in order, until the limit is reached.
+	let mut storage_keys = Vec::new();
+	let mut start_key = page.start_key.clone();
+	loop {
+		let count = match page.limit {
+			Some(limit) => (limit - storage_keys.len() as u32).min(PAGE_SIZE),
+			None => PAGE_SIZE,
+		};
+		if count == 0 {
+			break;
+		}
+		let keys = rpc
+			.state_get_keys_paged(&prefix, count, start_key.as_deref(), Some(block_hash))
+			.await
+			.map_err(|e| Error::SubXtError(Box::new(e.into())))?;
+		let exhausted = (keys.len() as u32) < count;
+		start_key = keys.last().cloned();
+		storage_keys.extend(keys);
+		if exhausted {
+			start_key = None;
+			break;
+		}
+	}
+
+	let at = client.storage().at(block_hash);
+	let values: Vec<_> = stream::iter(storage_keys.iter().map(|key| at.fetch_raw(key.clone())))
+		.buffered(MAX_CONCURRENT_FETCHES)
+		.try_collect()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?;
+	let metadata = client.metadata();
+	let entries = storage_keys
+		.into_iter()
+		.zip(values)
+		.map(|(key, value)| {
+			let decoded = decode_storage_entry(&metadata, &key, value.as_deref())?;
+			Ok(StorageEntry { key, keys: decoded.keys, value: decoded.value })
+		})
+		.collect::<Result<_, Error>>()?;
+	Ok(StorageEntries { block_hash, entries, next_start_key: start_key })
+}
+
+/// Writes storage entries in the given format.
+///
+/// # Arguments
+/// * `entries` - The storage entries.
+/// * `format` - The format to write the entries in.
+/// * `writer` - Where to write the entries to.
+pub fn export_storage(
+	entries: &[StorageEntry],
+	format: ExportFormat,
+	mut writer: impl Write,
+) -> Result<(), Error> {
+	match format {
+		ExportFormat::Csv => {
+			let columns = entries.iter().map(|entry| entry.keys.len()).max().unwrap_or_default();
+			let mut header = vec!["storage_key".to_string()];
+			header.extend((0..columns).map(|index| format!("key_{index}")));
+			header.push("value".to_string());
+			writeln!(writer, "{}", header.join(","))?;
+			for entry in entries {
+				let mut row = vec![to_hex(&entry.key)];
+				for key in &entry.keys {
+					row.push(csv_field(&key_to_string(key)?));
+				}
+				row.extend((entry.keys.len()..columns).map(|_| String::new()));
+				let value = match &entry.value {
+					Some(value) => raw_value_to_string(value, "")
+						.map_err(|e| Error::DecodingError(e.to_string()))?,
+					None => String::new(),
+				};
+				row.push(csv_field(&value));
+				writeln!(writer, "{}", row.join(","))?;
+			}
+		},
+		ExportFormat::JsonLines =>
+			for entry in entries {
+				let keys: Vec<_> = entry
+					.keys
+					.iter()
+					.map(|key| match key {
+						StorageKeyPart::Value(value) => serde_json::json!({ "value": value }),
+						StorageKeyPart::Hash(hash) => serde_json::json!({ "hash": hash }),
+					})
+					.collect();
+				let line = serde_json::json!({
+					"storage_key": to_hex(&entry.key),
+					"keys": keys,
+					"value": entry.value,
+				});
+				writeln!(writer, "{line}")?;
+			},
+	}
+	Ok(())
+}
+
+/// Renders a key of a storage map in a human-readable format.
+///
+/// # Arguments
+/// * `key` - The key of the storage map.
+pub fn key_to_string(key: &StorageKeyPart) -> Result<String, Error> {
+	match key {
+		StorageKeyPart::Value(value) =>
+			raw_value_to_string(value, "").map_err(|e| Error::DecodingError(e.to_string())),
+		StorageKeyPart::Hash(hash) => Ok(hash.clone()),
+	}
+}
+
+// Quotes a CSV field containing separators, quotes or line breaks.
+fn csv_field(field: &str) -> String {
+	if field.contains([',', '"', '\n', '\r']) {
+		format!("\"{}\"", field.replace('"', "\"\""))
+	} else {
+		field.to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{find_pallet_by_name, parse_chain_metadata, set_up_client};
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	fn entry(key: u8, keys: Vec<StorageKeyPart>, value: Option<u128>) -> StorageEntry {
+		StorageEntry {
+			key: vec![key],
+			keys,
+			value: value.map(|value| Value::u128(value).map_context(|_| 0)),
+		}
+	}
+
+	#[test]
+	fn export_format_from_path_works() {
+		assert_eq!(ExportFormat::from_path(Path::new("balances.csv")), ExportFormat::Csv);
+		assert_eq!(ExportFormat::from_path(Path::new("balances.CSV")), ExportFormat::Csv);
+		assert_eq!(ExportFormat::from_path(Path::new("balances.jsonl")), ExportFormat::JsonLines);
+		assert_eq!(ExportFormat::from_path(Path::new("balances")), ExportFormat::JsonLines);
+	}
+
+	#[test]
+	fn export_csv_works() -> Result<()> {
+		let entries = vec![
+			entry(1, vec![StorageKeyPart::Value(Value::u128(7).map_context(|_| 0))], Some(10)),
+			entry(
+				2,
+				vec![
+					StorageKeyPart::Value(Value::u128(8).map_context(|_| 0)),
+					StorageKeyPart::Hash("a,\"b\"".into()),
+				],
+				None,
+			),
+		];
+		let mut output = Vec::new();
+		export_storage(&entries, ExportFormat::Csv, &mut output)?;
+		assert_eq!(
+			String::from_utf8(output)?,
+			"storage_key,key_0,key_1,value\n0x01,7,,10\n0x02,8,\"a,\"\"b\"\"\",\n"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn export_json_lines_works() -> Result<()> {
+		let entries = vec![
+			entry(1, vec![StorageKeyPart::Hash("0x01".into())], Some(10)),
+			entry(2, vec![], None),
+		];
+		let mut output = Vec::new();
+		export_storage(&entries, ExportFormat::JsonLines, &mut output)?;
+		let lines: Vec<serde_json::Value> = String::from_utf8(output)?
+			.lines()
+			.map(serde_json::from_str)
+			.collect::<Result<_, _>>()?;
+		assert_eq!(lines.len(), 2);
+		assert_eq!(lines[0]["storage_key"], "0x01");
+		assert_eq!(lines[0]["keys"][0]["hash"], "0x01");
+		assert!(lines[1]["value"].is_null());
+		Ok(())
+	}
+
+	#[test]
+	fn csv_field_works() {
+		assert_eq!(csv_field("plain"), "plain");
+		assert_eq!(csv_field("a,b"), "\"a,b\"");
+		assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+	}
+
+	#[tokio::test]
+	async fn iter_storage_pages_through_entries() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let url = url::Url::parse(&node_url)?;
+		let client = set_up_client(&node_url).await?;
+		let pallets = parse_chain_metadata(&client)?;
+		let account = find_pallet_by_name(&pallets, "System")?
+			.state
+			.iter()
+			.find(|storage| storage.name == "Account")
+			.expect("System::Account exists");
+
+		let page = StoragePage { limit: Some(1), ..Default::default() };
+		let first = iter_storage(&client, &url, account, vec![], &page).await?;
+		assert_eq!(first.entries.len(), 1);
+		assert!(first.entries[0].value.is_some());
+		let start_key = first.next_start_key.expect("dev accounts are endowed");
+		assert_eq!(start_key, first.entries[0].key);
+
+		let page = StoragePage { start_key: Some(start_key), limit: Some(1), at: None };
+		let second = iter_storage(&client, &url, account, vec![], &page).await?;
+		assert_eq!(second.entries.len(), 1);
+		assert!(second.entries[0].key > first.entries[0].key);
+
+		let page = StoragePage { at: Some(0), ..Default::default() };
+		let genesis = iter_storage(&client, &url, account, vec![], &page).await?;
+		assert!(!genesis.entries.is_empty());
+		assert!(genesis.next_start_key.is_none());
+		Ok(())
+	}
+}
@@ -0,0 +1,557 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, raw_value_to_string};
+use frame_decode::extrinsics::ExtrinsicTypeInfo;
+use scale::{Compact, Decode};
+use sp_core::{
+	bytes::{from_hex, to_hex},
+	twox_128,
+};
+use std::{fmt::Write, fs, path::Path};
+use subxt::{
+	Metadata,
+	dynamic::Value,
+	events::Events,
+	metadata::types::{StorageEntryType, StorageHasher},
+};
+
+/// The version of extrinsics which can be decoded.
+const EXTRINSIC_FORMAT_VERSION: u8 = 4;
+/// The version of extrinsics introducing general transactions, which cannot be decoded yet.
+const EXTRINSIC_V5: u8 = 5;
+/// The bit of the extrinsic version byte which marks a signed extrinsic.
+const SIGNED_BIT: u8 = 0b1000_0000;
+
+/// A value decoded using the type information of the metadata.
+pub type DecodedValue = Value<u32>;
+
+/// A decoded extrinsic.
+#[derive(Debug)]
+pub struct DecodedExtrinsic {
+	/// The extrinsic format version.
+	pub version: u8,
+	/// The signature details, if the extrinsic is signed.
+	pub signature: Option<DecodedSignature>,
+	/// The call of the extrinsic.
+	pub call: DecodedValue,
+}
+
+/// The signature details of a decoded extrinsic.
+#[derive(Debug)]
+pub struct DecodedSignature {
+	/// The address of the signer.
+	pub address: DecodedValue,
+	/// The signature.
+	pub signature: DecodedValue,
+	/// The signed extensions, by identifier.
+	pub extensions: Vec<(String, DecodedValue)>,
+}
+
+/// A decoded event record.
+#[derive(Debug)]
+pub struct DecodedEvent {
+	/// The phase of the block in which the event was emitted.
+	pub phase: String,
+	/// The pallet emitting the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub name: String,
+	/// The fields of the event.
+	pub fields: DecodedValue,
+}
+
+/// A part of a decoded storage key.
+#[derive(Debug)]
+pub enum StorageKeyPart {
+	/// A key which can be recovered from the storage key, as it is hashed with a concatenating
+	/// hasher.
+	Value(DecodedValue),
+	/// A key which cannot be recovered, as only its hash is part of the storage key.
+	Hash(String),
+}
+
+/// A decoded storage key and, optionally, value.
+#[derive(Debug)]
+pub struct DecodedStorage {
+	/// The pallet of the storage item.
+	pub pallet: String,
+	/// The name of the storage item.
+	pub entry: String,
+	/// The keys of a storage map.
+	pub keys: Vec<StorageKeyPart>,
+	/// The decoded value.
+	pub value: Option<DecodedValue>,
+}
+
+/// Loads metadata from a file, as SCALE-encoded bytes (e.g. the output of `subxt metadata`) or as
+/// a hex string (e.g. the result of the `state_getMetadata` RPC).
+///
+/// # Arguments
+/// * `path` - The path of the metadata file.
+pub fn load_metadata(path: &Path) -> Result<Metadata, Error> {
+	let bytes = fs::read(path)?;
+	let bytes = match std::str::from_utf8(&bytes) {
+		Ok(hex) if hex.trim().starts_with("0x") => decode_hex(hex.trim())?,
+		_ => bytes,
+	};
+	Metadata::decode(&mut &bytes[..]).map_err(|e| {
+		Error::MetadataParsingError(format!("Failed to decode metadata {}: {e}", path.display()))
+	})
+}
+
+/// Decodes hex-encoded call data into a `RuntimeCall`.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `call_data` - The hex-encoded call data.
+pub fn decode_call(metadata: &Metadata, call_data: &str) -> Result<DecodedValue, Error> {
+	let bytes = decode_hex(call_data)?;
+	let input = &mut &bytes[..];
+	let call = decode_type(input, metadata.outer_enums().call_enum_ty(), metadata, "call")?;
+	ensure_consumed(input, "call")?;
+	Ok(call)
+}
+
+/// Decodes a hex-encoded extrinsic, signed or unsigned, including its signed extensions.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `extrinsic` - The hex-encoded extrinsic, prefixed with its length.
+pub fn decode_extrinsic(metadata: &Metadata, extrinsic: &str) -> Result<DecodedExtrinsic, Error> {
+	let bytes = decode_hex(extrinsic)?;
+	let input = &mut &bytes[..];
+	let length = Compact::<u32>::decode(input)
+		.map_err(|e| Error::DecodingError(format!("Invalid extrinsic length: {e}")))?;
+	if length.0 as usize != input.len() {
+		return Err(Error::DecodingError(format!(
+			"The extrinsic length is {} bytes, but {} bytes were provided",
+			length.0,
+			input.len()
+		)));
+	}
+	let version_byte = u8::decode(input)
+		.map_err(|e| Error::DecodingError(format!("Invalid extrinsic version: {e}")))?;
+	let version = version_byte & !SIGNED_BIT;
+	if version == EXTRINSIC_V5 {
+		return Err(Error::DecodingError(format!(
+			"Extrinsic version {EXTRINSIC_V5} (general transactions) is not supported yet, only \
+			 version {EXTRINSIC_FORMAT_VERSION} is supported"
+		)));
+	}
+	if version != EXTRINSIC_FORMAT_VERSION {
+		return Err(Error::DecodingError(format!(
+			"Unsupported extrinsic version {version}, only version {EXTRINSIC_FORMAT_VERSION} \
+			 is supported"
+		)));
+	}
+
+	let signature = if version_byte & SIGNED_BIT != 0 {
+		let info =
+			metadata.get_signature_info().map_err(|e| Error::DecodingError(e.to_string()))?;
+		let address = decode_type(input, info.address_id, metadata, "address")?;
+		let signature = decode_type(input, info.signature_id, metadata, "signature")?;
+		let extensions = metadata
+			.extrinsic()
+			.transaction_extensions_by_version(0)
+			.into_iter()
+			.flatten()
+			.map(|extension| {
+				let value =
+					decode_type(input, extension.extra_ty(), metadata, extension.identifier())?;
+				Ok((extension.identifier().to_string(), value))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+		Some(DecodedSignature { address, signature, extensions })
+	} else {
+		None
+	};
+	let call = decode_type(input, metadata.outer_enums().call_enum_ty(), metadata, "call")?;
+	ensure_consumed(input, "extrinsic")?;
+	Ok(DecodedExtrinsic { version, signature, call })
+}
+
+/// Decodes hex-encoded event records, such as the value of the `System::Events` storage item.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `events` - The hex-encoded event records.
+pub fn decode_events(metadata: &Metadata, events: &str) -> Result<Vec<DecodedEvent>, Error> {
+	let events =
+		Events::<subxt::SubstrateConfig>::decode_from(decode_hex(events)?, metadata.clone());
+	decode_event_records(&events)
+}
+
+/// Decodes the event records of a block.
+///
+/// # Arguments
+/// * `events` - The events of the block.
+pub(crate) fn decode_event_records(
+	events: &Events<subxt::SubstrateConfig>,
+) -> Result<Vec<DecodedEvent>, Error> {
+	events
+		.iter()
+		.map(|event| {
+			let event = event.map_err(|e| Error::DecodingError(e.to_string()))?;
+			let fields = event.field_values().map_err(|e| Error::DecodingError(e.to_string()))?;
+			Ok(DecodedEvent {
+				phase: format!("{:?}", event.phase()),
+				pallet: event.pallet_name().to_string(),
+				name: event.variant_name().to_string(),
+				fields: Value { value: scale_value::ValueDef::Composite(fields), context: 0 },
+			})
+		})
+		.collect()
+}
+
+/// Decodes a hex-encoded storage key and, optionally, its value. The storage item is identified
+/// from the prefix of the key.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `key` - The hex-encoded storage key.
+/// * `value` - The hex-encoded storage value.
+pub fn decode_storage(
+	metadata: &Metadata,
+	key: &str,
+	value: Option<&str>,
+) -> Result<DecodedStorage, Error> {
+	let value = value.map(decode_hex).transpose()?;
+	decode_storage_entry(metadata, &decode_hex(key)?, value.as_deref())
+}
+
+/// Decodes a storage key and, optionally, its value.
+///
+/// # Arguments
+/// * `metadata` - The metadata of the runtime.
+/// * `key` - The storage key.
+/// * `value` - The SCALE-encoded storage value.
+pub(crate) fn decode_storage_entry(
+	metadata: &Metadata,
+	key: &[u8],
+	value: Option<&[u8]>,
+) -> Result<DecodedStorage, Error> {
+	let (pallet, entry) = metadata
+		.pallets()
+		.filter_map(|pallet| pallet.storage().map(|storage| (pallet, storage)))
+		.find_map(|(pallet, storage)| {
+			let prefix = twox_128(storage.prefix().as_bytes());
+			key.starts_with(&prefix)
+				.then(|| {
+					storage
+						.entries()
+						.iter()
+						.find(|entry| key[16..].starts_with(&twox_128(entry.name().as_bytes())))
+				})
+				.flatten()
+				.map(|entry| (pallet.name().to_string(), entry))
+		})
+		.ok_or_else(|| {
+			Error::DecodingError("The key does not belong to a storage item of the runtime".into())
+		})?;
+
+	let input = &mut &key[32..];
+	let keys = match entry.entry_type() {
+		StorageEntryType::Plain(_) => vec![],
+		StorageEntryType::Map { hashers, key_ty, .. } => {
+			let key_types = key_type_ids(metadata, *key_ty, hashers.len())?;
+			hashers
+				.iter()
+				.zip(key_types)
+				.map(|(hasher, key_ty)| decode_key(input, hasher, key_ty, metadata))
+				.collect::<Result<Vec<_>, Error>>()?
+		},
+	};
+	ensure_consumed(input, "storage key")?;
+
+	let value = value
+		.map(|bytes| {
+			let input = &mut &bytes[..];
+			let value = decode_type(input, entry.entry_type().value_ty(), metadata, "value")?;
+			ensure_consumed(input, "storage value")?;
+			Ok::<_, Error>(value)
+		})
+		.transpose()?;
+	Ok(DecodedStorage { pallet, entry: entry.name().to_string(), keys, value })
+}
+
+impl DecodedExtrinsic {
+	/// Renders the extrinsic in a human-readable format.
+	pub fn render(&self) -> anyhow::Result<String> {
+		let mut output = format!("Version: {}\n", self.version);
+		match &self.signature {
+			Some(signature) => {
+				writeln!(output, "Signed: true")?;
+				writeln!(output, "Address: {}", raw_value_to_string(&signature.address, "")?)?;
+				writeln!(output, "Signature: {}", raw_value_to_string(&signature.signature, "")?)?;
+				writeln!(output, "Signed extensions:")?;
+				for (identifier, value) in &signature.extensions {
+					writeln!(output, "  {identifier}: {}", raw_value_to_string(value, "")?)?;
+				}
+			},
+			None => writeln!(output, "Signed: false")?,
+		}
+		write!(output, "Call:\n{}", raw_value_to_string(&self.call, "  ")?)?;
+		Ok(output)
+	}
+}
+
+impl DecodedEvent {
+	/// Renders the event in a human-readable format.
+	pub fn render(&self) -> anyhow::Result<String> {
+		Ok(format!(
+			"{}.{} ({})\n{}",
+			self.pallet,
+			self.name,
+			self.phase,
+			raw_value_to_string(&self.fields, "  ")?
+		))
+	}
+}
+
+impl DecodedStorage {
+	/// Renders the storage key and value in a human-readable format.
+	pub fn render(&self) -> anyhow::Result<String> {
+		let mut output = format!("Storage: {}::{}\n", self.pallet, self.entry);
+		for (index, key) in self.keys.iter().enumerate() {
+			match key {
+				StorageKeyPart::Value(value) =>
+					writeln!(output, "Key {index}: {}", raw_value_to_string(value, "")?)?,
+				StorageKeyPart::Hash(hash) => writeln!(output, "Key {index} (hashed): {hash}")?,
+			}
+		}
+		if let Some(value) = &self.value {
+			write!(output, "Value:\n{}", raw_value_to_string(value, "  ")?)?;
+		}
+		Ok(output.trim_end().to_string())
+	}
+}
+
+// Resolves the type of each key of a storage map: the fields of the key tuple when the map has
+// several hashers, otherwise the key type itself.
+fn key_type_ids(metadata: &Metadata, key_ty: u32, hashers: usize) -> Result<Vec<u32>, Error> {
+	if hashers == 1 {
+		return Ok(vec![key_ty]);
+	}
+	match metadata.types().resolve(key_ty).map(|ty| &ty.type_def) {
+		Some(scale_info::TypeDef::Tuple(tuple)) if tuple.fields.len() == hashers =>
+			Ok(tuple.fields.iter().map(|field| field.id).collect()),
+		_ => Err(Error::DecodingError(format!(
+			"The key type {key_ty} does not match the {hashers} hashers of the storage item"
+		))),
+	}
+}
+
+// Decodes a key of a storage map, which can only be recovered from concatenating hashers.
+fn decode_key(
+	input: &mut &[u8],
+	hasher: &StorageHasher,
+	key_ty: u32,
+	metadata: &Metadata,
+) -> Result<StorageKeyPart, Error> {
+	let (hash_len, concat) = match hasher {
+		StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+		StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+		StorageHasher::Blake2_128Concat => (16, true),
+		StorageHasher::Twox64Concat => (8, true),
+		StorageHasher::Identity => (0, true),
+	};
+	if input.len() < hash_len {
+		return Err(Error::DecodingError("The storage key is too short".into()));
+	}
+	let (hash, rest) = input.split_at(hash_len);
+	*input = rest;
+	if concat {
+		Ok(StorageKeyPart::Value(decode_type(input, key_ty, metadata, "storage key")?))
+	} else {
+		Ok(StorageKeyPart::Hash(to_hex(hash, false)))
+	}
+}
+
+fn decode_type(
+	input: &mut &[u8],
+	type_id: u32,
+	metadata: &Metadata,
+	what: &str,
+) -> Result<DecodedValue, Error> {
+	scale_value::scale::decode_as_type(input, type_id, metadata.types())
+		.map_err(|e| Error::DecodingError(format!("Failed to decode the {what}: {e}")))
+}
+
+fn ensure_consumed(input: &[u8], what: &str) -> Result<(), Error> {
+	if input.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::DecodingError(format!(
+			"{} bytes left over after decoding the {what}",
+			input.len()
+		)))
+	}
+}
+
+fn decode_hex(data: &str) -> Result<Vec<u8>, Error> {
+	from_hex(data.trim()).map_err(|e| Error::DecodingError(format!("Invalid hex data: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{TxOptions, set_up_client, sign_extrinsic_with_options};
+	use anyhow::Result;
+	use pop_common::{create_signer, test_env::shared_substrate_ws_url};
+	use scale::Encode;
+
+	// `System::remark(0x11)`.
+	const REMARK: &str = "0x00000411";
+
+	async fn metadata() -> Result<Metadata> {
+		let node_url = shared_substrate_ws_url().await;
+		Ok(set_up_client(&node_url).await?.metadata())
+	}
+
+	#[tokio::test]
+	async fn decode_call_works() -> Result<()> {
+		let metadata = metadata().await?;
+		let call = decode_call(&metadata, REMARK)?;
+		let rendered = raw_value_to_string(&call, "")?;
+		assert!(rendered.contains("System"));
+		assert!(rendered.contains("remark"));
+		assert!(matches!(
+			decode_call(&metadata, &format!("{REMARK}00")),
+			Err(Error::DecodingError(e)) if e.contains("left over")
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decode_unsigned_extrinsic_works() -> Result<()> {
+		let metadata = metadata().await?;
+		let mut extrinsic = vec![EXTRINSIC_FORMAT_VERSION];
+		extrinsic.extend(decode_hex(REMARK)?);
+		let extrinsic = to_hex(&extrinsic.encode(), false);
+		let decoded = decode_extrinsic(&metadata, &extrinsic)?;
+		assert_eq!(decoded.version, EXTRINSIC_FORMAT_VERSION);
+		assert!(decoded.signature.is_none());
+		assert_eq!(decoded.call, decode_call(&metadata, REMARK)?);
+		assert!(decoded.render()?.starts_with("Version: 4\nSigned: false\nCall:"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decode_signed_extrinsic_works() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let client = set_up_client(&node_url).await?;
+		let metadata = client.metadata();
+		let xt = subxt::dynamic::tx("System", "remark", vec![Value::from_bytes([0x11])]);
+		let extrinsic = sign_extrinsic_with_options(
+			&client,
+			&url::Url::parse(&node_url)?,
+			&xt,
+			&create_signer("//Alice")?,
+			&TxOptions { tip: 1, ..Default::default() },
+		)
+		.await?;
+
+		let decoded = decode_extrinsic(&metadata, &to_hex(&extrinsic, false))?;
+		assert_eq!(decoded.version, EXTRINSIC_FORMAT_VERSION);
+		assert_eq!(decoded.call, decode_call(&metadata, REMARK)?);
+		let signature = decoded.signature.as_ref().expect("the extrinsic is signed");
+		assert_eq!(
+			signature.extensions.len(),
+			metadata
+				.extrinsic()
+				.transaction_extensions_by_version(0)
+				.into_iter()
+				.flatten()
+				.count()
+		);
+		assert!(signature.extensions.iter().any(|(identifier, _)| identifier == "CheckNonce"));
+		assert!(decoded.render()?.starts_with("Version: 4\nSigned: true\n"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decode_extrinsic_rejects_v5() -> Result<()> {
+		let metadata = metadata().await?;
+		let mut extrinsic = vec![EXTRINSIC_V5];
+		extrinsic.extend(decode_hex(REMARK)?);
+		let extrinsic = to_hex(&extrinsic.encode(), false);
+		assert!(matches!(
+			decode_extrinsic(&metadata, &extrinsic),
+			Err(Error::DecodingError(e)) if e.contains("general transactions")
+		));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decode_storage_works() -> Result<()> {
+		let metadata = metadata().await?;
+		let mut key = twox_128(b"System").to_vec();
+		key.extend(twox_128(b"Number"));
+		let decoded = decode_storage(&metadata, &to_hex(&key, false), Some("0x0a000000"))?;
+		assert_eq!(decoded.pallet, "System");
+		assert_eq!(decoded.entry, "Number");
+		assert!(decoded.keys.is_empty());
+		assert_eq!(decoded.render()?, "Storage: System::Number\nValue:\n  10");
+
+		// `System::Account` is a map with a `Blake2_128Concat` hasher, so the key is recovered.
+		let alice = [1u8; 32];
+		let mut key = twox_128(b"System").to_vec();
+		key.extend(twox_128(b"Account"));
+		key.extend(sp_core::blake2_128(&alice));
+		key.extend(alice);
+		let decoded = decode_storage(&metadata, &to_hex(&key, false), None)?;
+		assert!(matches!(decoded.keys.as_slice(), [StorageKeyPart::Value(_)]));
+		assert!(decoded.value.is_none());
+
+		assert!(matches!(decode_storage(&metadata, "0x0102", None), Err(Error::DecodingError(_))));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn decode_events_works() -> Result<()> {
+		let metadata = metadata().await?;
+		assert!(decode_events(&metadata, "0x00")?.is_empty());
+
+		// The events of the latest block include at least the outcome of its inherents.
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		let mut key = twox_128(b"System").to_vec();
+		key.extend(twox_128(b"Events"));
+		let events = client
+			.storage()
+			.at_latest()
+			.await?
+			.fetch_raw(key)
+			.await?
+			.expect("System::Events is set in every block");
+		let decoded = decode_events(&metadata, &to_hex(&events, false))?;
+		assert!(!decoded.is_empty());
+		assert!(decoded.iter().any(|event| event.pallet == "System" &&
+			event.name == "ExtrinsicSuccess" &&
+			event.phase.starts_with("ApplyExtrinsic")));
+		Ok(())
+	}
+
+	#[test]
+	fn ensure_consumed_works() {
+		assert!(ensure_consumed(&[], "call").is_ok());
+		assert!(matches!(
+			ensure_consumed(&[1, 2], "call"),
+			Err(Error::DecodingError(e)) if e == "2 bytes left over after decoding the call"
+		));
+	}
+
+	#[test]
+	fn decode_hex_works() {
+		assert_eq!(decode_hex(" 0x0102 ").unwrap(), vec![1, 2]);
+		assert!(matches!(decode_hex("0xzz"), Err(Error::DecodingError(_))));
+	}
+
+	#[test]
+	fn load_metadata_fails_on_invalid_file() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("metadata.scale");
+		fs::write(&path, [1, 2, 3])?;
+		assert!(matches!(load_metadata(&path), Err(Error::MetadataParsingError(_))));
+		Ok(())
+	}
+}
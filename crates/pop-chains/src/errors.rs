@@ -36,6 +36,9 @@ pub enum Error {
 	/// The current directory could not be accessed.
 	#[error("Failed to access the current directory")]
 	CurrentDirAccess,
+	/// Failed to decode SCALE-encoded data.
+	#[error("Failed to decode: {0}")]
+	DecodingError(String),
 	/// The endowment value could not be parsed.
 	#[error("Failed to parse the endowment value")]
 	EndowmentError,
@@ -45,6 +48,9 @@ pub enum Error {
 	/// An error occurred during the submission of an extrinsic.
 	#[error("Extrinsic submission error: {0}")]
 	ExtrinsicSubmissionError(String),
+	/// Failed to estimate the fee of an extrinsic.
+	#[error("Failed to estimate the fee: {0}")]
+	FeeEstimationError(String),
 	/// The callable item is not supported.
 	#[error("The callable item is not supported")]
 	CallableNotSupported,
@@ -54,6 +60,9 @@ pub enum Error {
 	/// An error occurred while working with the genesis builder.
 	#[error("Genesis builder error: {0}")]
 	GenesisBuilderError(String),
+	/// An error occurred while opening or closing an HRMP channel.
+	#[error("HRMP error: {0}")]
+	HrmpError(String),
 	/// Failed to retrieve the image tag.
 	#[error("Failed to retrieve image tag.")]
 	ImageTagRetrievalFailed,
@@ -80,9 +89,21 @@ pub enum Error {
 		/// The binary used.
 		binary: String,
 	},
+	/// An error occurred while constructing or querying a multisig operation.
+	#[error("Multisig error: {0}")]
+	MultisigError(String),
 	/// A network configuration error occurred.
 	#[error("Configuration error: {0:?}")]
 	NetworkConfigurationError(Vec<anyhow::Error>),
+	/// An error occurred while loading or running the assertions of a network test.
+	#[error("Network test error: {0}")]
+	NetworkTestError(String),
+	/// The specified node is not part of the network.
+	#[error("Node {0} not found in the network")]
+	NodeNotFound(String),
+	/// An error occurred while preparing, signing or assembling an offline transaction.
+	#[error("Offline signing error: {0}")]
+	OfflineSigningError(String),
 	/// An orchestrator error occurred.
 	#[error("Orchestrator error: {0}")]
 	OrchestratorError(#[from] OrchestratorError),
@@ -101,12 +122,24 @@ pub enum Error {
 	/// The path is invalid.
 	#[error("Invalid path")]
 	PathError,
+	/// A chain could not be added to the registry.
+	#[error("Chain registry error: {0}")]
+	RegistryError(String),
 	/// Failed to execute `rustfmt`.
 	#[error("Failed to execute rustfmt")]
 	RustfmtError(std::io::Error),
 	/// The specified runtime could not be found.
 	#[error("Failed to find the runtime {0}")]
 	RuntimeNotFound(String),
+	/// An error occurred while upgrading the runtime of a chain.
+	#[error("Runtime upgrade error: {0}")]
+	RuntimeUpgradeError(String),
+	/// An error occurred while verifying a runtime.
+	#[error("Runtime verification error: {0}")]
+	RuntimeVerificationError(String),
+	/// An error occurred while loading or executing a script of calls.
+	#[error("Script error: {0}")]
+	ScriptError(String),
 	/// An error occurred sourcing a binary.
 	#[error("Template error: {0}")]
 	SourcingError(#[from] pop_common::sourcing::Error),
@@ -116,12 +149,18 @@ pub enum Error {
 	/// A TOML error occurred.
 	#[error("Toml error: {0}")]
 	TomlError(#[from] toml_edit::de::Error),
+	/// The options of a transaction are invalid or unsupported by the chain.
+	#[error("Invalid transaction options: {0}")]
+	TransactionOptionsError(String),
 	/// An error occurred while testing with try-runtime.
 	#[error("Failed to test with try-runtime: {0}")]
 	TryRuntimeError(String),
 	/// The command is not supported.
 	#[error("Unsupported command: {0}")]
 	UnsupportedCommand(String),
+	/// An error occurred while watching a chain.
+	#[error("Watch error: {0}")]
+	WatchError(String),
 	/// The workspace could not be located.
 	#[error("Failed to locate the workspace")]
 	WorkspaceLocate,
@@ -10,10 +10,14 @@ pub mod bench;
 mod build;
 /// Provides functionality to construct, encode, sign, and submit chain extrinsics.
 mod call;
+/// Provides functionality for decoding SCALE-encoded calls, extrinsics, events and storage.
+mod decode;
 /// Error types and handling for the crate.
 mod errors;
 /// Code generation utilities.
 mod generator;
+/// Provides functionality for comparing the metadata of two runtimes.
+mod metadata_diff;
 /// Functionality for creating new blockchain implementations.
 mod new_chain;
 /// Tools for creating new runtime pallets.
@@ -36,6 +40,10 @@ pub mod try_runtime;
 pub mod up;
 /// General utility functions and helpers.
 pub mod utils;
+/// Provides functionality for verifying a runtime against a live chain or a published build.
+mod verify;
+/// Provides functionality for following the events and storage of a live chain.
+mod watch;
 
 pub use bench::{
 	BenchmarkingCliCommand, GENESIS_BUILDER_DEV_PRESET, GenesisBuilderPolicy,
@@ -44,26 +52,50 @@ pub use bench::{
 	load_pallet_extrinsics,
 };
 pub use build::{
-	ChainSpec, ChainSpecBuilder, binary_path, build_chain, build_project,
-	export_wasm_file_with_node, generate_genesis_state_file_with_node,
-	generate_plain_chain_spec_with_node, generate_raw_chain_spec_with_node, is_supported, runtime,
+	ChainSpec, ChainSpecBuilder, ChainSpecChange, ChainSpecDiff, GenesisError, GenesisJson,
+	RuntimeGenesis, binary_path, build_chain, build_project, export_wasm_file_with_node,
+	generate_genesis_state_file_with_node, generate_plain_chain_spec_with_node,
+	generate_raw_chain_spec_with_node, is_supported, load_genesis_patch, merge_patch, runtime,
 	runtime::DeterministicBuilder, runtime_binary_path,
 };
 pub use call::{
 	CallData, construct_extrinsic, construct_proxy_extrinsic, construct_sudo_extrinsic,
-	decode_call_data, encode_call_data,
+	decode_call_data, dispatch_error, encode_call_data, ensure_dispatched,
 	metadata::{
-		CallItem, Constant, Function, Pallet, Storage,
+		CallItem, Constant, Function, Pallet, RuntimeApi, Storage,
 		action::{Action, supported_actions},
 		find_callable_by_name, find_pallet_by_name,
 		params::{Param, field_to_param, type_to_param},
-		parse_chain_metadata, parse_dispatchable_arguments, raw_value_to_string,
+		parse_chain_metadata, parse_dispatchable_arguments, parse_metadata, raw_value_to_string,
 		render_storage_key_values,
 	},
-	parse_and_format_events, set_up_client, sign_and_submit_extrinsic, submit_signed_extrinsic,
+	multisig::{Multisig, MultisigApproval, MultisigOperation, Timepoint},
+	offline::{Era, OfflineTransaction},
+	options::{
+		FeeAsset, FeeEstimate, Junction, Junctions, Location, NetworkId, TxOptions, estimate_fee,
+		sign_extrinsic_with_ethereum_key, sign_extrinsic_with_options, sign_extrinsic_with_suri,
+	},
+	parse_and_format_events,
+	script::{
+		CallScript, CallStep, ScriptExtrinsic, construct_batch_extrinsic, event_names,
+		missing_events, step_dispatch_errors, step_events, submit_extrinsic,
+	},
+	set_up_client, sign_and_submit_extrinsic, sign_and_submit_extrinsic_with_options,
+	sign_and_submit_extrinsic_with_signer, sign_and_submit_sudo_extrinsic,
+	sign_extrinsic_with_nonce,
+	storage::{
+		ExportFormat, StorageEntries, StorageEntry, StoragePage, export_storage, iter_storage,
+		key_to_string,
+	},
+	submit_and_watch_extrinsic, submit_signed_extrinsic,
+};
+pub use decode::{
+	DecodedEvent, DecodedExtrinsic, DecodedSignature, DecodedStorage, DecodedValue, StorageKeyPart,
+	decode_call, decode_events, decode_extrinsic, decode_storage, load_metadata,
 };
 pub use errors::Error;
 pub use indexmap::IndexSet;
+pub use metadata_diff::{ChangeKind, ItemKind, MetadataChange, MetadataDiff, diff_metadata};
 pub use new_chain::instantiate_template_dir;
 pub use new_pallet::{TemplatePalletConfig, create_pallet_template, new_pallet_options::*};
 pub use relay::{RelayChain, Reserved, clear_dmpq};
@@ -74,12 +106,17 @@ pub use try_runtime::{
 };
 // External exports from subxt.
 pub use subxt::{
-	OnlineClient, SubstrateConfig,
+	Metadata, OnlineClient, SubstrateConfig,
 	blocks::ExtrinsicEvents,
-	tx::{DynamicPayload, Payload},
+	tx::{DynamicPayload, Payload, Signer, SubmittableTransaction},
+	utils::H256,
 };
 pub use templates::{ChainTemplate, Config, Provider};
 pub use utils::helpers::{get_preset_names, is_initial_endowment_valid};
+pub use verify::{
+	PreimageMatch, RuntimeHashes, SrtoolDigest, fetch_code, fetch_preimage, match_preimage,
+};
+pub use watch::{ChainWatcher, EventFilter, Heads, StorageWatch, WatchUpdate};
 /// Information about the Node. External export from Zombienet-SDK.
 pub use zombienet_sdk::NetworkNode;
 
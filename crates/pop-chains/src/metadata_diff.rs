@@ -0,0 +1,591 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{Pallet, errors::Error, parse_metadata, raw_value_to_string};
+use pop_common::format_type;
+use scale_info::{Field, Type, TypeDef, Variant, form::PortableForm};
+use serde::Serialize;
+use std::{
+	collections::BTreeMap,
+	fmt::{Display, Formatter},
+};
+use subxt::{
+	Metadata,
+	metadata::types::{PalletMetadata, StorageEntryType},
+};
+
+/// The outer enums and pallet enums of a runtime. Their layouts change whenever a pallet, call,
+/// event or error is added or removed, which is already reported in more detail.
+const AGGREGATED_TYPES: [&str; 9] = [
+	"RuntimeCall",
+	"RuntimeEvent",
+	"RuntimeError",
+	"RuntimeHoldReason",
+	"RuntimeFreezeReason",
+	"OriginCaller",
+	"Call",
+	"Event",
+	"Error",
+];
+
+/// The kind of change made to an item of the metadata.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+	/// The item only exists in the new metadata.
+	Added,
+	/// The item only exists in the old metadata.
+	Removed,
+	/// The item exists in both, but differs.
+	Changed,
+}
+
+/// The kind of item of the metadata which changed.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ItemKind {
+	/// A pallet.
+	Pallet,
+	/// A dispatchable function.
+	Call,
+	/// A storage item.
+	Storage,
+	/// An event.
+	Event,
+	/// An error.
+	Error,
+	/// A constant.
+	Constant,
+	/// A type of the type registry.
+	Type,
+}
+
+impl Display for ItemKind {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let kind = match self {
+			ItemKind::Pallet => "Pallet",
+			ItemKind::Call => "Call",
+			ItemKind::Storage => "Storage",
+			ItemKind::Event => "Event",
+			ItemKind::Error => "Error",
+			ItemKind::Constant => "Constant",
+			ItemKind::Type => "Type",
+		};
+		write!(f, "{kind}")
+	}
+}
+
+/// A change between two versions of runtime metadata.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct MetadataChange {
+	/// The kind of change.
+	pub change: ChangeKind,
+	/// The kind of item which changed.
+	pub item: ItemKind,
+	/// The path of the item, e.g. `Balances::transfer_allow_death`.
+	pub path: String,
+	/// A description of what changed.
+	pub detail: Option<String>,
+	/// Whether the change breaks clients built for the old metadata, such as wallets and indexers.
+	pub breaking: bool,
+}
+
+impl Display for MetadataChange {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		let symbol = match self.change {
+			ChangeKind::Added => '+',
+			ChangeKind::Removed => '-',
+			ChangeKind::Changed => '~',
+		};
+		write!(f, "{symbol} {} {}", self.item, self.path)?;
+		if let Some(detail) = &self.detail {
+			write!(f, ": {detail}")?;
+		}
+		if self.breaking {
+			write!(f, " [BREAKING]")?;
+		}
+		Ok(())
+	}
+}
+
+/// The differences between two versions of runtime metadata.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize)]
+pub struct MetadataDiff {
+	/// The changes, ordered by pallet.
+	pub changes: Vec<MetadataChange>,
+}
+
+impl MetadataDiff {
+	/// Whether the two versions of the metadata are equivalent.
+	pub fn is_empty(&self) -> bool {
+		self.changes.is_empty()
+	}
+
+	/// The changes which break clients built for the old metadata.
+	pub fn breaking(&self) -> impl Iterator<Item = &MetadataChange> {
+		self.changes.iter().filter(|change| change.breaking)
+	}
+
+	/// Renders the changes in a human-readable format, one change per line.
+	pub fn render(&self) -> String {
+		self.changes.iter().map(ToString::to_string).collect::<Vec<_>>().join("\n")
+	}
+}
+
+/// An item of the metadata, reduced to what is compared.
+#[derive(Debug, Default)]
+struct Entry {
+	name: String,
+	/// The index used to encode the item, for pallets, calls, events and errors.
+	index: Option<u8>,
+	/// The types of the item.
+	signature: String,
+	/// The value of a constant.
+	value: Option<String>,
+}
+
+/// Compares two versions of runtime metadata, reporting added, removed and changed pallets,
+/// calls, storage items, events, errors, constants and type layouts.
+///
+/// Changes which break clients built for the old metadata are flagged: removed items other than
+/// errors, moved pallet, call, event and error indexes, and changed types.
+///
+/// # Arguments
+/// * `old` - The metadata of the old runtime.
+/// * `new` - The metadata of the new runtime.
+pub fn diff_metadata(old: &Metadata, new: &Metadata) -> Result<MetadataDiff, Error> {
+	let old_pallets = parse_metadata(old)?;
+	let new_pallets = parse_metadata(new)?;
+	let mut changes = Vec::new();
+
+	let pallets = |pallets: &[Pallet]| {
+		pallets
+			.iter()
+			.filter(|pallet| !pallet.is_runtime_api())
+			.map(|pallet| Entry {
+				name: pallet.name.clone(),
+				index: Some(pallet.index),
+				..Default::default()
+			})
+			.collect::<Vec<_>>()
+	};
+	compare_entries(
+		&mut changes,
+		ItemKind::Pallet,
+		"",
+		pallets(&old_pallets),
+		pallets(&new_pallets),
+	);
+
+	for old_pallet in old_pallets.iter().filter(|pallet| !pallet.is_runtime_api()) {
+		let Some(new_pallet) = new_pallets.iter().find(|pallet| pallet.name == old_pallet.name)
+		else {
+			continue;
+		};
+		let (Some(old_metadata), Some(new_metadata)) =
+			(old.pallet_by_name(&old_pallet.name), new.pallet_by_name(&new_pallet.name))
+		else {
+			continue;
+		};
+		let prefix = format!("{}::", old_pallet.name);
+
+		compare_entries(
+			&mut changes,
+			ItemKind::Call,
+			&prefix,
+			call_entries(old, old_pallet, &old_metadata),
+			call_entries(new, new_pallet, &new_metadata),
+		);
+		compare_entries(
+			&mut changes,
+			ItemKind::Storage,
+			&prefix,
+			storage_entries(old, old_pallet, &old_metadata),
+			storage_entries(new, new_pallet, &new_metadata),
+		);
+		compare_entries(
+			&mut changes,
+			ItemKind::Event,
+			&prefix,
+			variant_entries(old, old_metadata.event_variants()),
+			variant_entries(new, new_metadata.event_variants()),
+		);
+		compare_entries(
+			&mut changes,
+			ItemKind::Error,
+			&prefix,
+			variant_entries(old, old_metadata.error_variants()),
+			variant_entries(new, new_metadata.error_variants()),
+		);
+		compare_entries(
+			&mut changes,
+			ItemKind::Constant,
+			&prefix,
+			constant_entries(old, old_pallet, &old_metadata)?,
+			constant_entries(new, new_pallet, &new_metadata)?,
+		);
+	}
+
+	compare_type_layouts(&mut changes, old, new);
+	Ok(MetadataDiff { changes })
+}
+
+fn call_entries(
+	metadata: &Metadata,
+	pallet: &Pallet,
+	pallet_metadata: &PalletMetadata,
+) -> Vec<Entry> {
+	pallet
+		.functions
+		.iter()
+		.map(|function| Entry {
+			name: function.name.clone(),
+			index: Some(function.index),
+			signature: pallet_metadata
+				.call_variant_by_index(function.index)
+				.map(|variant| variant_signature(metadata, variant))
+				.unwrap_or_default(),
+			value: None,
+		})
+		.collect()
+}
+
+fn storage_entries(
+	metadata: &Metadata,
+	pallet: &Pallet,
+	pallet_metadata: &PalletMetadata,
+) -> Vec<Entry> {
+	pallet
+		.state
+		.iter()
+		.map(|storage| {
+			// A changed hasher moves every key of the storage map.
+			let hashers = pallet_metadata
+				.storage()
+				.and_then(|s| s.entry_by_name(&storage.name))
+				.map(|entry| match entry.entry_type() {
+					StorageEntryType::Plain(_) => String::new(),
+					StorageEntryType::Map { hashers, .. } => format!("{hashers:?} "),
+				})
+				.unwrap_or_default();
+			let signature = match storage.key_id {
+				Some(key_id) => format!(
+					"{hashers}{} => {}",
+					type_name(metadata, key_id),
+					type_name(metadata, storage.type_id)
+				),
+				None => type_name(metadata, storage.type_id),
+			};
+			Entry { name: storage.name.clone(), signature, ..Default::default() }
+		})
+		.collect()
+}
+
+fn variant_entries(metadata: &Metadata, variants: Option<&[Variant<PortableForm>]>) -> Vec<Entry> {
+	variants
+		.unwrap_or_default()
+		.iter()
+		.map(|variant| Entry {
+			name: variant.name.clone(),
+			index: Some(variant.index),
+			signature: variant_signature(metadata, variant),
+			value: None,
+		})
+		.collect()
+}
+
+fn constant_entries(
+	metadata: &Metadata,
+	pallet: &Pallet,
+	pallet_metadata: &PalletMetadata,
+) -> Result<Vec<Entry>, Error> {
+	pallet
+		.constants
+		.iter()
+		.map(|constant| {
+			Ok(Entry {
+				name: constant.name.clone(),
+				index: None,
+				signature: pallet_metadata
+					.constant_by_name(&constant.name)
+					.map(|c| type_name(metadata, c.ty()))
+					.unwrap_or_default(),
+				value: Some(
+					raw_value_to_string(&constant.value, "")
+						.map_err(|e| Error::MetadataParsingError(e.to_string()))?,
+				),
+			})
+		})
+		.collect()
+}
+
+// Compares the items of the same kind within a pallet, matching them by name.
+fn compare_entries(
+	changes: &mut Vec<MetadataChange>,
+	item: ItemKind,
+	prefix: &str,
+	old: Vec<Entry>,
+	new: Vec<Entry>,
+) {
+	let change = |change, name: &str, detail: Option<String>, breaking| MetadataChange {
+		change,
+		item,
+		path: format!("{prefix}{name}"),
+		detail,
+		breaking,
+	};
+	for old_entry in &old {
+		let Some(new_entry) = new.iter().find(|entry| entry.name == old_entry.name) else {
+			// Removing an error only affects how failures are reported.
+			changes.push(change(
+				ChangeKind::Removed,
+				&old_entry.name,
+				None,
+				item != ItemKind::Error,
+			));
+			continue;
+		};
+		if let (Some(old_index), Some(new_index)) = (old_entry.index, new_entry.index) &&
+			old_index != new_index
+		{
+			changes.push(change(
+				ChangeKind::Changed,
+				&old_entry.name,
+				Some(format!("index moved from {old_index} to {new_index}")),
+				true,
+			));
+		}
+		if old_entry.signature != new_entry.signature {
+			changes.push(change(
+				ChangeKind::Changed,
+				&old_entry.name,
+				Some(format!(
+					"type changed from `{}` to `{}`",
+					old_entry.signature, new_entry.signature
+				)),
+				true,
+			));
+		}
+		if old_entry.value != new_entry.value {
+			changes.push(change(
+				ChangeKind::Changed,
+				&old_entry.name,
+				Some(format!(
+					"value changed from {} to {}",
+					old_entry.value.as_deref().unwrap_or_default(),
+					new_entry.value.as_deref().unwrap_or_default()
+				)),
+				false,
+			));
+		}
+	}
+	for new_entry in new.iter().filter(|entry| !old.iter().any(|e| e.name == entry.name)) {
+		changes.push(change(ChangeKind::Added, &new_entry.name, None, false));
+	}
+}
+
+// Compares the layouts of the types present in both versions of the metadata, matching them by
+// path and type parameters. Types, or instances of generic types, which are only present in one
+// version are not reported, as the items using them are.
+fn compare_type_layouts(changes: &mut Vec<MetadataChange>, old: &Metadata, new: &Metadata) {
+	let new_layouts = type_layouts(new);
+	for (path, old_layout) in type_layouts(old) {
+		if let Some(new_layout) = new_layouts.get(&path) &&
+			&old_layout != new_layout
+		{
+			changes.push(MetadataChange {
+				change: ChangeKind::Changed,
+				item: ItemKind::Type,
+				path,
+				detail: Some(format!("layout changed from `{old_layout}` to `{new_layout}`")),
+				breaking: true,
+			});
+		}
+	}
+}
+
+// The layouts of the named types of the registry, by path and type parameters, e.g.
+// `frame_system::AccountInfo<u32, AccountData<u128>>`, so that each instance of a generic type is
+// compared with the same instance.
+fn type_layouts(metadata: &Metadata) -> BTreeMap<String, String> {
+	let registry = metadata.types();
+	let mut layouts = BTreeMap::new();
+	for ty in &registry.types {
+		let ty = &ty.ty;
+		match ty.path.segments.last() {
+			Some(name) if !AGGREGATED_TYPES.contains(&name.as_str()) => {},
+			_ => continue,
+		}
+		let path = format!("{}{}", ty.path.segments.join("::"), type_params(metadata, ty));
+		layouts.insert(path, type_layout(metadata, ty));
+	}
+	layouts
+}
+
+// The type parameters of a generic type, e.g. `<u32, AccountData<u128>>`.
+fn type_params(metadata: &Metadata, ty: &Type<PortableForm>) -> String {
+	if ty.type_params.is_empty() {
+		return String::new();
+	}
+	let params = ty
+		.type_params
+		.iter()
+		.map(|param| match param.ty.and_then(|param| metadata.types().resolve(param.id)) {
+			// Named types are identified by their name and parameters rather than their layout.
+			Some(param) => match param.path.segments.last() {
+				Some(name) => format!("{name}{}", type_params(metadata, param)),
+				None => format_type(param, metadata.types()),
+			},
+			None => param.name.clone(),
+		})
+		.collect::<Vec<_>>();
+	format!("<{}>", params.join(", "))
+}
+
+// The layout of a type: its fields, or its variants with their indices.
+fn type_layout(metadata: &Metadata, ty: &Type<PortableForm>) -> String {
+	match &ty.type_def {
+		TypeDef::Composite(composite) => fields_signature(metadata, &composite.fields),
+		TypeDef::Variant(variant) => variant
+			.variants
+			.iter()
+			.map(|variant| {
+				format!(
+					"{}{} = {}",
+					variant.name,
+					fields_signature(metadata, &variant.fields),
+					variant.index
+				)
+			})
+			.collect::<Vec<_>>()
+			.join(" | "),
+		_ => format_type(ty, metadata.types()),
+	}
+}
+
+// The fields of a call, event or error, with their types.
+fn variant_signature(metadata: &Metadata, variant: &Variant<PortableForm>) -> String {
+	fields_signature(metadata, &variant.fields)
+}
+
+// Fields with their types.
+fn fields_signature(metadata: &Metadata, fields: &[Field<PortableForm>]) -> String {
+	let fields = fields
+		.iter()
+		.map(|field| {
+			let ty = type_name(metadata, field.ty.id);
+			match &field.name {
+				Some(name) => format!("{name}: {ty}"),
+				None => ty,
+			}
+		})
+		.collect::<Vec<_>>();
+	format!("({})", fields.join(", "))
+}
+
+fn type_name(metadata: &Metadata, type_id: u32) -> String {
+	metadata
+		.types()
+		.resolve(type_id)
+		.map(|ty| format_type(ty, metadata.types()))
+		.unwrap_or_else(|| format!("#{type_id}"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	fn entry(name: &str, index: Option<u8>, signature: &str) -> Entry {
+		Entry { name: name.into(), index, signature: signature.into(), value: None }
+	}
+
+	#[test]
+	fn compare_entries_works() {
+		let mut changes = Vec::new();
+		compare_entries(
+			&mut changes,
+			ItemKind::Call,
+			"Balances::",
+			vec![
+				entry("transfer", Some(0), "(dest: MultiAddress, value: u128)"),
+				entry("burn", Some(1), "(value: u128)"),
+				entry("force_transfer", Some(2), "(value: u128)"),
+			],
+			vec![
+				entry("transfer", Some(0), "(dest: MultiAddress, value: u64)"),
+				entry("force_transfer", Some(3), "(value: u128)"),
+				entry("mint", Some(4), "(value: u128)"),
+			],
+		);
+		assert_eq!(
+			changes.iter().map(ToString::to_string).collect::<Vec<_>>(),
+			vec![
+				"~ Call Balances::transfer: type changed from `(dest: MultiAddress, value: u128)` \
+				 to `(dest: MultiAddress, value: u64)` [BREAKING]",
+				"- Call Balances::burn [BREAKING]",
+				"~ Call Balances::force_transfer: index moved from 2 to 3 [BREAKING]",
+				"+ Call Balances::mint",
+			]
+		);
+	}
+
+	#[test]
+	fn compare_entries_flags_breaking_changes_only() {
+		let mut changes = Vec::new();
+		let constant = |value: &str| Entry {
+			name: "ExistentialDeposit".into(),
+			signature: "u128".into(),
+			value: Some(value.into()),
+			..Default::default()
+		};
+		compare_entries(
+			&mut changes,
+			ItemKind::Constant,
+			"Balances::",
+			vec![constant("1")],
+			vec![constant("2")],
+		);
+		compare_entries(
+			&mut changes,
+			ItemKind::Error,
+			"Balances::",
+			vec![entry("VestingBalance", Some(0), "()")],
+			vec![],
+		);
+		let diff = MetadataDiff { changes };
+		assert_eq!(diff.changes.len(), 2);
+		assert_eq!(diff.breaking().count(), 0);
+		assert_eq!(
+			diff.render(),
+			"~ Constant Balances::ExistentialDeposit: value changed from 1 to 2\n- Error \
+			 Balances::VestingBalance"
+		);
+	}
+
+	#[tokio::test]
+	async fn type_layouts_are_keyed_by_instance() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let metadata = set_up_client(&node_url).await?.metadata();
+		let layouts = type_layouts(&metadata);
+		let (path, layout) = layouts
+			.iter()
+			.find(|(path, _)| path.starts_with("frame_system::AccountInfo<"))
+			.expect("the runtime uses `AccountInfo`");
+		assert!(path.ends_with(", AccountData<u128>>"), "{path}");
+		assert!(layout.starts_with("(nonce: "), "{layout}");
+		let layout = &layouts["sp_runtime::DispatchError"];
+		assert!(layout.starts_with("Other() = 0 | CannotLookup() = 1"), "{layout}");
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn diff_metadata_of_same_runtime_is_empty() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let metadata = set_up_client(&node_url).await?.metadata();
+		let diff = diff_metadata(&metadata, &metadata)?;
+		assert!(diff.is_empty(), "{}", diff.render());
+		Ok(())
+	}
+}
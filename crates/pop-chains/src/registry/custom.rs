@@ -0,0 +1,635 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{traits::Requires, *};
+use crate::{
+	merge_patch,
+	traits::{Args, Binary},
+};
+use pop_common::{
+	git::GitHub,
+	polkadot_sdk::sort_by_latest_semantic_version,
+	sourcing::{
+		ArchiveFileSpec,
+		GitHub::{ReleaseArchive, SourceCodeArchive},
+		Source,
+		traits::Source as SourceT,
+	},
+	target,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::{
+	fs,
+	path::{Path, PathBuf},
+};
+use strum::VariantArray as _;
+use url::Url;
+
+/// The default name of the release archive of a binary, where `{binary}` and `{target}` are
+/// replaced by the name of the binary and the target triple respectively.
+const DEFAULT_ARCHIVE: &str = "{binary}-{target}.tar.gz";
+
+/// The chains defined within a configuration file.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct ChainDefinitions {
+	#[serde(default, rename = "chain")]
+	chains: Vec<ChainDefinition>,
+}
+
+/// The definition of a chain, as provided by configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainDefinition {
+	/// The name of the chain, as used by `--parachain`.
+	pub name: String,
+	/// The relay chain the chain is registered for - e.g. `paseo`.
+	pub relay: String,
+	/// The default chain identifier.
+	pub id: Id,
+	/// The identifier of the chain, as used by the chain specification.
+	pub chain: String,
+	/// The name of the binary used to launch a node. Defaults to the file name of the binary
+	/// when sourced from a local path.
+	pub binary: Option<String>,
+	/// The source of the binary.
+	pub source: BinarySource,
+	/// The default arguments to be used when launching a node.
+	#[serde(default)]
+	pub args: Vec<String>,
+	/// A patch applied to the genesis state of the chain, as a JSON merge patch.
+	pub genesis: Option<Map<String, Value>>,
+	/// The chains required, by name, along with any patch applied to their genesis state.
+	#[serde(default)]
+	pub requires: HashMap<String, Map<String, Value>>,
+}
+
+/// The source of the binary of a chain defined by configuration.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum BinarySource {
+	/// An archive attached to a GitHub release.
+	#[serde(rename = "github")]
+	GitHub {
+		/// The url of the repository - e.g. `https://github.com/r0gue-io/pop-node`.
+		repository: String,
+		/// The release tag to be used, where `None` is latest.
+		tag: Option<String>,
+		/// If applicable, a pattern to be used to determine applicable releases - e.g.
+		/// `node-{version}`.
+		tag_pattern: Option<String>,
+		/// Whether pre-releases are to be used.
+		#[serde(default)]
+		prerelease: bool,
+		/// The version to use if an appropriate version cannot be resolved.
+		fallback: String,
+		/// The name of the archive (asset) to download, where `{binary}` and `{target}` are
+		/// replaced by the name of the binary and the target triple. Defaults to
+		/// `{binary}-{target}.tar.gz`.
+		archive: Option<String>,
+	},
+	/// A git repository, built from source.
+	Git {
+		/// The url of the repository.
+		url: String,
+		/// If applicable, the branch, tag or commit.
+		reference: Option<String>,
+		/// If applicable, the path to the manifest within the repository.
+		manifest: Option<PathBuf>,
+		/// The name of the package to be built. Defaults to the name of the binary.
+		package: Option<String>,
+	},
+	/// A local binary. Relative paths are resolved against the directory of the configuration
+	/// file.
+	Path(PathBuf),
+}
+
+/// A chain defined by configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Custom {
+	chain: Chain,
+	binary: String,
+	source: BinarySource,
+	args: Vec<String>,
+	genesis: Option<Map<String, Value>>,
+	requires: Vec<(ChainTypeId, Map<String, Value>)>,
+}
+
+impl Custom {
+	/// Whether the binary of the chain is local, rather than sourced.
+	pub fn is_local(&self) -> bool {
+		matches!(self.source, BinarySource::Path(_))
+	}
+}
+
+impl SourceT for Custom {
+	type Error = Error;
+	/// Defines the source of a binary.
+	fn source(&self) -> Result<Source, Error> {
+		let binary = self.binary();
+		match &self.source {
+			BinarySource::GitHub {
+				repository,
+				tag,
+				tag_pattern,
+				prerelease,
+				fallback,
+				archive,
+			} => {
+				let repo = GitHub::parse(repository)?;
+				let archive = archive
+					.as_deref()
+					.unwrap_or(DEFAULT_ARCHIVE)
+					.replace("{binary}", binary)
+					.replace("{target}", target()?);
+				Ok(Source::GitHub(ReleaseArchive {
+					owner: repo.org,
+					repository: repo.name,
+					tag: tag.clone(),
+					tag_pattern: tag_pattern.as_deref().map(Into::into),
+					prerelease: *prerelease,
+					version_comparator: sort_by_latest_semantic_version,
+					fallback: fallback.clone(),
+					archive,
+					contents: vec![ArchiveFileSpec::new(binary.into(), None, true)],
+					latest: None,
+				}))
+			},
+			BinarySource::Git { url, reference, manifest, package } => {
+				let url = Url::parse(url)
+					.map_err(|e| Error::RegistryError(format!("invalid url {url}: {e}")))?;
+				let package = package.clone().unwrap_or_else(|| binary.to_string());
+				// Download GitHub repositories as an archive rather than cloning
+				if url.host_str().is_some_and(|h| h.to_lowercase() == "github.com") {
+					let repo = GitHub::parse(url.as_str())?;
+					return Ok(Source::GitHub(SourceCodeArchive {
+						owner: repo.org,
+						repository: repo.name,
+						reference: reference.clone(),
+						manifest: manifest.clone(),
+						package,
+						artifacts: vec![binary.to_string()],
+					}));
+				}
+				Ok(Source::Git {
+					url,
+					reference: reference.clone(),
+					manifest: manifest.clone(),
+					package,
+					artifacts: vec![binary.to_string()],
+				})
+			},
+			BinarySource::Path(path) => Err(Error::RegistryError(format!(
+				"the binary of {} is local and cannot be sourced: {}",
+				self.name(),
+				path.display()
+			))),
+		}
+	}
+}
+
+impl Binary for Custom {
+	fn binary(&self) -> &str {
+		&self.binary
+	}
+}
+
+impl Args for Custom {
+	fn args(&self) -> Option<Vec<&str>> {
+		(!self.args.is_empty()).then(|| self.args.iter().map(String::as_str).collect())
+	}
+}
+
+impl GenesisOverrides for Custom {
+	fn genesis_overrides(&self) -> Option<Override> {
+		let genesis = self.genesis.clone()?;
+		Some(Box::new(move |overrides: &mut Map<String, Value>| patch(overrides, &genesis)))
+	}
+}
+
+impl Requires for Custom {
+	/// Defines the requirements of a chain, namely which other chains it depends on and any
+	/// corresponding overrides to genesis state.
+	fn requires(&self) -> Option<HashMap<ChainTypeId, Override>> {
+		(!self.requires.is_empty()).then(|| {
+			self.requires
+				.iter()
+				.map(|(chain, genesis)| {
+					let genesis = genesis.clone();
+					let r#override: Override =
+						Box::new(move |overrides: &mut Map<String, Value>| {
+							patch(overrides, &genesis)
+						});
+					(chain.clone(), r#override)
+				})
+				.collect()
+		})
+	}
+}
+
+impl AsChain for Custom {
+	fn as_chain(&self) -> &Chain {
+		&self.chain
+	}
+
+	fn as_chain_mut(&mut self) -> &mut Chain {
+		&mut self.chain
+	}
+}
+
+impl traits::Chain for Custom {
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn chain_type_id(&self) -> ChainTypeId {
+		ChainTypeId::custom(self.name())
+	}
+}
+
+/// Loads the chains defined within a configuration file, in either TOML or JSON format.
+///
+/// # Arguments
+/// * `path` - The path to the configuration file.
+pub fn load(path: &Path) -> Result<Vec<ChainDefinition>, Error> {
+	let contents = fs::read_to_string(path)?;
+	let definitions: ChainDefinitions = match path.extension().and_then(|e| e.to_str()) {
+		Some("json") => serde_json::from_str(&contents)?,
+		_ => toml_edit::de::from_str(&contents)?,
+	};
+	let dir = path.parent().unwrap_or(Path::new(""));
+	Ok(definitions
+		.chains
+		.into_iter()
+		.map(|mut definition| {
+			if let BinarySource::Path(path) = &mut definition.source &&
+				path.is_relative()
+			{
+				*path = dir.join(path.as_path());
+			}
+			definition
+		})
+		.collect())
+}
+
+// Resolves the definitions of chains against the registry, validating them and their
+// requirements.
+pub(super) fn resolve(
+	registry: Registry,
+	definitions: Vec<ChainDefinition>,
+) -> Result<CustomChains, Error> {
+	let relays = definitions
+		.iter()
+		.map(|definition| {
+			Relay::VARIANTS
+				.iter()
+				.find(|relay| relay.name().eq_ignore_ascii_case(&definition.relay))
+				.cloned()
+				.ok_or_else(|| {
+					Error::RegistryError(format!(
+						"unknown relay chain {} for {}",
+						definition.relay, definition.name
+					))
+				})
+		})
+		.collect::<Result<Vec<_>, _>>()?;
+
+	let mut chains: CustomChains = Vec::new();
+	for (relay, definition) in relays.iter().zip(&definitions) {
+		let name = definition.name.as_str();
+		if name.is_empty() ||
+			!name
+				.chars()
+				.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_".contains(c))
+		{
+			return Err(Error::RegistryError(format!(
+				"invalid chain name {name:?}, expected lowercase letters, digits, `-` or `_`"
+			)));
+		}
+		let registered = registry.get(relay).into_iter().flatten().any(|c| c.name() == name) ||
+			chains.iter().any(|(r, c)| r == relay && c.name() == name);
+		if registered {
+			return Err(Error::RegistryError(format!(
+				"{name} is already registered for {}",
+				relay.name()
+			)));
+		}
+
+		let requires = definition
+			.requires
+			.iter()
+			.map(|(required, genesis)| {
+				let chain = match registry
+					.get(relay)
+					.into_iter()
+					.flatten()
+					.find(|c| c.name() == required)
+				{
+					Some(chain) => chain.chain_type_id(),
+					None if relays
+						.iter()
+						.zip(&definitions)
+						.any(|(r, d)| r == relay && &d.name == required && d.name != name) =>
+						ChainTypeId::custom(required),
+					None =>
+						return Err(Error::RegistryError(format!(
+							"{name} requires {required}, which is not registered for {}",
+							relay.name()
+						))),
+				};
+				Ok((chain, genesis.clone()))
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		let binary = match (&definition.binary, &definition.source) {
+			(Some(binary), _) => binary.clone(),
+			(None, BinarySource::Path(path)) => path
+				.file_name()
+				.and_then(|f| f.to_str())
+				.ok_or_else(|| {
+					Error::RegistryError(format!("unable to determine binary of {name}"))
+				})?
+				.to_string(),
+			(None, _) =>
+				return Err(Error::RegistryError(format!("{name} does not specify a binary"))),
+		};
+
+		let chain = Custom {
+			chain: Chain::new(name, definition.id, definition.chain.as_str()),
+			binary: match &definition.source {
+				// Local binaries are launched via their path.
+				BinarySource::Path(path) => path.to_string_lossy().into_owned(),
+				_ => binary,
+			},
+			source: definition.source.clone(),
+			args: definition.args.clone(),
+			genesis: definition.genesis.clone(),
+			requires,
+		};
+		chains.push((relay.clone(), chain.into()));
+	}
+	Ok(chains)
+}
+
+// Applies a patch to genesis state, as a JSON merge patch.
+fn patch(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+	let mut merged = Value::Object(std::mem::take(target));
+	merge_patch(&mut merged, Value::Object(patch.clone()));
+	if let Value::Object(merged) = merged {
+		*target = merged;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use Relay::*;
+	use serde_json::json;
+	use std::io::Write;
+	use tempfile::NamedTempFile;
+
+	const CONFIG: &str = r#"
+[[chain]]
+name = "our-chain"
+relay = "paseo"
+id = 2000
+chain = "our-chain-local"
+binary = "our-node"
+args = ["-lxcm=trace"]
+
+[chain.source.github]
+repository = "https://github.com/our-org/our-chain"
+tag_pattern = "node-{version}"
+fallback = "v1.0.0"
+
+[chain.genesis.balances]
+balances = [["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1000]]
+
+[chain.requires.asset-hub.balances]
+balances = [["5Eg2fnsomjubNiqxnqSSeVwcmQYQzsHdyr79YhcJDKRYfPCL", 1000]]
+
+[[chain]]
+name = "local-chain"
+relay = "Paseo"
+id = 2001
+chain = "local-chain-dev"
+source.path = "bin/local-node"
+"#;
+
+	fn load_config() -> Result<(NamedTempFile, Vec<ChainDefinition>), Error> {
+		let mut file = tempfile::Builder::new().suffix(".toml").tempfile()?;
+		file.write_all(CONFIG.as_bytes())?;
+		let definitions = load(file.path())?;
+		Ok((file, definitions))
+	}
+
+	fn resolved(definitions: Vec<ChainDefinition>) -> Result<Vec<Custom>, Error> {
+		Ok(resolve(REGISTRAR(HashMap::new()), definitions)?
+			.into_iter()
+			.map(|(_, chain)| chain.as_any().downcast_ref::<Custom>().unwrap().clone())
+			.collect())
+	}
+
+	#[test]
+	fn load_works() -> Result<(), Error> {
+		let (file, definitions) = load_config()?;
+		assert_eq!(definitions.len(), 2);
+		assert_eq!(definitions[0].name, "our-chain");
+		assert_eq!(definitions[0].id, 2000);
+		assert_eq!(definitions[0].args, vec!["-lxcm=trace"]);
+		assert!(definitions[0].requires.contains_key("asset-hub"));
+		assert!(definitions[1].binary.is_none());
+		assert_eq!(
+			definitions[1].source,
+			BinarySource::Path(file.path().parent().unwrap().join("bin/local-node"))
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn load_json_works() -> Result<(), Error> {
+		let mut file = tempfile::Builder::new().suffix(".json").tempfile()?;
+		file.write_all(
+			json!({"chain": [{
+				"name": "our-chain",
+				"relay": "polkadot",
+				"id": 2000,
+				"chain": "our-chain-local",
+				"binary": "our-node",
+				"source": {"git": {"url": "https://gitlab.com/our-org/our-chain"}}
+			}]})
+			.to_string()
+			.as_bytes(),
+		)?;
+		let definitions = load(file.path())?;
+		assert_eq!(
+			definitions[0].source,
+			BinarySource::Git {
+				url: "https://gitlab.com/our-org/our-chain".into(),
+				reference: None,
+				manifest: None,
+				package: None
+			}
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn load_rejects_unknown_fields() -> Result<(), Error> {
+		let mut file = tempfile::Builder::new().suffix(".toml").tempfile()?;
+		file.write_all(CONFIG.replace("args =", "arguments =").as_bytes())?;
+		assert!(matches!(load(file.path()), Err(Error::TomlError(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_works() -> Result<(), Error> {
+		let (file, definitions) = load_config()?;
+		let chains = resolve(REGISTRAR(HashMap::new()), definitions)?;
+		assert!(chains.iter().all(|(relay, _)| relay == &Paseo));
+
+		let chain = &chains[0].1;
+		assert_eq!(chain.name(), "our-chain");
+		assert_eq!(chain.id(), 2000);
+		assert_eq!(chain.chain(), "our-chain-local");
+		assert_eq!(chain.binary(), "our-node");
+		assert_eq!(chain.args().unwrap(), vec!["-lxcm=trace"]);
+		assert_eq!(chain.chain_type_id(), ChainTypeId::custom("our-chain"));
+		let requires = chain.requires().unwrap();
+		assert!(requires.contains_key(&ChainTypeId::of::<AssetHub>()));
+
+		let local = &chains[1].1;
+		let path = file.path().parent().unwrap().join("bin/local-node");
+		assert_eq!(local.binary(), path.to_string_lossy());
+		assert!(local.as_any().downcast_ref::<Custom>().unwrap().is_local());
+		assert!(local.args().is_none());
+		assert!(local.genesis_overrides().is_none());
+		assert!(local.requires().is_none());
+		assert!(matches!(local.source(), Err(Error::RegistryError(..))));
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_requires_custom_chain() -> Result<(), Error> {
+		let (_file, mut definitions) = load_config()?;
+		definitions[1].requires.insert("our-chain".into(), Map::new());
+		let chains = resolved(definitions)?;
+		assert!(chains[1].requires().unwrap().contains_key(&ChainTypeId::custom("our-chain")));
+		Ok(())
+	}
+
+	#[test]
+	fn resolve_rejects_invalid_definitions() -> Result<(), Error> {
+		let (_file, definitions) = load_config()?;
+		let invalid = |update: fn(&mut ChainDefinition)| {
+			let mut definitions = definitions.clone();
+			update(&mut definitions[0]);
+			resolved(definitions).unwrap_err().to_string()
+		};
+		assert!(invalid(|d| d.relay = "rococo".into()).contains("unknown relay chain rococo"));
+		assert!(invalid(|d| d.name = "Our Chain".into()).contains("invalid chain name"));
+		assert!(invalid(|d| d.name = "pop".into()).contains("pop is already registered"));
+		assert!(invalid(|d| d.name = "local-chain".into()).contains("already registered"));
+		assert!(invalid(|d| d.binary = None).contains("does not specify a binary"));
+		assert!(
+			invalid(|d| {
+				d.requires.insert("our-chain".into(), Map::new());
+			})
+			.contains("requires our-chain, which is not registered")
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn source_works() -> Result<(), Error> {
+		let (_file, definitions) = load_config()?;
+		let chains = resolved(definitions.clone())?;
+		assert!(matches!(
+			chains[0].source()?,
+			Source::GitHub(ReleaseArchive { owner, repository, tag, tag_pattern, prerelease, fallback, archive, contents, .. })
+				if owner == "our-org" &&
+					repository == "our-chain" &&
+					tag.is_none() &&
+					tag_pattern == Some("node-{version}".into()) &&
+					!prerelease &&
+					fallback == "v1.0.0" &&
+					archive == format!("our-node-{}.tar.gz", target().unwrap()) &&
+					contents == vec![ArchiveFileSpec::new("our-node".into(), None, true)]
+		));
+
+		let mut definitions = definitions;
+		definitions[0].source = BinarySource::Git {
+			url: "https://github.com/our-org/our-chain".into(),
+			reference: Some("v1.0.0".into()),
+			manifest: None,
+			package: Some("our-chain-node".into()),
+		};
+		definitions[1].binary = Some("local-node".into());
+		definitions[1].source = BinarySource::Git {
+			url: "https://gitlab.com/our-org/our-chain".into(),
+			reference: None,
+			manifest: None,
+			package: None,
+		};
+		let chains = resolved(definitions)?;
+		assert!(matches!(
+			chains[0].source()?,
+			Source::GitHub(SourceCodeArchive { owner, repository, reference, package, artifacts, .. })
+				if owner == "our-org" &&
+					repository == "our-chain" &&
+					reference == Some("v1.0.0".into()) &&
+					package == "our-chain-node" &&
+					artifacts == vec!["our-node".to_string()]
+		));
+		assert!(matches!(
+			chains[1].source()?,
+			Source::Git { url, package, artifacts, .. }
+				if url.as_str() == "https://gitlab.com/our-org/our-chain" &&
+					package == "local-node" &&
+					artifacts == vec!["local-node".to_string()]
+		));
+		Ok(())
+	}
+
+	#[test]
+	fn genesis_overrides_work() -> Result<(), Error> {
+		let (_file, definitions) = load_config()?;
+		let chains = resolved(definitions)?;
+		let mut overrides = Map::new();
+		overrides.insert("balances".into(), json!({"balances": [["alice", 1]]}));
+		chains[0].genesis_overrides().unwrap()(&mut overrides);
+		assert_eq!(
+			Value::Object(overrides),
+			json!({"balances": {"balances": [
+				["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY", 1000]
+			]}})
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn patch_works() {
+		let mut target = json!({
+			"balances": {"balances": [["alice", 1]]},
+			"sudo": {"key": "alice"},
+			"parachainInfo": {"parachainId": 1000}
+		})
+		.as_object()
+		.cloned()
+		.unwrap();
+		let value = json!({
+			"balances": {"balances": [["bob", 2]]},
+			"sudo": null,
+			"parachainInfo": {"parachainId": 2000},
+			"aura": {"authorities": ["alice"]}
+		});
+		patch(&mut target, value.as_object().unwrap());
+		assert_eq!(
+			Value::Object(target),
+			json!({
+				"balances": {"balances": [["bob", 2]]},
+				"parachainInfo": {"parachainId": 2000},
+				"aura": {"authorities": ["alice"]}
+			})
+		);
+	}
+}
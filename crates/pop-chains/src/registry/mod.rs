@@ -1,11 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use crate::{
+	Error,
 	traits::{
 		Args, Binary, Chain as ChainT, ChainSpec, GenesisOverrides, Id, Node, Override, Port,
 	},
 	up::Relay,
 };
+pub use custom::*;
 pub use pop::*;
 use pop_common::sourcing::traits::Source;
 use std::{
@@ -36,11 +38,38 @@ macro_rules! impl_chain {
 	};
 }
 
+mod custom;
 mod pop;
 mod system;
 
-pub(crate) type ChainTypeId = TypeId;
 type Registry = HashMap<Relay, Vec<Box<dyn traits::Chain>>>;
+type CustomChains = Vec<(Relay, Box<dyn traits::Chain>)>;
+
+static REGISTRY: OnceLock<Registry> = OnceLock::new();
+static CUSTOM: OnceLock<CustomChains> = OnceLock::new();
+
+/// Identifies a type of chain, used to define the requirements of a chain.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ChainTypeId(ChainType);
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+enum ChainType {
+	// A chain implemented by a type.
+	Type(TypeId),
+	// A chain defined by configuration, identified by its name.
+	Custom(String),
+}
+
+impl ChainTypeId {
+	/// The identifier of a chain implemented by the type `T`.
+	pub fn of<T: Any>() -> Self {
+		Self(ChainType::Type(TypeId::of::<T>()))
+	}
+
+	fn custom(name: impl Into<String>) -> Self {
+		Self(ChainType::Custom(name.into()))
+	}
+}
 
 const REGISTRAR: fn(Registry) -> Registry = |mut registry| {
 	use Relay::*;
@@ -100,10 +129,33 @@ const REGISTRAR: fn(Registry) -> Registry = |mut registry| {
 /// # Arguments
 /// * `relay` - The relay chain.
 pub fn chains(relay: &Relay) -> &'static [Box<dyn traits::Chain>] {
-	static REGISTRY: OnceLock<HashMap<Relay, Vec<Box<dyn traits::Chain>>>> = OnceLock::new();
 	static EMPTY: Vec<Box<dyn traits::Chain>> = Vec::new();
 
-	REGISTRY.get_or_init(|| REGISTRAR(HashMap::new())).get(relay).unwrap_or(&EMPTY)
+	REGISTRY
+		.get_or_init(|| {
+			let mut registry = REGISTRAR(HashMap::new());
+			for (relay, chain) in CUSTOM.get().into_iter().flatten() {
+				registry.entry(relay.clone()).or_default().push(chain.clone());
+			}
+			registry
+		})
+		.get(relay)
+		.unwrap_or(&EMPTY)
+}
+
+/// Registers chains defined by configuration, making them available alongside the built-in
+/// chains. Chains can only be registered once, before the registry is first used.
+///
+/// # Arguments
+/// * `definitions` - The definitions of the chains.
+pub fn register(definitions: Vec<ChainDefinition>) -> Result<(), Error> {
+	if REGISTRY.get().is_some() || CUSTOM.get().is_some() {
+		return Err(Error::RegistryError("chains have already been registered".into()));
+	}
+	let chains = resolve(REGISTRAR(HashMap::new()), definitions)?;
+	CUSTOM
+		.set(chains)
+		.map_err(|_| Error::RegistryError("chains have already been registered".into()))
 }
 
 // A base type, used by chain implementations to reduce boilerplate code.
@@ -213,6 +265,12 @@ pub mod traits {
 	{
 		/// Allows casting to [`Any`].
 		fn as_any(&self) -> &dyn Any;
+
+		/// The identifier of the type of the chain, used to resolve the requirements of other
+		/// chains.
+		fn chain_type_id(&self) -> ChainTypeId {
+			ChainTypeId(ChainType::Type(self.as_any().type_id()))
+		}
 	}
 
 	/// A helper trait for ensuring [Chain] trait objects can be cloned.
@@ -348,6 +406,14 @@ mod tests {
 		assert_eq!(bridge_hub.as_any().type_id(), TypeId::of::<BridgeHub>());
 	}
 
+	#[test]
+	fn chain_type_id_works() {
+		let asset_hub: Box<dyn traits::Chain> = Box::new(AssetHub::new(1_000, Paseo));
+		assert_eq!(asset_hub.chain_type_id(), ChainTypeId::of::<AssetHub>());
+		assert_ne!(asset_hub.chain_type_id(), ChainTypeId::of::<BridgeHub>());
+		assert_ne!(asset_hub.chain_type_id(), ChainTypeId::custom("asset-hub"));
+	}
+
 	fn contains<T: 'static>(registry: &[Box<dyn traits::Chain>], id: Id) -> bool {
 		registry
 			.iter()
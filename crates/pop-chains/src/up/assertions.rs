@@ -0,0 +1,567 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::live::query_node;
+use crate::{ChainWatcher, DecodedValue, EventFilter, Heads, errors::Error, set_up_client};
+use regex::Regex;
+use scale::Decode;
+use scale_value::{Composite, ValueDef};
+use serde::Deserialize;
+use sp_core::twox_128;
+use std::{
+	fmt::{Display, Formatter},
+	path::Path,
+	time::{Duration, Instant},
+};
+use subxt::{
+	SubstrateConfig,
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+};
+use tokio::time::{sleep, timeout};
+use zombienet_sdk::{LocalFileSystem, Network, NetworkNode};
+
+/// The default number of seconds to wait for an assertion to hold.
+const DEFAULT_TIMEOUT: u64 = 300;
+/// How often a node is queried while waiting for an assertion to hold.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// A suite of assertions run against a network, as defined in YAML.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct TestSuite {
+	/// The name of the suite, as used by reports.
+	pub name: Option<String>,
+	/// The default number of seconds to wait for an assertion to hold.
+	#[serde(default = "default_timeout")]
+	pub timeout: u64,
+	/// The assertions, run in order.
+	pub assertions: Vec<TestCase>,
+}
+
+impl TestSuite {
+	/// Loads a suite of assertions from a YAML file.
+	///
+	/// # Arguments
+	/// * `path` - The path to the file.
+	pub fn from_path(path: &Path) -> Result<Self, Error> {
+		Self::parse(&std::fs::read_to_string(path)?)
+	}
+
+	/// Parses a suite of assertions from YAML.
+	///
+	/// # Arguments
+	/// * `yaml` - The definition of the suite.
+	pub fn parse(yaml: &str) -> Result<Self, Error> {
+		let suite: Self =
+			serde_norway::from_str(yaml).map_err(|e| Error::NetworkTestError(e.to_string()))?;
+		if suite.assertions.is_empty() {
+			return Err(Error::NetworkTestError("no assertions defined".into()));
+		}
+		// Validate any patterns before a network is launched.
+		for case in &suite.assertions {
+			if let Assertion::Log { pattern, .. } = &case.assertion {
+				Regex::new(pattern).map_err(|e| {
+					Error::NetworkTestError(format!("invalid pattern of {}: {e}", case.name()))
+				})?;
+			}
+		}
+		Ok(suite)
+	}
+
+	/// The name of the suite.
+	pub fn name(&self) -> &str {
+		self.name.as_deref().unwrap_or("network")
+	}
+}
+
+/// An assertion about a node of a network.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct TestCase {
+	/// The name of the assertion, as used by reports. Defaults to a description of the
+	/// assertion.
+	pub name: Option<String>,
+	/// The name of the node the assertion is made against.
+	pub node: String,
+	/// The number of seconds to wait for the assertion to hold, overriding the default of the
+	/// suite.
+	pub timeout: Option<u64>,
+	/// The assertion.
+	#[serde(flatten)]
+	pub assertion: Assertion,
+}
+
+impl TestCase {
+	/// The name of the assertion.
+	pub fn name(&self) -> String {
+		self.name
+			.clone()
+			.unwrap_or_else(|| format!("{}: {}", self.node, self.assertion))
+	}
+
+	/// Waits for the assertion to hold against the network.
+	///
+	/// # Arguments
+	/// * `network` - The network.
+	/// * `timeout` - The default number of seconds to wait for the assertion to hold.
+	pub async fn run(&self, network: &Network<LocalFileSystem>, timeout: u64) -> TestResult {
+		let start = Instant::now();
+		let failure = match network.get_node(&self.node) {
+			Ok(node) => self.wait(node, self.timeout.unwrap_or(timeout)).await.err(),
+			Err(_) => Some(Error::NodeNotFound(self.node.clone()).to_string()),
+		};
+		TestResult {
+			name: self.name(),
+			node: self.node.clone(),
+			duration: start.elapsed(),
+			failure,
+		}
+	}
+
+	// Waits for the assertion to hold, returning a description of the failure otherwise.
+	async fn wait(&self, node: &NetworkNode, seconds: u64) -> Result<(), String> {
+		let mut observed = None;
+		match timeout(Duration::from_secs(seconds), self.assertion.holds(node, &mut observed)).await
+		{
+			Ok(result) => result.map_err(|e| e.to_string()),
+			Err(_) => Err(format!(
+				"{} did not hold within {seconds}s{}",
+				self.assertion,
+				observed.map(|o| format!(", last observed {o}")).unwrap_or_default()
+			)),
+		}
+	}
+}
+
+/// An assertion about a node.
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Assertion {
+	/// The block height of the node reaches a number.
+	BlockHeight {
+		/// The minimum block number.
+		at_least: u64,
+		/// Whether the finalized block is used, rather than the best block.
+		#[serde(default)]
+		finalized: bool,
+	},
+	/// A metric reported by the Prometheus endpoint of the node meets a threshold.
+	Metric {
+		/// The name of the metric, including any labels - e.g. `block_height{status="best"}`.
+		name: String,
+		/// The minimum value.
+		at_least: Option<f64>,
+		/// The maximum value.
+		at_most: Option<f64>,
+		/// The exact value.
+		equals: Option<f64>,
+	},
+	/// The logs of the node contain lines matching a pattern.
+	Log {
+		/// The regular expression matched against each line.
+		pattern: String,
+		/// The minimum number of matching lines.
+		#[serde(default = "one")]
+		count: usize,
+	},
+	/// A parachain is registered with the relay chain, as seen by a relay chain node.
+	ParachainRegistered(u32),
+	/// XCM messages are processed successfully by the node, following the start of the
+	/// assertion.
+	XcmDelivered {
+		/// The minimum number of messages.
+		#[serde(default = "one")]
+		count: usize,
+	},
+}
+
+impl Assertion {
+	// Waits until the assertion holds, recording the latest observation of the node.
+	async fn holds(&self, node: &NetworkNode, observed: &mut Option<String>) -> Result<(), Error> {
+		match self {
+			Assertion::BlockHeight { at_least, finalized } => loop {
+				if let Ok((best, finalized_block, _)) = query_node(node.ws_uri()).await {
+					let height = if *finalized { finalized_block } else { best };
+					*observed = Some(format!("#{height}"));
+					if height >= *at_least {
+						return Ok(());
+					}
+				}
+				sleep(POLL_INTERVAL).await;
+			},
+			Assertion::Metric { name, .. } => loop {
+				if let Ok(value) = node.reports(name.as_str()).await {
+					*observed = Some(value.to_string());
+					if self.within_threshold(value) {
+						return Ok(());
+					}
+				}
+				sleep(POLL_INTERVAL).await;
+			},
+			Assertion::Log { pattern, count } => {
+				let pattern =
+					Regex::new(pattern).map_err(|e| Error::NetworkTestError(e.to_string()))?;
+				loop {
+					if let Ok(logs) = node.logs().await {
+						let matches = logs.lines().filter(|line| pattern.is_match(line)).count();
+						*observed = Some(format!("{matches} matching lines"));
+						if matches >= *count {
+							return Ok(());
+						}
+					}
+					sleep(POLL_INTERVAL).await;
+				}
+			},
+			Assertion::ParachainRegistered(para_id) => loop {
+				if let Ok(parachains) = parachains(node.ws_uri()).await {
+					*observed = Some(format!("{parachains:?}"));
+					if parachains.contains(para_id) {
+						return Ok(());
+					}
+				}
+				sleep(POLL_INTERVAL).await;
+			},
+			Assertion::XcmDelivered { count } => {
+				let client = set_up_client(node.ws_uri()).await?;
+				let filter = EventFilter {
+					pallet: Some("MessageQueue".into()),
+					event: Some("Processed".into()),
+					..Default::default()
+				};
+				let mut watcher = ChainWatcher::new(client, Heads::Best, filter, None).await?;
+				let mut delivered = 0;
+				while let Some(update) = watcher.next().await {
+					delivered +=
+						update?.events.iter().filter(|event| processed(&event.fields)).count();
+					*observed = Some(format!("{delivered} messages"));
+					if delivered >= *count {
+						return Ok(());
+					}
+				}
+				Err(Error::NetworkTestError(format!("{} stopped producing blocks", node.name())))
+			},
+		}
+	}
+
+	// Whether a value of a metric is within the thresholds of the assertion.
+	fn within_threshold(&self, value: f64) -> bool {
+		match self {
+			Assertion::Metric { at_least, at_most, equals, .. } =>
+				at_least.is_none_or(|min| value >= min) &&
+					at_most.is_none_or(|max| value <= max) &&
+					equals.is_none_or(|expected| value == expected),
+			_ => false,
+		}
+	}
+}
+
+impl Display for Assertion {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Assertion::BlockHeight { at_least, finalized } => write!(
+				f,
+				"{} block height >= {at_least}",
+				if *finalized { "finalized" } else { "best" }
+			),
+			Assertion::Metric { name, at_least, at_most, equals } => {
+				let thresholds: Vec<_> = [(">=", at_least), ("<=", at_most), ("==", equals)]
+					.into_iter()
+					.filter_map(|(op, value)| value.map(|value| format!("{op} {value}")))
+					.collect();
+				write!(f, "metric {name} {}", thresholds.join(" and "))
+			},
+			Assertion::Log { pattern, count } => write!(f, "log matches `{pattern}` {count}x"),
+			Assertion::ParachainRegistered(para_id) =>
+				write!(f, "parachain {para_id} is registered"),
+			Assertion::XcmDelivered { count } => write!(f, "{count} XCM messages delivered"),
+		}
+	}
+}
+
+/// The outcome of an assertion.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestResult {
+	/// The name of the assertion.
+	pub name: String,
+	/// The name of the node the assertion was made against.
+	pub node: String,
+	/// The time taken for the assertion to hold or fail.
+	pub duration: Duration,
+	/// A description of the failure, if the assertion did not hold.
+	pub failure: Option<String>,
+}
+
+/// The outcomes of a suite of assertions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TestReport {
+	/// The name of the suite.
+	pub name: String,
+	/// The outcome of each assertion, in order.
+	pub results: Vec<TestResult>,
+}
+
+impl TestReport {
+	/// The number of assertions which did not hold.
+	pub fn failures(&self) -> usize {
+		self.results.iter().filter(|result| result.failure.is_some()).count()
+	}
+
+	/// The report in JUnit XML format.
+	pub fn junit(&self) -> String {
+		let name = escape(&self.name);
+		let tests = self.results.len();
+		let failures = self.failures();
+		let time = self.results.iter().map(|result| result.duration).sum::<Duration>();
+		let time = time.as_secs_f64();
+		let mut xml = format!(
+			"<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+			<testsuites name=\"{name}\" tests=\"{tests}\" failures=\"{failures}\" time=\"{time:.3}\">\n\
+			\x20 <testsuite name=\"{name}\" tests=\"{tests}\" failures=\"{failures}\" time=\"{time:.3}\">\n"
+		);
+		for result in &self.results {
+			xml.push_str(&format!(
+				"    <testcase name=\"{}\" classname=\"{}\" time=\"{:.3}\"",
+				escape(&result.name),
+				escape(&result.node),
+				result.duration.as_secs_f64()
+			));
+			match &result.failure {
+				Some(failure) => xml.push_str(&format!(
+					">\n      <failure message=\"{}\"/>\n    </testcase>\n",
+					escape(failure)
+				)),
+				None => xml.push_str("/>\n"),
+			}
+		}
+		xml.push_str("  </testsuite>\n</testsuites>\n");
+		xml
+	}
+}
+
+fn default_timeout() -> u64 {
+	DEFAULT_TIMEOUT
+}
+
+fn one() -> usize {
+	1
+}
+
+// Escapes text for use within XML attributes.
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+		.replace('"', "&quot;")
+		.replace('\'', "&apos;")
+}
+
+// Whether the fields of a `MessageQueue::Processed` event report the message as successful.
+fn processed(fields: &DecodedValue) -> bool {
+	matches!(&fields.value, ValueDef::Composite(Composite::Named(fields))
+		if fields.iter().any(|(name, value)| name == "success" && value.as_bool() == Some(true)))
+}
+
+// The parachains registered with the relay chain, as stored by `Paras::Parachains`.
+async fn parachains(ws_uri: &str) -> Result<Vec<u32>, Error> {
+	let rpc = LegacyRpcMethods::<SubstrateConfig>::new(
+		RpcClient::from_url(ws_uri)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+	);
+	let value = rpc
+		.state_get_storage(&parachains_key(), None)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?;
+	value
+		.map(|value| Vec::<u32>::decode(&mut value.as_slice()))
+		.transpose()
+		.map(Option::unwrap_or_default)
+		.map_err(|e| Error::DecodingError(e.to_string()))
+}
+
+// The storage key of `Paras::Parachains`.
+fn parachains_key() -> Vec<u8> {
+	let mut key = twox_128("Paras".as_bytes()).to_vec();
+	key.extend(twox_128("Parachains".as_bytes()));
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use scale_value::Value;
+	use subxt::utils::to_hex;
+
+	const SUITE: &str = r#"
+name: pop
+timeout: 120
+assertions:
+  - node: alice
+    block_height: { at_least: 10, finalized: true }
+  - name: collator produces blocks
+    node: pop-collator
+    metric: { name: 'block_height{status="best"}', at_least: 5 }
+    timeout: 60
+  - node: alice
+    log: { pattern: 'Imported #\d+' }
+  - node: alice
+    parachain_registered: 4001
+  - node: asset-hub-collator
+    xcm_delivered: {}
+"#;
+
+	#[test]
+	fn parse_works() -> Result<()> {
+		let suite = TestSuite::parse(SUITE)?;
+		assert_eq!(suite.name(), "pop");
+		assert_eq!(suite.timeout, 120);
+		assert_eq!(
+			suite.assertions.iter().map(|case| case.assertion.clone()).collect::<Vec<_>>(),
+			vec![
+				Assertion::BlockHeight { at_least: 10, finalized: true },
+				Assertion::Metric {
+					name: "block_height{status=\"best\"}".into(),
+					at_least: Some(5.0),
+					at_most: None,
+					equals: None
+				},
+				Assertion::Log { pattern: "Imported #\\d+".into(), count: 1 },
+				Assertion::ParachainRegistered(4001),
+				Assertion::XcmDelivered { count: 1 },
+			]
+		);
+		assert_eq!(suite.assertions[0].name(), "alice: finalized block height >= 10");
+		assert_eq!(suite.assertions[1].name(), "collator produces blocks");
+		assert_eq!(suite.assertions[1].timeout, Some(60));
+		Ok(())
+	}
+
+	#[test]
+	fn parse_uses_defaults() -> Result<()> {
+		let suite =
+			TestSuite::parse("assertions:\n  - node: alice\n    block_height: { at_least: 1 }\n")?;
+		assert_eq!(suite.name(), "network");
+		assert_eq!(suite.timeout, DEFAULT_TIMEOUT);
+		assert_eq!(suite.assertions[0].timeout, None);
+		assert_eq!(
+			suite.assertions[0].assertion,
+			Assertion::BlockHeight { at_least: 1, finalized: false }
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn parse_rejects_invalid_suites() {
+		for (yaml, expected) in [
+			("assertions: []", "no assertions defined"),
+			("assertions:\n  - node: alice\n    unknown: 1\n", "Assertion"),
+			("assertions:\n  - node: alice\n    log: { pattern: '(' }\n", "invalid pattern"),
+			("assertion: []", "unknown field"),
+		] {
+			let error = TestSuite::parse(yaml).unwrap_err();
+			assert!(matches!(error, Error::NetworkTestError(..)));
+			assert!(error.to_string().contains(expected), "{error}");
+		}
+	}
+
+	#[test]
+	fn assertion_display_works() {
+		for (assertion, expected) in [
+			(Assertion::BlockHeight { at_least: 5, finalized: false }, "best block height >= 5"),
+			(
+				Assertion::Metric {
+					name: "peers".into(),
+					at_least: Some(1.0),
+					at_most: Some(3.0),
+					equals: None,
+				},
+				"metric peers >= 1 and <= 3",
+			),
+			(Assertion::Log { pattern: "ready".into(), count: 2 }, "log matches `ready` 2x"),
+			(Assertion::ParachainRegistered(1000), "parachain 1000 is registered"),
+			(Assertion::XcmDelivered { count: 1 }, "1 XCM messages delivered"),
+		] {
+			assert_eq!(assertion.to_string(), expected);
+		}
+	}
+
+	#[test]
+	fn within_threshold_works() {
+		let metric = |at_least, at_most, equals| Assertion::Metric {
+			name: "peers".into(),
+			at_least,
+			at_most,
+			equals,
+		};
+		assert!(metric(Some(1.0), None, None).within_threshold(1.0));
+		assert!(!metric(Some(1.0), None, None).within_threshold(0.0));
+		assert!(metric(None, Some(3.0), None).within_threshold(3.0));
+		assert!(!metric(Some(1.0), Some(3.0), None).within_threshold(4.0));
+		assert!(metric(None, None, Some(2.0)).within_threshold(2.0));
+		assert!(!metric(None, None, Some(2.0)).within_threshold(2.5));
+		assert!(metric(None, None, None).within_threshold(0.0));
+		assert!(!Assertion::ParachainRegistered(1000).within_threshold(1000.0));
+	}
+
+	#[test]
+	fn junit_works() {
+		let report = TestReport {
+			name: "pop".into(),
+			results: vec![
+				TestResult {
+					name: "alice: best block height >= 5".into(),
+					node: "alice".into(),
+					duration: Duration::from_millis(1500),
+					failure: None,
+				},
+				TestResult {
+					name: "log".into(),
+					node: "bob".into(),
+					duration: Duration::from_secs(2),
+					failure: Some("log matches `<\"a\" & 'b'>` 1x did not hold".into()),
+				},
+			],
+		};
+		assert_eq!(report.failures(), 1);
+		assert_eq!(
+			report.junit(),
+			r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="pop" tests="2" failures="1" time="3.500">
+  <testsuite name="pop" tests="2" failures="1" time="3.500">
+    <testcase name="alice: best block height &gt;= 5" classname="alice" time="1.500"/>
+    <testcase name="log" classname="bob" time="2.000">
+      <failure message="log matches `&lt;&quot;a&quot; &amp; &apos;b&apos;&gt;` 1x did not hold"/>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+		);
+	}
+
+	#[test]
+	fn processed_works() {
+		let event = |success: bool| {
+			Value::named_composite([
+				("id", Value::unnamed_composite([])),
+				("success", Value::bool(success)),
+			])
+			.map_context(|_| 0)
+		};
+		assert!(processed(&event(true)));
+		assert!(!processed(&event(false)));
+		assert!(!processed(&Value::unnamed_composite([Value::bool(true)]).map_context(|_| 0)));
+	}
+
+	#[test]
+	fn parachains_key_works() {
+		let key = parachains_key();
+		assert_eq!(key.len(), 32);
+		assert_eq!(to_hex(&key[..16]), "0xcd710b30bd2eab0352ddcc26417aa194");
+	}
+
+	#[tokio::test]
+	async fn parachains_is_empty_without_paras_pallet() -> Result<()> {
+		assert!(parachains(&shared_substrate_ws_url().await).await?.is_empty());
+		Ok(())
+	}
+}
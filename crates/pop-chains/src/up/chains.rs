@@ -1,7 +1,11 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use super::{Binary, Relay, chain_specs::chain_spec_generator};
-use crate::{Error, registry, registry::System, traits::Binary as BinaryT};
+use crate::{
+	Error, registry,
+	registry::{Custom, System},
+	traits::Binary as BinaryT,
+};
 use pop_common::sourcing::{filters::prefix, traits::*};
 use std::path::Path;
 
@@ -60,6 +64,10 @@ pub(super) async fn from(
 	cache: &Path,
 ) -> Result<Option<super::Chain>, Error> {
 	if let Some(para) = registry::chains(relay).iter().find(|p| p.binary() == command) {
+		// Chains defined with a local binary are launched from its path.
+		if para.as_any().downcast_ref::<Custom>().is_some_and(Custom::is_local) {
+			return Ok(None);
+		}
 		let name = para.binary().to_string();
 		let source =
 			para.source()?.resolve(&name, version, cache, |f| prefix(f, &name)).await.into();
@@ -0,0 +1,226 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{DynamicPayload, call::sign_and_submit_sudo_extrinsic, errors::Error};
+use scale::Encode;
+use serde::Serialize;
+use sp_core::{twox_64, twox_128};
+use std::fmt::{Display, Formatter};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	dynamic::{self, Value},
+};
+
+/// A channel for messages from one parachain to another, as tracked by the relay chain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Serialize)]
+pub struct HrmpChannel {
+	/// The identifier of the parachain sending messages.
+	pub sender: u32,
+	/// The identifier of the parachain receiving messages.
+	pub recipient: u32,
+}
+
+impl HrmpChannel {
+	// The storage key of the channel within `Hrmp::HrmpChannels`, a `Twox64Concat` map.
+	fn key(&self) -> Vec<u8> {
+		let id = self.encode();
+		let mut key = twox_128("Hrmp".as_bytes()).to_vec();
+		key.extend(twox_128("HrmpChannels".as_bytes()));
+		key.extend(twox_64(&id));
+		key.extend(id);
+		key
+	}
+}
+
+impl Display for HrmpChannel {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{} -> {}", self.sender, self.recipient)
+	}
+}
+
+/// Whether a channel is open, according to the latest block of the relay chain.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `channel` - The channel.
+pub async fn hrmp_channel_exists(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	channel: HrmpChannel,
+) -> Result<bool, Error> {
+	Ok(relay_chain
+		.storage()
+		.at_latest()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.fetch_raw(channel.key())
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.is_some())
+}
+
+/// Opens a channel between two parachains through the `Sudo` pallet of the relay chain, using
+/// `Hrmp::force_open_hrmp_channel`. The channel is opened at the next session change.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `url` - Endpoint of the relay chain node.
+/// * `channel` - The channel to open.
+/// * `max_capacity` - The maximum number of messages in the channel.
+/// * `max_message_size` - The maximum size of a message in the channel.
+/// * `suri` - The secret URI of the sudo account.
+pub async fn open_hrmp_channel(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	channel: HrmpChannel,
+	max_capacity: u32,
+	max_message_size: u32,
+	suri: &str,
+) -> Result<String, Error> {
+	ensure_sudo(relay_chain)?;
+	let open = dynamic::tx(
+		"Hrmp",
+		"force_open_hrmp_channel",
+		vec![
+			Value::u128(channel.sender.into()),
+			Value::u128(channel.recipient.into()),
+			Value::u128(max_capacity.into()),
+			Value::u128(max_message_size.into()),
+		],
+	);
+	sign_and_submit_sudo_extrinsic(relay_chain, url, open, suri).await
+}
+
+/// Closes a channel between two parachains through the `Sudo` pallet of the relay chain, by
+/// dispatching `Hrmp::hrmp_close_channel` as the sending parachain using
+/// `Utility::dispatch_as`. The channel is closed at the next session change.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `url` - Endpoint of the relay chain node.
+/// * `channel` - The channel to close.
+/// * `suri` - The secret URI of the sudo account.
+pub async fn close_hrmp_channel(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	channel: HrmpChannel,
+	suri: &str,
+) -> Result<String, Error> {
+	ensure_sudo(relay_chain)?;
+	let dispatches_as = relay_chain
+		.metadata()
+		.pallet_by_name("Utility")
+		.and_then(|pallet| pallet.call_variant_by_name("dispatch_as"))
+		.is_some();
+	if !dispatches_as {
+		return Err(Error::HrmpError(
+			"the relay chain has no Utility::dispatch_as call to close the channel with".into(),
+		));
+	}
+	sign_and_submit_sudo_extrinsic(relay_chain, url, construct_close_call(channel), suri).await
+}
+
+/// Waits for the relay chain to open or close a channel, returning the number of the relay chain
+/// block the change was observed in.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `channel` - The channel.
+/// * `open` - Whether the channel is expected to be open, rather than closed.
+pub async fn wait_for_hrmp_channel(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	channel: HrmpChannel,
+	open: bool,
+) -> Result<u64, Error> {
+	let mut blocks = relay_chain
+		.blocks()
+		.subscribe_best()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?;
+	while let Some(block) = blocks.next().await {
+		let block = block.map_err(|e| Error::SubXtError(e.into()))?;
+		let exists = block
+			.storage()
+			.fetch_raw(channel.key())
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.is_some();
+		if exists == open {
+			return Ok(block.number().into());
+		}
+	}
+	Err(Error::HrmpError(format!(
+		"the relay chain stopped producing blocks before channel {channel} was {}",
+		if open { "opened" } else { "closed" }
+	)))
+}
+
+// Ensures the relay chain has a `Sudo` pallet to submit the calls through.
+fn ensure_sudo(relay_chain: &OnlineClient<SubstrateConfig>) -> Result<(), Error> {
+	if relay_chain.metadata().pallet_by_name("Sudo").is_none() {
+		return Err(Error::HrmpError(
+			"the relay chain has no Sudo pallet, the channel needs to be managed by governance"
+				.into(),
+		));
+	}
+	Ok(())
+}
+
+// `Utility::dispatch_as` of `Hrmp::hrmp_close_channel`, with the origin of the sending parachain.
+fn construct_close_call(channel: HrmpChannel) -> DynamicPayload {
+	let close = dynamic::tx(
+		"Hrmp",
+		"hrmp_close_channel",
+		vec![Value::named_composite([
+			("sender", Value::u128(channel.sender.into())),
+			("recipient", Value::u128(channel.recipient.into())),
+		])],
+	);
+	let origin = Value::unnamed_variant(
+		"ParachainsOrigin",
+		[Value::unnamed_variant("Parachain", [Value::u128(channel.sender.into())])],
+	);
+	dynamic::tx("Utility", "dispatch_as", vec![origin, close.into_value()])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	#[test]
+	fn key_works() {
+		let channel = HrmpChannel { sender: 1000, recipient: 4001 };
+		let key = channel.key();
+		assert_eq!(key[..16], twox_128("Hrmp".as_bytes()));
+		assert_eq!(key[16..32], twox_128("HrmpChannels".as_bytes()));
+		let id = [1000u32.to_le_bytes(), 4001u32.to_le_bytes()].concat();
+		assert_eq!(key[32..40], twox_64(&id));
+		assert_eq!(key[40..], id);
+	}
+
+	#[test]
+	fn display_works() {
+		assert_eq!(HrmpChannel { sender: 1000, recipient: 4001 }.to_string(), "1000 -> 4001");
+	}
+
+	#[test]
+	fn construct_close_call_works() {
+		let call = construct_close_call(HrmpChannel { sender: 4001, recipient: 1000 });
+		assert_eq!(call.pallet_name(), "Utility");
+		assert_eq!(call.call_name(), "dispatch_as");
+		let origin = Value::unnamed_variant(
+			"ParachainsOrigin",
+			[Value::unnamed_variant("Parachain", [Value::u128(4001)])],
+		);
+		assert_eq!(call.call_data().values().next(), Some(&origin));
+	}
+
+	#[tokio::test]
+	async fn hrmp_channel_does_not_exist_without_hrmp_pallet() -> Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		let channel = HrmpChannel { sender: 1000, recipient: 4001 };
+		assert!(!hrmp_channel_exists(&client, channel).await?);
+		Ok(())
+	}
+}
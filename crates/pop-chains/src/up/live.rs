@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::errors::Error;
+use serde::Serialize;
+use std::{
+	fmt::{Display, Formatter},
+	path::{Path, PathBuf},
+	time::Duration,
+};
+use subxt::{
+	SubstrateConfig,
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	config::Header as _,
+	ext::futures::future::join_all,
+};
+use zombienet_sdk::{AttachToLive, AttachToLiveNetwork, LocalFileSystem, Network, NetworkNode};
+
+/// The time to wait for a node to respond before reporting it as unreachable.
+const RPC_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The role of a node within a network.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeRole {
+	/// A validator of the relay chain.
+	Validator,
+	/// A collator of a parachain.
+	Collator,
+}
+
+impl Display for NodeRole {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			NodeRole::Validator => write!(f, "validator"),
+			NodeRole::Collator => write!(f, "collator"),
+		}
+	}
+}
+
+/// A node of a running network.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeInfo {
+	/// The name of the node.
+	pub name: String,
+	/// The role of the node.
+	pub role: NodeRole,
+	/// The chain the node belongs to.
+	pub chain: String,
+	/// The identifier of the parachain, if the node is a collator.
+	pub para_id: Option<u32>,
+	/// The websocket endpoint of the node.
+	pub ws_uri: String,
+	/// The RPC port of the node.
+	pub rpc_port: Option<u16>,
+	/// The peer-to-peer port of the node.
+	pub p2p_port: Option<u16>,
+}
+
+/// The status of a node, queried via its RPC endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NodeStatus {
+	/// The node.
+	#[serde(flatten)]
+	pub node: NodeInfo,
+	/// Whether the node responded.
+	pub reachable: bool,
+	/// The number of the best block.
+	pub best: Option<u64>,
+	/// The number of the finalized block.
+	pub finalized: Option<u64>,
+	/// The number of connected peers.
+	pub peers: Option<usize>,
+}
+
+/// A network launched with `pop up network`, attached to via its `zombie.json`.
+pub struct LiveNetwork {
+	network: Network<LocalFileSystem>,
+	base_dir: PathBuf,
+}
+
+impl LiveNetwork {
+	/// Attaches to a running network.
+	///
+	/// # Arguments
+	/// * `zombie_json` - The path to the `zombie.json` file of the network.
+	pub async fn attach(zombie_json: &Path) -> Result<Self, Error> {
+		let base_dir = zombie_json.parent().ok_or(Error::PathError)?.to_path_buf();
+		let network = AttachToLiveNetwork::attach_native(zombie_json.to_path_buf()).await?;
+		Ok(Self { network, base_dir })
+	}
+
+	/// The base directory of the network.
+	pub fn base_dir(&self) -> &Path {
+		&self.base_dir
+	}
+
+	/// The nodes of the network: the relay chain validators followed by the collators of each
+	/// parachain, ordered by parachain identifier.
+	pub fn nodes(&self) -> Vec<NodeInfo> {
+		let relay_chain = self.network.relaychain();
+		let mut validators = relay_chain.nodes();
+		validators.sort_by_key(|node| node.name());
+		let mut nodes: Vec<_> = validators
+			.into_iter()
+			.map(|node| node_info(node, NodeRole::Validator, relay_chain.chain(), None))
+			.collect();
+		let mut parachains = self.network.parachains();
+		parachains.sort_by_key(|parachain| parachain.para_id());
+		for parachain in parachains {
+			let chain = parachain
+				.chain_id()
+				.map_or_else(|| parachain.para_id().to_string(), ToString::to_string);
+			let mut collators = parachain.collators();
+			collators.sort_by_key(|node| node.name());
+			nodes.extend(collators.into_iter().map(|node| {
+				node_info(node, NodeRole::Collator, &chain, Some(parachain.para_id()))
+			}));
+		}
+		nodes
+	}
+
+	/// Queries the best and finalized block and the peer count of each node.
+	pub async fn status(&self) -> Vec<NodeStatus> {
+		join_all(self.nodes().into_iter().map(|node| async move {
+			match tokio::time::timeout(RPC_TIMEOUT, query_node(&node.ws_uri)).await {
+				Ok(Ok((best, finalized, peers))) => NodeStatus {
+					node,
+					reachable: true,
+					best: Some(best),
+					finalized: Some(finalized),
+					peers: Some(peers),
+				},
+				_ =>
+					NodeStatus { node, reachable: false, best: None, finalized: None, peers: None },
+			}
+		}))
+		.await
+	}
+
+	/// The path of the log file of a node.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub fn log_path(&self, name: &str) -> Result<PathBuf, Error> {
+		let node = self.node(name)?;
+		Ok(self.base_dir.join(node.name()).join(format!("{}.log", node.name())))
+	}
+
+	/// Restarts a node.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub async fn restart(&self, name: &str) -> Result<(), Error> {
+		self.node(name)?.restart(None).await?;
+		Ok(())
+	}
+
+	/// Pauses a node, suspending its process until it is resumed.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub async fn pause(&self, name: &str) -> Result<(), Error> {
+		self.node(name)?.pause().await?;
+		Ok(())
+	}
+
+	/// Resumes a paused node.
+	///
+	/// # Arguments
+	/// * `name` - The name of the node.
+	pub async fn resume(&self, name: &str) -> Result<(), Error> {
+		self.node(name)?.resume().await?;
+		Ok(())
+	}
+
+	fn node(&self, name: &str) -> Result<&NetworkNode, Error> {
+		self.network.get_node(name).map_err(|_| Error::NodeNotFound(name.to_string()))
+	}
+}
+
+fn node_info(node: &NetworkNode, role: NodeRole, chain: &str, para_id: Option<u32>) -> NodeInfo {
+	NodeInfo {
+		name: node.name().to_string(),
+		role,
+		chain: chain.to_string(),
+		para_id,
+		ws_uri: node.ws_uri().to_string(),
+		rpc_port: url::Url::parse(node.ws_uri()).ok().and_then(|url| url.port()),
+		p2p_port: p2p_port(node.multiaddr()),
+	}
+}
+
+// The TCP port of a multiaddress, e.g. `/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3KooW...`.
+fn p2p_port(multiaddr: &str) -> Option<u16> {
+	let mut protocols = multiaddr.split('/');
+	protocols.find(|protocol| *protocol == "tcp")?;
+	protocols.next()?.parse().ok()
+}
+
+// Queries the best and finalized block numbers and the peer count of a node.
+pub(super) async fn query_node(ws_uri: &str) -> Result<(u64, u64, usize), Error> {
+	let rpc = LegacyRpcMethods::<SubstrateConfig>::new(
+		RpcClient::from_url(ws_uri)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+	);
+	let missing = || Error::ConnectionFailure(format!("{ws_uri}: block header not found"));
+	let best = rpc
+		.chain_get_header(None)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.ok_or_else(missing)?
+		.number();
+	let finalized_hash = rpc
+		.chain_get_finalized_head()
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?;
+	let finalized = rpc
+		.chain_get_header(Some(finalized_hash))
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.ok_or_else(missing)?
+		.number();
+	let health = rpc.system_health().await.map_err(|e| Error::SubXtError(Box::new(e.into())))?;
+	Ok((best.into(), finalized.into(), health.peers))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	#[test]
+	fn p2p_port_works() {
+		assert_eq!(
+			p2p_port(
+				"/ip4/127.0.0.1/tcp/30333/ws/p2p/12D3KooWQCkBm1BYtkHpocxCwMgR8yjitEeHGx8spzcDLGt2gkBm"
+			),
+			Some(30333)
+		);
+		assert_eq!(p2p_port("/ip4/127.0.0.1/udp/30333"), None);
+		assert_eq!(p2p_port(""), None);
+	}
+
+	#[test]
+	fn node_role_display_works() {
+		assert_eq!(NodeRole::Validator.to_string(), "validator");
+		assert_eq!(NodeRole::Collator.to_string(), "collator");
+	}
+
+	#[tokio::test]
+	async fn query_node_works() -> Result<()> {
+		let (best, finalized, _peers) = query_node(&shared_substrate_ws_url().await).await?;
+		assert!(best >= finalized);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn attach_fails_without_network() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let zombie_json = temp.path().join("zombie.json");
+		std::fs::write(&zombie_json, "{}")?;
+		assert!(LiveNetwork::attach(&zombie_json).await.is_err());
+		Ok(())
+	}
+}
@@ -4,9 +4,14 @@ use crate::{
 	errors::Error, omni_node::PolkadotOmniNodeCli::PolkadotOmniNode,
 	registry::traits::Chain as ChainT, up::chain_specs::Runtime,
 };
+pub use assertions::{Assertion, TestCase, TestReport, TestResult, TestSuite};
 pub use chain_specs::Runtime as Relay;
 use glob::glob;
+pub use hrmp::{
+	HrmpChannel, close_hrmp_channel, hrmp_channel_exists, open_hrmp_channel, wait_for_hrmp_channel,
+};
 use indexmap::IndexMap;
+pub use live::{LiveNetwork, NodeInfo, NodeRole, NodeStatus};
 use pop_common::sourcing::traits::{Source as _, enums::Source as _};
 pub use pop_common::{
 	Profile,
@@ -22,6 +27,10 @@ use std::{
 use strum::VariantArray;
 use symlink::{remove_symlink_file, symlink_file};
 use toml_edit::DocumentMut;
+pub use upgrade::{
+	UpgradeMethod, code_hash, current_code_hash, force_set_validation_code, runtime_spec_version,
+	upgrade_parachain_runtime, wait_for_validation_code,
+};
 use zombienet_configuration::{
 	NodeConfig,
 	shared::node::{Buildable, Initial, NodeConfigBuilder},
@@ -31,10 +40,14 @@ use zombienet_sdk::{
 	AttachToLive, AttachToLiveNetwork, LocalFileSystem, Network, NetworkConfig, NetworkConfigExt,
 };
 
+mod assertions;
 mod chain_specs;
 /// Configuration for supported parachains.
 pub mod chains;
+mod hrmp;
+mod live;
 mod relay;
+mod upgrade;
 
 const VALIDATORS: [&str; 6] = ["alice", "bob", "charlie", "dave", "eve", "ferdie"];
 
@@ -392,7 +405,7 @@ impl NetworkConfiguration {
 						r#override(&mut genesis_overrides);
 					}
 					for (_, r#override) in
-						dependencies.iter_mut().filter(|(t, _)| t == &chain.as_any().type_id())
+						dependencies.iter_mut().filter(|(t, _)| t == &chain.chain_type_id())
 					{
 						r#override(&mut genesis_overrides);
 					}
@@ -2029,7 +2042,7 @@ chain = "paseo-local"
 						chain.genesis_overrides().is_some() ||
 							chains.iter().any(|r| r
 								.requires()
-								.map(|r| r.contains_key(&chain.as_any().type_id()))
+								.map(|r| r.contains_key(&chain.chain_type_id()))
 								.unwrap_or_default())
 					);
 					let collators = parachain.collators();
@@ -0,0 +1,259 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	call::{sign_and_submit_extrinsic, sign_and_submit_sudo_extrinsic},
+	errors::Error,
+};
+use scale::Decode;
+use serde::Serialize;
+use sp_core::{blake2_256, twox_64, twox_128};
+use std::fmt::{Display, Formatter};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	dynamic::{self, Value},
+	utils::H256,
+};
+
+/// How the runtime upgrade of a parachain was applied.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpgradeMethod {
+	/// The code hash was authorized with `System::authorize_upgrade` through `Sudo`, before the
+	/// code was applied with `System::apply_authorized_upgrade`.
+	AuthorizeUpgrade,
+	/// The code was set with `System::set_code` through `Sudo`.
+	SetCode,
+	/// The validation code was set by the relay chain with `Paras::force_set_current_code`
+	/// through its `Sudo` pallet, for parachains without a `Sudo` pallet.
+	ForceSetCurrentCode,
+}
+
+impl Display for UpgradeMethod {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			UpgradeMethod::AuthorizeUpgrade => write!(f, "authorize_upgrade"),
+			UpgradeMethod::SetCode => write!(f, "set_code"),
+			UpgradeMethod::ForceSetCurrentCode => write!(f, "force_set_current_code"),
+		}
+	}
+}
+
+/// The hash of the runtime code, as used by `System::authorize_upgrade` and stored as the
+/// validation code hash of a parachain by the relay chain.
+///
+/// # Arguments
+/// * `code` - The runtime code.
+pub fn code_hash(code: &[u8]) -> H256 {
+	H256(blake2_256(code))
+}
+
+/// Submits a runtime upgrade of a parachain through its `Sudo` pallet. The code hash is
+/// authorized before the code is applied when the runtime supports it, otherwise the code is set
+/// directly.
+///
+/// # Arguments
+/// * `client` - The client used to interact with the parachain.
+/// * `url` - Endpoint of the parachain node.
+/// * `code` - The runtime code.
+/// * `suri` - The secret URI of the sudo account.
+pub async fn upgrade_parachain_runtime(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	code: &[u8],
+	suri: &str,
+) -> Result<UpgradeMethod, Error> {
+	let metadata = client.metadata();
+	if metadata.pallet_by_name("Sudo").is_none() {
+		return Err(Error::RuntimeUpgradeError(
+			"the parachain has no Sudo pallet, the upgrade needs to be authorized by governance"
+				.into(),
+		));
+	}
+	let authorizes_upgrades = metadata
+		.pallet_by_name("System")
+		.and_then(|pallet| pallet.call_variant_by_name("authorize_upgrade"))
+		.is_some();
+	if !authorizes_upgrades {
+		let set_code = dynamic::tx("System", "set_code", vec![Value::from_bytes(code)]);
+		sign_and_submit_sudo_extrinsic(client, url, set_code, suri).await?;
+		return Ok(UpgradeMethod::SetCode);
+	}
+	let authorize = dynamic::tx(
+		"System",
+		"authorize_upgrade",
+		vec![Value::from_bytes(code_hash(code).as_bytes())],
+	);
+	sign_and_submit_sudo_extrinsic(client, url, authorize, suri).await?;
+	let apply = dynamic::tx("System", "apply_authorized_upgrade", vec![Value::from_bytes(code)]);
+	sign_and_submit_extrinsic(client, url, apply, suri).await?;
+	Ok(UpgradeMethod::AuthorizeUpgrade)
+}
+
+/// Sets the validation code of a parachain through the `Sudo` pallet of the relay chain, using
+/// `Paras::force_set_current_code`. Used for parachains without a `Sudo` pallet.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `url` - Endpoint of the relay chain node.
+/// * `para_id` - The identifier of the parachain.
+/// * `code` - The runtime code.
+/// * `suri` - The secret URI of the sudo account of the relay chain.
+pub async fn force_set_validation_code(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	url: &url::Url,
+	para_id: u32,
+	code: &[u8],
+	suri: &str,
+) -> Result<UpgradeMethod, Error> {
+	let metadata = relay_chain.metadata();
+	if metadata.pallet_by_name("Sudo").is_none() {
+		return Err(Error::RuntimeUpgradeError(
+			"neither the parachain nor the relay chain has a Sudo pallet, the upgrade needs to be \
+			 authorized by governance"
+				.into(),
+		));
+	}
+	let force_set_current_code = dynamic::tx(
+		"Paras",
+		"force_set_current_code",
+		vec![Value::u128(para_id.into()), Value::from_bytes(code)],
+	);
+	sign_and_submit_sudo_extrinsic(relay_chain, url, force_set_current_code, suri).await?;
+	Ok(UpgradeMethod::ForceSetCurrentCode)
+}
+
+/// The hash of the current validation code of a parachain, as stored by the relay chain.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `para_id` - The identifier of the parachain.
+pub async fn current_code_hash(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+) -> Result<Option<H256>, Error> {
+	let value = relay_chain
+		.storage()
+		.at_latest()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.fetch_raw(current_code_hash_key(para_id))
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?;
+	value
+		.map(|value| H256::decode(&mut value.as_slice()))
+		.transpose()
+		.map_err(|e| Error::DecodingError(e.to_string()))
+}
+
+/// Waits for the relay chain to enact the validation code of a parachain, returning the number
+/// of the relay chain block it was enacted in.
+///
+/// # Arguments
+/// * `relay_chain` - The client used to interact with the relay chain.
+/// * `para_id` - The identifier of the parachain.
+/// * `code_hash` - The hash of the expected validation code.
+pub async fn wait_for_validation_code(
+	relay_chain: &OnlineClient<SubstrateConfig>,
+	para_id: u32,
+	code_hash: H256,
+) -> Result<u64, Error> {
+	let mut blocks = relay_chain
+		.blocks()
+		.subscribe_best()
+		.await
+		.map_err(|e| Error::SubXtError(e.into()))?;
+	while let Some(block) = blocks.next().await {
+		let block = block.map_err(|e| Error::SubXtError(e.into()))?;
+		let current = block
+			.storage()
+			.fetch_raw(current_code_hash_key(para_id))
+			.await
+			.map_err(|e| Error::SubXtError(e.into()))?
+			.map(|value| H256::decode(&mut value.as_slice()))
+			.transpose()
+			.map_err(|e| Error::DecodingError(e.to_string()))?;
+		if current == Some(code_hash) {
+			return Ok(block.number().into());
+		}
+	}
+	Err(Error::RuntimeUpgradeError(
+		"the relay chain stopped producing blocks before the code was enacted".into(),
+	))
+}
+
+/// The spec version of the runtime a node is running.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+pub async fn runtime_spec_version(url: &str) -> Result<u32, Error> {
+	let rpc = LegacyRpcMethods::<SubstrateConfig>::new(
+		RpcClient::from_url(url)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+	);
+	let version = rpc
+		.state_get_runtime_version(None)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?;
+	Ok(version.spec_version)
+}
+
+// The storage key of `Paras::CurrentCodeHash` for a parachain, a `Twox64Concat` map.
+fn current_code_hash_key(para_id: u32) -> Vec<u8> {
+	let id = para_id.to_le_bytes();
+	let mut key = twox_128("Paras".as_bytes()).to_vec();
+	key.extend(twox_128("CurrentCodeHash".as_bytes()));
+	key.extend(twox_64(&id));
+	key.extend(id);
+	key
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use subxt::utils::to_hex;
+
+	#[test]
+	fn code_hash_works() {
+		assert_eq!(
+			to_hex(code_hash(&[])),
+			"0x0e5751c026e543b2e8ab2eb06099daa1d1e5df47778f7787faab45cdf12fe3a8"
+		);
+	}
+
+	#[test]
+	fn current_code_hash_key_works() {
+		let key = current_code_hash_key(1000);
+		assert_eq!(
+			to_hex(&key[..32]),
+			"0xcd710b30bd2eab0352ddcc26417aa194e2d1c22ba0a888147714a3487bd51c63"
+		);
+		assert_eq!(key[40..], 1000u32.to_le_bytes());
+	}
+
+	#[test]
+	fn upgrade_method_display_works() {
+		assert_eq!(UpgradeMethod::AuthorizeUpgrade.to_string(), "authorize_upgrade");
+		assert_eq!(UpgradeMethod::SetCode.to_string(), "set_code");
+		assert_eq!(UpgradeMethod::ForceSetCurrentCode.to_string(), "force_set_current_code");
+	}
+
+	#[tokio::test]
+	async fn runtime_spec_version_works() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let client = set_up_client(&node_url).await?;
+		assert_eq!(runtime_spec_version(&node_url).await?, client.runtime_version().spec_version);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn current_code_hash_is_none_without_paras_pallet() -> Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		assert_eq!(current_code_hash(&client, 1000).await?, None);
+		Ok(())
+	}
+}
@@ -0,0 +1,337 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, up::code_hash};
+use scale::{Decode, Encode};
+use serde::{Deserialize, Serialize};
+use sp_core::{blake2_256, twox_128};
+use std::{
+	fmt::{Display, Formatter},
+	path::Path,
+};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	backend::{legacy::LegacyRpcMethods, rpc::RpcClient},
+	dynamic::{self, Value},
+	utils::H256,
+};
+
+/// The storage key of the runtime code.
+const CODE_KEY: &[u8] = b":code";
+/// The index of `System` in the runtimes of most chains.
+const SYSTEM_PALLET_INDEX: u8 = 0;
+/// The index of `System::authorize_upgrade` within the calls of `frame_system`.
+const AUTHORIZE_UPGRADE_CALL_INDEX: u8 = 9;
+
+/// The hashes identifying a runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct RuntimeHashes {
+	/// The blake2-256 hash of the runtime code.
+	pub code_hash: H256,
+	/// The blake2-256 hash of the `System::authorize_upgrade` call authorizing the code, which is
+	/// the preimage hash of a referendum proposing the upgrade.
+	pub authorize_upgrade_hash: H256,
+}
+
+impl RuntimeHashes {
+	/// Hashes the runtime code, encoding `System::authorize_upgrade` with the indices used by
+	/// chains which declare `System` as their first pallet.
+	///
+	/// # Arguments
+	/// * `code` - The runtime code.
+	pub fn new(code: &[u8]) -> Self {
+		let code_hash = code_hash(code);
+		let call = (SYSTEM_PALLET_INDEX, AUTHORIZE_UPGRADE_CALL_INDEX, code_hash).encode();
+		Self { code_hash, authorize_upgrade_hash: H256(blake2_256(&call)) }
+	}
+
+	/// Hashes the runtime code, encoding `System::authorize_upgrade` according to the metadata of
+	/// a chain.
+	///
+	/// # Arguments
+	/// * `code` - The runtime code.
+	/// * `client` - The client used to interact with the chain.
+	pub fn for_chain(code: &[u8], client: &OnlineClient<SubstrateConfig>) -> Result<Self, Error> {
+		let code_hash = code_hash(code);
+		let authorize =
+			dynamic::tx("System", "authorize_upgrade", vec![Value::from_bytes(code_hash)]);
+		let call = client.tx().call_data(&authorize).map_err(|e| {
+			Error::RuntimeVerificationError(format!(
+				"the chain does not support System::authorize_upgrade: {e}"
+			))
+		})?;
+		Ok(Self { code_hash, authorize_upgrade_hash: H256(blake2_256(&call)) })
+	}
+}
+
+/// How a referendum preimage relates to a runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreimageMatch {
+	/// The preimage is the `System::authorize_upgrade` call authorizing the code.
+	AuthorizeUpgrade,
+	/// The preimage contains the code hash, e.g. an authorization nested in a batch or sent to a
+	/// parachain over XCM.
+	CodeHash,
+	/// The preimage contains the code, e.g. a `System::set_code` call.
+	Code,
+}
+
+impl Display for PreimageMatch {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			PreimageMatch::AuthorizeUpgrade => write!(f, "authorizes the upgrade to the runtime"),
+			PreimageMatch::CodeHash => write!(f, "contains the code hash of the runtime"),
+			PreimageMatch::Code => write!(f, "contains the code of the runtime"),
+		}
+	}
+}
+
+/// Determines how a referendum preimage relates to a runtime, if at all.
+///
+/// # Arguments
+/// * `preimage` - The preimage, an encoded call.
+/// * `code` - The runtime code.
+/// * `hashes` - The hashes of the runtime.
+pub fn match_preimage(
+	preimage: &[u8],
+	code: &[u8],
+	hashes: &RuntimeHashes,
+) -> Option<PreimageMatch> {
+	let contains = |bytes: &[u8]| preimage.windows(bytes.len()).any(|window| window == bytes);
+	if H256(blake2_256(preimage)) == hashes.authorize_upgrade_hash {
+		Some(PreimageMatch::AuthorizeUpgrade)
+	} else if contains(hashes.code_hash.as_bytes()) {
+		Some(PreimageMatch::CodeHash)
+	} else if !code.is_empty() && contains(code) {
+		Some(PreimageMatch::Code)
+	} else {
+		None
+	}
+}
+
+/// The digest of a runtime, as published by srtool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SrtoolDigest {
+	/// The blake2-256 hash of the runtime code.
+	pub code_hash: H256,
+}
+
+impl SrtoolDigest {
+	/// Loads the digest from the JSON output of `srtool build --json`. The compressed runtime is
+	/// preferred, as that is what gets deployed.
+	///
+	/// # Arguments
+	/// * `path` - The path of the JSON output.
+	pub fn from_path(path: &Path) -> Result<Self, Error> {
+		Self::parse(&std::fs::read_to_string(path)?)
+	}
+
+	/// Parses the digest from the JSON output of `srtool build --json`.
+	///
+	/// # Arguments
+	/// * `json` - The JSON output.
+	pub fn parse(json: &str) -> Result<Self, Error> {
+		let report: SrtoolReport = serde_json::from_str(json).map_err(|e| {
+			Error::RuntimeVerificationError(format!("invalid srtool JSON output: {e}"))
+		})?;
+		let runtime = report.runtimes.compressed.or(report.runtimes.compact).ok_or_else(|| {
+			Error::RuntimeVerificationError("the srtool JSON output contains no runtime".into())
+		})?;
+		Ok(Self { code_hash: runtime.blake2_256 })
+	}
+}
+
+#[derive(Deserialize)]
+struct SrtoolReport {
+	runtimes: SrtoolRuntimes,
+}
+
+#[derive(Deserialize)]
+struct SrtoolRuntimes {
+	compact: Option<SrtoolRuntime>,
+	compressed: Option<SrtoolRuntime>,
+}
+
+#[derive(Deserialize)]
+struct SrtoolRuntime {
+	blake2_256: H256,
+}
+
+/// Fetches the runtime code of a chain, returning the number of the block it was read at along
+/// with the code.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+/// * `at` - The number of the block to read the code at, defaulting to the best block.
+pub async fn fetch_code(url: &str, at: Option<u64>) -> Result<(u64, Vec<u8>), Error> {
+	let rpc = rpc(url).await?;
+	let number = match at {
+		Some(number) => number,
+		None => rpc
+			.chain_get_header(None)
+			.await
+			.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+			.ok_or_else(|| Error::RuntimeVerificationError("the chain has no best block".into()))?
+			.number
+			.into(),
+	};
+	let block_hash = rpc
+		.chain_get_block_hash(Some(number.into()))
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.ok_or_else(|| Error::RuntimeVerificationError(format!("block #{number} not found")))?;
+	let code = rpc
+		.state_get_storage(CODE_KEY, Some(block_hash))
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.ok_or_else(|| {
+			Error::RuntimeVerificationError(format!("no runtime code found at block #{number}"))
+		})?;
+	Ok((number, code))
+}
+
+/// Fetches a preimage noted with the `Preimage` pallet of a chain.
+///
+/// # Arguments
+/// * `url` - Endpoint of the node.
+/// * `hash` - The hash of the preimage.
+pub async fn fetch_preimage(url: &str, hash: H256) -> Result<Vec<u8>, Error> {
+	let rpc = rpc(url).await?;
+	// Preimages are keyed by their hash and length, so the key is found by its prefix.
+	let key = rpc
+		.state_get_keys_paged(&preimage_prefix(hash), 1, None, None)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.pop()
+		.ok_or_else(|| {
+			Error::RuntimeVerificationError(format!("no preimage found for {hash:?}"))
+		})?;
+	let preimage = rpc
+		.state_get_storage(&key, None)
+		.await
+		.map_err(|e| Error::SubXtError(Box::new(e.into())))?
+		.ok_or_else(|| {
+			Error::RuntimeVerificationError(format!("no preimage found for {hash:?}"))
+		})?;
+	Vec::<u8>::decode(&mut &preimage[..])
+		.map_err(|e| Error::RuntimeVerificationError(format!("invalid preimage: {e}")))
+}
+
+// The prefix of the keys of the preimages with the given hash within `Preimage::PreimageFor`, an
+// `Identity` map keyed by the hash and length of the preimage.
+fn preimage_prefix(hash: H256) -> Vec<u8> {
+	let mut key = twox_128("Preimage".as_bytes()).to_vec();
+	key.extend(twox_128("PreimageFor".as_bytes()));
+	key.extend(hash.as_bytes());
+	key
+}
+
+async fn rpc(url: &str) -> Result<LegacyRpcMethods<SubstrateConfig>, Error> {
+	Ok(LegacyRpcMethods::<SubstrateConfig>::new(
+		RpcClient::from_url(url)
+			.await
+			.map_err(|e| Error::ConnectionFailure(e.to_string()))?,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	const CODE: &[u8] = b"\0asm runtime";
+
+	#[test]
+	fn runtime_hashes_works() {
+		let hashes = RuntimeHashes::new(CODE);
+		assert_eq!(hashes.code_hash, code_hash(CODE));
+		let call = [&[0u8, 9][..], hashes.code_hash.as_bytes()].concat();
+		assert_eq!(hashes.authorize_upgrade_hash, H256(blake2_256(&call)));
+	}
+
+	#[test]
+	fn match_preimage_works() {
+		let hashes = RuntimeHashes::new(CODE);
+		let authorize = [&[0u8, 9][..], hashes.code_hash.as_bytes()].concat();
+		assert_eq!(
+			match_preimage(&authorize, CODE, &hashes),
+			Some(PreimageMatch::AuthorizeUpgrade)
+		);
+		// An authorization of the upgrade within a batch.
+		let batch = [&[40u8, 0, 4][..], &authorize].concat();
+		assert_eq!(match_preimage(&batch, CODE, &hashes), Some(PreimageMatch::CodeHash));
+		// `System::set_code`, with the length of the code prefixed.
+		let set_code = [&[0u8, 2][..], &CODE.to_vec().encode()].concat();
+		assert_eq!(match_preimage(&set_code, CODE, &hashes), Some(PreimageMatch::Code));
+		assert_eq!(match_preimage(&[0, 7, 0], CODE, &hashes), None);
+	}
+
+	#[test]
+	fn srtool_digest_parse_works() -> Result<()> {
+		let hash = |byte: u8| format!("0x{}", format!("{byte:02x}").repeat(32));
+		let json = format!(
+			r#"{{
+				"gen": "srtool v0.17.0",
+				"rustc": "rustc 1.84.1",
+				"runtimes": {{
+					"compact": {{ "size": 2048, "blake2_256": "{}" }},
+					"compressed": {{ "size": 1024, "blake2_256": "{}" }}
+				}}
+			}}"#,
+			hash(1),
+			hash(2)
+		);
+		assert_eq!(SrtoolDigest::parse(&json)?.code_hash, H256([2; 32]));
+
+		let json =
+			format!(r#"{{ "runtimes": {{ "compact": {{ "blake2_256": "{}" }} }} }}"#, hash(1));
+		assert_eq!(SrtoolDigest::parse(&json)?.code_hash, H256([1; 32]));
+
+		assert!(matches!(
+			SrtoolDigest::parse(r#"{ "runtimes": {} }"#),
+			Err(Error::RuntimeVerificationError(message)) if message.contains("no runtime")
+		));
+		assert!(SrtoolDigest::parse("{}").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn preimage_prefix_works() {
+		let hash = H256([7; 32]);
+		let prefix = preimage_prefix(hash);
+		assert_eq!(prefix[..16], twox_128("Preimage".as_bytes()));
+		assert_eq!(prefix[16..32], twox_128("PreimageFor".as_bytes()));
+		assert_eq!(prefix[32..], hash.0);
+	}
+
+	#[tokio::test]
+	async fn fetch_code_works() -> Result<()> {
+		let url = shared_substrate_ws_url().await;
+		let (_, code) = fetch_code(&url, None).await?;
+		assert!(!code.is_empty());
+		let (number, code) = fetch_code(&url, Some(0)).await?;
+		assert_eq!(number, 0);
+		assert!(!code.is_empty());
+		assert!(matches!(
+			fetch_code(&url, Some(u32::MAX.into())).await,
+			Err(Error::RuntimeVerificationError(message)) if message.contains("not found")
+		));
+
+		let client = set_up_client(&url).await?;
+		let hashes = RuntimeHashes::for_chain(&[], &client)?;
+		assert_eq!(hashes.code_hash, code_hash(&[]));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn fetch_preimage_fails_when_not_noted() -> Result<()> {
+		let url = shared_substrate_ws_url().await;
+		assert!(matches!(
+			fetch_preimage(&url, H256([7; 32])).await,
+			Err(Error::RuntimeVerificationError(message)) if message.contains("no preimage found")
+		));
+		Ok(())
+	}
+}
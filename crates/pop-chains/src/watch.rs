@@ -0,0 +1,338 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	DecodedEvent, DecodedValue, Storage, decode::decode_event_records, errors::Error,
+	find_pallet_by_name, parse_metadata,
+};
+use scale_value::ValueDef;
+use serde::Serialize;
+use std::{
+	collections::BTreeSet,
+	fmt::{Display, Formatter},
+};
+use subxt::{
+	OnlineClient, SubstrateConfig,
+	blocks::{Block, ExtrinsicEvents},
+	dynamic::Value,
+	events::Phase,
+	ext::futures::{StreamExt, stream::BoxStream},
+	utils::H256,
+};
+
+type ChainBlock = Block<SubstrateConfig, OnlineClient<SubstrateConfig>>;
+
+/// The heads of a chain to follow.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Heads {
+	/// Follow the best block, which may be retracted on a reorg.
+	Best,
+	/// Follow the finalized block.
+	#[default]
+	Finalized,
+}
+
+impl Display for Heads {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Heads::Best => write!(f, "best"),
+			Heads::Finalized => write!(f, "finalized"),
+		}
+	}
+}
+
+/// Selects the events to report while watching a chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EventFilter {
+	/// The pallet emitting the event, matched case-insensitively.
+	pub pallet: Option<String>,
+	/// The name of the event, matched case-insensitively.
+	pub event: Option<String>,
+	/// Accounts which must appear in the fields of the event. An event matches if any of them
+	/// does.
+	pub accounts: Vec<[u8; 32]>,
+}
+
+impl EventFilter {
+	/// Whether the event is selected by the filter.
+	///
+	/// # Arguments
+	/// * `event` - The decoded event.
+	pub fn matches(&self, event: &DecodedEvent) -> bool {
+		self.pallet
+			.as_ref()
+			.is_none_or(|pallet| pallet.eq_ignore_ascii_case(&event.pallet)) &&
+			self.event.as_ref().is_none_or(|name| name.eq_ignore_ascii_case(&event.name)) &&
+			(self.accounts.is_empty() ||
+				self.accounts.iter().any(|account| contains_account(&event.fields, account)))
+	}
+}
+
+// Whether the value contains the account, as a composite of its 32 bytes.
+fn contains_account(value: &DecodedValue, account: &[u8; 32]) -> bool {
+	match &value.value {
+		ValueDef::Composite(composite) => {
+			let is_account = composite.len() == 32 &&
+				composite
+					.values()
+					.zip(account)
+					.all(|(value, byte)| value.as_u128() == Some(*byte as u128));
+			is_account || composite.values().any(|value| contains_account(value, account))
+		},
+		ValueDef::Variant(variant) =>
+			variant.values.values().any(|value| contains_account(value, account)),
+		_ => false,
+	}
+}
+
+/// A storage item watched for changes.
+pub struct StorageWatch {
+	storage: Storage,
+	keys: Vec<Value>,
+	value: Option<Option<DecodedValue>>,
+}
+
+impl StorageWatch {
+	/// Creates a watch of a storage item.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `item` - The storage item, as `Pallet.Item`, e.g. `System.Account`.
+	/// * `keys` - The keys of a storage map, encoded as strings.
+	pub fn new(
+		client: &OnlineClient<SubstrateConfig>,
+		item: &str,
+		keys: Vec<String>,
+	) -> Result<Self, Error> {
+		let (pallet, name) = item.split_once(['.', ':']).ok_or_else(|| {
+			Error::WatchError(format!("invalid storage item `{item}`, expected `Pallet.Item`"))
+		})?;
+		let name = name.trim_start_matches(':');
+		let metadata = client.metadata();
+		let pallets = parse_metadata(&metadata)?;
+		let storage = find_pallet_by_name(&pallets, pallet)?
+			.state
+			.iter()
+			.find(|storage| storage.name == name)
+			.cloned()
+			.ok_or_else(|| Error::WatchError(format!("storage item `{item}` not found")))?;
+		let keys = storage.parse_keys(&metadata, keys)?;
+		Ok(Self { storage, keys, value: None })
+	}
+
+	/// The name of the storage item, as `Pallet.Item`.
+	pub fn item(&self) -> String {
+		format!("{}.{}", self.storage.pallet, self.storage.name)
+	}
+
+	/// Queries the storage item at the given block, returning its value if it changed since the
+	/// last query. The first query always returns the value.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `block_hash` - The hash of the block to query the storage at.
+	pub async fn changed(
+		&mut self,
+		client: &OnlineClient<SubstrateConfig>,
+		block_hash: H256,
+	) -> Result<Option<Option<DecodedValue>>, Error> {
+		let value = self.storage.query_at(client, self.keys.clone(), block_hash).await?;
+		if self.value.as_ref() == Some(&value) {
+			return Ok(None);
+		}
+		self.value = Some(value.clone());
+		Ok(Some(value))
+	}
+}
+
+/// The events and storage changes of a block, as reported while watching a chain.
+pub struct WatchUpdate {
+	/// The number of the block.
+	pub number: u64,
+	/// The hash of the block.
+	pub hash: H256,
+	/// The events of the block selected by the filter.
+	pub events: Vec<DecodedEvent>,
+	/// All the events of the extrinsics which emitted a selected event, in order.
+	pub extrinsic_events: Vec<ExtrinsicEvents<SubstrateConfig>>,
+	/// The new value of the watched storage item, if it changed in the block.
+	pub storage: Option<Option<DecodedValue>>,
+}
+
+/// Follows the heads of a chain, reporting the events and storage changes of each block.
+pub struct ChainWatcher {
+	client: OnlineClient<SubstrateConfig>,
+	blocks: BoxStream<'static, Result<ChainBlock, subxt::Error>>,
+	filter: EventFilter,
+	storage: Option<StorageWatch>,
+}
+
+impl ChainWatcher {
+	/// Subscribes to the heads of the chain.
+	///
+	/// # Arguments
+	/// * `client` - The client used to interact with the chain.
+	/// * `heads` - The heads to follow.
+	/// * `filter` - Selects the events to report.
+	/// * `storage` - A storage item to watch, if any.
+	pub async fn new(
+		client: OnlineClient<SubstrateConfig>,
+		heads: Heads,
+		filter: EventFilter,
+		storage: Option<StorageWatch>,
+	) -> Result<Self, Error> {
+		let blocks = match heads {
+			Heads::Best => client.blocks().subscribe_best().await,
+			Heads::Finalized => client.blocks().subscribe_finalized().await,
+		}
+		.map_err(|e| Error::SubXtError(e.into()))?
+		.boxed();
+		Ok(Self { client, blocks, filter, storage })
+	}
+
+	/// Waits for the next block, returning its events selected by the filter and the change of
+	/// the watched storage item. Returns `None` once the subscription ends.
+	pub async fn next(&mut self) -> Option<Result<WatchUpdate, Error>> {
+		let block = self.blocks.next().await?;
+		Some(self.update(block).await)
+	}
+
+	async fn update(
+		&mut self,
+		block: Result<ChainBlock, subxt::Error>,
+	) -> Result<WatchUpdate, Error> {
+		let block = block.map_err(|e| Error::SubXtError(e.into()))?;
+		let events = block.events().await.map_err(|e| Error::SubXtError(e.into()))?;
+		let mut selected = Vec::new();
+		let mut extrinsics = BTreeSet::new();
+		for (event, decoded) in events.iter().zip(decode_event_records(&events)?) {
+			if !self.filter.matches(&decoded) {
+				continue;
+			}
+			if let Ok(event) = event &&
+				let Phase::ApplyExtrinsic(index) = event.phase()
+			{
+				extrinsics.insert(index);
+			}
+			selected.push(decoded);
+		}
+		let mut extrinsic_events = Vec::new();
+		if !extrinsics.is_empty() {
+			let block_extrinsics =
+				block.extrinsics().await.map_err(|e| Error::SubXtError(e.into()))?;
+			for extrinsic in block_extrinsics
+				.iter()
+				.filter(|extrinsic| extrinsics.contains(&extrinsic.index()))
+			{
+				extrinsic_events
+					.push(extrinsic.events().await.map_err(|e| Error::SubXtError(e.into()))?);
+			}
+		}
+		let storage = match self.storage.as_mut() {
+			Some(storage) => storage.changed(&self.client, block.hash()).await?,
+			None => None,
+		};
+		Ok(WatchUpdate {
+			number: block.number().into(),
+			hash: block.hash(),
+			events: selected,
+			extrinsic_events,
+			storage,
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::set_up_client;
+	use anyhow::Result;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	const ALICE: [u8; 32] = [
+		212, 53, 147, 199, 21, 253, 211, 28, 97, 20, 26, 189, 4, 169, 159, 214, 130, 44, 133, 88,
+		133, 76, 205, 227, 154, 86, 132, 231, 165, 109, 162, 125,
+	];
+
+	fn event(pallet: &str, name: &str, fields: DecodedValue) -> DecodedEvent {
+		DecodedEvent {
+			phase: "Initialization".into(),
+			pallet: pallet.into(),
+			name: name.into(),
+			fields,
+		}
+	}
+
+	fn account(id: [u8; 32]) -> Value {
+		Value::unnamed_composite([Value::unnamed_composite(id.map(|b| Value::u128(b as u128)))])
+	}
+
+	#[test]
+	fn event_filter_matches_pallet_and_event() {
+		let transfer =
+			event("Balances", "Transfer", Value::unnamed_composite([]).map_context(|_| 0));
+		assert!(EventFilter::default().matches(&transfer));
+		let filter = EventFilter { pallet: Some("balances".into()), ..Default::default() };
+		assert!(filter.matches(&transfer));
+		let filter = EventFilter {
+			pallet: Some("Balances".into()),
+			event: Some("Deposit".into()),
+			..Default::default()
+		};
+		assert!(!filter.matches(&transfer));
+		let filter = EventFilter { pallet: Some("System".into()), ..Default::default() };
+		assert!(!filter.matches(&transfer));
+	}
+
+	#[test]
+	fn event_filter_matches_accounts() {
+		let fields = Value::named_composite([
+			("from", account(ALICE)),
+			("to", account([1; 32])),
+			("amount", Value::u128(10)),
+		])
+		.map_context(|_| 0);
+		let transfer = event("Balances", "Transfer", fields);
+		assert!(EventFilter { accounts: vec![ALICE], ..Default::default() }.matches(&transfer));
+		assert!(
+			EventFilter { accounts: vec![[2; 32], [1; 32]], ..Default::default() }
+				.matches(&transfer)
+		);
+		assert!(!EventFilter { accounts: vec![[2; 32]], ..Default::default() }.matches(&transfer));
+	}
+
+	#[tokio::test]
+	async fn storage_watch_reports_changes() -> Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		assert!(matches!(
+			StorageWatch::new(&client, "System", vec![]),
+			Err(Error::WatchError(e)) if e.contains("expected `Pallet.Item`")
+		));
+		assert!(matches!(
+			StorageWatch::new(&client, "System.Unknown", vec![]),
+			Err(Error::WatchError(e)) if e.contains("not found")
+		));
+
+		let mut watch = StorageWatch::new(
+			&client,
+			"System.Account",
+			vec!["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into()],
+		)?;
+		assert_eq!(watch.item(), "System.Account");
+		let block = client.blocks().at_latest().await?;
+		assert!(matches!(watch.changed(&client, block.hash()).await?, Some(Some(_))));
+		assert!(watch.changed(&client, block.hash()).await?.is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn chain_watcher_follows_heads() -> Result<()> {
+		let client = set_up_client(&shared_substrate_ws_url().await).await?;
+		let storage = StorageWatch::new(&client, "System.Number", vec![])?;
+		let mut watcher =
+			ChainWatcher::new(client, Heads::Best, EventFilter::default(), Some(storage)).await?;
+		let update = watcher.next().await.expect("subscription is open")?;
+		assert!(update.storage.is_some());
+		Ok(())
+	}
+}
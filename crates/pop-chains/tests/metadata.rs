@@ -6,9 +6,11 @@
 
 use anyhow::Result;
 use pop_chains::{
-	Error, Function, Payload, construct_extrinsic, construct_proxy_extrinsic, decode_call_data,
-	encode_call_data, field_to_param, find_callable_by_name, find_pallet_by_name,
-	parse_chain_metadata, set_up_client, sign_and_submit_extrinsic,
+	Error, Function, Payload, construct_batch_extrinsic, construct_extrinsic,
+	construct_proxy_extrinsic, construct_sudo_extrinsic, decode_call_data, encode_call_data,
+	field_to_param, find_callable_by_name, find_pallet_by_name, parse_chain_metadata,
+	set_up_client, sign_and_submit_extrinsic, sign_and_submit_sudo_extrinsic, step_dispatch_errors,
+	submit_extrinsic,
 };
 use pop_common::test_env::shared_substrate_ws_url;
 use std::time::Duration;
@@ -99,6 +101,58 @@ async fn sign_and_submit_wrong_extrinsic_fails() -> Result<()> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn sign_and_submit_sudo_extrinsic_reports_dispatch_error() -> Result<()> {
+	let node_url = shared_substrate_ws_url().await;
+	let client = set_up_client(&node_url).await?;
+	let pallets = parse_chain_metadata(&client)?;
+	let call_item = find_callable_by_name(&pallets, "Balances", "force_transfer")?;
+	let force_transfer = call_item.as_function().unwrap();
+	// Bob does not have enough funds, so the call fails while `Sudo::sudo` succeeds.
+	let xt = construct_extrinsic(
+		force_transfer,
+		vec![
+			"Id(5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty)".to_string(),
+			"Id(5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y)".to_string(),
+			u128::MAX.to_string(),
+		],
+	)?;
+	assert!(matches!(
+		sign_and_submit_sudo_extrinsic(&client, &Url::parse(&node_url)?, xt, ALICE_SURI).await,
+		Err(Error::ExtrinsicSubmissionError(message)) if message.starts_with("Sudo::Sudid reported the call failed")
+	));
+	Ok(())
+}
+
+#[tokio::test]
+async fn step_dispatch_errors_reports_failed_sudo_step() -> Result<()> {
+	let node_url = shared_substrate_ws_url().await;
+	let client = set_up_client(&node_url).await?;
+	let pallets = parse_chain_metadata(&client)?;
+	let remark = find_callable_by_name(&pallets, "System", "remark")?;
+	let remark = construct_extrinsic(remark.as_function().unwrap(), vec!["0x11".to_string()])?;
+	let force_transfer = find_callable_by_name(&pallets, "Balances", "force_transfer")?;
+	let force_transfer = construct_extrinsic(
+		force_transfer.as_function().unwrap(),
+		vec![
+			"Id(5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty)".to_string(),
+			"Id(5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y)".to_string(),
+			u128::MAX.to_string(),
+		],
+	)?;
+	// `Utility::batch_all` succeeds, as `Sudo::sudo` succeeds although its call fails.
+	let xt = construct_batch_extrinsic(vec![remark, construct_sudo_extrinsic(force_transfer)]);
+	let events = submit_extrinsic(&client, &xt, &pop_common::create_signer(ALICE_SURI)?).await?;
+	let errors = step_dispatch_errors(&client, &events, 2)?;
+	assert!(errors[0].is_none());
+	assert!(
+		errors[1]
+			.as_deref()
+			.is_some_and(|error| error.starts_with("Sudo::Sudid reported the call failed"))
+	);
+	Ok(())
+}
+
 #[tokio::test]
 async fn parse_chain_metadata_works() -> Result<()> {
 	let node_url = shared_substrate_ws_url().await;
@@ -166,6 +220,26 @@ async fn find_dispatchable_by_name_works() -> Result<()> {
 	Ok(())
 }
 
+#[tokio::test]
+async fn runtime_api_call_works() -> Result<()> {
+	let node_url = shared_substrate_ws_url().await;
+	let client = set_up_client(&node_url).await?;
+	let pallets = parse_chain_metadata(&client)?;
+	let api = find_pallet_by_name(&pallets, "AccountNonceApi")?;
+	assert!(api.is_runtime_api());
+	assert!(api.functions.is_empty());
+	let call_item = find_callable_by_name(&pallets, "AccountNonceApi", "account_nonce")?;
+	let method = call_item.as_runtime_api().unwrap();
+	assert!(method.is_supported);
+	assert_eq!(method.params.len(), 1);
+	assert!(matches!(method.call(&client, vec![]).await, Err(Error::ParamParsingError(_))));
+	let nonce = method
+		.call(&client, vec!["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string()])
+		.await?;
+	assert!(nonce.as_u128().is_some());
+	Ok(())
+}
+
 #[tokio::test]
 async fn field_to_param_works() -> Result<()> {
 	let node_url = shared_substrate_ws_url().await;
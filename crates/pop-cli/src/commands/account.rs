@@ -0,0 +1,221 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use self::Command::*;
+use crate::{
+	cli::traits::*,
+	common::accounts,
+	output::{CliResponse, OutputMode},
+};
+use anyhow::{Result, bail};
+use clap::{Args, Subcommand};
+use pop_common::{KeyInfo, KeyScheme, Keystore};
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+/// Arguments for managing the accounts of the keystore.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct AccountArgs {
+	/// Account management subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Manage the keys of the encrypted keystore.
+///
+/// Commands signing extrinsics accept `--account <NAME>` to use a key of the keystore instead of
+/// a plaintext `--suri`. The password is prompted for, or read from `POP_KEYSTORE_PASSWORD`.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Generate a new key and store it in the keystore.
+	#[clap(alias = "g")]
+	Generate {
+		/// Name of the account.
+		name: String,
+		/// Signature scheme of the key.
+		#[arg(short, long, value_enum, default_value_t)]
+		scheme: KeyScheme,
+	},
+	/// Import an existing key into the keystore. The secret is prompted for, so it never ends up
+	/// in the shell history.
+	#[clap(alias = "i")]
+	Import {
+		/// Name of the account.
+		name: String,
+		/// Signature scheme of the key.
+		#[arg(short, long, value_enum, default_value_t)]
+		scheme: KeyScheme,
+	},
+	/// List the accounts of the keystore.
+	#[clap(alias = "ls")]
+	List,
+}
+
+/// Structured output for `account generate` in JSON mode.
+#[derive(Serialize)]
+struct GenerateOutput {
+	#[serde(flatten)]
+	account: KeyInfo,
+	secret: String,
+}
+
+/// Entry point called from the command dispatcher.
+pub(crate) fn execute(
+	command: &Command,
+	cli: &mut impl Cli,
+	output_mode: OutputMode,
+) -> Result<()> {
+	command.execute(&accounts::keystore()?, cli, output_mode)
+}
+
+impl Command {
+	fn execute(
+		&self,
+		keystore: &Keystore,
+		cli: &mut impl Cli,
+		output_mode: OutputMode,
+	) -> Result<()> {
+		match self {
+			Generate { name, scheme } => {
+				cli.intro("Generate an account")?;
+				let password = new_password(cli)?;
+				let (account, secret) = keystore.generate(name, *scheme, &password)?;
+				if output_mode == OutputMode::Json {
+					CliResponse::ok(GenerateOutput { account, secret }).print_json();
+					return Ok(());
+				}
+				cli.warning(
+					"Back up the secret below: it is stored encrypted and will not be shown again.",
+				)?;
+				cli.plain(secret)?;
+				cli.outro(format!("Account `{}` generated: {}", account.name, account.address))?;
+			},
+			Import { name, scheme } => {
+				cli.intro("Import an account")?;
+				let prompt = match scheme {
+					KeyScheme::Ethereum => "Mnemonic or private key of the account:",
+					_ => "Secret URI of the account (mnemonic, seed or derivation path):",
+				};
+				let secret = cli.password(prompt).interact()?;
+				let password = new_password(cli)?;
+				let account = keystore.import(name, *scheme, secret.trim(), &password)?;
+				if output_mode == OutputMode::Json {
+					CliResponse::ok(account).print_json();
+					return Ok(());
+				}
+				cli.outro(format!("Account `{}` imported: {}", account.name, account.address))?;
+			},
+			List => {
+				let accounts = keystore.list()?;
+				if output_mode == OutputMode::Json {
+					CliResponse::ok(accounts).print_json();
+					return Ok(());
+				}
+				if accounts.is_empty() {
+					cli.info(format!("No accounts in {}", keystore.path().display()))?;
+					return Ok(());
+				}
+				for account in accounts {
+					cli.plain(format!(
+						"{} ({}): {}",
+						account.name, account.scheme, account.address
+					))?;
+				}
+			},
+		}
+		Ok(())
+	}
+}
+
+/// Prompt for the password of a new key, asking for it twice unless it is read from the
+/// environment.
+fn new_password(cli: &mut impl Cli) -> Result<String> {
+	if let Ok(password) = std::env::var(accounts::KEYSTORE_PASSWORD_ENV) {
+		return Ok(password);
+	}
+	let password = cli.password("Password to encrypt the key:").interact()?;
+	if password.is_empty() {
+		bail!("The password cannot be empty");
+	}
+	if cli.password("Confirm the password:").interact()? != password {
+		bail!("The passwords do not match");
+	}
+	Ok(password)
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Generate { .. } => write!(f, "generate"),
+			Import { .. } => write!(f, "import"),
+			List => write!(f, "list"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	fn with_prompted_password<T>(f: impl FnOnce() -> T) -> T {
+		temp_env::with_var_unset(accounts::KEYSTORE_PASSWORD_ENV, f)
+	}
+
+	#[test]
+	fn import_stores_prompted_secret() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		let mut cli = MockCli::new()
+			.expect_password(
+				"Secret URI of the account (mnemonic, seed or derivation path):",
+				"//Alice".into(),
+			)
+			.expect_password("Password to encrypt the key:", "secret".into())
+			.expect_password("Confirm the password:", "secret".into())
+			.expect_outro(
+				"Account `alice` imported: 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY",
+			);
+		let command = Import { name: "alice".into(), scheme: KeyScheme::Sr25519 };
+		with_prompted_password(|| command.execute(&keystore, &mut cli, OutputMode::Human))?;
+		assert_eq!(keystore.unlock("alice", "secret")?.1, "//Alice");
+		cli.verify()
+	}
+
+	#[test]
+	fn generate_rejects_mismatching_passwords() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		let mut cli = MockCli::new()
+			.expect_password("Password to encrypt the key:", "secret".into())
+			.expect_password("Confirm the password:", "other".into());
+		let command = Generate { name: "evm".into(), scheme: KeyScheme::Ethereum };
+		let err =
+			with_prompted_password(|| command.execute(&keystore, &mut cli, OutputMode::Human))
+				.unwrap_err();
+		assert_eq!(err.to_string(), "The passwords do not match");
+		assert!(keystore.list()?.is_empty());
+		cli.verify()
+	}
+
+	#[test]
+	fn list_shows_accounts() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		let mut cli = MockCli::new()
+			.expect_plain("alice (sr25519): 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+		List.execute(&keystore, &mut cli, OutputMode::Human)?;
+		cli.verify()
+	}
+
+	#[test]
+	fn command_display_works() {
+		assert_eq!(List.to_string(), "list");
+		assert_eq!(
+			Generate { name: "a".into(), scheme: KeyScheme::Sr25519 }.to_string(),
+			"generate"
+		);
+		assert_eq!(Import { name: "a".into(), scheme: KeyScheme::Ecdsa }.to_string(), "import");
+	}
+}
@@ -0,0 +1,210 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli,
+	output::{CliResponse, OutputMode, build_error_with_details, invalid_input_error},
+};
+use clap::Args;
+use pop_chains::bench::compare::{
+	BenchmarkComparison, WeightChange, WeightMetric, compare_benchmark_weights,
+	load_benchmark_weights,
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Args, Default, Serialize)]
+pub(crate) struct BenchmarkCompare {
+	/// The results of the old run: the raw JSON output of `pop bench pallet --json-file`, or a
+	/// directory of generated weight files.
+	#[arg(value_name = "OLD")]
+	pub(crate) old: PathBuf,
+	/// The results of the new run, in the same format as the old run.
+	#[arg(value_name = "NEW")]
+	pub(crate) new: PathBuf,
+	/// The percentage by which a weight may increase before the comparison fails.
+	#[arg(long, default_value_t = 5.0)]
+	pub(crate) threshold: f64,
+}
+
+impl BenchmarkCompare {
+	pub(crate) async fn execute_with_output_mode(
+		&self,
+		cli: &mut impl cli::traits::Cli,
+		output_mode: OutputMode,
+	) -> anyhow::Result<()> {
+		cli.intro("Comparing benchmark results")?;
+		let comparison = self.compare()?;
+		if output_mode == OutputMode::Human {
+			self.display(cli, &comparison)?;
+		}
+
+		let regressions: Vec<_> = comparison.regressions().map(describe).collect();
+		// The comparison is output regardless of any regressions, which fail the command.
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(&comparison).print_json();
+		}
+		if !regressions.is_empty() {
+			let message =
+				format!("{} weights increased by more than {}%", regressions.len(), self.threshold);
+			cli.outro_cancel(&message)?;
+			return Err(build_error_with_details(message, regressions.join("\n")));
+		}
+		cli.outro(format!("No weights increased by more than {}%", self.threshold))?;
+		Ok(())
+	}
+
+	fn compare(&self) -> anyhow::Result<BenchmarkComparison> {
+		if is_weight_files(&self.old) != is_weight_files(&self.new) {
+			return Err(invalid_input_error(
+				"Both runs must be raw JSON results or generated weight files",
+			));
+		}
+		let load = |path: &Path| {
+			load_benchmark_weights(path)
+				.map_err(|e| invalid_input_error(format!("Failed to load {}: {e}", path.display())))
+		};
+		Ok(compare_benchmark_weights(&load(&self.old)?, &load(&self.new)?, self.threshold))
+	}
+
+	fn display(
+		&self,
+		cli: &mut impl cli::traits::Cli,
+		comparison: &BenchmarkComparison,
+	) -> anyhow::Result<()> {
+		for id in &comparison.added {
+			cli.info(format!("Added: {id}"))?;
+		}
+		for id in &comparison.removed {
+			cli.warning(format!("Removed: {id}"))?;
+		}
+		if comparison.changes.is_empty() {
+			cli.info("No weights changed.")?;
+			return Ok(());
+		}
+		let changes: Vec<_> = comparison
+			.changes
+			.iter()
+			.map(|change| {
+				format!("{}{}", if change.regression { "❌ " } else { "" }, describe(change))
+			})
+			.collect();
+		cli.plain(changes.join("\n"))?;
+		Ok(())
+	}
+}
+
+// Whether the results are generated weight files, rather than raw JSON results.
+fn is_weight_files(path: &Path) -> bool {
+	path.is_dir() || path.extension().is_some_and(|ext| ext == "rs")
+}
+
+// Describes a change, e.g. `pallet_balances::transfer (n) ref time: 1.00 µs -> 1.10 µs (+10.0%)`.
+fn describe(change: &WeightChange) -> String {
+	let format = |value: f64| match change.metric {
+		WeightMetric::RefTime => format!("{:.2} µs", value / 1_000_000.0),
+		WeightMetric::ProofSize => format!("{value:.0} B"),
+		WeightMetric::Reads | WeightMetric::Writes => value.to_string(),
+	};
+	format!(
+		"{}{} {}: {} -> {} ({})",
+		change.benchmark,
+		change.component.as_ref().map(|c| format!(" ({c})")).unwrap_or_default(),
+		change.metric,
+		format(change.old),
+		format(change.new),
+		change
+			.delta()
+			.map(|delta| format!("{delta:+.1}%"))
+			.unwrap_or_else(|| "new".into())
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		cli::MockCli,
+		output::{BuildCommandError, InvalidInputError},
+	};
+	use pop_chains::bench::compare::BenchmarkId;
+
+	const WEIGHTS: &str = r#"
+impl<T: frame_system::Config> pallet_balances::WeightInfo for WeightInfo<T> {
+	fn transfer_allow_death() -> Weight {
+		Weight::from_parts(REF_TIME, 3593)
+	}
+}
+"#;
+
+	fn weights(dir: &Path, ref_time: &str) -> anyhow::Result<()> {
+		std::fs::create_dir_all(dir)?;
+		std::fs::write(dir.join("pallet_balances.rs"), WEIGHTS.replace("REF_TIME", ref_time))?;
+		Ok(())
+	}
+
+	#[test]
+	fn describe_works() {
+		let change = |component: Option<&str>, metric, old, new| WeightChange {
+			benchmark: BenchmarkId {
+				pallet: "pallet_balances".into(),
+				extrinsic: "transfer".into(),
+			},
+			component: component.map(String::from),
+			metric,
+			old,
+			new,
+			regression: false,
+		};
+		assert_eq!(
+			describe(&change(None, WeightMetric::RefTime, 1_000_000.0, 1_100_000.0)),
+			"pallet_balances::transfer ref time: 1.00 µs -> 1.10 µs (+10.0%)"
+		);
+		assert_eq!(
+			describe(&change(Some("n"), WeightMetric::ProofSize, 0.0, 512.0)),
+			"pallet_balances::transfer (n) proof size: 0 B -> 512 B (new)"
+		);
+		assert_eq!(
+			describe(&change(None, WeightMetric::Writes, 1.0, 2.0)),
+			"pallet_balances::transfer writes: 1 -> 2 (+100.0%)"
+		);
+	}
+
+	#[tokio::test]
+	async fn execute_fails_on_regression() -> anyhow::Result<()> {
+		let temp = tempfile::tempdir()?;
+		let (old, new) = (temp.path().join("old"), temp.path().join("new"));
+		weights(&old, "40_000_000")?;
+		weights(&new, "41_000_000")?;
+
+		let command = BenchmarkCompare { old: old.clone(), new: new.clone(), threshold: 5.0 };
+		let mut cli = MockCli::new()
+			.expect_intro("Comparing benchmark results")
+			.expect_outro("No weights increased by more than 5%");
+		command.execute_with_output_mode(&mut cli, OutputMode::Human).await?;
+		cli.verify()?;
+
+		let command = BenchmarkCompare { old, new, threshold: 1.0 };
+		let mut cli = MockCli::new()
+			.expect_intro("Comparing benchmark results")
+			.expect_outro_cancel("1 weights increased by more than 1%");
+		let err = command.execute_with_output_mode(&mut cli, OutputMode::Human).await.unwrap_err();
+		let err = err.downcast_ref::<BuildCommandError>().expect("expected BuildCommandError");
+		assert_eq!(
+			err.details(),
+			Some("pallet_balances::transfer_allow_death ref time: 40.00 µs -> 41.00 µs (+2.5%)")
+		);
+		cli.verify()
+	}
+
+	#[test]
+	fn compare_rejects_mixed_formats() -> anyhow::Result<()> {
+		let temp = tempfile::tempdir()?;
+		let old = temp.path().join("old");
+		weights(&old, "40_000_000")?;
+		let command =
+			BenchmarkCompare { old, new: temp.path().join("results.json"), threshold: 5.0 };
+		let err = command.compare().unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		Ok(())
+	}
+}
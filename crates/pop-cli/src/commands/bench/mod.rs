@@ -6,6 +6,7 @@ use crate::{
 };
 use block::BenchmarkBlock;
 use clap::{Args, Subcommand};
+use compare::BenchmarkCompare;
 use machine::BenchmarkMachine;
 use overhead::BenchmarkOverhead;
 use pallet::BenchmarkPallet;
@@ -15,6 +16,7 @@ use storage::BenchmarkStorage;
 use tracing_subscriber::EnvFilter;
 
 mod block;
+mod compare;
 mod machine;
 mod overhead;
 mod pallet;
@@ -33,6 +35,9 @@ pub enum Command {
 	/// Benchmark the execution time of historic blocks.
 	#[clap(alias = "b")]
 	Block(BenchmarkBlock),
+	/// Compare the weights of two benchmark runs.
+	#[clap(alias = "c")]
+	Compare(BenchmarkCompare),
 	/// Benchmark the machine performance.
 	#[clap(alias = "m")]
 	Machine(BenchmarkMachine),
@@ -75,6 +80,8 @@ impl Command {
 				let mut cli = cli::Cli;
 				match &mut args.command {
 					Command::Block(cmd) => cmd.execute(&mut cli).await,
+					Command::Compare(cmd) =>
+						cmd.execute_with_output_mode(&mut cli, output_mode).await,
 					Command::Machine(cmd) => cmd.execute(&mut cli).await,
 					Command::Overhead(cmd) => cmd.execute(&mut cli).await,
 					Command::Pallet(cmd) => cmd.execute(&mut cli).await,
@@ -84,6 +91,8 @@ impl Command {
 			OutputMode::Json => {
 				let mut cli = cli::JsonCli;
 				match &mut args.command {
+					Command::Compare(cmd) =>
+						cmd.execute_with_output_mode(&mut cli, output_mode).await,
 					Command::Pallet(cmd) =>
 						cmd.execute_with_output_mode(&mut cli, output_mode).await,
 					_ => reject_unsupported_json("bench"),
@@ -98,6 +107,7 @@ impl Display for Command {
 		use Command::*;
 		match self {
 			Block(_) => write!(f, "block"),
+			Compare(_) => write!(f, "compare"),
 			Machine(_) => write!(f, "machine"),
 			Overhead(_) => write!(f, "overhead"),
 			Pallet(_) => write!(f, "pallet"),
@@ -113,6 +123,7 @@ mod tests {
 	// Others can not be tested yet due to private external types.
 	#[test]
 	fn command_display_works() {
+		assert_eq!(Command::Compare(Default::default()).to_string(), "compare");
 		assert_eq!(Command::Pallet(Default::default()).to_string(), "pallet");
 	}
 }
@@ -36,6 +36,10 @@ pub(crate) mod contract;
 pub(crate) mod runtime;
 #[cfg(feature = "chain")]
 pub(crate) mod spec;
+#[cfg(feature = "chain")]
+pub(crate) mod spec_diff;
+#[cfg(feature = "chain")]
+pub(crate) mod spec_validate;
 
 #[cfg(feature = "chain")]
 const CHAIN_HELP_HEADER: &str = "Chain options";
@@ -568,7 +572,10 @@ impl Command {
 impl Display for Command {
 	fn fmt(&self, f: &mut Formatter<'_>) -> Result {
 		match self {
-			Command::Spec(_) => write!(f, "spec"),
+			Command::Spec(cmd) => match &cmd.command {
+				Some(command) => write!(f, "spec {command}"),
+				None => write!(f, "spec"),
+			},
 		}
 	}
 }
@@ -781,7 +788,19 @@ mod tests {
 	#[test]
 	fn command_display_works() {
 		#[cfg(feature = "chain")]
-		assert_eq!(Command::Spec(Default::default()).to_string(), "spec");
+		{
+			assert_eq!(Command::Spec(Default::default()).to_string(), "spec");
+			let validate = spec::BuildSpecCommand {
+				command: Some(spec::SpecCommand::Validate(Default::default())),
+				..Default::default()
+			};
+			assert_eq!(Command::Spec(validate).to_string(), "spec validate");
+			let diff = spec::BuildSpecCommand {
+				command: Some(spec::SpecCommand::Diff(Default::default())),
+				..Default::default()
+			};
+			assert_eq!(Command::Spec(diff).to_string(), "spec diff");
+		}
 	}
 
 	#[test]
@@ -1,5 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
+use super::{spec_diff::DiffSpecCommand, spec_validate::ValidateSpecCommand};
 use crate::{
 	cli::{
 		self, Cli,
@@ -10,16 +11,21 @@ use crate::{
 		omni_node::source_polkadot_omni_node_binary,
 		runtime::build_deterministic_runtime,
 	},
-	output::{BuildCommandError, CliResponse, OutputMode, PromptRequiredError},
+	output::{
+		BuildCommandError, CliResponse, OutputMode, PromptRequiredError, invalid_input_error,
+	},
 	style::style,
 };
-use clap::{Args, ValueEnum};
+use clap::{Args, Subcommand, ValueEnum};
 use pop_chains::{
 	ChainSpec, ChainSpecBuilder, generate_genesis_state_file_with_node, is_supported,
+	load_genesis_patch,
 };
 use pop_common::{Profile, manifest::from_path};
 use serde::Serialize;
+use serde_json::Value;
 use std::{
+	fmt::Formatter,
 	fs::create_dir_all,
 	path::{Path, PathBuf},
 };
@@ -152,7 +158,12 @@ pub(crate) enum RelayChain {
 
 /// Command for generating a chain specification.
 #[derive(Args, Default, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct BuildSpecCommand {
+	/// Work with an existing chain specification instead.
+	#[serde(skip_serializing)]
+	#[command(subcommand)]
+	pub(crate) command: Option<SpecCommand>,
 	/// Directory path for your project [default: current directory]
 	#[serde(skip_serializing)]
 	#[arg(short, long, default_value = "./")]
@@ -227,11 +238,56 @@ pub struct BuildSpecCommand {
 	/// Generate a raw chain specification.
 	#[arg(long)]
 	pub(crate) raw: bool,
+	/// JSON merge patch applied to the genesis config patch of the specification
+	/// (`genesis.runtimeGenesis.patch`), given inline or as the path to a JSON file.
+	#[arg(long, value_name = "JSON|FILE")]
+	pub(crate) genesis_patch: Option<String>,
+}
+
+/// Subcommands for working with an existing chain specification.
+#[derive(Subcommand)]
+pub(crate) enum SpecCommand {
+	/// Validate the genesis config of a plain chain specification by building the genesis state
+	/// with its runtime.
+	#[clap(alias = "v")]
+	Validate(ValidateSpecCommand),
+	/// Compare two chain specifications: the genesis config of plain ones, or the decoded genesis
+	/// storage of raw ones.
+	#[clap(alias = "d")]
+	Diff(DiffSpecCommand),
+}
+
+impl SpecCommand {
+	/// Executes the subcommand.
+	async fn execute(&self, output_mode: OutputMode) -> anyhow::Result<()> {
+		match self {
+			SpecCommand::Validate(cmd) => match output_mode {
+				OutputMode::Human => cmd.execute(&mut Cli, output_mode).await,
+				OutputMode::Json => cmd.execute(&mut crate::cli::JsonCli, output_mode).await,
+			},
+			SpecCommand::Diff(cmd) => match output_mode {
+				OutputMode::Human => cmd.execute(&mut Cli, output_mode).await,
+				OutputMode::Json => cmd.execute(&mut crate::cli::JsonCli, output_mode).await,
+			},
+		}
+	}
+}
+
+impl std::fmt::Display for SpecCommand {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			SpecCommand::Validate(_) => write!(f, "validate"),
+			SpecCommand::Diff(_) => write!(f, "diff"),
+		}
+	}
 }
 
 impl BuildSpecCommand {
 	/// Executes the build spec command.
 	pub(crate) async fn execute(&self, output_mode: OutputMode) -> anyhow::Result<()> {
+		if let Some(command) = &self.command {
+			return command.execute(output_mode).await;
+		}
 		match output_mode {
 			OutputMode::Human => {
 				let mut cli = Cli;
@@ -350,6 +406,9 @@ impl BuildSpecCommand {
 		if self.raw {
 			full_message.push_str(" --raw");
 		}
+		if let Some(genesis_patch) = &self.genesis_patch {
+			full_message.push_str(&format!(" --genesis-patch '{}'", genesis_patch));
+		}
 		full_message
 	}
 
@@ -450,6 +509,7 @@ impl BuildSpecCommand {
 			runtime_dir,
 			use_existing_plain_spec: false,
 			raw: self.raw,
+			genesis_patch: self.parse_genesis_patch()?,
 		})
 	}
 
@@ -486,6 +546,8 @@ impl BuildSpecCommand {
 			raw,
 			..
 		} = self;
+		// Validate the genesis patch before prompting.
+		let genesis_patch = self.parse_genesis_patch()?;
 
 		// Features
 		let features = features.split(",").map(|s| s.trim().to_string()).collect();
@@ -737,8 +799,20 @@ impl BuildSpecCommand {
 			runtime_dir,
 			use_existing_plain_spec: !prompt,
 			raw: *raw,
+			genesis_patch,
 		})
 	}
+
+	// Loads the genesis patch, if any.
+	fn parse_genesis_patch(&self) -> anyhow::Result<Option<Value>> {
+		self.genesis_patch
+			.as_deref()
+			.map(|patch| {
+				load_genesis_patch(patch)
+					.map_err(|e| invalid_input_error(format!("Invalid genesis patch: {e}")))
+			})
+			.transpose()
+	}
 }
 
 fn map_json_build_spec_error(err: anyhow::Error) -> anyhow::Error {
@@ -819,6 +893,7 @@ pub(crate) struct BuildSpec {
 	runtime_dir: Option<PathBuf>,
 	use_existing_plain_spec: bool,
 	raw: bool,
+	genesis_patch: Option<Value>,
 }
 
 impl BuildSpec {
@@ -899,6 +974,9 @@ impl BuildSpec {
 				self.output_file.display()
 			));
 		}
+		if let Some(patch) = &self.genesis_patch {
+			self.patch_genesis(patch.clone())?;
+		}
 
 		let (raw_chain_spec, genesis_code_file, genesis_state_file) = if self.raw ||
 			self.genesis_code ||
@@ -1005,6 +1083,14 @@ impl BuildSpec {
 		Ok(())
 	}
 
+	// Applies a merge patch to the genesis config patch of the chain specification.
+	fn patch_genesis(&self, patch: Value) -> anyhow::Result<()> {
+		let mut chain_spec = ChainSpec::from(&self.output_file)?;
+		chain_spec.apply_genesis_patch(patch)?;
+		chain_spec.to_file(&self.output_file)?;
+		Ok(())
+	}
+
 	// Updates the chain specification with the runtime code.
 	fn update_code(&self, bytes: &[u8]) -> anyhow::Result<()> {
 		let mut chain_spec = ChainSpec::from(&self.output_file)?;
@@ -1045,7 +1131,7 @@ fn prepare_output_path(output_path: impl AsRef<Path>) -> anyhow::Result<PathBuf>
 #[cfg(test)]
 mod tests {
 	use super::{ChainType::*, RelayChain::*, *};
-	use crate::cli::MockCli;
+	use crate::{cli::MockCli, output::InvalidInputError};
 	use serde_json::json;
 	use sp_core::bytes::from_hex;
 	use std::{
@@ -1057,6 +1143,7 @@ mod tests {
 	#[test]
 	fn test_build_spec_command_display() {
 		let cmd = BuildSpecCommand {
+			command: None,
 			path: PathBuf::from("./my-project"),
 			output_file: Some(PathBuf::from("output.json")),
 			profile: Some(Profile::Release),
@@ -1079,10 +1166,11 @@ mod tests {
 			runtime_dir: Some(PathBuf::from("./runtime")),
 			package: Some("my-package".to_string()),
 			raw: true,
+			genesis_patch: Some(r#"{"sudo":{"key":"alice"}}"#.to_string()),
 		};
 		assert_eq!(
 			cmd.display(),
-			"pop build spec --path ./my-project --output output.json --profile release --para-id 2000 --default-bootnode true --type Development --features \"feature1,feature2\" --skip-build --chain dev --is-relay --relay paseo --name \"My Chain\" --id my_chain --protocol-id my_protocol --properties \"tokenSymbol=UNIT,decimals=12\" --genesis-state true --genesis-code true --deterministic true --tag v1 --runtime-dir ./runtime --package my-package --raw"
+			"pop build spec --path ./my-project --output output.json --profile release --para-id 2000 --default-bootnode true --type Development --features \"feature1,feature2\" --skip-build --chain dev --is-relay --relay paseo --name \"My Chain\" --id my_chain --protocol-id my_protocol --properties \"tokenSymbol=UNIT,decimals=12\" --genesis-state true --genesis-code true --deterministic true --tag v1 --runtime-dir ./runtime --package my-package --raw --genesis-patch '{\"sudo\":{\"key\":\"alice\"}}'"
 		);
 
 		let cmd = BuildSpecCommand { path: PathBuf::from("./"), ..Default::default() };
@@ -1117,6 +1205,7 @@ mod tests {
 			// All flags used. Parachain
 			(
 				BuildSpecCommand {
+					command: None,
 					path: path.clone(),
 					output_file: Some(PathBuf::from(output_file)),
 					profile: Some(profile),
@@ -1139,12 +1228,14 @@ mod tests {
 					package: Some(package.to_string()),
 					runtime_dir: Some(runtime_dir.clone()),
 					raw,
+					genesis_patch: None,
 				},
 				Some("local".to_string()),
 			),
 			// All flags used. Relay
 			(
 				BuildSpecCommand {
+					command: None,
 					path: path.clone(),
 					output_file: Some(PathBuf::from(output_file)),
 					profile: Some(profile),
@@ -1167,6 +1258,7 @@ mod tests {
 					package: Some(package.to_string()),
 					runtime_dir: Some(runtime_dir.clone()),
 					raw,
+					genesis_patch: None,
 				},
 				Some("local".to_string()),
 			),
@@ -1292,6 +1384,7 @@ mod tests {
 				},
 				// All flags used. Parachain
 				BuildSpecCommand {
+					command: None,
 					path: path.clone(),
 					output_file: Some(PathBuf::from(output_file)),
 					profile: Some(profile),
@@ -1314,9 +1407,11 @@ mod tests {
 					package: Some(package.to_string()),
 					runtime_dir: Some(runtime_dir.clone()),
 					raw: true,
+					genesis_patch: None,
 				},
 				// All flags used. Relay
 				BuildSpecCommand {
+					command: None,
 					path: path.clone(),
 					output_file: Some(PathBuf::from(output_file)),
 					profile: Some(profile),
@@ -1339,6 +1434,7 @@ mod tests {
 					package: Some(package.to_string()),
 					runtime_dir: Some(runtime_dir.clone()),
 					raw: true,
+					genesis_patch: None,
 				},
 			] {
 				let mut cli = MockCli::new().expect_confirm(
@@ -1627,6 +1723,7 @@ mod tests {
 		let temp_dir = tempdir()?;
 		let output_file = temp_dir.path().join("chain-spec.json");
 		let command = BuildSpecCommand {
+			command: None,
 			path: PathBuf::from("./"),
 			output_file: Some(output_file.clone()),
 			profile: Some(Profile::Release),
@@ -1649,6 +1746,7 @@ mod tests {
 			runtime_dir: None,
 			package: None,
 			raw: false,
+			genesis_patch: Some(r#"{"sudo":{"key":"alice"}}"#.to_string()),
 		};
 		let build_spec = command.configure_build_spec_json()?;
 		assert_eq!(build_spec.output_file, output_file);
@@ -1658,6 +1756,40 @@ mod tests {
 		assert_eq!(build_spec.protocol_id, "json".to_string());
 		assert_eq!(build_spec.features, vec!["runtime-benchmarks".to_string()]);
 		assert!(!build_spec.use_existing_plain_spec);
+		assert_eq!(build_spec.genesis_patch, Some(json!({ "sudo": { "key": "alice" } })));
+
+		let command = BuildSpecCommand { genesis_patch: Some("[]".to_string()), ..command };
+		assert!(
+			command
+				.configure_build_spec_json()
+				.unwrap_err()
+				.downcast_ref::<InvalidInputError>()
+				.is_some()
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn patch_genesis_works() -> anyhow::Result<()> {
+		let temp_dir = tempdir()?;
+		let output_file = temp_dir.path().join("chain-spec.json");
+		fs::write(
+			&output_file,
+			json!({"genesis": {"runtimeGenesis": {
+				"code": "0x00",
+				"patch": { "parachainInfo": { "parachainId": 2000 }, "sudo": { "key": "alice" } }
+			}}})
+			.to_string(),
+		)?;
+		let build_spec = BuildSpec { output_file: output_file.clone(), ..Default::default() };
+		build_spec.patch_genesis(json!({ "sudo": { "key": "bob" }, "parachainInfo": null }))?;
+		assert_eq!(
+			serde_json::from_str::<Value>(&fs::read_to_string(&output_file)?)?,
+			json!({"genesis": {"runtimeGenesis": {
+				"code": "0x00",
+				"patch": { "sudo": { "key": "bob" } }
+			}}})
+		);
 		Ok(())
 	}
 
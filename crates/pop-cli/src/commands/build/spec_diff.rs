@@ -0,0 +1,152 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	output::{CliResponse, OutputMode, invalid_input_error},
+};
+use anyhow::Result;
+use clap::Args;
+use pop_chains::{ChainSpec, ChainSpecChange, Metadata};
+use pop_fork::RuntimeExecutor;
+use scale::Decode;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// Compare two chain specifications.
+#[derive(Args, Default)]
+pub(crate) struct DiffSpecCommand {
+	/// The old chain specification.
+	pub(crate) old: PathBuf,
+	/// The new chain specification.
+	pub(crate) new: PathBuf,
+}
+
+/// Structured output for `build spec diff` in JSON mode.
+#[derive(Serialize)]
+struct DiffSpecOutput {
+	raw: bool,
+	changes: Vec<ChainSpecChange>,
+}
+
+impl DiffSpecCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Compare chain specs")?;
+		let old = load(&self.old)?;
+		let new = load(&self.new)?;
+
+		// Only the genesis storage of raw chain specs needs decoding.
+		let (old_metadata, new_metadata) = if old.is_raw() && new.is_raw() {
+			let spinner = cli.spinner();
+			spinner.start("Loading the metadata of the runtimes of the chain specs...");
+			let metadata = tokio::join!(runtime_metadata(&old), runtime_metadata(&new));
+			spinner.clear();
+			let mut loaded = |path: &Path, metadata: Result<Metadata>| match metadata {
+				Ok(metadata) => Ok::<_, anyhow::Error>(Some(metadata)),
+				Err(e) => {
+					cli.warning(format!(
+						"Failed to load the metadata of the runtime of {}, its storage entries are \
+						 shown undecoded: {e}",
+						path.display()
+					))?;
+					Ok(None)
+				},
+			};
+			(loaded(&self.old, metadata.0)?, loaded(&self.new, metadata.1)?)
+		} else {
+			(None, None)
+		};
+
+		let diff = old
+			.diff(&new, old_metadata.as_ref(), new_metadata.as_ref())
+			.map_err(|e| invalid_input_error(e.to_string()))?;
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(DiffSpecOutput { raw: old.is_raw(), changes: diff.changes })
+				.print_json();
+		} else if diff.is_empty() {
+			cli.outro("No differences found.")?;
+		} else {
+			cli.plain(diff.render())?;
+			cli.outro(format!("{} change(s) found.", diff.changes.len()))?;
+		}
+		Ok(())
+	}
+}
+
+// Loads a chain specification.
+fn load(path: &Path) -> Result<ChainSpec> {
+	ChainSpec::from(path)
+		.map_err(|e| invalid_input_error(format!("Failed to load {}: {e}", path.display())))
+}
+
+// Loads the metadata of the runtime embedded in a chain specification.
+async fn runtime_metadata(chain_spec: &ChainSpec) -> Result<Metadata> {
+	let executor = RuntimeExecutor::new(chain_spec.runtime_code()?, None)?;
+	Ok(Metadata::decode(&mut executor.metadata().await?.as_slice())?)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::InvalidInputError};
+	use serde_json::json;
+	use std::fs;
+
+	fn write(dir: &Path, name: &str, spec: serde_json::Value) -> Result<PathBuf> {
+		let path = dir.join(name);
+		fs::write(&path, spec.to_string())?;
+		Ok(path)
+	}
+
+	#[tokio::test]
+	async fn diff_plain_specs_works() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let old = write(
+			dir.path(),
+			"old.json",
+			json!({ "name": "Development", "genesis": { "runtimeGenesis": { "patch": {} } } }),
+		)?;
+		let new = write(
+			dir.path(),
+			"new.json",
+			json!({ "name": "Local", "genesis": { "runtimeGenesis": { "patch": {} } } }),
+		)?;
+
+		let mut cli = MockCli::new()
+			.expect_intro("Compare chain specs")
+			.expect_plain("~ name: \"Development\" -> \"Local\"")
+			.expect_outro("1 change(s) found.");
+		DiffSpecCommand { old: old.clone(), new }
+			.execute(&mut cli, OutputMode::Human)
+			.await?;
+		cli.verify()?;
+
+		let mut cli = MockCli::new()
+			.expect_intro("Compare chain specs")
+			.expect_outro("No differences found.");
+		DiffSpecCommand { old: old.clone(), new: old }
+			.execute(&mut cli, OutputMode::Human)
+			.await?;
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn diff_plain_and_raw_specs_fails() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let plain = write(
+			dir.path(),
+			"plain.json",
+			json!({ "genesis": { "runtimeGenesis": { "patch": {} } } }),
+		)?;
+		let raw = write(dir.path(), "raw.json", json!({ "genesis": { "raw": { "top": {} } } }))?;
+
+		let mut cli = MockCli::new().expect_intro("Compare chain specs");
+		let error = DiffSpecCommand { old: plain, new: raw }
+			.execute(&mut cli, OutputMode::Human)
+			.await
+			.unwrap_err();
+		assert!(error.downcast_ref::<InvalidInputError>().is_some());
+		cli.verify()
+	}
+}
@@ -0,0 +1,167 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	output::{CliResponse, OutputMode, build_error_with_details, invalid_input_error},
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use pop_chains::{ChainSpec, GenesisError, GenesisJson, RuntimeGenesis, merge_patch};
+use pop_fork::{ExecutorError, RuntimeExecutor};
+use serde::Serialize;
+use serde_json::Value;
+use std::path::PathBuf;
+
+/// Validate the genesis config of a chain specification.
+#[derive(Args, Default)]
+pub(crate) struct ValidateSpecCommand {
+	/// The plain chain specification to validate.
+	pub(crate) spec: PathBuf,
+}
+
+/// JSON output for `build spec validate --json`.
+#[derive(Debug, Serialize)]
+pub(crate) struct ValidateSpecOutput {
+	chain_spec_path: String,
+	/// Whether the chain specification holds a patch of the default genesis config, rather than a
+	/// complete one.
+	patch: bool,
+}
+
+impl ValidateSpecCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Validate your chain spec")?;
+		let chain_spec = ChainSpec::from(&self.spec).map_err(|e| {
+			invalid_input_error(format!("Failed to load {}: {e}", self.spec.display()))
+		})?;
+		let genesis = chain_spec.runtime_genesis().map_err(|e| {
+			invalid_input_error(format!(
+				"{} is not a plain chain specification: {e}",
+				self.spec.display()
+			))
+		})?;
+		let patch = matches!(genesis, RuntimeGenesis::Patch(_));
+
+		let spinner = cli.spinner();
+		spinner.start("Building the genesis state with the runtime of the chain spec...");
+		let validated = match chain_spec.runtime_code() {
+			Ok(code) => validate_genesis(code, genesis).await,
+			Err(e) => Err(e.into()),
+		};
+		spinner.clear();
+		if let Some(error) = validated? {
+			cli.outro_cancel(format!("Invalid genesis config: {error}"))?;
+			return Err(build_error_with_details(
+				"The genesis config of the chain spec is invalid",
+				error.to_string(),
+			));
+		}
+
+		cli.outro(format!("The genesis config of {} is valid ✅", self.spec.display()))?;
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(ValidateSpecOutput {
+				chain_spec_path: self.spec.display().to_string(),
+				patch,
+			})
+			.print_json();
+		}
+		Ok(())
+	}
+}
+
+/// Builds the genesis state from a genesis config with the runtime, returning the error reported
+/// by the runtime if the config is invalid.
+///
+/// # Arguments
+/// * `code` - The runtime code.
+/// * `genesis` - The genesis config.
+async fn validate_genesis(code: Vec<u8>, genesis: RuntimeGenesis) -> Result<Option<GenesisError>> {
+	let executor = RuntimeExecutor::new(code, None)?;
+	let config = match genesis {
+		RuntimeGenesis::Config(config) => config,
+		RuntimeGenesis::Patch(patch) => {
+			// As done by the node, patches are applied to the default genesis config.
+			let preset = executor
+				.genesis_preset(None)
+				.await?
+				.ok_or_else(|| anyhow!("The runtime does not provide a default genesis config"))?;
+			let mut config: Value = serde_json::from_slice(&preset)?;
+			merge_patch(&mut config, patch);
+			config
+		},
+	};
+
+	let json = GenesisJson::new(&config);
+	match executor.build_genesis_state(json.as_bytes()).await {
+		Ok(result) => Ok(result.err().map(|error| json.error(&error))),
+		// Some runtimes panic on an invalid genesis config rather than reporting it.
+		Err(ExecutorError::RuntimeError { message, .. }) => Ok(Some(json.error(&message))),
+		Err(e) => Err(e.into()),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::BuildCommandError};
+	use pop_chains::fetch_code;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use serde_json::json;
+	use sp_core::bytes::to_hex;
+	use std::fs;
+
+	async fn live_runtime_code() -> Result<Vec<u8>> {
+		Ok(fetch_code(&shared_substrate_ws_url().await, None).await?.1)
+	}
+
+	#[tokio::test]
+	async fn validate_genesis_works() -> Result<()> {
+		let code = live_runtime_code().await?;
+		assert_eq!(validate_genesis(code.clone(), RuntimeGenesis::Patch(json!({}))).await?, None);
+
+		let error = validate_genesis(
+			code,
+			RuntimeGenesis::Patch(json!({ "balances": { "balancse": [] } })),
+		)
+		.await?
+		.expect("the genesis config is invalid");
+		assert_eq!(error.path.as_deref(), Some("balances.balancse"));
+		assert!(error.message.contains("unknown field `balancse`"));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn validate_spec_fails_on_invalid_genesis() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let spec = dir.path().join("chain-spec.json");
+		fs::write(
+			&spec,
+			json!({
+				"name": "Development",
+				"genesis": { "runtimeGenesis": {
+					"code": to_hex(&live_runtime_code().await?, false),
+					"patch": { "system": { "unknown": 1 } }
+				}}
+			})
+			.to_string(),
+		)?;
+		let mut cli = MockCli::new().expect_intro("Validate your chain spec");
+		let command = ValidateSpecCommand { spec };
+		let error = command.execute(&mut cli, OutputMode::Human).await.unwrap_err();
+		let error = error.downcast_ref::<BuildCommandError>().expect("expected BuildCommandError");
+		assert!(error.details().is_some_and(|details| details.starts_with("`system.unknown`: ")));
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn validate_spec_fails_on_raw_spec() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let spec = dir.path().join("chain-spec-raw.json");
+		fs::write(&spec, json!({ "genesis": { "raw": { "top": {} } } }).to_string())?;
+		let mut cli = MockCli::new().expect_intro("Validate your chain spec");
+		let command = ValidateSpecCommand { spec };
+		assert!(command.execute(&mut cli, OutputMode::Human).await.is_err());
+		cli.verify()
+	}
+}
@@ -1,11 +1,18 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use std::{path::Path, time::Duration};
+use std::{
+	fs::File,
+	io::BufWriter,
+	path::{Path, PathBuf},
+	time::Duration,
+};
 
 use crate::{
 	cli::{self, traits::*},
 	common::{
+		accounts,
 		chain::{self, Chain},
+		fork::{self as fork_preview, ForkPreview},
 		prompt::display_message,
 		urls,
 		wallet::{self, prompt_to_use_wallet},
@@ -15,15 +22,21 @@ use crate::{
 use anyhow::{Result, anyhow};
 use clap::Args;
 use pop_chains::{
-	Action, CallData, CallItem, DynamicPayload, Function, OnlineClient, Pallet, Param, Payload,
-	SubstrateConfig, construct_extrinsic, construct_sudo_extrinsic, decode_call_data,
-	encode_call_data, find_callable_by_name, find_pallet_by_name, raw_value_to_string,
-	render_storage_key_values, sign_and_submit_extrinsic, supported_actions, type_to_param,
+	Action, CallData, CallItem, CallScript, CallStep, DynamicPayload, ExportFormat, FeeAsset,
+	FeeEstimate, Function, Multisig, MultisigApproval, MultisigOperation, OfflineTransaction,
+	OnlineClient, Pallet, Param, Payload, ScriptExtrinsic, Signer, StoragePage,
+	SubmittableTransaction, SubstrateConfig, SupportedChains, TxOptions, construct_extrinsic,
+	construct_sudo_extrinsic, decode_call_data, encode_call_data, estimate_fee, event_names,
+	export_storage, find_callable_by_name, find_pallet_by_name, iter_storage, key_to_string,
+	missing_events, raw_value_to_string, render_storage_key_values, sign_extrinsic_with_suri,
+	step_dispatch_errors, step_events, submit_and_watch_extrinsic, submit_signed_extrinsic,
+	supported_actions, type_to_param,
 };
-use pop_common::create_signer;
+use pop_common::{AccountSigner, KeyScheme, parse_account};
 use scale_info::PortableRegistry;
 use scale_value::{Composite, Value, ValueDef};
 use serde::Serialize;
+use sp_core::bytes::{from_hex, to_hex};
 use url::Url;
 
 const DEFAULT_URI: &str = "//Alice";
@@ -111,6 +124,10 @@ pub struct CallChainCommand {
 	/// Websocket endpoint of a node.
 	#[arg(short, long, value_parser)]
 	url: Option<Url>,
+	/// Well-known chain to call (e.g., paseo, polkadot, asset-hub), instead of a `--url`.
+	#[arg(long, value_enum, conflicts_with = "url")]
+	#[serde(skip)]
+	chain: Option<SupportedChains>,
 	/// Secret key URI for the account signing the extrinsic.
 	///
 	/// e.g.
@@ -128,6 +145,14 @@ pub struct CallChainCommand {
 		conflicts_with = "suri"
 	)]
 	use_wallet: bool,
+	/// Name of a keystore account to sign the extrinsic with, instead of a `--suri`. The
+	/// password is prompted for. See `pop account`.
+	#[arg(long, conflicts_with_all = ["suri", "use-wallet"])]
+	account: Option<String>,
+	/// Key scheme of the signer, resolved when unlocking `--account`.
+	#[arg(skip)]
+	#[serde(skip)]
+	scheme: KeyScheme,
 	/// SCALE encoded bytes representing the call data of the extrinsic.
 	#[arg(name = "call", short, long, conflicts_with_all = ["pallet", "function", "args"])]
 	call_data: Option<String>,
@@ -140,12 +165,85 @@ pub struct CallChainCommand {
 	/// Submit the extrinsic without prompting for execution confirmation.
 	#[arg(short = 'x', long)]
 	execute: bool,
+	/// Tip to pay the block author, in the smallest unit of the token the fees are paid in.
+	#[arg(long, default_value = "0", conflicts_with = "use-wallet")]
+	tip: u128,
+	/// Nonce to sign the extrinsic with, instead of the next nonce of the signer. Useful to
+	/// replace a pending transaction or to queue several transactions.
+	#[arg(long, conflicts_with = "use-wallet")]
+	nonce: Option<u64>,
+	/// Make the extrinsic mortal, valid for this number of blocks from the latest block.
+	#[arg(long, value_name = "BLOCKS", conflicts_with = "use-wallet")]
+	mortal: Option<u64>,
+	/// Pay the fees in an asset instead of the native token, on chains with the
+	/// `ChargeAssetTxPayment` extension such as Asset Hub. Either the id of an asset of the
+	/// `Assets` pallet, e.g. 1984 for USDT, or a JSON location.
+	#[arg(long, value_name = "ASSET_ID", conflicts_with_all = ["use-wallet", "sign_offline"])]
+	fee_asset: Option<FeeAsset>,
+	/// Preview the extrinsic on a local fork of the chain before submitting it. The extrinsic
+	/// is executed with mocked signatures and its events and storage changes are displayed.
+	#[arg(long, conflicts_with = "use-wallet")]
+	fork: bool,
+	/// Block number to fork at when previewing with `--fork`, or to query storage at. Defaults to
+	/// the latest block.
+	#[arg(long)]
+	at: Option<u32>,
+	/// Maximum number of entries to return when iterating a storage map.
+	#[arg(long)]
+	limit: Option<u32>,
+	/// Only return the storage entries after this hex-encoded storage key, e.g. the last key of
+	/// the previous page.
+	#[arg(long, value_name = "KEY")]
+	start_key: Option<String>,
+	/// Export the storage entries to this file, as CSV for `.csv` files or otherwise as JSON
+	/// lines, with their decoded keys and values.
+	#[arg(long, value_name = "FILE")]
+	export: Option<PathBuf>,
+	/// Prepare the extrinsic for offline signing instead of submitting it. The signer payload and
+	/// the transaction parameters are written to this file, to be signed with `pop tx sign` and
+	/// submitted with `pop tx submit`.
+	#[arg(
+		long,
+		value_name = "FILE",
+		requires = "signer",
+		conflicts_with_all = ["suri", "use-wallet", "account", "execute", "fork"]
+	)]
+	sign_offline: Option<PathBuf>,
+	/// SS58 address of the account signing the extrinsic offline.
+	#[arg(long, requires = "sign_offline")]
+	signer: Option<String>,
+	/// Dispatch the call from a multisig account with this approval threshold. The call is
+	/// approved on behalf of the signer, which must be one of the `--signatories`.
+	#[arg(
+		long,
+		value_name = "THRESHOLD",
+		requires = "signatories",
+		conflicts_with_all = ["use-wallet", "call"]
+	)]
+	multisig: Option<u16>,
+	/// Signatories of the multisig account, separated by commas.
+	#[arg(long, value_delimiter = ',', requires = "multisig")]
+	signatories: Vec<String>,
+	/// List the operations of the multisig account awaiting approval instead of executing a
+	/// call.
+	#[arg(long, requires = "multisig", conflicts_with_all = ["function", "args", "execute", "fork", "sign_offline"])]
+	pending: bool,
+	/// Execute the steps of a YAML or JSON script instead of a single call. Each step names a
+	/// `pallet`, `function` and `args`, and optionally a `signer` and its `scheme`, `sudo`,
+	/// `proxy` and the `expect_events` it must emit. Steps without a signer are signed with
+	/// `--suri` or `--account`.
+	#[arg(
+		long,
+		value_name = "FILE",
+		conflicts_with_all = ["pallet", "function", "args", "call", "use-wallet", "sudo", "fork", "sign_offline", "multisig"]
+	)]
+	pub(crate) script: Option<PathBuf>,
 	/// Display chain metadata instead of executing a call.
 	/// Use alone to list all pallets, or with --pallet to show pallet details.
 	#[arg(
 		short = 'm',
 		long,
-		conflicts_with_all = ["function", "args", "suri", "use-wallet", "call", "sudo", "execute"]
+		conflicts_with_all = ["function", "args", "suri", "use-wallet", "account", "call", "sudo", "execute", "fork", "sign_offline", "multisig", "script"]
 	)]
 	metadata: bool,
 }
@@ -159,12 +257,74 @@ pub(crate) struct CallChainOutput {
 	result: CallChainResult,
 }
 
+/// Structured output for `pop --json call chain --script`.
+#[derive(Debug, Serialize)]
+pub(crate) struct CallScriptOutput {
+	script: String,
+	steps: Vec<ScriptStepOutput>,
+}
+
+impl CallScriptOutput {
+	/// The first step of the script which failed, if any.
+	pub(crate) fn failed_step(&self) -> Option<&ScriptStepOutput> {
+		self.steps.iter().find(|step| step.status == ScriptStepStatus::Failed)
+	}
+}
+
+/// Result of a step of a script.
+#[derive(Debug, Serialize)]
+pub(crate) struct ScriptStepOutput {
+	step: String,
+	status: ScriptStepStatus,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	tx_hash: Option<String>,
+	events: Vec<String>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	error: Option<String>,
+}
+
+impl ScriptStepOutput {
+	/// Describes the failure of the step, if it failed.
+	pub(crate) fn failure(&self) -> Option<String> {
+		self.error.as_ref().map(|error| format!("{}: {error}", self.step))
+	}
+}
+
+/// The outcome of a step of a script.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ScriptStepStatus {
+	/// The step was executed and emitted its expected events.
+	Succeeded,
+	/// The extrinsic of the step failed, or the step did not emit its expected events.
+	Failed,
+	/// The step was not executed, as a previous step failed.
+	Skipped,
+}
+
 /// Result details for a chain call.
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "snake_case", tag = "kind")]
 pub(crate) enum CallChainResult {
-	DryRun { return_value: String },
-	Submitted { tx_hash: String, block_hash: Option<String>, events: Vec<String> },
+	DryRun {
+		return_value: String,
+		fee: Option<FeeEstimate>,
+	},
+	OfflinePrepared {
+		file: String,
+		signer_payload: String,
+	},
+	ForkPreview {
+		dropped: Option<String>,
+		events: Vec<String>,
+		storage_changes: Vec<String>,
+	},
+	Submitted {
+		tx_hash: String,
+		block_hash: Option<String>,
+		events: Vec<String>,
+		fee: Option<FeeEstimate>,
+	},
 }
 
 impl CallChainCommand {
@@ -177,7 +337,7 @@ impl CallChainCommand {
 			"Select a chain (type to filter)",
 			"Which chain would you like to interact with?",
 			urls::LOCAL,
-			&self.url,
+			&self.resolve_url()?,
 			|_| true,
 			&mut cli,
 		)
@@ -188,6 +348,34 @@ impl CallChainCommand {
 			return self.display_metadata(&chain, &mut cli);
 		}
 
+		// List the pending operations of a multisig account.
+		if self.pending {
+			return self.display_pending_operations(&chain, &mut cli).await;
+		}
+
+		self.unlock_account(&mut cli)?;
+
+		// Execute the steps of a script.
+		if let Some(path) = self.script.clone() {
+			let steps = match self.execute_script(&chain, &path, &mut cli).await {
+				Ok(steps) => steps,
+				Err(e) => {
+					display_message(&e.to_string(), false, &mut cli)?;
+					return Err(e);
+				},
+			};
+			match steps.iter().find_map(ScriptStepOutput::failure) {
+				_ if steps.is_empty() =>
+					display_message("The script was not executed.", false, &mut cli)?,
+				Some(failure) => {
+					display_message(&failure, false, &mut cli)?;
+					return Err(anyhow!(failure));
+				},
+				None => display_message("Script complete.", true, &mut cli)?,
+			}
+			return Ok(());
+		}
+
 		// Execute the call if call_data is provided.
 		if let Some(call_data) = self.call_data.as_ref() {
 			self.submit_extrinsic_from_call_data(
@@ -220,9 +408,33 @@ impl CallChainCommand {
 							return Err(e);
 						},
 					};
+					// Approve the call as a signatory of a multisig account.
+					let xt = match self
+						.approve_as_multisig(&chain.client, xt, call.suri.as_deref(), &mut cli)
+						.await
+					{
+						Ok(payload) => payload,
+						Err(e) => {
+							display_message(&e.to_string(), false, &mut cli)?;
+							return Err(e);
+						},
+					};
 
-					// Sign and submit the extrinsic.
-					let result = if self.use_wallet {
+					// Preview the extrinsic on a fork before it can be submitted.
+					if self.fork {
+						let suri = call.suri.as_deref().unwrap_or(DEFAULT_URI);
+						if let Err(e) = self.preview_on_fork(&chain, &xt, suri, &mut cli).await {
+							display_message(&e.to_string(), false, &mut cli)?;
+							return Err(e);
+						}
+					}
+
+					// Sign and submit the extrinsic, or prepare it for offline signing.
+					let result = if let Some(path) = &self.sign_offline {
+						self.prepare_offline(&chain.client, &chain.url, &xt, path)
+							.await
+							.and_then(|_| Ok(cli.success(offline_prepared_message(path))?))
+					} else if self.use_wallet {
 						let call_data = xt.encode_call_data(&chain.client.metadata())?;
 						wallet::submit_extrinsic(&chain.client, &chain.url, call_data, &mut cli)
 							.await
@@ -244,6 +456,17 @@ impl CallChainCommand {
 					// We already have the value of a constant, so we don't need to query it
 					cli.success(&raw_value_to_string(&constant.value, "")?)?;
 				},
+				CallItem::RuntimeApi(ref api) =>
+					match api.call(&chain.client, call.args.clone()).await {
+						Ok(value) => cli.success(&raw_value_to_string(&value, "")?)?,
+						Err(e) => {
+							cli.error(format!("Failed to call the runtime API: {e}"))?;
+							return Err(anyhow!("Failed to call the runtime API: {e}"));
+						},
+					},
+				CallItem::Storage(ref storage) if self.iterates_storage() => {
+					self.iterate_storage(&chain, storage, call.args.clone(), &mut cli).await?;
+				},
 				CallItem::Storage(ref storage) => {
 					// Parse string arguments to Value types for storage query
 					let keys = if !call.args.is_empty() {
@@ -336,12 +559,15 @@ impl CallChainCommand {
 	}
 
 	/// Executes `call chain` in JSON mode and returns structured output.
-	pub(crate) async fn execute_json(self) -> Result<CallChainOutput> {
+	pub(crate) async fn execute_json(mut self) -> Result<CallChainOutput> {
 		if self.metadata {
 			return Err(invalid_input_error(
 				"`pop --json call chain` does not support `--metadata`",
 			));
 		}
+		if self.pending {
+			return Err(invalid_input_error("`pop --json call chain` does not support `--pending`"));
+		}
 		if self.use_wallet {
 			return Err(invalid_input_error(
 				"`pop --json call chain` does not support `--use-wallet`; provide `--suri`",
@@ -353,8 +579,14 @@ impl CallChainCommand {
 			));
 		}
 
+		if self.fork && self.execute {
+			return Err(invalid_input_error(
+				"`pop --json call chain` does not support `--fork` together with `--execute`",
+			));
+		}
+
 		let mut missing = Vec::new();
-		if self.url.is_none() {
+		if self.url.is_none() && self.chain.is_none() {
 			missing.push("--url");
 		}
 		if self.pallet.is_none() {
@@ -363,7 +595,7 @@ impl CallChainCommand {
 		if self.function.is_none() {
 			missing.push("--function");
 		}
-		if self.suri.is_none() {
+		if self.suri.is_none() && self.account.is_none() && self.sign_offline.is_none() {
 			missing.push("--suri");
 		}
 		if !missing.is_empty() {
@@ -374,11 +606,12 @@ impl CallChainCommand {
 		}
 
 		let mut json_cli = crate::cli::JsonCli;
+		self.unlock_account(&mut json_cli)?;
 		let chain = chain::configure(
 			"Select a chain (type to filter)",
 			"Which chain would you like to interact with?",
 			urls::LOCAL,
-			&self.url,
+			&self.resolve_url()?,
 			|_| true,
 			&mut json_cli,
 		)
@@ -417,34 +650,86 @@ impl CallChainCommand {
 			function: call_item.clone(),
 			args: expanded_args,
 			suri: self.suri.clone(),
+			account: self.account.clone(),
+			scheme: self.scheme,
 			use_wallet: false,
 			skip_confirm: true,
 			execute: self.execute,
 			sudo: self.sudo,
+			multisig: self.multisig,
+			signatories: self.signatories.clone(),
+			options: self.tx_options(),
 		};
 		let xt = call
 			.prepare_extrinsic(&chain.client, &mut json_cli)
 			.map_err(|e| invalid_input_error(e.to_string()))?;
+		let xt = self
+			.approve_as_multisig(&chain.client, xt, self.suri.as_deref(), &mut json_cli)
+			.await
+			.map_err(|e| invalid_input_error(e.to_string()))?;
 		let call_data =
 			encode_call_data(&chain.client, &xt).map_err(|e| invalid_input_error(e.to_string()))?;
-		let suri = self.suri.expect("checked above; qed");
-
-		let result = if self.execute {
-			let submit_output = sign_and_submit_extrinsic(&chain.client, &chain.url, xt, &suri)
-				.await
-				.map_err(map_chain_submit_error)?;
-			let (tx_hash, events) = parse_chain_submit_output(&submit_output);
-			CallChainResult::Submitted { tx_hash, block_hash: None, events }
-		} else {
-			let signer = create_signer(&suri).map_err(|e| invalid_input_error(e.to_string()))?;
-			let tx = chain
-				.client
-				.tx()
-				.create_signed(&xt, &signer, Default::default())
+		if let Some(path) = &self.sign_offline {
+			let transaction = self
+				.prepare_offline(&chain.client, &chain.url, &xt, path)
 				.await
 				.map_err(map_chain_network_error)?;
-			let validation = tx.validate().await.map_err(map_chain_network_error)?;
-			CallChainResult::DryRun { return_value: format!("{validation:?}") }
+			return Ok(CallChainOutput {
+				pallet: function.pallet.clone(),
+				function: function.name.clone(),
+				call_data,
+				result: CallChainResult::OfflinePrepared {
+					file: path.display().to_string(),
+					signer_payload: transaction.signer_payload,
+				},
+			});
+		}
+		let suri = self.suri.as_deref().expect("checked above; qed");
+
+		let result = if self.fork {
+			let signer = AccountSigner::from_uri(suri, self.scheme)
+				.map_err(|e| invalid_input_error(e.to_string()))?;
+			let preview = fork_preview::preview_extrinsic(
+				&self.fork_endpoints(&chain)?,
+				self.at,
+				&chain.client,
+				&xt,
+				&signer,
+			)
+			.await
+			.map_err(map_chain_network_error)?;
+			CallChainResult::ForkPreview {
+				dropped: preview.dropped,
+				events: preview.events.iter().map(fork_preview::format_event).collect(),
+				storage_changes: preview.changes.iter().map(ToString::to_string).collect(),
+			}
+		} else {
+			let extrinsic = sign_extrinsic_with_suri(
+				&chain.client,
+				&chain.url,
+				&xt,
+				suri,
+				self.scheme,
+				&self.tx_options(),
+			)
+			.await
+			.map_err(|e| invalid_input_error(e.to_string()))?;
+			let fee = estimate_fee(&chain.client, &extrinsic).await.ok();
+			if self.execute {
+				let submit_output =
+					submit_and_watch_extrinsic(&chain.client, &chain.url, extrinsic)
+						.await
+						.map_err(map_chain_submit_error)?;
+				let (tx_hash, events) = parse_chain_submit_output(&submit_output);
+				CallChainResult::Submitted { tx_hash, block_hash: None, events, fee }
+			} else {
+				let validation =
+					SubmittableTransaction::from_bytes(chain.client.clone(), extrinsic)
+						.validate()
+						.await
+						.map_err(map_chain_network_error)?;
+				CallChainResult::DryRun { return_value: format!("{validation:?}"), fee }
+			}
 		};
 
 		Ok(CallChainOutput {
@@ -455,6 +740,185 @@ impl CallChainCommand {
 		})
 	}
 
+	/// Executes `call chain --script` in JSON mode and returns the results of the steps, including
+	/// the step which failed, if any.
+	pub(crate) async fn execute_script_json(mut self) -> Result<CallScriptOutput> {
+		let path = self.script.clone().ok_or(anyhow!("`--script` is required"))?;
+		if self.url.is_none() && self.chain.is_none() {
+			return Err(prompt_required_error(
+				"Missing required flags for `pop --json call chain`: --url",
+			));
+		}
+
+		let mut json_cli = crate::cli::JsonCli;
+		self.unlock_account(&mut json_cli)?;
+		self.skip_confirm = true;
+		let chain = chain::configure(
+			"Select a chain (type to filter)",
+			"Which chain would you like to interact with?",
+			urls::LOCAL,
+			&self.resolve_url()?,
+			|_| true,
+			&mut json_cli,
+		)
+		.await
+		.map_err(map_chain_network_error)?;
+		let steps = self
+			.execute_script(&chain, &path, &mut json_cli)
+			.await
+			.map_err(map_chain_submit_error)?;
+		Ok(CallScriptOutput { script: path.display().to_string(), steps })
+	}
+
+	// Executes the steps of a script in order, submitting an extrinsic per step or per batch of
+	// steps. Stops at the first step which fails, also when dispatched through `Sudo` or `Proxy`,
+	// or does not emit its expected events, which is marked as failed and the remaining steps as
+	// skipped. Returns no results if the submission
+	// was not confirmed.
+	async fn execute_script(
+		&self,
+		chain: &Chain,
+		path: &Path,
+		cli: &mut impl Cli,
+	) -> Result<Vec<ScriptStepOutput>> {
+		let script = CallScript::load(path)?;
+		let extrinsics = script.extrinsics(&chain.pallets)?;
+		cli.info(format!(
+			"Loaded {} steps from {}, to be submitted in {} extrinsics.",
+			script.steps.len(),
+			path.display(),
+			extrinsics.len()
+		))?;
+		if !self.skip_confirm &&
+			!self.execute &&
+			!cli.confirm("Do you want to submit the extrinsics?")
+				.initial_value(true)
+				.interact()?
+		{
+			return Ok(vec![]);
+		}
+
+		let mut results = Vec::with_capacity(script.steps.len());
+		let mut failed = false;
+		for extrinsic in extrinsics {
+			let steps: Vec<_> = extrinsic.steps.iter().map(|&index| &script.steps[index]).collect();
+			let output = |step: &CallStep, status, tx_hash, events, error| ScriptStepOutput {
+				step: step.label(),
+				status,
+				tx_hash,
+				events,
+				error,
+			};
+			if failed {
+				results.extend(
+					steps
+						.iter()
+						.map(|step| output(step, ScriptStepStatus::Skipped, None, vec![], None)),
+				);
+				continue;
+			}
+			let labels = steps.iter().map(|step| step.label()).collect::<Vec<_>>().join(", ");
+			let (tx_hash, names, dispatch_errors) = match self
+				.submit_script_extrinsic(chain, &extrinsic, &labels, cli)
+				.await
+			{
+				Ok(submitted) => submitted,
+				Err(e) => {
+					// The steps of a batch are dispatched all together or not at all.
+					failed = true;
+					results.extend(steps.iter().map(|step| {
+						output(step, ScriptStepStatus::Failed, None, vec![], Some(e.to_string()))
+					}));
+					continue;
+				},
+			};
+			for ((step, events), dispatch_error) in
+				steps.iter().zip(step_events(&names, steps.len())).zip(dispatch_errors)
+			{
+				let missing = missing_events(&step.expect_events, &events);
+				let error = dispatch_error.or_else(|| {
+					(!missing.is_empty()).then(|| {
+						format!("expected events were not emitted: {}", missing.join(", "))
+					})
+				});
+				if let Some(error) = error {
+					failed = true;
+					results.push(output(
+						step,
+						ScriptStepStatus::Failed,
+						Some(tx_hash.clone()),
+						events,
+						Some(error),
+					));
+				} else {
+					cli.success(format!("{} submitted with hash: {tx_hash}", step.label()))?;
+					results.push(output(
+						step,
+						ScriptStepStatus::Succeeded,
+						Some(tx_hash.clone()),
+						events,
+						None,
+					));
+				}
+			}
+		}
+		Ok(results)
+	}
+
+	// Signs and submits an extrinsic of a script, returning its hash, the names of the events it
+	// emitted once finalized and the failure of each of its steps dispatched through `Sudo` or
+	// `Proxy`.
+	async fn submit_script_extrinsic(
+		&self,
+		chain: &Chain,
+		extrinsic: &ScriptExtrinsic,
+		labels: &str,
+		cli: &mut impl Cli,
+	) -> Result<(String, Vec<String>, Vec<Option<String>>)> {
+		let (suri, scheme) =
+			self.script_signer(extrinsic.signer.as_deref(), extrinsic.scheme, labels)?;
+		let signed = sign_extrinsic_with_suri(
+			&chain.client,
+			&chain.url,
+			&extrinsic.xt,
+			&suri,
+			scheme,
+			&TxOptions::default(),
+		)
+		.await?;
+		let spinner = cli.spinner();
+		spinner.start(format!("Submitting {labels} and waiting for finalization..."));
+		let events = submit_signed_extrinsic(chain.client.clone(), to_hex(&signed, false)).await;
+		spinner.clear();
+		let events = events?;
+		Ok((
+			format!("{:?}", events.extrinsic_hash()),
+			event_names(&events)?,
+			step_dispatch_errors(&chain.client, &events, extrinsic.steps.len())?,
+		))
+	}
+
+	// The secret key URI and scheme of the signer of script steps: the signer set by the script,
+	// with its scheme or `--scheme`, otherwise `--suri` or `--account`.
+	fn script_signer(
+		&self,
+		suri: Option<&str>,
+		scheme: Option<KeyScheme>,
+		steps: &str,
+	) -> Result<(String, KeyScheme)> {
+		let signer = match (suri, &self.suri) {
+			(Some(suri), _) => (suri.to_string(), scheme.unwrap_or(self.scheme)),
+			(None, Some(suri)) => (suri.clone(), self.scheme),
+			(None, None) =>
+				return Err(anyhow!(
+					"No signer for {steps}: set `signer` in the script, or provide `--suri` or `--account`"
+				)),
+		};
+		// Fail before submitting if the secret is not a valid key of its scheme.
+		signer.1.public_key(&signer.0)?;
+		Ok(signer)
+	}
+
 	// Configure the call based on command line arguments/call UI.
 	fn configure_call(&mut self, chain: &Chain, cli: &mut impl Cli) -> Result<Call> {
 		loop {
@@ -510,13 +974,22 @@ impl CallChainCommand {
 						self.check_sudo(chain, cli)?;
 					}
 
-					let (use_wallet, suri) = self.determine_signing_method(cli)?;
-					self.use_wallet = use_wallet;
-					(args, Some(suri))
+					// Extrinsics prepared for offline signing are signed on another machine.
+					if self.sign_offline.is_some() {
+						(args, None)
+					} else {
+						let (use_wallet, suri) = self.determine_signing_method(cli)?;
+						self.use_wallet = use_wallet;
+						(args, Some(suri))
+					}
 				},
 				CallItem::Storage(storage) => {
 					// Handle storage queries - check if parameters are needed
-					let args = if let Some(key_ty) = storage.key_id {
+					let args = if self.iterates_storage() {
+						// The keys provided are the first keys of the entries to iterate.
+						storage.query_all = true;
+						self.expand_file_arguments()?
+					} else if let Some(key_ty) = storage.key_id {
 						// Storage map requires key parameters
 						self.expand_file_arguments()?;
 						// Get metadata to convert type_id to Param
@@ -590,16 +1063,32 @@ impl CallChainCommand {
 				},
 				// Constants don't require parameters
 				CallItem::Constant(_) => (vec![], None),
+				CallItem::RuntimeApi(api) => {
+					if !api.is_supported {
+						cli.outro_cancel(
+							"The selected runtime API is not supported yet. Please choose another one.",
+						)?;
+						self.reset_for_new_call();
+						continue;
+					}
+					// Runtime API calls don't require signing
+					(self.resolve_args(&api.name, &api.params, cli)?, None)
+				},
 			};
 
 			return Ok(Call {
 				function: call_item,
 				args,
 				suri,
+				account: self.account.clone(),
+				scheme: self.scheme,
 				skip_confirm: self.skip_confirm,
 				execute: self.execute,
 				sudo: self.sudo,
 				use_wallet: self.use_wallet,
+				multisig: self.multisig,
+				signatories: self.signatories.clone(),
+				options: self.tx_options(),
 			});
 		}
 	}
@@ -612,6 +1101,12 @@ impl CallChainCommand {
 		call_data: &str,
 		cli: &mut impl Cli,
 	) -> Result<()> {
+		if let Some(path) = &self.sign_offline {
+			let call_data_bytes = decode_call_data(call_data).map_err(|err| anyhow!("{err:?}"))?;
+			self.prepare_offline(client, url, &CallData::new(call_data_bytes), path).await?;
+			display_message(&offline_prepared_message(path), true, cli)?;
+			return Ok(());
+		}
 		let (use_wallet, suri) = self.determine_signing_method(cli)?;
 
 		// Perform signing steps with wallet integration and return early.
@@ -624,6 +1119,23 @@ impl CallChainCommand {
 			return Ok(());
 		}
 		cli.info(format!("Encoded call data: {}", call_data))?;
+		if self.fork {
+			let chain = Chain { url: url.clone(), client: client.clone(), pallets: vec![] };
+			let call_data_bytes = decode_call_data(call_data).map_err(|err| anyhow!("{err:?}"))?;
+			self.preview_on_fork(&chain, &CallData::new(call_data_bytes), &suri, cli)
+				.await?;
+		}
+		let call_data_bytes = decode_call_data(call_data).map_err(|err| anyhow!("{err:?}"))?;
+		let extrinsic = sign_with_fee_estimate(
+			client,
+			url,
+			&CallData::new(call_data_bytes),
+			&suri,
+			self.scheme,
+			&self.tx_options(),
+			cli,
+		)
+		.await?;
 		if !self.skip_confirm &&
 			!self.execute &&
 			!cli.confirm("Do you want to submit the extrinsic?")
@@ -639,10 +1151,9 @@ impl CallChainCommand {
 		}
 		let spinner = cli.spinner();
 		spinner.start(
-			"Signing and submitting the extrinsic and then waiting for finalization, please be patient...",
+			"Submitting the extrinsic and then waiting for finalization, please be patient...",
 		);
-		let call_data_bytes = decode_call_data(call_data).map_err(|err| anyhow!("{err:?}"))?;
-		let result = sign_and_submit_extrinsic(client, url, CallData::new(call_data_bytes), &suri)
+		let result = submit_and_watch_extrinsic(client, url, extrinsic)
 			.await
 			.map_err(|err| anyhow!("{err:?}"))?;
 
@@ -700,6 +1211,242 @@ impl CallChainCommand {
 		}
 	}
 
+	// Writes the extrinsic and its signing parameters to the `--sign-offline` file.
+	async fn prepare_offline<Xt: Payload>(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		url: &Url,
+		xt: &Xt,
+		path: &Path,
+	) -> Result<OfflineTransaction> {
+		let signer =
+			self.signer.as_deref().ok_or(anyhow!("`--sign-offline` requires `--signer`"))?;
+		let transaction =
+			OfflineTransaction::new(client, url, xt, parse_account(signer)?, &self.tx_options())
+				.await?;
+		transaction.save(path)?;
+		Ok(transaction)
+	}
+
+	// Whether to iterate the entries of a storage item with `--limit`, `--start-key`, `--export`
+	// or `--at`, rather than query a single value.
+	fn iterates_storage(&self) -> bool {
+		self.limit.is_some() ||
+			self.start_key.is_some() ||
+			self.export.is_some() ||
+			(self.at.is_some() && !self.fork)
+	}
+
+	// Iterates the entries of a storage item under the keys provided, displaying them or
+	// exporting them to the `--export` file.
+	async fn iterate_storage(
+		&self,
+		chain: &Chain,
+		storage: &pop_chains::Storage,
+		args: Vec<String>,
+		cli: &mut impl Cli,
+	) -> Result<()> {
+		let keys = storage
+			.parse_keys(&chain.client.metadata(), args)
+			.map_err(|e| anyhow!("Failed to parse storage arguments: {e}"))?;
+		let start_key = self
+			.start_key
+			.as_deref()
+			.map(|key| from_hex(key).map_err(|e| anyhow!("Invalid start key `{key}`: {e}")))
+			.transpose()?;
+		let page = StoragePage { start_key, limit: self.limit, at: self.at };
+		let spinner = cli.spinner();
+		spinner.start(format!("Iterating {}::{}...", storage.pallet, storage.name));
+		let result = iter_storage(&chain.client, &chain.url, storage, keys, &page).await;
+		spinner.clear();
+		let result = result.map_err(|e| anyhow!("Failed to query storage: {e}"))?;
+
+		if let Some(path) = &self.export {
+			let format = ExportFormat::from_path(path);
+			export_storage(&result.entries, format, BufWriter::new(File::create(path)?))?;
+			cli.success(format!(
+				"Exported {} entries to {} as {format}.",
+				result.entries.len(),
+				path.display()
+			))?;
+		} else if result.entries.is_empty() {
+			cli.warning("No storage entries found")?;
+		} else {
+			let mut output = String::new();
+			for entry in &result.entries {
+				let keys = entry.keys.iter().map(key_to_string).collect::<Result<Vec<_>, _>>()?;
+				let value = match &entry.value {
+					Some(value) => raw_value_to_string(value, "")?,
+					None => "None".to_string(),
+				};
+				output.push_str(&format!("[{}] => {value}\n", keys.join(", ")));
+			}
+			cli.success(output.trim_end())?;
+		}
+		if let Some(next) = result.next_start_key {
+			cli.info(format!(
+				"More entries may follow. Continue with `--start-key {}`.",
+				to_hex(&next, false)
+			))?;
+		}
+		Ok(())
+	}
+
+	// The transaction options provided with `--tip`, `--nonce`, `--mortal` and `--fee-asset`.
+	fn tx_options(&self) -> TxOptions {
+		TxOptions {
+			tip: self.tip,
+			nonce: self.nonce,
+			mortality: self.mortal,
+			fee_asset: self.fee_asset.clone(),
+		}
+	}
+
+	// The multisig account provided with `--multisig` and `--signatories`, if any.
+	fn multisig(&self) -> Result<Option<Multisig>> {
+		let Some(threshold) = self.multisig else {
+			return Ok(None);
+		};
+		let signatories = self
+			.signatories
+			.iter()
+			.map(|signatory| parse_account(signatory.trim()))
+			.collect::<Result<Vec<_>, _>>()?;
+		Ok(Some(Multisig::new(threshold, signatories)?))
+	}
+
+	// Wraps the extrinsic for approval by the signer when dispatching from a multisig account,
+	// otherwise returns it unchanged.
+	async fn approve_as_multisig(
+		&self,
+		client: &OnlineClient<SubstrateConfig>,
+		xt: DynamicPayload,
+		suri: Option<&str>,
+		cli: &mut impl Cli,
+	) -> Result<DynamicPayload> {
+		let Some(multisig) = self.multisig()? else {
+			return Ok(xt);
+		};
+		let signatory = match (&self.signer, suri) {
+			(Some(signer), _) if self.sign_offline.is_some() => parse_account(signer)?,
+			(_, Some(suri)) if !self.use_wallet =>
+				Signer::<SubstrateConfig>::account_id(&AccountSigner::from_uri(suri, self.scheme)?),
+			_ =>
+				return Err(anyhow!(
+					"Multisig approvals must be signed with `--suri` or `--account`"
+				)),
+		};
+		let approval = multisig.approve(client, &signatory, xt).await?;
+		cli.info(approval_message(&multisig, &approval))?;
+		Ok(approval.xt)
+	}
+
+	// Displays the operations of the multisig account awaiting approval.
+	async fn display_pending_operations(&self, chain: &Chain, cli: &mut impl Cli) -> Result<()> {
+		let multisig = self.multisig()?.ok_or(anyhow!("`--pending` requires `--multisig`"))?;
+		let operations = multisig.pending_operations(&chain.client).await?;
+		if operations.is_empty() {
+			cli.info(format!(
+				"No pending operations for multisig account {}.",
+				multisig.account()
+			))?;
+			return Ok(());
+		}
+		cli.info(format!(
+			"Pending operations of multisig account {} ({}):\n",
+			multisig.account(),
+			operations.len()
+		))?;
+		for operation in &operations {
+			cli.plain(format_operation(&multisig, operation))?;
+		}
+		Ok(())
+	}
+
+	// Unlocks the keystore account provided with `--account`, if any, and signs with its key
+	// from then on.
+	fn unlock_account(&mut self, cli: &mut impl Cli) -> Result<()> {
+		if let Some(name) = &self.account {
+			let (suri, scheme) = accounts::unlock(&accounts::keystore()?, name, cli)?;
+			// Fail before configuring the call if the secret is not a valid key of its scheme.
+			scheme.public_key(&suri)?;
+			self.suri = Some(suri);
+			self.scheme = scheme;
+		}
+		self.ensure_scheme_supported()
+	}
+
+	// Ethereum keys can only sign the extrinsic itself: fork previews and multisig approvals use
+	// Substrate accounts.
+	fn ensure_scheme_supported(&self) -> Result<()> {
+		if self.scheme == KeyScheme::Ethereum && (self.fork || self.multisig.is_some()) {
+			return Err(invalid_input_error(
+				"Ethereum keys cannot be used with `--fork` or `--multisig`.",
+			));
+		}
+		Ok(())
+	}
+
+	// Resolves the URL of the chain, using the first RPC endpoint of a well-known chain if one
+	// was provided.
+	fn resolve_url(&self) -> Result<Option<Url>> {
+		match (&self.url, &self.chain) {
+			(None, Some(chain)) => {
+				let url = chain
+					.rpc_urls()
+					.first()
+					.ok_or_else(|| anyhow!("No RPC endpoints available for {chain}"))?;
+				Ok(Some(Url::parse(url)?))
+			},
+			(url, _) => Ok(url.clone()),
+		}
+	}
+
+	// Endpoints to fork from: all RPC endpoints of a well-known chain, or the chain URL.
+	fn fork_endpoints(&self, chain: &Chain) -> Result<Vec<Url>> {
+		match &self.chain {
+			Some(supported) => supported
+				.rpc_urls()
+				.iter()
+				.map(|url| Url::parse(url).map_err(Into::into))
+				.collect(),
+			None => Ok(vec![chain.url.clone()]),
+		}
+	}
+
+	// Executes the extrinsic on a local fork of the chain and displays its events and storage
+	// changes. Fails if the extrinsic could not be applied on the fork.
+	async fn preview_on_fork<Xt: Payload>(
+		&self,
+		chain: &Chain,
+		xt: &Xt,
+		suri: &str,
+		cli: &mut impl Cli,
+	) -> Result<()> {
+		let spinner = cli.spinner();
+		let signer = AccountSigner::from_uri(suri, self.scheme)?;
+		spinner.start("Forking the chain and executing the extrinsic, please be patient...");
+		let preview = fork_preview::preview_extrinsic(
+			&self.fork_endpoints(chain)?,
+			self.at,
+			&chain.client,
+			xt,
+			&signer,
+		)
+		.await;
+		spinner.clear();
+		let ForkPreview { dropped, events, changes } = preview?;
+		if let Some(reason) = dropped {
+			return Err(anyhow!("The extrinsic could not be applied on the fork: {reason}"));
+		}
+
+		let events: Vec<_> = events.iter().map(fork_preview::format_event).collect();
+		cli.info(format!("Events emitted on the fork:\n{}", events.join("\n")))?;
+		let changes: Vec<_> = changes.iter().map(ToString::to_string).collect();
+		cli.info(format!("Storage changes on the fork:\n{}", changes.join("\n")))?;
+		Ok(())
+	}
+
 	// Resets specific fields to default values for a new call.
 	fn reset_for_new_call(&mut self) {
 		self.pallet = None;
@@ -745,20 +1492,31 @@ impl CallChainCommand {
 		&mut self,
 		function: &Function,
 		cli: &mut impl Cli,
+	) -> Result<Vec<String>> {
+		self.resolve_args(&function.name, &function.params, cli)
+	}
+
+	/// Resolves the arguments of a dispatchable function or runtime API method named `name`.
+	/// See [`Self::resolve_function_args`].
+	fn resolve_args(
+		&mut self,
+		name: &str,
+		params: &[Param],
+		cli: &mut impl Cli,
 	) -> Result<Vec<String>> {
 		let expanded_args = self.expand_file_arguments()?;
-		if expanded_args.len() > function.params.len() {
+		if expanded_args.len() > params.len() {
 			return Err(anyhow!(
 				"Expected {} arguments for `{}`, but received {}. Remove the extra values or run \
 				 without `--args` to be prompted.",
-				function.params.len(),
-				function.name,
+				params.len(),
+				name,
 				expanded_args.len()
 			));
 		}
 
-		let mut resolved_args = Vec::with_capacity(function.params.len());
-		for (idx, param) in function.params.iter().enumerate() {
+		let mut resolved_args = Vec::with_capacity(params.len());
+		for (idx, param) in params.iter().enumerate() {
 			if let Some(value) = expanded_args.get(idx) {
 				resolved_args.push(value.clone());
 			} else {
@@ -771,8 +1529,41 @@ impl CallChainCommand {
 	}
 }
 
-/// Lists all pallets available on the chain.
+/// Describes the approval of a call by a signatory of a multisig account.
+fn approval_message(multisig: &Multisig, approval: &MultisigApproval) -> String {
+	let status = if approval.executes {
+		"the call will be dispatched"
+	} else {
+		"the call will await further approvals"
+	};
+	format!(
+		"Approving call {} from multisig account {} ({} of {} approvals, {status}).",
+		approval.call_hash,
+		multisig.account(),
+		approval.approvals,
+		multisig.threshold()
+	)
+}
+
+/// Formats a pending operation of a multisig account.
+fn format_operation(multisig: &Multisig, operation: &MultisigOperation) -> String {
+	format!(
+		"  {}\n    Opened at: block {}, extrinsic {}\n    Approvals ({}/{}): {}\n    Deposit: {} \
+		 held from {}",
+		operation.call_hash,
+		operation.when.height,
+		operation.when.index,
+		operation.approvals.len(),
+		multisig.threshold(),
+		operation.approvals.join(", "),
+		operation.deposit,
+		operation.depositor
+	)
+}
+
+/// Lists all pallets and runtime APIs available on the chain.
 fn list_pallets(pallets: &[Pallet], cli: &mut impl Cli) -> Result<()> {
+	let (apis, pallets): (Vec<_>, Vec<_>) = pallets.iter().partition(|p| p.is_runtime_api());
 	cli.info(format!("Available pallets ({}):\n", pallets.len()))?;
 	for pallet in pallets {
 		if pallet.docs.is_empty() {
@@ -781,12 +1572,19 @@ fn list_pallets(pallets: &[Pallet], cli: &mut impl Cli) -> Result<()> {
 			cli.plain(format!("  {} - {}", pallet.name, pallet.docs))?;
 		}
 	}
+	if !apis.is_empty() {
+		cli.info(format!("Available runtime APIs ({}):\n", apis.len()))?;
+		for api in apis {
+			cli.plain(format!("  {}", api.name))?;
+		}
+	}
 	Ok(())
 }
 
 /// Shows details of a specific pallet (calls, storage, constants).
 fn show_pallet(pallet: &Pallet, registry: &PortableRegistry, cli: &mut impl Cli) -> Result<()> {
-	cli.info(format!("Pallet: {}\n", pallet.name))?;
+	let kind = if pallet.is_runtime_api() { "Runtime API" } else { "Pallet" };
+	cli.info(format!("{kind}: {}\n", pallet.name))?;
 	if !pallet.docs.is_empty() {
 		cli.plain(format!("{}\n", pallet.docs))?;
 	}
@@ -837,6 +1635,30 @@ fn show_pallet(pallet: &Pallet, registry: &PortableRegistry, cli: &mut impl Cli)
 		}
 	}
 
+	// Show runtime API methods with parameters, result and docs
+	if !pallet.runtime_apis.is_empty() {
+		cli.plain(format!(
+			"\n━━━ Runtime API methods ({}) ━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━",
+			pallet.runtime_apis.len()
+		))?;
+		for api in &pallet.runtime_apis {
+			let status = if api.is_supported { "" } else { " [NOT SUPPORTED]" };
+			cli.plain(format!("\n  {}{}", api.name, status))?;
+			if !api.params.is_empty() {
+				cli.plain("    Parameters:".to_string())?;
+				for param in &api.params {
+					cli.plain(format!("      - {}: {}", param.name, param.type_name))?;
+				}
+			}
+			if let Ok(output) = type_to_param("output", registry, api.output_type_id) {
+				cli.plain(format!("    Returns: {}", output.type_name))?;
+			}
+			if !api.docs.is_empty() {
+				cli.plain(format!("    Description: {}", api.docs))?;
+			}
+		}
+	}
+
 	// Show constants with values and docs
 	if !pallet.constants.is_empty() {
 		cli.plain(format!(
@@ -871,6 +1693,10 @@ pub(crate) struct Call {
 	/// - for a dev account "//Alice"
 	/// - with a password "//Alice///SECRET_PASSWORD"
 	pub(crate) suri: Option<String>,
+	/// Name of the keystore account the secret key URI was unlocked from, if any.
+	pub(crate) account: Option<String>,
+	/// Key scheme of the signer.
+	pub(crate) scheme: KeyScheme,
 	/// Whether to use your browser wallet to sign the extrinsic.
 	pub(crate) use_wallet: bool,
 	/// Whether to automatically sign and submit the extrinsic without prompting for confirmation.
@@ -879,6 +1705,12 @@ pub(crate) struct Call {
 	pub(crate) execute: bool,
 	/// Whether to dispatch the function call with `Root` origin.
 	pub(crate) sudo: bool,
+	/// The approval threshold of the multisig account dispatching the call, if any.
+	pub(crate) multisig: Option<u16>,
+	/// The signatories of the multisig account.
+	pub(crate) signatories: Vec<String>,
+	/// The tip, nonce, mortality and fee asset of the transaction.
+	pub(crate) options: TxOptions,
 }
 
 impl Call {
@@ -920,6 +1752,10 @@ impl Call {
 			.function
 			.as_function()
 			.ok_or(anyhow!("Error: The call is not an extrinsic call"))?;
+		let suri = self.suri.clone().ok_or(anyhow!("Error: The secret key URI is missing"))?;
+		let extrinsic =
+			sign_with_fee_estimate(client, url, &tx, &suri, self.scheme, &self.options, cli)
+				.await?;
 		if !self.skip_confirm &&
 			!self.execute &&
 			!cli.confirm("Do you want to submit the extrinsic?")
@@ -935,10 +1771,9 @@ impl Call {
 		}
 		let spinner = cli.spinner();
 		spinner.start(
-			"Signing and submitting the extrinsic and then waiting for finalization, please be patient...",
+			"Submitting the extrinsic and then waiting for finalization, please be patient...",
 		);
-		let suri = self.suri.clone().ok_or(anyhow!("Error: The secret key URI is missing"))?;
-		let result = sign_and_submit_extrinsic(client, url, tx, &suri)
+		let result = submit_and_watch_extrinsic(client, url, extrinsic)
 			.await
 			.map_err(|err| anyhow!("{err:?}"))?;
 		spinner.stop(result);
@@ -967,12 +1802,32 @@ impl Call {
 		full_message.push_str(&format!(" --url {}", chain.url));
 		if self.use_wallet {
 			full_message.push_str(" --use-wallet");
+		} else if let Some(account) = &self.account {
+			full_message.push_str(&format!(" --account {account}"));
 		} else if let Some(suri) = &self.suri {
 			full_message.push_str(&format!(" --suri {suri}"));
 		}
 		if self.sudo {
 			full_message.push_str(" --sudo");
 		}
+		if let Some(threshold) = self.multisig {
+			full_message.push_str(&format!(
+				" --multisig {threshold} --signatories {}",
+				self.signatories.join(",")
+			));
+		}
+		if self.options.tip > 0 {
+			full_message.push_str(&format!(" --tip {}", self.options.tip));
+		}
+		if let Some(nonce) = self.options.nonce {
+			full_message.push_str(&format!(" --nonce {nonce}"));
+		}
+		if let Some(blocks) = self.options.mortality {
+			full_message.push_str(&format!(" --mortal {blocks}"));
+		}
+		if let Some(asset) = &self.options.fee_asset {
+			full_message.push_str(&format!(" --fee-asset '{asset}'"));
+		}
 		if self.execute {
 			full_message.push_str(" --execute");
 		}
@@ -983,6 +1838,30 @@ impl Call {
 	}
 }
 
+// Signs the extrinsic with the transaction options and displays its estimated fee, which is
+// skipped with a warning when the chain cannot estimate it. The estimate is in the native token,
+// also when the fee is paid in an asset.
+async fn sign_with_fee_estimate<Xt: Payload>(
+	client: &OnlineClient<SubstrateConfig>,
+	url: &Url,
+	xt: &Xt,
+	suri: &str,
+	scheme: KeyScheme,
+	options: &TxOptions,
+	cli: &mut impl Cli,
+) -> Result<Vec<u8>> {
+	let extrinsic = sign_extrinsic_with_suri(client, url, xt, suri, scheme, options).await?;
+	match estimate_fee(client, &extrinsic).await {
+		Ok(fee) => match &options.fee_asset {
+			Some(asset) =>
+				cli.info(format!("{fee}, paid in asset {asset} after conversion by the chain"))?,
+			None => cli.info(fee.to_string())?,
+		},
+		Err(e) => cli.warning(format!("Unable to estimate the fee: {e}"))?,
+	}
+	Ok(extrinsic)
+}
+
 // Prompts the user for some predefined actions.
 fn prompt_predefined_actions(pallets: &[Pallet], cli: &mut impl Cli) -> Result<Option<Action>> {
 	let mut predefined_action = cli.select("What would you like to do?");
@@ -1168,6 +2047,13 @@ fn map_chain_submit_error(err: impl std::fmt::Display) -> anyhow::Error {
 	anyhow::anyhow!("{err}")
 }
 
+fn offline_prepared_message(path: &Path) -> String {
+	format!(
+		"Transaction written to {0}. Sign it with `pop tx sign {0}`, then submit it with `pop tx submit {0}`.",
+		path.display()
+	)
+}
+
 fn parse_chain_submit_output(output: &str) -> (String, Vec<String>) {
 	let mut lines = output.lines();
 	let tx_hash = lines
@@ -1401,10 +2287,15 @@ mod tests {
 			}),
 			args: vec!["0x11".to_string()],
 			suri: Some(DEFAULT_URI.to_string()),
+			account: None,
+			scheme: KeyScheme::Sr25519,
 			use_wallet: false,
 			skip_confirm: false,
 			execute: false,
 			sudo: false,
+			multisig: None,
+			signatories: vec![],
+			options: TxOptions::default(),
 		};
 		let mut cli = MockCli::new();
 		// Error, wrong name of the pallet.
@@ -1451,6 +2342,7 @@ mod tests {
 			function: None,
 			args: vec![],
 			url: Some(url.clone()),
+			chain: None,
 			suri: None,
 			use_wallet: false,
 			skip_confirm: false,
@@ -1458,6 +2350,23 @@ mod tests {
 			call_data: Some("0x00000411".to_string()),
 			sudo: false,
 			metadata: false,
+			fork: false,
+			at: None,
+			account: None,
+			scheme: KeyScheme::Sr25519,
+			sign_offline: None,
+			signer: None,
+			multisig: None,
+			signatories: vec![],
+			pending: false,
+			script: None,
+			tip: 0,
+			nonce: None,
+			mortal: None,
+			fee_asset: None,
+			limit: None,
+			start_key: None,
+			export: None,
 		};
 		let mut cli = MockCli::new()
 			.expect_confirm(USE_WALLET_PROMPT, false)
@@ -1481,6 +2390,7 @@ mod tests {
 			function: None,
 			args: vec![],
 			url: Some(url.clone()),
+			chain: None,
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
 			skip_confirm: false,
@@ -1488,6 +2398,23 @@ mod tests {
 			call_data: Some("0x00000411".to_string()),
 			sudo: false,
 			metadata: false,
+			fork: false,
+			at: None,
+			account: None,
+			scheme: KeyScheme::Sr25519,
+			sign_offline: None,
+			signer: None,
+			multisig: None,
+			signatories: vec![],
+			pending: false,
+			script: None,
+			tip: 0,
+			nonce: None,
+			mortal: None,
+			fee_asset: None,
+			limit: None,
+			start_key: None,
+			export: None,
 		};
 		let mut cli = MockCli::new()
 			.expect_info("Encoded call data: 0x00000411")
@@ -1506,6 +2433,7 @@ mod tests {
 			function: Some("remark".to_string()),
 			args: vec!["0x11".to_string()],
 			url: Some(Url::parse(urls::LOCAL)?),
+			chain: None,
 			use_wallet: true,
 			suri: Some(DEFAULT_URI.to_string()),
 			skip_confirm: false,
@@ -1513,6 +2441,23 @@ mod tests {
 			call_data: None,
 			sudo: true,
 			metadata: false,
+			fork: false,
+			at: None,
+			account: None,
+			scheme: KeyScheme::Sr25519,
+			sign_offline: None,
+			signer: None,
+			multisig: None,
+			signatories: vec![],
+			pending: false,
+			script: None,
+			tip: 0,
+			nonce: None,
+			mortal: None,
+			fee_asset: None,
+			limit: None,
+			start_key: None,
+			export: None,
 		};
 		call_config.reset_for_new_call();
 		assert_eq!(call_config.pallet, None);
@@ -1530,6 +2475,7 @@ mod tests {
 			function: Some("register".to_string()),
 			args: vec!["2000".to_string(), "0x1".to_string(), "0x12".to_string()],
 			url: Some(Url::parse(urls::LOCAL)?),
+			chain: None,
 			suri: Some(DEFAULT_URI.to_string()),
 			use_wallet: false,
 			call_data: None,
@@ -1537,6 +2483,23 @@ mod tests {
 			execute: false,
 			sudo: false,
 			metadata: false,
+			fork: false,
+			at: None,
+			account: None,
+			scheme: KeyScheme::Sr25519,
+			sign_offline: None,
+			signer: None,
+			multisig: None,
+			signatories: vec![],
+			pending: false,
+			script: None,
+			tip: 0,
+			nonce: None,
+			mortal: None,
+			fee_asset: None,
+			limit: None,
+			start_key: None,
+			export: None,
 		};
 		assert_eq!(
 			call_config.expand_file_arguments()?,
@@ -1852,6 +2815,7 @@ mod tests {
 				functions: vec![],
 				constants: vec![],
 				state: vec![],
+				runtime_apis: vec![],
 			},
 			Pallet {
 				name: "Balances".to_string(),
@@ -1860,6 +2824,7 @@ mod tests {
 				functions: vec![],
 				constants: vec![],
 				state: vec![],
+				runtime_apis: vec![],
 			},
 			Pallet {
 				name: "Assets".to_string(),
@@ -1868,6 +2833,7 @@ mod tests {
 				functions: vec![],
 				constants: vec![],
 				state: vec![],
+				runtime_apis: vec![],
 			},
 		];
 
@@ -1881,6 +2847,29 @@ mod tests {
 		assert!(cli.verify().is_ok());
 	}
 
+	#[test]
+	fn list_pallets_with_runtime_apis_works() -> Result<()> {
+		let pallets = vec![
+			Pallet { name: "System".to_string(), ..Default::default() },
+			Pallet {
+				name: "AccountNonceApi".to_string(),
+				runtime_apis: vec![pop_chains::RuntimeApi {
+					api: "AccountNonceApi".to_string(),
+					name: "account_nonce".to_string(),
+					..Default::default()
+				}],
+				..Default::default()
+			},
+		];
+		let mut cli = MockCli::new()
+			.expect_info("Available pallets (1):\n")
+			.expect_plain("  System")
+			.expect_info("Available runtime APIs (1):\n")
+			.expect_plain("  AccountNonceApi");
+		list_pallets(&pallets, &mut cli)?;
+		cli.verify()
+	}
+
 	#[tokio::test]
 	async fn show_pallet_works() -> Result<()> {
 		let node_url = shared_substrate_ws_url().await;
@@ -1948,6 +2937,26 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn resolve_url_works() -> Result<()> {
+		let url = Url::parse(urls::LOCAL)?;
+		let cmd = CallChainCommand { url: Some(url.clone()), ..Default::default() };
+		assert_eq!(cmd.resolve_url()?, Some(url));
+		assert_eq!(CallChainCommand::default().resolve_url()?, None);
+
+		let chain = SupportedChains::PASEO;
+		let cmd = CallChainCommand { chain: Some(chain), ..Default::default() };
+		assert_eq!(cmd.resolve_url()?, Some(Url::parse(chain.rpc_urls()[0])?));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn execute_json_rejects_fork_with_execute() {
+		let cmd = CallChainCommand { fork: true, execute: true, ..Default::default() };
+		let err = cmd.execute_json().await.unwrap_err();
+		assert!(err.to_string().contains("`--fork` together with `--execute`"), "{err}");
+	}
+
 	#[test]
 	fn determine_signing_method_works() -> Result<()> {
 		let mut cli = MockCli::new();
@@ -1990,4 +2999,231 @@ mod tests {
 		let err = cmd.execute_json().await.expect_err("expected prompt required error");
 		assert!(err.downcast_ref::<crate::output::PromptRequiredError>().is_some());
 	}
+
+	#[test]
+	fn ensure_scheme_supported_rejects_ethereum_keys_for_fork_and_multisig() {
+		let cmd = CallChainCommand { scheme: KeyScheme::Ethereum, ..Default::default() };
+		assert!(cmd.ensure_scheme_supported().is_ok());
+		for cmd in [
+			CallChainCommand { scheme: KeyScheme::Ethereum, fork: true, ..Default::default() },
+			CallChainCommand {
+				scheme: KeyScheme::Ethereum,
+				multisig: Some(2),
+				..Default::default()
+			},
+		] {
+			let err = cmd.ensure_scheme_supported().unwrap_err();
+			assert!(err.downcast_ref::<crate::output::InvalidInputError>().is_some());
+		}
+		let cmd = CallChainCommand { scheme: KeyScheme::Ecdsa, fork: true, ..Default::default() };
+		assert!(cmd.ensure_scheme_supported().is_ok());
+	}
+
+	#[test]
+	fn script_signer_works() -> Result<()> {
+		let cmd = CallChainCommand { scheme: KeyScheme::Ed25519, ..Default::default() };
+		assert_eq!(
+			cmd.script_signer(Some("//Bob"), None, "System.remark")?,
+			("//Bob".to_string(), KeyScheme::Ed25519)
+		);
+		assert_eq!(
+			cmd.script_signer(Some("//Bob"), Some(KeyScheme::Ecdsa), "System.remark")?,
+			("//Bob".to_string(), KeyScheme::Ecdsa)
+		);
+		assert_eq!(
+			cmd.script_signer(None, None, "System.remark").unwrap_err().to_string(),
+			"No signer for System.remark: set `signer` in the script, or provide `--suri` or \
+			 `--account`"
+		);
+		let cmd = CallChainCommand { suri: Some("//Alice".to_string()), ..Default::default() };
+		assert_eq!(
+			cmd.script_signer(None, Some(KeyScheme::Ed25519), "System.remark")?,
+			("//Alice".to_string(), KeyScheme::Sr25519)
+		);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn execute_script_json_requires_url() {
+		let cmd =
+			CallChainCommand { script: Some(PathBuf::from("calls.yaml")), ..Default::default() };
+		let err = cmd.execute_script_json().await.expect_err("expected prompt required error");
+		assert!(err.downcast_ref::<crate::output::PromptRequiredError>().is_some());
+	}
+
+	#[test]
+	fn call_script_output_reports_failed_step() -> Result<()> {
+		let step = |name: &str, status, error: Option<&str>| ScriptStepOutput {
+			step: name.into(),
+			status,
+			tx_hash: None,
+			events: vec![],
+			error: error.map(String::from),
+		};
+		let output = CallScriptOutput {
+			script: "calls.yaml".into(),
+			steps: vec![
+				step("create", ScriptStepStatus::Succeeded, None),
+				step("mint", ScriptStepStatus::Failed, Some("expected events were not emitted")),
+				step("transfer", ScriptStepStatus::Skipped, None),
+			],
+		};
+		assert_eq!(
+			output.failed_step().and_then(ScriptStepOutput::failure).as_deref(),
+			Some("mint: expected events were not emitted")
+		);
+		let json = serde_json::to_value(&output)?;
+		assert_eq!(json["steps"][1]["status"], "failed");
+		assert_eq!(json["steps"][2]["status"], "skipped");
+		assert!(json["steps"][2].get("error").is_none());
+		Ok(())
+	}
+
+	#[test]
+	fn multisig_works() -> Result<()> {
+		assert!(CallChainCommand::default().multisig()?.is_none());
+
+		let signatories = vec![
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+			" 5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string(),
+			"5FLSigC9HGRKVhB9FiEo4Y3koPsNmBmLJbpXg2mp1hXcS59Y".to_string(),
+		];
+		let cmd = CallChainCommand { multisig: Some(2), signatories, ..Default::default() };
+		let multisig = cmd.multisig()?.expect("multisig provided");
+		assert_eq!(multisig.threshold(), 2);
+		assert_eq!(multisig.signatories().len(), 3);
+
+		let cmd = CallChainCommand {
+			multisig: Some(2),
+			signatories: vec!["invalid".to_string()],
+			..Default::default()
+		};
+		assert!(cmd.multisig().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn iterates_storage_works() {
+		assert!(!CallChainCommand::default().iterates_storage());
+		assert!(CallChainCommand { limit: Some(10), ..Default::default() }.iterates_storage());
+		assert!(
+			CallChainCommand { start_key: Some("0x00".into()), ..Default::default() }
+				.iterates_storage()
+		);
+		assert!(
+			CallChainCommand { export: Some("balances.csv".into()), ..Default::default() }
+				.iterates_storage()
+		);
+		assert!(CallChainCommand { at: Some(1), ..Default::default() }.iterates_storage());
+		// `--at` is the block to fork at when previewing on a fork.
+		assert!(
+			!CallChainCommand { at: Some(1), fork: true, ..Default::default() }.iterates_storage()
+		);
+	}
+
+	#[tokio::test]
+	async fn iterate_storage_exports_entries() -> Result<()> {
+		let node_url = shared_substrate_ws_url().await;
+		let client = set_up_client(&node_url).await?;
+		let chain = Chain { url: Url::parse(&node_url)?, client: client.clone(), pallets: vec![] };
+		let pallets = parse_chain_metadata(&client)?;
+		let storage = find_pallet_by_name(&pallets, "System")?
+			.state
+			.iter()
+			.find(|storage| storage.name == "Account")
+			.expect("System::Account should exist")
+			.clone();
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("accounts.csv");
+		let cmd =
+			CallChainCommand { limit: Some(2), export: Some(path.clone()), ..Default::default() };
+		let mut cli = MockCli::new()
+			.expect_success(format!("Exported 2 entries to {} as CSV.", path.display()));
+		cmd.iterate_storage(&chain, &storage, vec![], &mut cli).await?;
+		let csv = std::fs::read_to_string(&path)?;
+		assert!(csv.starts_with("storage_key,key_0,value\n"));
+		assert_eq!(csv.lines().count(), 3);
+		cli.verify()
+	}
+
+	#[test]
+	fn tx_options_works() -> Result<()> {
+		assert_eq!(CallChainCommand::default().tx_options(), TxOptions::default());
+
+		let cmd = CallChainCommand {
+			tip: 10,
+			nonce: Some(3),
+			mortal: Some(64),
+			fee_asset: Some("1984".parse()?),
+			..Default::default()
+		};
+		assert_eq!(
+			cmd.tx_options(),
+			TxOptions {
+				tip: 10,
+				nonce: Some(3),
+				mortality: Some(64),
+				fee_asset: Some(FeeAsset::Id(1984)),
+			}
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn approval_message_works() -> Result<()> {
+		let cmd = CallChainCommand {
+			multisig: Some(2),
+			signatories: vec![
+				"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string(),
+				"5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string(),
+			],
+			..Default::default()
+		};
+		let multisig = cmd.multisig()?.expect("multisig provided");
+		let approval = MultisigApproval {
+			xt: subxt::dynamic::tx("System", "remark", vec![DynamicValue::from_bytes([0x11])]),
+			call_hash: "0x01".to_string(),
+			timepoint: None,
+			approvals: 1,
+			executes: false,
+		};
+		assert_eq!(
+			approval_message(&multisig, &approval),
+			format!(
+				"Approving call 0x01 from multisig account {} (1 of 2 approvals, the call will \
+				 await further approvals).",
+				multisig.account()
+			)
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn format_operation_works() -> Result<()> {
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".to_string();
+		let cmd = CallChainCommand {
+			multisig: Some(2),
+			signatories: vec![
+				alice.clone(),
+				"5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string(),
+			],
+			..Default::default()
+		};
+		let multisig = cmd.multisig()?.expect("multisig provided");
+		let operation = MultisigOperation {
+			call_hash: "0x01".to_string(),
+			when: pop_chains::Timepoint { height: 10, index: 1 },
+			deposit: 100,
+			depositor: alice.clone(),
+			approvals: vec![alice.clone()],
+		};
+		assert_eq!(
+			format_operation(&multisig, &operation),
+			format!(
+				"  0x01\n    Opened at: block 10, extrinsic 1\n    Approvals (1/2): {alice}\n    \
+				 Deposit: 100 held from {alice}"
+			)
+		);
+		Ok(())
+	}
 }
@@ -3,6 +3,7 @@
 use crate::{
 	cli::traits::{Cli, Confirm, Input, Select},
 	common::{
+		accounts,
 		builds::{ensure_project_path, get_project_path},
 		contracts::{
 			build_contract_artifacts, has_contract_been_built, map_account, normalize_call_args,
@@ -82,6 +83,10 @@ pub struct CallContractCommand {
 		conflicts_with = "suri"
 	)]
 	pub(crate) use_wallet: bool,
+	/// Name of a keystore account to call the contract with, instead of a `--suri`. The password
+	/// is prompted for. See `pop account`.
+	#[arg(long, conflicts_with_all = ["suri", "use-wallet"])]
+	pub(crate) account: Option<String>,
 	/// Submit an extrinsic for on-chain execution.
 	#[arg(short = 'x', long)]
 	pub(crate) execute: bool,
@@ -110,6 +115,7 @@ impl Default for CallContractCommand {
 			url: None,
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -141,6 +147,12 @@ impl CallContractCommand {
 
 	/// Executes the command.
 	pub(crate) async fn execute(mut self, cli: &mut impl Cli) -> Result<()> {
+		// The account may already be unlocked, e.g. when calling a contract just deployed.
+		if let Some(account) = &self.account &&
+			self.suri.is_none()
+		{
+			self.suri = Some(accounts::unlock_sr25519(account, cli)?);
+		}
 		// Check if message specified via command line argument.
 		let prompt_to_repeat_call = self.message.is_none();
 		// Configure the call based on command line arguments/call UI.
@@ -175,6 +187,9 @@ impl CallContractCommand {
 				"`pop --json call contract` does not support `--use-wallet`; provide `--suri`",
 			));
 		}
+		if let Some(account) = &self.account {
+			self.suri = Some(accounts::unlock_sr25519(account, &mut crate::cli::JsonCli)?);
+		}
 
 		let mut missing = Vec::new();
 		if self.url.is_none() {
@@ -327,6 +342,8 @@ impl CallContractCommand {
 		}
 		if self.use_wallet {
 			full_message.push_str(" --use-wallet");
+		} else if let Some(account) = &self.account {
+			full_message.push_str(&format!(" --account {}", account));
 		} else if let Some(suri) = &self.suri {
 			full_message.push_str(&format!(" --suri {}", suri));
 		}
@@ -906,6 +923,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1047,6 +1065,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1131,6 +1150,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: true,
@@ -1196,6 +1216,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1256,6 +1277,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1284,6 +1306,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1342,6 +1365,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1390,6 +1414,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1435,6 +1460,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: false,
 			skip_confirm: false,
@@ -1485,6 +1511,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: true,
 			skip_confirm: false,
@@ -1540,6 +1567,7 @@ mod tests {
 			url: Some(Url::parse(urls::LOCAL)?),
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: false,
 			deployed: true,
 			skip_confirm: false,
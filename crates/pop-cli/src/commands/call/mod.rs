@@ -36,6 +36,8 @@ pub(crate) enum Command {
 pub(crate) enum CallJsonOutput {
 	#[cfg(feature = "chain")]
 	Chain(chain::CallChainOutput),
+	#[cfg(feature = "chain")]
+	ChainScript(chain::CallScriptOutput),
 	#[cfg(feature = "contract")]
 	Contract(contract::CallContractOutput),
 }
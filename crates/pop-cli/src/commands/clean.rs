@@ -543,7 +543,8 @@ fn contents(path: &PathBuf) -> Result<Vec<(String, PathBuf, u64)>> {
 	Ok(contents)
 }
 
-fn resolve_zombie_json_path(path: &Path) -> Result<PathBuf> {
+/// Resolves the `zombie.json` of a network from its path or base directory.
+pub(crate) fn resolve_zombie_json_path(path: &Path) -> Result<PathBuf> {
 	if path.is_file() {
 		if path.file_name().and_then(|f| f.to_str()) == Some("zombie.json") {
 			return Ok(path.to_path_buf());
@@ -618,12 +619,16 @@ fn push_pid(pid: String, pids: &mut Vec<String>) {
 	}
 }
 
-struct ZombieJsonCandidate {
-	path: PathBuf,
-	modified: Option<time::OffsetDateTime>,
+/// The `zombie.json` of a running network, found in the temporary directory.
+pub(crate) struct ZombieJsonCandidate {
+	/// The path to the `zombie.json` file.
+	pub(crate) path: PathBuf,
+	/// When the file was last modified.
+	pub(crate) modified: Option<time::OffsetDateTime>,
 }
 
-fn find_zombie_jsons() -> Result<Vec<ZombieJsonCandidate>> {
+/// Finds the `zombie.json` of running networks, most recently modified first.
+pub(crate) fn find_zombie_jsons() -> Result<Vec<ZombieJsonCandidate>> {
 	let temp_dir = std::env::temp_dir();
 	let pattern = regex::Regex::new(
 		r"^zombie-[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}$",
@@ -0,0 +1,312 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	common::urls,
+	output::{CliResponse, OutputMode, prompt_required_error},
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use pop_chains::{
+	DecodedExtrinsic, DecodedStorage, Metadata, StorageKeyPart, decode_call, decode_events,
+	decode_extrinsic, decode_storage, load_metadata, raw_value_to_string, set_up_client,
+};
+use serde::Serialize;
+use std::{
+	fmt::{Display, Formatter},
+	path::PathBuf,
+};
+use url::Url;
+
+/// Arguments for decoding SCALE-encoded data.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct DecodeArgs {
+	/// Decode subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Decode SCALE-encoded data using the metadata of a runtime.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Decode call data, e.g. the call data of a multisig or proxy announcement.
+	#[clap(alias = "c")]
+	Call {
+		/// The hex-encoded call data.
+		data: String,
+		#[command(flatten)]
+		source: MetadataSource,
+	},
+	/// Decode a signed or unsigned extrinsic, including its signed extensions.
+	#[clap(alias = "x")]
+	Extrinsic {
+		/// The hex-encoded extrinsic.
+		data: String,
+		#[command(flatten)]
+		source: MetadataSource,
+	},
+	/// Decode event records, such as the value of the `System::Events` storage item.
+	#[clap(alias = "e")]
+	Events {
+		/// The hex-encoded event records.
+		data: String,
+		#[command(flatten)]
+		source: MetadataSource,
+	},
+	/// Decode a storage key and, optionally, its value.
+	#[clap(alias = "s")]
+	Storage {
+		/// The hex-encoded storage key.
+		key: String,
+		/// The hex-encoded storage value.
+		value: Option<String>,
+		#[command(flatten)]
+		source: MetadataSource,
+	},
+}
+
+/// Where to get the metadata used for decoding.
+#[derive(Args, Serialize)]
+pub(crate) struct MetadataSource {
+	/// Websocket endpoint of a node to fetch the metadata from.
+	#[arg(short, long, conflicts_with = "metadata")]
+	pub(crate) url: Option<Url>,
+	/// Path to a metadata file, SCALE-encoded or hex-encoded.
+	#[arg(short, long)]
+	pub(crate) metadata: Option<PathBuf>,
+}
+
+/// Structured output for `decode` in JSON mode.
+#[derive(Serialize)]
+struct DecodeOutput {
+	kind: String,
+	decoded: serde_json::Value,
+}
+
+impl Command {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro(format!("Decode {}", self.kind()))?;
+		let metadata = self.source().metadata(cli, output_mode).await?;
+		let decoded = match self {
+			Command::Call { data, .. } => {
+				let call = decode_call(&metadata, data)?;
+				Decoded::new(raw_value_to_string(&call, "")?, serde_json::to_value(&call)?)
+			},
+			Command::Extrinsic { data, .. } => {
+				let extrinsic = decode_extrinsic(&metadata, data)?;
+				Decoded::new(extrinsic.render()?, extrinsic_json(&extrinsic)?)
+			},
+			Command::Events { data, .. } => {
+				let events = decode_events(&metadata, data)?;
+				let rendered = if events.is_empty() {
+					"No events".to_string()
+				} else {
+					events
+						.iter()
+						.map(|event| event.render())
+						.collect::<Result<Vec<_>>>()?
+						.join("\n")
+				};
+				let json = events
+					.iter()
+					.map(|event| {
+						serde_json::json!({
+							"phase": event.phase,
+							"pallet": event.pallet,
+							"name": event.name,
+							"fields": event.fields,
+						})
+					})
+					.collect();
+				Decoded::new(rendered, json)
+			},
+			Command::Storage { key, value, .. } => {
+				let storage = decode_storage(&metadata, key, value.as_deref())?;
+				Decoded::new(storage.render()?, storage_json(&storage))
+			},
+		};
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(DecodeOutput { kind: self.to_string(), decoded: decoded.json })
+				.print_json();
+			return Ok(());
+		}
+		cli.plain(decoded.rendered)?;
+		cli.outro(format!("Decoded the {}.", self.kind()))?;
+		Ok(())
+	}
+
+	fn kind(&self) -> &'static str {
+		match self {
+			Command::Call { .. } => "call data",
+			Command::Extrinsic { .. } => "extrinsic",
+			Command::Events { .. } => "events",
+			Command::Storage { .. } => "storage",
+		}
+	}
+
+	fn source(&self) -> &MetadataSource {
+		match self {
+			Command::Call { source, .. } |
+			Command::Extrinsic { source, .. } |
+			Command::Events { source, .. } |
+			Command::Storage { source, .. } => source,
+		}
+	}
+}
+
+impl MetadataSource {
+	/// Loads the metadata from the file or node provided, prompting for a node if neither is.
+	async fn metadata(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<Metadata> {
+		if let Some(path) = &self.metadata {
+			return Ok(load_metadata(path)?);
+		}
+		let url = match &self.url {
+			Some(url) => url.clone(),
+			None if output_mode == OutputMode::Json =>
+				return Err(prompt_required_error(
+					"Missing required flags for `pop --json decode`: --url or --metadata",
+				)),
+			None => Url::parse(
+				&cli.input("Which chain would you like to fetch the metadata from?")
+					.placeholder(urls::LOCAL)
+					.default_input(urls::LOCAL)
+					.interact()?,
+			)?,
+		};
+		let spinner = cli.spinner();
+		spinner.start("Fetching the metadata...");
+		let client = set_up_client(url.as_str()).await;
+		spinner.clear();
+		Ok(client?.metadata())
+	}
+}
+
+/// The decoded data, rendered for humans and as JSON.
+struct Decoded {
+	rendered: String,
+	json: serde_json::Value,
+}
+
+impl Decoded {
+	fn new(rendered: String, json: serde_json::Value) -> Self {
+		Self { rendered, json }
+	}
+}
+
+fn extrinsic_json(extrinsic: &DecodedExtrinsic) -> Result<serde_json::Value> {
+	let signature = match &extrinsic.signature {
+		Some(signature) => {
+			let mut extensions = serde_json::Map::new();
+			for (identifier, value) in &signature.extensions {
+				extensions.insert(identifier.clone(), serde_json::to_value(value)?);
+			}
+			serde_json::json!({
+				"address": signature.address,
+				"signature": signature.signature,
+				"extensions": extensions,
+			})
+		},
+		None => serde_json::Value::Null,
+	};
+	Ok(serde_json::json!({
+		"version": extrinsic.version,
+		"signature": signature,
+		"call": extrinsic.call,
+	}))
+}
+
+fn storage_json(storage: &DecodedStorage) -> serde_json::Value {
+	let keys: Vec<_> = storage
+		.keys
+		.iter()
+		.map(|key| match key {
+			StorageKeyPart::Value(value) => serde_json::json!({ "value": value }),
+			StorageKeyPart::Hash(hash) => serde_json::json!({ "hash": hash }),
+		})
+		.collect();
+	serde_json::json!({
+		"pallet": storage.pallet,
+		"entry": storage.entry,
+		"keys": keys,
+		"value": storage.value,
+	})
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Call { .. } => write!(f, "call"),
+			Command::Extrinsic { .. } => write!(f, "extrinsic"),
+			Command::Events { .. } => write!(f, "events"),
+			Command::Storage { .. } => write!(f, "storage"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::PromptRequiredError};
+	use std::fs;
+
+	fn source(metadata: Option<PathBuf>) -> MetadataSource {
+		MetadataSource { url: None, metadata }
+	}
+
+	#[tokio::test]
+	async fn json_mode_requires_metadata_source() {
+		let command = Command::Call { data: "0x00000411".into(), source: source(None) };
+		let err = command.execute(&mut crate::cli::JsonCli, OutputMode::Json).await.unwrap_err();
+		assert!(err.downcast_ref::<PromptRequiredError>().is_some());
+	}
+
+	#[tokio::test]
+	async fn invalid_metadata_file_fails() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("metadata.scale");
+		fs::write(&path, [1, 2, 3])?;
+		let command = Command::Events { data: "0x00".into(), source: source(Some(path)) };
+		let mut cli = MockCli::new().expect_intro("Decode events");
+		assert!(command.execute(&mut cli, OutputMode::Human).await.is_err());
+		cli.verify()
+	}
+
+	#[test]
+	fn storage_json_works() {
+		let storage = DecodedStorage {
+			pallet: "System".into(),
+			entry: "Account".into(),
+			keys: vec![StorageKeyPart::Hash("0x01".into())],
+			value: None,
+		};
+		assert_eq!(
+			storage_json(&storage),
+			serde_json::json!({
+				"pallet": "System",
+				"entry": "Account",
+				"keys": [{ "hash": "0x01" }],
+				"value": null,
+			})
+		);
+	}
+
+	#[test]
+	fn command_display_works() {
+		assert_eq!(Command::Call { data: "0x".into(), source: source(None) }.to_string(), "call");
+		assert_eq!(
+			Command::Extrinsic { data: "0x".into(), source: source(None) }.to_string(),
+			"extrinsic"
+		);
+		assert_eq!(
+			Command::Events { data: "0x".into(), source: source(None) }.to_string(),
+			"events"
+		);
+		assert_eq!(
+			Command::Storage { key: "0x".into(), value: None, source: source(None) }.to_string(),
+			"storage"
+		);
+	}
+}
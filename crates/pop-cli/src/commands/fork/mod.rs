@@ -6,7 +6,7 @@ use crate::{
 	output::{CliResponse, OutputMode},
 };
 use anyhow::Result;
-use clap::{ArgGroup, Args};
+use clap::{ArgGroup, Args, Subcommand};
 use console::style;
 use pop_chains::SupportedChains;
 use pop_fork::{
@@ -24,6 +24,8 @@ use std::{
 use tempfile::NamedTempFile;
 use url::Url;
 
+pub(crate) mod profile;
+
 /// Timeout for waiting for the detached fork server to become ready.
 const DETACH_READY_TIMEOUT_SECS: u64 = 120;
 /// Poll interval when checking for fork server readiness.
@@ -45,6 +47,16 @@ mod messages {
 		format!("Forking {endpoint}...")
 	}
 
+	/// Format "Starting `path` from genesis..." progress message.
+	pub fn from_chain_spec(path: &std::path::Path) -> String {
+		format!("Starting {} from genesis...", path.display())
+	}
+
+	/// Format "Using N upstream endpoints" message.
+	pub fn upstreams(count: usize) -> String {
+		format!("Using {count} upstream endpoints with automatic failover")
+	}
+
 	/// Format "Dev accounts funded on `chain`" message.
 	pub fn dev_accounts_funded(chain_name: &str) -> String {
 		format!("Dev accounts funded on {chain_name}")
@@ -58,8 +70,13 @@ mod messages {
 
 /// Arguments for the fork command.
 #[derive(Args, Clone, Default, Serialize)]
-#[command(group = ArgGroup::new("source").args(["chain", "endpoint"]))]
+#[command(args_conflicts_with_subcommands = true)]
+#[command(group = ArgGroup::new("source").args(["chain", "endpoint", "chain_spec"]))]
 pub(crate) struct ForkArgs {
+	#[command(subcommand)]
+	#[serde(skip)]
+	pub command: Option<Command>,
+
 	/// Well-known chain to fork (e.g., paseo, polkadot, asset-hub, asset-hub-polkadot).
 	#[arg(value_enum, index = 1)]
 	#[serde(skip)]
@@ -69,6 +86,16 @@ pub(crate) struct ForkArgs {
 	#[arg(short = 'e', long = "endpoint")]
 	pub endpoint: Option<String>,
 
+	/// Additional RPC endpoint of the same chain, used for failover and to spread storage
+	/// fetches. Can be repeated.
+	#[arg(long = "upstream", value_name = "URL")]
+	pub upstreams: Vec<String>,
+
+	/// Raw chain spec to start the fork from, instead of an upstream node. The fork starts at
+	/// the genesis block of the spec, which allows testing a chain before it is launched.
+	#[arg(long = "chain-spec", value_name = "PATH", conflicts_with_all = ["upstreams", "at"])]
+	pub chain_spec: Option<PathBuf>,
+
 	/// Path to persist SQLite cache. If not specified, uses in-memory cache.
 	#[arg(short, long)]
 	pub cache: Option<PathBuf>,
@@ -96,7 +123,7 @@ pub(crate) struct ForkArgs {
 	pub at: Option<u32>,
 
 	/// Internal flag: run as background server (used by detach mode).
-	#[arg(long, hide = true, requires = "endpoint")]
+	#[arg(long, hide = true, requires = "source")]
 	#[serde(skip)]
 	pub serve: bool,
 
@@ -118,7 +145,21 @@ pub(crate) struct ForkOutput {
 	log_file: Option<String>,
 }
 
-pub(crate) struct Command;
+/// Commands operating on a fork.
+#[derive(Subcommand, Clone)]
+pub(crate) enum Command {
+	/// Build a block with the given extrinsics on a fork and report its weight, fees and PoV
+	/// size.
+	Profile(profile::ProfileArgs),
+}
+
+impl std::fmt::Display for Command {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Profile(_) => write!(f, "profile"),
+		}
+	}
+}
 
 impl Command {
 	pub(crate) async fn execute(
@@ -126,20 +167,28 @@ impl Command {
 		cli: &mut impl cli::traits::Cli,
 		output_mode: OutputMode,
 	) -> Result<()> {
+		if let Some(Command::Profile(profile)) = &args.command {
+			return profile.execute(cli, output_mode).await;
+		}
+
 		// --serve is an internal flag used by spawn_detached; it always receives the
-		// endpoint via CLI args, so no prompting or intro is needed.
+		// endpoint or chain spec via CLI args, so no prompting or intro is needed.
 		if args.serve {
-			if args.endpoint.is_none() {
-				anyhow::bail!("--serve requires --endpoint");
+			if args.endpoint.is_none() && args.chain_spec.is_none() {
+				anyhow::bail!("--serve requires --endpoint or --chain-spec");
 			}
 			return Self::run_server(args).await;
 		}
 		if output_mode == OutputMode::Json && !args.detach {
 			anyhow::bail!("`fork --json` requires `--detach`");
 		}
-		if output_mode == OutputMode::Json && args.endpoint.is_none() && args.chain.is_none() {
+		if output_mode == OutputMode::Json &&
+			args.endpoint.is_none() &&
+			args.chain.is_none() &&
+			args.chain_spec.is_none()
+		{
 			anyhow::bail!(
-				"`fork --json --detach` requires either `--endpoint` or a chain argument"
+				"`fork --json --detach` requires either `--endpoint`, `--chain-spec` or a chain argument"
 			);
 		}
 
@@ -150,9 +199,9 @@ impl Command {
 			cli.intro(messages::INTRO)?;
 		}
 
-		// When a well-known chain is specified, try each RPC URL with fallback.
+		// When a well-known chain is specified, use all of its RPC URLs as an upstream pool.
 		if let Some(chain) = args.chain {
-			if let Some(output) = Self::execute_with_fallback(args, &chain, cli).await? &&
+			if let Some(output) = Self::execute_with_chain(args, &chain, cli).await? &&
 				output_mode == OutputMode::Json
 			{
 				CliResponse::ok(output).print_json();
@@ -161,7 +210,7 @@ impl Command {
 		}
 
 		// Prompt for endpoint if none provided.
-		if args.endpoint.is_none() {
+		if args.endpoint.is_none() && args.chain_spec.is_none() {
 			let url = prompt_to_select_chain_rpc(
 				"Which chain would you like to fork? (type to filter)",
 				"Type the chain RPC URL",
@@ -184,30 +233,45 @@ impl Command {
 		Self::run_interactive(args, cli).await
 	}
 
-	/// Try each RPC URL for a well-known chain, falling back on failure.
-	async fn execute_with_fallback(
+	/// Fork a well-known chain, using all of its RPC URLs as an upstream pool.
+	///
+	/// Unreachable endpoints are skipped at startup and the fork fails over between the
+	/// remaining ones while running.
+	async fn execute_with_chain(
 		args: &ForkArgs,
 		chain: &SupportedChains,
 		cli: &mut impl cli::traits::Cli,
 	) -> Result<Option<ForkOutput>> {
-		let rpc_urls = chain.rpc_urls();
-		let mut last_error = None;
-
-		for rpc_url in rpc_urls {
-			let resolved =
-				ForkArgs { endpoint: Some(rpc_url.to_string()), chain: None, ..args.clone() };
-
-			match Self::execute_resolved(&resolved, cli).await {
-				Ok(output) => return Ok(output),
-				Err(e) => {
-					cli.warning(format!("{} did not respond, trying next endpoint...", rpc_url))?;
-					last_error = Some(e);
-				},
-			}
-		}
+		let resolved = Self::resolve_chain(args, chain)?;
+		Self::execute_resolved(&resolved, cli).await
+	}
+
+	/// Resolve a well-known chain into an endpoint and additional upstreams.
+	/// Extracted for testability.
+	fn resolve_chain(args: &ForkArgs, chain: &SupportedChains) -> Result<ForkArgs> {
+		let (endpoint, upstreams) = chain
+			.rpc_urls()
+			.split_first()
+			.ok_or_else(|| anyhow::anyhow!("No RPC endpoints available for {}", chain))?;
+		Ok(ForkArgs {
+			endpoint: Some(endpoint.to_string()),
+			upstreams: upstreams
+				.iter()
+				.map(|url| url.to_string())
+				.chain(args.upstreams.iter().cloned())
+				.collect(),
+			chain: None,
+			..args.clone()
+		})
+	}
 
-		Err(last_error
-			.unwrap_or_else(|| anyhow::anyhow!("No RPC endpoints available for {}", chain)))
+	/// Parse the endpoint and any additional upstreams into the upstream pool.
+	fn upstream_urls(args: &ForkArgs) -> Result<Vec<Url>> {
+		let endpoint = args.endpoint.as_ref().expect("endpoint required");
+		std::iter::once(endpoint)
+			.chain(&args.upstreams)
+			.map(|url| url.parse().map_err(|e| anyhow::anyhow!("Invalid endpoint `{url}`: {e}")))
+			.collect()
 	}
 
 	/// Execute with an already-resolved endpoint (no chain fallback).
@@ -324,29 +388,32 @@ impl Command {
 	/// Run as a background server (called via --serve flag).
 	/// Output goes to log file, waits for termination signal.
 	async fn run_server(args: &ForkArgs) -> Result<()> {
-		let endpoint: Url =
-			args.endpoint.as_ref().expect("endpoint required for --serve").parse()?;
+		let executor_config = Self::executor_config(args.mock_all_signatures);
 
-		let executor_config = ExecutorConfig {
-			signature_mock: if args.mock_all_signatures {
-				SignatureMockMode::AlwaysValid
-			} else {
-				SignatureMockMode::MagicSignature
+		let blockchain = match &args.chain_spec {
+			Some(chain_spec) => {
+				log::info!("{}", messages::from_chain_spec(chain_spec));
+				Blockchain::fork_from_chain_spec(chain_spec, args.cache.as_deref(), executor_config)
+					.await?
 			},
-			..Default::default()
-		};
-
-		let fork_point = args.at.map(BlockForkPoint::from);
+			None => {
+				let endpoints = Self::upstream_urls(args)?;
+				let fork_point = args.at.map(BlockForkPoint::from);
 
-		log::info!("{}", messages::forking(&endpoint));
+				log::info!("{}", messages::forking(&endpoints[0]));
+				if endpoints.len() > 1 {
+					log::info!("{}", messages::upstreams(endpoints.len()));
+				}
 
-		let blockchain = Blockchain::fork_with_config(
-			&endpoint,
-			args.cache.as_deref(),
-			fork_point,
-			executor_config,
-		)
-		.await?;
+				Blockchain::fork_with_upstreams(
+					&endpoints,
+					args.cache.as_deref(),
+					fork_point,
+					executor_config,
+				)
+				.await?
+			},
+		};
 
 		if args.dev {
 			blockchain.initialize_dev_accounts().await?;
@@ -382,28 +449,32 @@ impl Command {
 
 	/// Run interactively with CLI output (default mode).
 	async fn run_interactive(args: &ForkArgs, cli: &mut impl cli::traits::Cli) -> Result<()> {
-		let endpoint: Url = args.endpoint.as_ref().expect("endpoint required").parse()?;
+		let executor_config = Self::executor_config(args.mock_all_signatures);
 
-		let executor_config = ExecutorConfig {
-			signature_mock: if args.mock_all_signatures {
-				SignatureMockMode::AlwaysValid
-			} else {
-				SignatureMockMode::MagicSignature
+		let blockchain = match &args.chain_spec {
+			Some(chain_spec) => {
+				cli.info(messages::from_chain_spec(chain_spec))?;
+				Blockchain::fork_from_chain_spec(chain_spec, args.cache.as_deref(), executor_config)
+					.await?
 			},
-			..Default::default()
-		};
-
-		let fork_point = args.at.map(BlockForkPoint::from);
+			None => {
+				let endpoints = Self::upstream_urls(args)?;
+				let fork_point = args.at.map(BlockForkPoint::from);
 
-		cli.info(messages::forking(&endpoint))?;
+				cli.info(messages::forking(&endpoints[0]))?;
+				if endpoints.len() > 1 {
+					cli.info(messages::upstreams(endpoints.len()))?;
+				}
 
-		let blockchain = Blockchain::fork_with_config(
-			&endpoint,
-			args.cache.as_deref(),
-			fork_point,
-			executor_config,
-		)
-		.await?;
+				Blockchain::fork_with_upstreams(
+					&endpoints,
+					args.cache.as_deref(),
+					fork_point,
+					executor_config,
+				)
+				.await?
+			},
+		};
 
 		if args.dev {
 			blockchain.initialize_dev_accounts().await?;
@@ -438,6 +509,18 @@ impl Command {
 		Ok(())
 	}
 
+	/// Build the executor configuration from the signature mocking flag.
+	fn executor_config(mock_all_signatures: bool) -> ExecutorConfig {
+		ExecutorConfig {
+			signature_mock: if mock_all_signatures {
+				SignatureMockMode::AlwaysValid
+			} else {
+				SignatureMockMode::MagicSignature
+			},
+			..Default::default()
+		}
+	}
+
 	/// Build the three summary lines shown after a fork completes.
 	/// Extracted for testability.
 	fn fork_summary_lines(chain_name: &str, block_number: u32, ws_url: &str) -> [String; 3] {
@@ -490,6 +573,14 @@ impl Command {
 			cmd_args.push("-e".to_string());
 			cmd_args.push(endpoint.clone());
 		}
+		for upstream in &args.upstreams {
+			cmd_args.push("--upstream".to_string());
+			cmd_args.push(upstream.clone());
+		}
+		if let Some(chain_spec) = &args.chain_spec {
+			cmd_args.push("--chain-spec".to_string());
+			cmd_args.push(chain_spec.to_string_lossy().to_string());
+		}
 		if let Some(cache) = &args.cache {
 			cmd_args.push("--cache".to_string());
 			cmd_args.push(cache.to_string_lossy().to_string());
@@ -575,7 +666,7 @@ mod tests {
 		let mut args = ForkArgs { serve: true, ..Default::default() };
 		let mut cli = MockCli::new();
 		let err = Command::execute(&mut args, &mut cli, OutputMode::Human).await.unwrap_err();
-		assert!(err.to_string().contains("--serve requires --endpoint"));
+		assert!(err.to_string().contains("--serve requires --endpoint or --chain-spec"));
 		cli.verify().unwrap();
 	}
 
@@ -594,7 +685,10 @@ mod tests {
 		let mut args = ForkArgs { detach: true, ..Default::default() };
 		let mut cli = MockCli::new();
 		let err = Command::execute(&mut args, &mut cli, OutputMode::Json).await.unwrap_err();
-		assert!(err.to_string().contains("requires either `--endpoint` or a chain argument"));
+		assert!(
+			err.to_string()
+				.contains("requires either `--endpoint`, `--chain-spec` or a chain argument")
+		);
 		cli.verify().unwrap();
 	}
 
@@ -610,6 +704,7 @@ mod tests {
 	fn build_serve_args_full() {
 		let args = ForkArgs {
 			endpoint: Some("wss://rpc.polkadot.io".to_string()),
+			upstreams: vec!["wss://polkadot.dotters.network".to_string()],
 			cache: Some(PathBuf::from("/tmp/cache.db")),
 			port: Some(9000),
 			mock_all_signatures: true,
@@ -618,7 +713,9 @@ mod tests {
 			detach: true,
 			serve: false,
 			chain: None,
+			chain_spec: None,
 			ready_file: None,
+			command: None,
 		};
 		let result = Command::build_serve_args(&args);
 		assert_eq!(
@@ -627,6 +724,8 @@ mod tests {
 				"fork",
 				"-e",
 				"wss://rpc.polkadot.io",
+				"--upstream",
+				"wss://polkadot.dotters.network",
 				"--cache",
 				"/tmp/cache.db",
 				"--port",
@@ -651,6 +750,25 @@ mod tests {
 		assert_eq!(result, vec!["fork", "-e", "wss://rpc.polkadot.io", "--at", "5000", "--serve"]);
 	}
 
+	#[test]
+	fn build_serve_args_with_chain_spec() {
+		let args = ForkArgs { chain_spec: Some(PathBuf::from("raw.json")), ..Default::default() };
+		let result = Command::build_serve_args(&args);
+		assert_eq!(result, vec!["fork", "--chain-spec", "raw.json", "--serve"]);
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn execute_skips_prompt_when_chain_spec_provided() {
+		let mut args =
+			ForkArgs { chain_spec: Some(PathBuf::from("missing.json")), ..Default::default() };
+		// No select expectation -- prompt should not be triggered
+		let mut cli = MockCli::new();
+		let err = Command::execute(&mut args, &mut cli, OutputMode::Human).await.unwrap_err();
+		assert!(err.to_string().contains("missing.json"));
+		assert!(args.endpoint.is_none());
+		cli.verify().unwrap();
+	}
+
 	#[test]
 	fn build_serve_args_without_at() {
 		let args =
@@ -659,6 +777,47 @@ mod tests {
 		assert!(!result.contains(&"--at".to_string()));
 	}
 
+	#[test]
+	fn resolve_chain_uses_all_rpc_urls_as_upstreams() {
+		let args = ForkArgs {
+			chain: Some(SupportedChains::PASEO),
+			upstreams: vec!["wss://my-paseo.example".to_string()],
+			..Default::default()
+		};
+		let resolved = Command::resolve_chain(&args, &SupportedChains::PASEO).unwrap();
+		let rpc_urls = SupportedChains::PASEO.rpc_urls();
+		assert_eq!(resolved.chain, None);
+		assert_eq!(resolved.endpoint.as_deref(), Some(rpc_urls[0]));
+		assert_eq!(resolved.upstreams.len(), rpc_urls.len());
+		assert_eq!(resolved.upstreams[..rpc_urls.len() - 1], rpc_urls[1..]);
+		assert_eq!(resolved.upstreams.last().unwrap(), "wss://my-paseo.example");
+	}
+
+	#[test]
+	fn upstream_urls_includes_endpoint_first() {
+		let args = ForkArgs {
+			endpoint: Some("wss://rpc.polkadot.io".to_string()),
+			upstreams: vec!["wss://polkadot.dotters.network".to_string()],
+			..Default::default()
+		};
+		let urls = Command::upstream_urls(&args).unwrap();
+		assert_eq!(
+			urls.iter().map(Url::as_str).collect::<Vec<_>>(),
+			vec!["wss://rpc.polkadot.io/", "wss://polkadot.dotters.network/"]
+		);
+	}
+
+	#[test]
+	fn upstream_urls_rejects_invalid_upstream() {
+		let args = ForkArgs {
+			endpoint: Some("wss://rpc.polkadot.io".to_string()),
+			upstreams: vec!["not a url".to_string()],
+			..Default::default()
+		};
+		let err = Command::upstream_urls(&args).unwrap_err();
+		assert!(err.to_string().contains("Invalid endpoint `not a url`"));
+	}
+
 	#[test]
 	fn build_serve_args_includes_serve_not_detach() {
 		let args = ForkArgs {
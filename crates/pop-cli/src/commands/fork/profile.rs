@@ -0,0 +1,317 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use super::{Command, messages};
+use crate::{
+	cli::{self},
+	common::{rpc::prompt_to_select_chain_rpc, urls},
+	output::{CliResponse, OutputMode},
+};
+use anyhow::{Context, Result};
+use clap::{ArgGroup, Args};
+use pop_chains::SupportedChains;
+use pop_fork::{
+	BlockForkPoint, BlockProfile, Blockchain, DispatchClass, Weight,
+	rpc_server::methods::BlockProfileResult,
+};
+use serde::Serialize;
+use sp_core::bytes::{from_hex, to_hex};
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Arguments for profiling extrinsics on a fork.
+#[derive(Args, Clone, Default, Serialize)]
+#[command(group = ArgGroup::new("source").args(["chain", "endpoint"]))]
+pub(crate) struct ProfileArgs {
+	/// File with the extrinsics to profile: one hex-encoded extrinsic per line. Empty lines and
+	/// lines starting with `#` are ignored.
+	#[arg(value_name = "EXTRINSIC_FILE")]
+	pub extrinsics: PathBuf,
+
+	/// Well-known chain to fork (e.g., paseo, polkadot, asset-hub, asset-hub-polkadot).
+	#[arg(long, value_enum)]
+	#[serde(skip)]
+	pub chain: Option<SupportedChains>,
+
+	/// RPC endpoint to fork from.
+	#[arg(short = 'e', long = "endpoint")]
+	pub endpoint: Option<String>,
+
+	/// Fork at a specific block number. If not specified, forks at the latest finalized block.
+	#[arg(long)]
+	pub at: Option<u32>,
+
+	/// Path to persist SQLite cache. If not specified, uses in-memory cache.
+	#[arg(short, long)]
+	pub cache: Option<PathBuf>,
+
+	/// Accept all signatures as valid (default: only magic signatures 0xdeadbeef).
+	#[arg(long = "mock-all-signatures")]
+	pub mock_all_signatures: bool,
+
+	/// Fund well-known dev accounts before building the block.
+	#[arg(long)]
+	pub dev: bool,
+}
+
+/// Structured output for `fork profile --json`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProfileOutput {
+	chain: String,
+	block_hash: String,
+	block_number: u32,
+	#[serde(flatten)]
+	profile: BlockProfileResult,
+}
+
+impl ProfileArgs {
+	/// Fork the chain, build a block with the extrinsics of the file and report its profile.
+	pub(crate) async fn execute(
+		&self,
+		cli: &mut impl cli::traits::Cli,
+		output_mode: OutputMode,
+	) -> Result<()> {
+		let extrinsics = read_extrinsics(&self.extrinsics)?;
+		if output_mode == OutputMode::Json && self.chain.is_none() && self.endpoint.is_none() {
+			anyhow::bail!("`fork profile --json` requires either `--endpoint` or `--chain`");
+		}
+
+		cli.intro("Profiling extrinsics on a fork")?;
+		let endpoints = self.endpoints(cli).await?;
+		cli.info(messages::forking(&endpoints[0]))?;
+
+		let blockchain = Blockchain::fork_with_upstreams(
+			&endpoints,
+			self.cache.as_deref(),
+			self.at.map(BlockForkPoint::from),
+			Command::executor_config(self.mock_all_signatures),
+		)
+		.await?;
+		if self.dev {
+			blockchain.initialize_dev_accounts().await?;
+			cli.info(messages::dev_accounts_funded(blockchain.chain_name()))?;
+		}
+
+		let spinner = cli.spinner();
+		spinner.start(format!("Building a block with {} extrinsic(s)...", extrinsics.len()));
+		let result = blockchain.build_block(extrinsics).await;
+		spinner.clear();
+		let result = result?;
+		let profile = blockchain.profile_block(&result).await?;
+
+		for failed in &result.failed {
+			cli.warning(format!(
+				"Dropped {}: {}",
+				to_hex(&sp_core::blake2_256(&failed.extrinsic), false),
+				failed.reason
+			))?;
+		}
+		if let Err(e) = blockchain.clear_local_storage().await {
+			cli.warning(format!("Failed to clear local storage: {}", e))?;
+		}
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(ProfileOutput {
+				chain: blockchain.chain_name().to_string(),
+				block_hash: to_hex(profile.hash.as_bytes(), false),
+				block_number: profile.number,
+				profile: profile.into(),
+			})
+			.print_json();
+			return Ok(());
+		}
+
+		cli.plain(report(&profile))?;
+		cli.outro(format!("Profiled block #{} of {}", profile.number, blockchain.chain_name()))?;
+		Ok(())
+	}
+
+	/// Resolve the upstream endpoints, prompting for one if no source was given.
+	async fn endpoints(&self, cli: &mut impl cli::traits::Cli) -> Result<Vec<Url>> {
+		let urls: Vec<String> = match (&self.chain, &self.endpoint) {
+			(Some(chain), _) => chain.rpc_urls().iter().map(|url| url.to_string()).collect(),
+			(None, Some(endpoint)) => vec![endpoint.clone()],
+			(None, None) => vec![
+				prompt_to_select_chain_rpc(
+					"Which chain would you like to fork? (type to filter)",
+					"Type the chain RPC URL",
+					urls::LOCAL,
+					|_| true,
+					cli,
+				)
+				.await?
+				.to_string(),
+			],
+		};
+		urls.iter()
+			.map(|url| url.parse().map_err(|e| anyhow::anyhow!("Invalid endpoint `{url}`: {e}")))
+			.collect()
+	}
+}
+
+/// Read the hex-encoded extrinsics of a file.
+fn read_extrinsics(path: &Path) -> Result<Vec<Vec<u8>>> {
+	let contents = std::fs::read_to_string(path)
+		.with_context(|| format!("Failed to read {}", path.display()))?;
+	let extrinsics = parse_extrinsics(&contents)?;
+	if extrinsics.is_empty() {
+		anyhow::bail!("No extrinsics found in {}", path.display());
+	}
+	Ok(extrinsics)
+}
+
+/// Parse one hex-encoded extrinsic per line, skipping empty lines and `#` comments.
+fn parse_extrinsics(contents: &str) -> Result<Vec<Vec<u8>>> {
+	contents
+		.lines()
+		.enumerate()
+		.map(|(number, line)| (number + 1, line.trim()))
+		.filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+		.map(|(number, line)| {
+			from_hex(line).map_err(|e| anyhow::anyhow!("Invalid extrinsic on line {number}: {e}"))
+		})
+		.collect()
+}
+
+/// Format `used` against an optional limit, e.g. `1500 / 6000 (25.00%)`.
+fn usage(used: u64, max: Option<u64>) -> String {
+	match max {
+		Some(max) if max > 0 => {
+			format!("{used} / {max} ({:.2}%)", used as f64 * 100.0 / max as f64)
+		},
+		_ => used.to_string(),
+	}
+}
+
+fn format_weight(weight: Option<Weight>) -> String {
+	weight.map_or_else(
+		|| "-".to_string(),
+		|weight| format!("ref_time {}, proof_size {}", weight.ref_time, weight.proof_size),
+	)
+}
+
+fn format_class(class: Option<DispatchClass>) -> &'static str {
+	match class {
+		Some(DispatchClass::Normal) => "normal",
+		Some(DispatchClass::Operational) => "operational",
+		Some(DispatchClass::Mandatory) => "mandatory",
+		None => "unknown",
+	}
+}
+
+/// Render the human-readable profile report.
+fn report(profile: &BlockProfile) -> String {
+	let mut lines = Vec::new();
+	for extrinsic in &profile.extrinsics {
+		let call = match (&extrinsic.pallet, &extrinsic.call) {
+			(Some(pallet), Some(call)) => format!("{pallet}.{call}"),
+			_ => "<unknown call>".to_string(),
+		};
+		let status = match extrinsic.success {
+			Some(true) => "ok",
+			Some(false) => "failed",
+			None => "unknown",
+		};
+		lines.push(format!(
+			"#{} {call} [{}, {status}]",
+			extrinsic.index,
+			format_class(extrinsic.class)
+		));
+		lines.push(format!("    length:    {} bytes", extrinsic.length));
+		lines.push(format!("    estimated: {}", format_weight(extrinsic.estimated_weight)));
+		lines.push(format!("    actual:    {}", format_weight(extrinsic.actual_weight)));
+		if let Some(fee) = extrinsic.partial_fee {
+			lines.push(format!("    fee:       {fee}"));
+		}
+	}
+
+	let weight = profile.total_weight();
+	let max_weight = profile.limits.map(|limits| limits.max_weight);
+	lines.push(String::new());
+	lines.push(format!(
+		"Block ref_time:   {}",
+		usage(weight.ref_time, max_weight.map(|max| max.ref_time))
+	));
+	lines.push(format!(
+		"Block proof_size: {}",
+		usage(weight.proof_size, max_weight.map(|max| max.proof_size))
+	));
+	lines.push(format!(
+		"Block length:     {}",
+		usage(profile.length as u64, profile.limits.map(|limits| limits.max_length as u64))
+	));
+	lines.push(format!(
+		"PoV estimate:     {}",
+		usage(profile.pov_estimate(), max_weight.map(|max| max.proof_size))
+	));
+	lines.push(format!("Total fees:       {}", profile.total_fee()));
+	if profile.dropped > 0 {
+		lines.push(format!("Dropped:          {}", profile.dropped));
+	}
+	lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use pop_fork::{BlockLimits, ExtrinsicProfile};
+	use subxt::config::substrate::H256;
+
+	#[test]
+	fn parse_extrinsics_skips_comments_and_blank_lines() {
+		let contents = "# transfer\n0x0102\n\n  0304  \n";
+		assert_eq!(parse_extrinsics(contents).unwrap(), vec![vec![1, 2], vec![3, 4]]);
+	}
+
+	#[test]
+	fn parse_extrinsics_reports_invalid_line() {
+		let err = parse_extrinsics("0x01\n0xzz").unwrap_err();
+		assert!(err.to_string().contains("line 2"), "{err}");
+	}
+
+	#[test]
+	fn read_extrinsics_rejects_empty_file() {
+		let file = tempfile::NamedTempFile::new().unwrap();
+		std::fs::write(file.path(), "# nothing here\n").unwrap();
+		assert!(read_extrinsics(file.path()).unwrap_err().to_string().contains("No extrinsics"));
+	}
+
+	#[test]
+	fn usage_includes_percentage_of_limit() {
+		assert_eq!(usage(1500, Some(6000)), "1500 / 6000 (25.00%)");
+		assert_eq!(usage(1500, Some(0)), "1500");
+		assert_eq!(usage(1500, None), "1500");
+	}
+
+	#[test]
+	fn report_lists_extrinsics_and_block_usage() {
+		let profile = BlockProfile {
+			hash: H256::zero(),
+			number: 1,
+			extrinsics: vec![ExtrinsicProfile {
+				index: 0,
+				hash: H256::zero(),
+				pallet: Some("Balances".to_string()),
+				call: Some("transfer_keep_alive".to_string()),
+				length: 100,
+				class: Some(DispatchClass::Normal),
+				estimated_weight: Some(Weight { ref_time: 10, proof_size: 5 }),
+				partial_fee: Some(42),
+				actual_weight: Some(Weight { ref_time: 8, proof_size: 4 }),
+				success: Some(true),
+			}],
+			dropped: 0,
+			weight: None,
+			length: 100,
+			limits: Some(BlockLimits {
+				max_weight: Weight { ref_time: 80, proof_size: 1000 },
+				max_length: 1000,
+			}),
+		};
+		let report = report(&profile);
+		assert!(report.contains("#0 Balances.transfer_keep_alive [normal, ok]"));
+		assert!(report.contains("Block ref_time:   8 / 80 (10.00%)"));
+		assert!(report.contains("PoV estimate:     104 / 1000 (10.40%)"));
+		assert!(report.contains("Total fees:       42"));
+	}
+}
@@ -0,0 +1,325 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	output::{CliResponse, OutputMode},
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use pop_chains::{Metadata, fetch_code, up::code_hash};
+use pop_fork::RuntimeExecutor;
+use scale::Decode;
+use serde::Serialize;
+use sp_core::{blake2_64, bytes::to_hex};
+use sp_maybe_compressed_blob::{CODE_BLOB_BOMB_LIMIT, decompress};
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	fmt::{Display, Formatter},
+	fs,
+	path::PathBuf,
+	str::FromStr,
+};
+use url::Url;
+
+/// Arguments for inspecting build artifacts.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct InspectArgs {
+	/// Inspect subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Inspect build artifacts.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Inspect a runtime: its version, runtime APIs, pallets, genesis presets, size and code hash.
+	#[clap(alias = "r")]
+	Runtime(RuntimeArgs),
+}
+
+/// Arguments for inspecting a runtime.
+#[derive(Args, Serialize)]
+pub(crate) struct RuntimeArgs {
+	/// The runtime: a runtime `.wasm` file or the websocket endpoint of a live chain.
+	pub(crate) runtime: RuntimeSource,
+}
+
+/// Where to get the code of a runtime from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) enum RuntimeSource {
+	/// A node of a live chain, whose current runtime is inspected.
+	Url(Url),
+	/// A runtime WASM blob, compressed or not.
+	Wasm(PathBuf),
+}
+
+impl FromStr for RuntimeSource {
+	type Err = url::ParseError;
+
+	fn from_str(source: &str) -> Result<Self, Self::Err> {
+		if ["ws://", "wss://", "http://", "https://"]
+			.iter()
+			.any(|scheme| source.starts_with(scheme))
+		{
+			return Ok(Self::Url(Url::parse(source)?));
+		}
+		Ok(Self::Wasm(PathBuf::from(source)))
+	}
+}
+
+impl Display for RuntimeSource {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Url(url) => write!(f, "{url}"),
+			Self::Wasm(path) => write!(f, "{}", path.display()),
+		}
+	}
+}
+
+impl RuntimeSource {
+	async fn code(&self) -> Result<Vec<u8>> {
+		Ok(match self {
+			Self::Url(url) => fetch_code(url.as_str(), None).await?.1,
+			Self::Wasm(path) => fs::read(path)?,
+		})
+	}
+}
+
+/// What is inside a runtime.
+#[derive(Debug, Serialize)]
+pub(crate) struct RuntimeInspection {
+	spec_name: String,
+	impl_name: String,
+	spec_version: u32,
+	impl_version: u32,
+	transaction_version: u32,
+	state_version: u8,
+	apis: Vec<RuntimeApiVersion>,
+	pallets: Vec<PalletIndex>,
+	presets: Vec<String>,
+	size: usize,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	compressed_size: Option<usize>,
+	code_hash: String,
+}
+
+/// A runtime API implemented by a runtime.
+#[derive(Debug, Serialize)]
+struct RuntimeApiVersion {
+	/// The name of the API, or the hash of its name if the metadata does not describe it.
+	name: String,
+	version: u32,
+}
+
+/// A pallet of a runtime.
+#[derive(Debug, Serialize)]
+struct PalletIndex {
+	name: String,
+	index: u8,
+}
+
+impl RuntimeInspection {
+	/// Inspects a runtime by executing it, without any chain state.
+	///
+	/// # Arguments
+	/// * `code` - The runtime code, compressed or not.
+	async fn new(code: Vec<u8>) -> Result<Self> {
+		let code_hash = format!("{:?}", code_hash(&code));
+		let (size, compressed_size) = match decompress(&code, CODE_BLOB_BOMB_LIMIT)? {
+			Cow::Owned(uncompressed) => (uncompressed.len(), Some(code.len())),
+			Cow::Borrowed(_) => (code.len(), None),
+		};
+
+		let executor = RuntimeExecutor::new(code, None)?;
+		let version = executor.core_version().await?;
+		let metadata = Metadata::decode(&mut executor.metadata().await?.as_slice())?;
+		// Runtimes without a genesis builder have no presets.
+		let presets = executor.genesis_preset_names().await.unwrap_or_default();
+
+		// Runtime APIs are identified by the hash of their name, described by metadata from V15.
+		let names: HashMap<_, _> = metadata
+			.runtime_api_traits()
+			.map(|api| (blake2_64(api.name().as_bytes()), api.name().to_string()))
+			.collect();
+		let apis = version
+			.apis
+			.into_iter()
+			.map(|(id, version)| RuntimeApiVersion {
+				name: names.get(&id).cloned().unwrap_or_else(|| to_hex(&id, false)),
+				version,
+			})
+			.collect();
+		let mut pallets: Vec<_> = metadata
+			.pallets()
+			.map(|pallet| PalletIndex { name: pallet.name().to_string(), index: pallet.index() })
+			.collect();
+		pallets.sort_by_key(|pallet| pallet.index);
+
+		Ok(Self {
+			spec_name: version.spec_name,
+			impl_name: version.impl_name,
+			spec_version: version.spec_version,
+			impl_version: version.impl_version,
+			transaction_version: version.transaction_version,
+			state_version: version.state_version,
+			apis,
+			pallets,
+			presets,
+			size,
+			compressed_size,
+			code_hash,
+		})
+	}
+
+	/// Renders the inspection for display.
+	fn render(&self) -> String {
+		let mut lines = vec![
+			format!("Spec: {} v{}", self.spec_name, self.spec_version),
+			format!("Implementation: {} v{}", self.impl_name, self.impl_version),
+			format!("Transaction version: {}", self.transaction_version),
+			format!("State version: {}", self.state_version),
+			format!("Code hash: {}", self.code_hash),
+			match self.compressed_size {
+				Some(compressed) =>
+					format!("Size: {} bytes ({compressed} bytes compressed)", self.size),
+				None => format!("Size: {} bytes (uncompressed)", self.size),
+			},
+			format!(
+				"Genesis presets: {}",
+				if self.presets.is_empty() { "none".to_string() } else { self.presets.join(", ") }
+			),
+			format!("Runtime APIs ({}):", self.apis.len()),
+		];
+		lines.extend(self.apis.iter().map(|api| format!("  {} v{}", api.name, api.version)));
+		lines.push(format!("Pallets ({}):", self.pallets.len()));
+		lines.extend(
+			self.pallets
+				.iter()
+				.map(|pallet| format!("  {:>3} {}", pallet.index, pallet.name)),
+		);
+		lines.join("\n")
+	}
+}
+
+impl Command {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		match self {
+			Command::Runtime(args) => args.execute(cli, output_mode).await,
+		}
+	}
+}
+
+impl RuntimeArgs {
+	async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Inspect a runtime")?;
+		let spinner = cli.spinner();
+		spinner.start(format!("Inspecting {}...", self.runtime));
+		let inspection = match self.runtime.code().await {
+			Ok(code) => RuntimeInspection::new(code).await,
+			Err(e) => Err(e),
+		};
+		spinner.clear();
+		let inspection = inspection?;
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(inspection).print_json();
+		} else {
+			cli.plain(inspection.render())?;
+			cli.outro(format!(
+				"Inspected {} v{} from {}",
+				inspection.spec_name, inspection.spec_version, self.runtime
+			))?;
+		}
+		Ok(())
+	}
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Runtime(_) => write!(f, "runtime"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	#[test]
+	fn runtime_source_from_str_works() -> Result<()> {
+		assert_eq!(
+			"wss://rpc.polkadot.io".parse::<RuntimeSource>()?,
+			RuntimeSource::Url(Url::parse("wss://rpc.polkadot.io")?)
+		);
+		assert_eq!(
+			"runtime.compact.compressed.wasm".parse::<RuntimeSource>()?,
+			RuntimeSource::Wasm(PathBuf::from("runtime.compact.compressed.wasm"))
+		);
+		assert!("ws://".parse::<RuntimeSource>().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn render_works() {
+		let inspection = RuntimeInspection {
+			spec_name: "pop".into(),
+			impl_name: "pop-node".into(),
+			spec_version: 1_000,
+			impl_version: 0,
+			transaction_version: 1,
+			state_version: 1,
+			apis: vec![RuntimeApiVersion { name: "Core".into(), version: 5 }],
+			pallets: vec![
+				PalletIndex { name: "System".into(), index: 0 },
+				PalletIndex { name: "Balances".into(), index: 10 },
+			],
+			presets: vec![],
+			size: 4_096,
+			compressed_size: Some(1_024),
+			code_hash: "0x01".into(),
+		};
+		assert_eq!(
+			inspection.render(),
+			"Spec: pop v1000
+Implementation: pop-node v0
+Transaction version: 1
+State version: 1
+Code hash: 0x01
+Size: 4096 bytes (1024 bytes compressed)
+Genesis presets: none
+Runtime APIs (1):
+  Core v5
+Pallets (2):
+    0 System
+   10 Balances"
+		);
+	}
+
+	#[tokio::test]
+	async fn inspect_live_runtime_works() -> Result<()> {
+		let source = RuntimeSource::Url(Url::parse(&shared_substrate_ws_url().await)?);
+		let inspection = RuntimeInspection::new(source.code().await?).await?;
+		assert!(!inspection.spec_name.is_empty());
+		assert!(inspection.apis.iter().any(|api| api.name == "Core"));
+		assert_eq!(inspection.pallets[0].name, "System");
+		assert_eq!(inspection.pallets[0].index, 0);
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn inspect_fails_on_invalid_runtime() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("runtime.wasm");
+		fs::write(&path, [1, 2, 3])?;
+		let args = RuntimeArgs { runtime: RuntimeSource::Wasm(path) };
+		let mut cli = MockCli::new().expect_intro("Inspect a runtime");
+		assert!(args.execute(&mut cli, OutputMode::Human).await.is_err());
+		cli.verify()
+	}
+}
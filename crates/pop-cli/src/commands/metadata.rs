@@ -0,0 +1,207 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	output::{CliResponse, OutputMode},
+};
+use anyhow::{Result, bail};
+use clap::{Args, Subcommand};
+use pop_chains::{
+	Metadata, MetadataChange, MetadataDiff, diff_metadata, load_metadata, set_up_client,
+};
+use pop_fork::RuntimeExecutor;
+use scale::Decode;
+use serde::Serialize;
+use std::{
+	fmt::{Display, Formatter},
+	fs,
+	path::PathBuf,
+	str::FromStr,
+};
+use url::Url;
+
+/// Arguments for inspecting runtime metadata.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct MetadataArgs {
+	/// Metadata subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Inspect the metadata of runtimes.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Compare the metadata of two runtimes or chains, e.g. before a runtime upgrade.
+	#[clap(alias = "d")]
+	Diff(DiffArgs),
+}
+
+/// Arguments for comparing the metadata of two runtimes.
+#[derive(Args, Serialize)]
+pub(crate) struct DiffArgs {
+	/// The old runtime: a websocket endpoint, a runtime `.wasm` file or a metadata file.
+	pub(crate) old: MetadataSource,
+	/// The new runtime: a websocket endpoint, a runtime `.wasm` file or a metadata file.
+	pub(crate) new: MetadataSource,
+	/// Only report changes which break clients built for the old runtime.
+	#[arg(long)]
+	pub(crate) breaking_only: bool,
+	/// Fail if any breaking changes are found, e.g. in CI.
+	#[arg(long)]
+	pub(crate) fail_on_breaking: bool,
+}
+
+/// Where to get the metadata of a runtime from.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub(crate) enum MetadataSource {
+	/// A node of a live chain.
+	Url(Url),
+	/// A runtime WASM blob, whose metadata is obtained by executing it.
+	Wasm(PathBuf),
+	/// A metadata file, SCALE-encoded or hex-encoded.
+	File(PathBuf),
+}
+
+impl FromStr for MetadataSource {
+	type Err = url::ParseError;
+
+	fn from_str(source: &str) -> Result<Self, Self::Err> {
+		if ["ws://", "wss://", "http://", "https://"]
+			.iter()
+			.any(|scheme| source.starts_with(scheme))
+		{
+			return Ok(Self::Url(Url::parse(source)?));
+		}
+		let path = PathBuf::from(source);
+		Ok(match path.extension().and_then(|e| e.to_str()) {
+			Some("wasm") => Self::Wasm(path),
+			_ => Self::File(path),
+		})
+	}
+}
+
+impl Display for MetadataSource {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Url(url) => write!(f, "{url}"),
+			Self::Wasm(path) | Self::File(path) => write!(f, "{}", path.display()),
+		}
+	}
+}
+
+impl MetadataSource {
+	async fn load(&self) -> Result<Metadata> {
+		Ok(match self {
+			Self::Url(url) => set_up_client(url.as_str()).await?.metadata(),
+			Self::Wasm(path) => {
+				let executor = RuntimeExecutor::new(fs::read(path)?, None)?;
+				let bytes = executor.metadata().await?;
+				Metadata::decode(&mut bytes.as_slice())?
+			},
+			Self::File(path) => load_metadata(path)?,
+		})
+	}
+}
+
+/// Structured output for `metadata diff` in JSON mode.
+#[derive(Serialize)]
+struct DiffOutput {
+	changes: Vec<MetadataChange>,
+	breaking: usize,
+}
+
+impl Command {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		match self {
+			Command::Diff(args) => args.execute(cli, output_mode).await,
+		}
+	}
+}
+
+impl DiffArgs {
+	async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Compare runtime metadata")?;
+		let spinner = cli.spinner();
+		spinner.start(format!("Loading the metadata of {} and {}...", self.old, self.new));
+		let metadata = tokio::try_join!(self.old.load(), self.new.load());
+		spinner.clear();
+		let (old, new) = metadata?;
+
+		let mut diff = diff_metadata(&old, &new)?;
+		let breaking = diff.breaking().count();
+		if self.breaking_only {
+			diff = MetadataDiff { changes: diff.breaking().cloned().collect() };
+		}
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(DiffOutput { changes: diff.changes, breaking }).print_json();
+		} else if diff.is_empty() {
+			cli.outro("No differences found.")?;
+		} else {
+			cli.plain(diff.render())?;
+			if breaking > 0 {
+				cli.warning(format!(
+					"{breaking} breaking change(s) found. Wallets and indexers built for {} need \
+					 to be updated.",
+					self.old
+				))?;
+			}
+			cli.outro(format!("{} change(s) found.", diff.changes.len()))?;
+		}
+
+		if self.fail_on_breaking && breaking > 0 {
+			bail!("{breaking} breaking change(s) found between {} and {}", self.old, self.new);
+		}
+		Ok(())
+	}
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Diff(_) => write!(f, "diff"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[test]
+	fn metadata_source_from_str_works() -> Result<()> {
+		assert_eq!(
+			"wss://rpc.polkadot.io".parse::<MetadataSource>()?,
+			MetadataSource::Url(Url::parse("wss://rpc.polkadot.io")?)
+		);
+		assert_eq!(
+			"runtime.compact.compressed.wasm".parse::<MetadataSource>()?,
+			MetadataSource::Wasm(PathBuf::from("runtime.compact.compressed.wasm"))
+		);
+		assert_eq!(
+			"metadata.scale".parse::<MetadataSource>()?,
+			MetadataSource::File(PathBuf::from("metadata.scale"))
+		);
+		assert!("ws://".parse::<MetadataSource>().is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn diff_fails_on_invalid_metadata_file() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let path = dir.path().join("metadata.scale");
+		fs::write(&path, [1, 2, 3])?;
+		let args = DiffArgs {
+			old: MetadataSource::File(path.clone()),
+			new: MetadataSource::File(path),
+			breaking_only: false,
+			fail_on_breaking: false,
+		};
+		let mut cli = MockCli::new().expect_intro("Compare runtime metadata");
+		assert!(args.execute(&mut cli, OutputMode::Human).await.is_err());
+		cli.verify()
+	}
+}
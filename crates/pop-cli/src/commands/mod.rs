@@ -19,6 +19,7 @@ use std::fmt::{Display, Formatter, Result};
 #[cfg(feature = "chain")]
 use up::network::Relay::*;
 
+pub(crate) mod account;
 #[cfg(feature = "chain")]
 pub(crate) mod bench;
 pub(crate) mod build;
@@ -28,18 +29,30 @@ pub(crate) mod clean;
 pub(crate) mod completion;
 pub(crate) mod convert;
 #[cfg(feature = "chain")]
+pub(crate) mod decode;
+#[cfg(feature = "chain")]
 pub(crate) mod fork;
 pub(crate) mod hash;
+#[cfg(feature = "chain")]
+pub(crate) mod inspect;
 #[cfg(any(feature = "chain", feature = "contract"))]
 pub(crate) mod install;
+#[cfg(feature = "chain")]
+pub(crate) mod metadata;
+#[cfg(feature = "chain")]
+pub(crate) mod network;
 #[cfg(any(feature = "chain", feature = "contract"))]
 pub(crate) mod new;
 pub(crate) mod test;
+#[cfg(feature = "chain")]
+pub(crate) mod tx;
 #[cfg(any(feature = "chain", feature = "contract"))]
 pub(crate) mod up;
 pub(crate) mod upgrade;
-#[cfg(feature = "contract")]
+#[cfg(any(feature = "chain", feature = "contract"))]
 pub(crate) mod verify;
+#[cfg(feature = "chain")]
+pub(crate) mod watch;
 
 #[derive(Subcommand, Serialize)]
 #[command(subcommand_required = true)]
@@ -81,14 +94,35 @@ pub(crate) enum Command {
 	Convert(convert::ConvertArgs),
 	/// Generate shell completions.
 	Completion(completion::CompletionArgs),
-	/// Verify a smart contract binary
+	/// Manage the accounts of the encrypted keystore.
+	#[clap(alias = "a")]
+	Account(account::AccountArgs),
+	/// Verify a smart contract binary or a runtime
 	#[clap(alias = "v")]
-	#[cfg(feature = "contract")]
-	Verify(verify::VerifyCommand),
+	#[cfg(any(feature = "chain", feature = "contract"))]
+	Verify(verify::VerifyArgs),
 	/// Fork a live chain and start a local RPC server.
 	#[cfg(feature = "chain")]
 	#[clap(alias = "f")]
 	Fork(fork::ForkArgs),
+	/// Sign and submit transactions prepared for offline signing.
+	#[cfg(feature = "chain")]
+	Tx(tx::TxArgs),
+	/// Decode SCALE-encoded calls, extrinsics, events and storage.
+	#[cfg(feature = "chain")]
+	Decode(decode::DecodeArgs),
+	/// Inspect runtime metadata, e.g. compare two runtimes before an upgrade.
+	#[cfg(feature = "chain")]
+	Metadata(metadata::MetadataArgs),
+	/// Inspect build artifacts, e.g. what is inside a compiled runtime.
+	#[cfg(feature = "chain")]
+	Inspect(inspect::InspectArgs),
+	/// Follow the events and storage of a live chain.
+	#[cfg(feature = "chain")]
+	Watch(watch::WatchArgs),
+	/// Inspect and control a running network, e.g. node status, logs and restarts.
+	#[cfg(feature = "chain")]
+	Network(network::NetworkArgs),
 }
 
 /// Help message for the build command.
@@ -120,6 +154,7 @@ impl Command {
 		match self {
 			Self::Hash(_) |
 			Self::Convert(_) |
+			Self::Account(_) |
 			Self::Test(_) |
 			Self::Clean(_) |
 			Self::Completion(_) |
@@ -127,10 +162,22 @@ impl Command {
 			Self::Build(_) => true,
 			#[cfg(any(feature = "chain", feature = "contract"))]
 			Self::Call(_) => true,
-			#[cfg(feature = "contract")]
+			#[cfg(any(feature = "chain", feature = "contract"))]
 			Self::Verify(_) => true,
 			#[cfg(feature = "chain")]
 			Self::Fork(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Tx(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Decode(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Metadata(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Inspect(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Watch(_) => true,
+			#[cfg(feature = "chain")]
+			Self::Network(_) => true,
 			#[cfg(any(feature = "chain", feature = "contract"))]
 			Self::Install(_) => true,
 			#[cfg(any(feature = "chain", feature = "contract"))]
@@ -255,6 +302,7 @@ impl Command {
 									"--json and --json-file cannot be used together"
 								));
 							},
+						bench::Command::Compare(_) => {},
 						_ => return reject_unsupported_json("bench"),
 					}
 				}
@@ -269,6 +317,14 @@ impl Command {
 				env_logger::init();
 				if output_mode == OutputMode::Json {
 					let output = match args.resolve_command()? {
+						#[cfg(feature = "chain")]
+						call::Command::Chain(cmd) if cmd.script.is_some() => {
+							let output = cmd.execute_script_json().await?;
+							// The results of the steps are output even if one of them failed.
+							let failure = output.failed_step().and_then(|step| step.failure());
+							CliResponse::ok(call::CallJsonOutput::ChainScript(output)).print_json();
+							return failure.map_or(Ok(()), |failure| Err(anyhow::anyhow!(failure)));
+						},
 						#[cfg(feature = "chain")]
 						call::Command::Chain(cmd) => call::CallJsonOutput::Chain(cmd.execute_json().await?),
 						#[cfg(feature = "contract")]
@@ -346,6 +402,12 @@ impl Command {
 										cmd.execute(&mut json_cli, output_mode).await?,
 									test::Command::FastForward(cmd) =>
 										cmd.execute(&mut json_cli, output_mode).await?,
+									test::Command::Network(cmd) => {
+										let output =
+											cmd.execute(&mut json_cli, output_mode).await?;
+										CliResponse::ok(output).print_json();
+										return Ok(());
+									},
 								};
 								CliResponse::ok(runtime_output).print_json();
 								return Ok(());
@@ -395,6 +457,11 @@ impl Command {
 							cmd.execute(&mut Cli, output_mode).await?;
 							Ok(())
 						},
+						#[cfg(feature = "chain")]
+						test::Command::Network(cmd) => {
+							cmd.execute(&mut Cli, output_mode).await?;
+							Ok(())
+						},
 						#[cfg(not(feature = "chain"))]
 						_ => Ok(()),
 					},
@@ -419,7 +486,15 @@ impl Command {
 				convert::execute(&args.command, output_mode)
 			},
 			Command::Completion(args) => completion::execute(args, output_mode),
-			#[cfg(feature = "contract")]
+			Command::Account(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					account::execute(&args.command, &mut crate::cli::JsonCli, output_mode)
+				} else {
+					account::execute(&args.command, &mut crate::cli::Cli, output_mode)
+				}
+			},
+			#[cfg(any(feature = "chain", feature = "contract"))]
 			Self::Verify(verify) =>
 				if output_mode == OutputMode::Json {
 					let mut json_cli = crate::cli::JsonCli;
@@ -440,6 +515,60 @@ impl Command {
 					fork::Command::execute(args, &mut Cli, output_mode).await
 				}
 			},
+			#[cfg(feature = "chain")]
+			Self::Tx(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.command.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.command.execute(&mut Cli, output_mode).await
+				}
+			},
+			#[cfg(feature = "chain")]
+			Self::Decode(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.command.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.command.execute(&mut Cli, output_mode).await
+				}
+			},
+			#[cfg(feature = "chain")]
+			Self::Metadata(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.command.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.command.execute(&mut Cli, output_mode).await
+				}
+			},
+			#[cfg(feature = "chain")]
+			Self::Inspect(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.command.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.command.execute(&mut Cli, output_mode).await
+				}
+			},
+			#[cfg(feature = "chain")]
+			Self::Watch(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.execute(&mut Cli, output_mode).await
+				}
+			},
+			#[cfg(feature = "chain")]
+			Self::Network(args) => {
+				env_logger::init();
+				if output_mode == OutputMode::Json {
+					args.execute(&mut crate::cli::JsonCli, output_mode).await
+				} else {
+					args.execute(&mut Cli, output_mode).await
+				}
+			},
 		}
 	}
 }
@@ -500,10 +629,26 @@ impl Display for Command {
 			Command::Convert(args) => write!(f, "convert {}", args.command),
 			Command::Upgrade(_) => write!(f, "upgrade"),
 			Command::Completion(_) => write!(f, "completion"),
-			#[cfg(feature = "contract")]
-			Command::Verify(_) => write!(f, "verify"),
+			Command::Account(args) => write!(f, "account {}", args.command),
+			#[cfg(any(feature = "chain", feature = "contract"))]
+			Command::Verify(args) => write!(f, "{args}"),
+			#[cfg(feature = "chain")]
+			Self::Fork(args) => match &args.command {
+				Some(cmd) => write!(f, "fork {}", cmd),
+				None => write!(f, "fork"),
+			},
 			#[cfg(feature = "chain")]
-			Self::Fork(_) => write!(f, "fork"),
+			Self::Tx(args) => write!(f, "tx {}", args.command),
+			#[cfg(feature = "chain")]
+			Self::Decode(args) => write!(f, "decode {}", args.command),
+			#[cfg(feature = "chain")]
+			Self::Metadata(args) => write!(f, "metadata {}", args.command),
+			#[cfg(feature = "chain")]
+			Self::Inspect(args) => write!(f, "inspect {}", args.command),
+			#[cfg(feature = "chain")]
+			Self::Watch(_) => write!(f, "watch"),
+			#[cfg(feature = "chain")]
+			Self::Network(args) => write!(f, "network {}", args.command),
 		}
 	}
 }
@@ -552,6 +697,13 @@ mod tests {
 				}),
 				"test fast forward",
 			),
+			(
+				Command::Test(test::TestArgs {
+					command: Some(test::Command::Network(Default::default())),
+					..Default::default()
+				}),
+				"test network",
+			),
 			// Build.
 			(Command::Build(build::BuildArgs { command: None, ..Default::default() }), "build"),
 			(
@@ -561,6 +713,35 @@ mod tests {
 				}),
 				"build spec",
 			),
+			(
+				Command::Build(build::BuildArgs {
+					command: Some(build::Command::Spec(build::spec::BuildSpecCommand {
+						command: Some(build::spec::SpecCommand::Validate(Default::default())),
+						..Default::default()
+					})),
+					..Default::default()
+				}),
+				"build spec validate",
+			),
+			(
+				Command::Build(build::BuildArgs {
+					command: Some(build::Command::Spec(build::spec::BuildSpecCommand {
+						command: Some(build::spec::SpecCommand::Diff(Default::default())),
+						..Default::default()
+					})),
+					..Default::default()
+				}),
+				"build spec diff",
+			),
+			// Fork.
+			(Command::Fork(Default::default()), "fork"),
+			(
+				Command::Fork(fork::ForkArgs {
+					command: Some(fork::Command::Profile(Default::default())),
+					..Default::default()
+				}),
+				"fork profile",
+			),
 			// Up.
 			(Command::Up(up::UpArgs { command: None, ..Default::default() }.into()), "up"),
 			(
@@ -624,18 +805,41 @@ mod tests {
 				}),
 				"bench pallet",
 			),
+			(
+				Command::Bench(bench::BenchmarkArgs {
+					command: bench::Command::Compare(Default::default()),
+				}),
+				"bench compare",
+			),
 			// Verify
 			(
-				Command::Verify(verify::VerifyCommand {
-					path: None,
-					path_pos: None,
-					contract_path: Some(std::path::PathBuf::from("test.contract")),
-					url: None,
-					address: None,
-					image: None,
+				Command::Verify(verify::VerifyArgs {
+					command: None,
+					contract: verify::contract::VerifyCommand {
+						path: None,
+						path_pos: None,
+						contract_path: Some(std::path::PathBuf::from("test.contract")),
+						url: None,
+						address: None,
+						image: None,
+					},
 				}),
 				"verify",
 			),
+			(
+				Command::Verify(verify::VerifyArgs {
+					command: Some(verify::Command::Runtime(Default::default())),
+					contract: verify::contract::VerifyCommand {
+						path: None,
+						path_pos: None,
+						contract_path: None,
+						url: None,
+						address: None,
+						image: None,
+					},
+				}),
+				"verify runtime",
+			),
 		];
 
 		for (command, expected) in test_cases {
@@ -660,6 +864,69 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn tx_command_display_works() {
+		use tx::{Command::*, SubmitArgs, TxArgs};
+		let command = Submit(SubmitArgs {
+			file: std::path::PathBuf::from("tx.json"),
+			url: url::Url::parse(crate::common::urls::LOCAL).unwrap(),
+		});
+		assert_eq!(Command::Tx(TxArgs { command }).to_string(), "tx submit");
+	}
+
+	#[test]
+	fn decode_command_display_works() {
+		use decode::{Command::*, DecodeArgs, MetadataSource};
+		let command = Events {
+			data: "0x00".to_string(),
+			source: MetadataSource { url: None, metadata: None },
+		};
+		assert_eq!(Command::Decode(DecodeArgs { command }).to_string(), "decode events");
+	}
+
+	#[test]
+	fn metadata_command_display_works() {
+		use metadata::{Command::*, DiffArgs, MetadataArgs, MetadataSource};
+		let command = Diff(DiffArgs {
+			old: MetadataSource::File("old.scale".into()),
+			new: MetadataSource::Wasm("new.wasm".into()),
+			breaking_only: false,
+			fail_on_breaking: false,
+		});
+		assert_eq!(Command::Metadata(MetadataArgs { command }).to_string(), "metadata diff");
+	}
+
+	#[test]
+	fn inspect_command_display_works() {
+		use inspect::{Command::*, InspectArgs, RuntimeArgs, RuntimeSource};
+		let command = Runtime(RuntimeArgs { runtime: RuntimeSource::Wasm("runtime.wasm".into()) });
+		let command = Command::Inspect(InspectArgs { command });
+		assert_eq!(command.to_string(), "inspect runtime");
+		assert!(command.supports_json());
+	}
+
+	#[test]
+	fn watch_command_display_works() {
+		let command = Command::Watch(Default::default());
+		assert_eq!(command.to_string(), "watch");
+		assert!(command.supports_json());
+	}
+
+	#[test]
+	fn network_command_display_works() {
+		use network::{Command::*, NetworkArgs};
+		let command = Command::Network(NetworkArgs { path: None, command: Status });
+		assert_eq!(command.to_string(), "network status");
+		assert!(command.supports_json());
+	}
+
+	#[test]
+	fn account_command_display_works() {
+		use account::{AccountArgs, Command::*};
+		assert_eq!(Command::Account(AccountArgs { command: List }).to_string(), "account list");
+		assert!(Command::Account(AccountArgs { command: List }).supports_json());
+	}
+
 	#[test]
 	fn fork_and_verify_support_json() {
 		assert!(Command::Build(Default::default()).supports_json());
@@ -671,13 +938,16 @@ mod tests {
 			.supports_json()
 		);
 		assert!(
-			Command::Verify(verify::VerifyCommand {
-				path: None,
-				path_pos: None,
-				contract_path: Some(std::path::PathBuf::from("test.contract")),
-				url: None,
-				address: None,
-				image: None,
+			Command::Verify(verify::VerifyArgs {
+				command: None,
+				contract: verify::contract::VerifyCommand {
+					path: None,
+					path_pos: None,
+					contract_path: Some(std::path::PathBuf::from("test.contract")),
+					url: None,
+					address: None,
+					image: None,
+				},
 			})
 			.supports_json()
 		);
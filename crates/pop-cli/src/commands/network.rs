@@ -0,0 +1,426 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	commands::clean::{find_zombie_jsons, resolve_zombie_json_path},
+	output::{CliResponse, OutputMode},
+};
+use anyhow::{Result, anyhow};
+use clap::{Args, Subcommand};
+use pop_chains::up::{LiveNetwork, NodeStatus};
+use serde::Serialize;
+use std::{
+	fmt::{Display, Formatter},
+	fs::File,
+	io::{Read, Seek, SeekFrom},
+	path::{Path, PathBuf},
+	time::Duration,
+};
+
+/// How often the log file is checked for new lines when following it.
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Arguments for inspecting and controlling a running network.
+#[derive(Args, Serialize)]
+pub(crate) struct NetworkArgs {
+	/// Path to the network base directory or zombie.json. Defaults to the most recently launched
+	/// network.
+	#[arg(long, global = true, value_name = "PATH")]
+	pub(crate) path: Option<PathBuf>,
+	/// Network subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Inspect and control a network launched with `pop up network --detach`.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// List the nodes of the network with their block heights and peer counts.
+	#[clap(alias = "s")]
+	Status,
+	/// Show the log of a node.
+	#[clap(alias = "l")]
+	Logs(LogsArgs),
+	/// Restart a node.
+	Restart(NodeArgs),
+	/// Pause a node, suspending its process until it is resumed.
+	Pause(NodeArgs),
+	/// Resume a paused node.
+	Resume(NodeArgs),
+}
+
+/// Arguments for showing the log of a node.
+#[derive(Args, Serialize)]
+pub(crate) struct LogsArgs {
+	/// The name of the node, e.g. `alice`.
+	pub(crate) node: String,
+	/// Keep printing new lines as they are written to the log.
+	#[arg(short, long)]
+	pub(crate) follow: bool,
+	/// The number of lines to show from the end of the log.
+	#[arg(short = 'n', long, default_value_t = 100)]
+	pub(crate) lines: usize,
+}
+
+/// Arguments for controlling a node.
+#[derive(Args, Serialize)]
+pub(crate) struct NodeArgs {
+	/// The name of the node, e.g. `alice`.
+	pub(crate) node: String,
+}
+
+/// Structured output for `network status` in JSON mode.
+#[derive(Serialize)]
+struct StatusOutput {
+	base_dir: PathBuf,
+	nodes: Vec<NodeStatus>,
+}
+
+/// Structured output for `network logs` in JSON mode.
+#[derive(Serialize)]
+struct LogsOutput {
+	node: String,
+	path: PathBuf,
+	lines: Vec<String>,
+}
+
+/// Structured output for `network restart|pause|resume` in JSON mode.
+#[derive(Serialize)]
+struct NodeOutput {
+	node: String,
+	action: String,
+}
+
+impl NetworkArgs {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro(match self.command {
+			Command::Status => "Network status",
+			Command::Logs(_) => "Network node logs",
+			Command::Restart(_) | Command::Pause(_) | Command::Resume(_) => "Control network node",
+		})?;
+		let zombie_json = zombie_json(self.path.as_deref())?;
+		let spinner = cli.spinner();
+		spinner.start(format!("Attaching to the network at {}...", zombie_json.display()));
+		let network = LiveNetwork::attach(&zombie_json).await;
+		spinner.clear();
+		let network = network?;
+
+		match &self.command {
+			Command::Status => status(&network, cli, output_mode).await,
+			Command::Logs(args) => args.execute(&network, cli, output_mode).await,
+			Command::Restart(args) | Command::Pause(args) | Command::Resume(args) => {
+				let spinner = cli.spinner();
+				spinner.start(format!("{} {}...", self.command.progress(), args.node));
+				let result = match self.command {
+					Command::Restart(_) => network.restart(&args.node).await,
+					Command::Pause(_) => network.pause(&args.node).await,
+					_ => network.resume(&args.node).await,
+				};
+				spinner.clear();
+				result?;
+				if output_mode == OutputMode::Json {
+					CliResponse::ok(NodeOutput {
+						node: args.node.clone(),
+						action: self.command.to_string(),
+					})
+					.print_json();
+				} else {
+					cli.outro(format!("{} {}.", self.command.done(), args.node))?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+/// The `zombie.json` of a running network, at the given path or of the most recently launched
+/// network.
+///
+/// # Arguments
+/// * `path` - Path to the network base directory or zombie.json, if any.
+pub(crate) fn zombie_json(path: Option<&Path>) -> Result<PathBuf> {
+	match path {
+		Some(path) => resolve_zombie_json_path(path),
+		None => find_zombie_jsons()?
+			.into_iter()
+			.next()
+			.map(|candidate| candidate.path)
+			.ok_or_else(|| {
+				anyhow!("No running networks found. Launch one with `pop up network --detach`.")
+			}),
+	}
+}
+
+impl Command {
+	fn progress(&self) -> &'static str {
+		match self {
+			Command::Restart(_) => "Restarting",
+			Command::Pause(_) => "Pausing",
+			_ => "Resuming",
+		}
+	}
+
+	fn done(&self) -> &'static str {
+		match self {
+			Command::Restart(_) => "Restarted",
+			Command::Pause(_) => "Paused",
+			_ => "Resumed",
+		}
+	}
+}
+
+async fn status(network: &LiveNetwork, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+	let spinner = cli.spinner();
+	spinner.start("Querying the nodes...");
+	let nodes = network.status().await;
+	spinner.clear();
+	if output_mode == OutputMode::Json {
+		CliResponse::ok(StatusOutput { base_dir: network.base_dir().to_path_buf(), nodes })
+			.print_json();
+		return Ok(());
+	}
+	cli.plain(render_status(&nodes))?;
+	let unreachable = nodes.iter().filter(|node| !node.reachable).count();
+	if unreachable > 0 {
+		cli.warning(format!("{unreachable} node(s) did not respond."))?;
+	}
+	cli.outro(format!(
+		"{} node(s) running at {}.",
+		nodes.len() - unreachable,
+		network.base_dir().display()
+	))?;
+	Ok(())
+}
+
+// Renders the status of the nodes as a table.
+fn render_status(nodes: &[NodeStatus]) -> String {
+	let optional = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+	let rows: Vec<[String; 9]> = nodes
+		.iter()
+		.map(|status| {
+			let node = &status.node;
+			[
+				node.name.clone(),
+				node.role.to_string(),
+				node.chain.clone(),
+				optional(node.para_id.map(|id| id.to_string())),
+				optional(node.rpc_port.map(|port| port.to_string())),
+				optional(node.p2p_port.map(|port| port.to_string())),
+				optional(status.best.map(|number| format!("#{number}"))),
+				optional(status.finalized.map(|number| format!("#{number}"))),
+				optional(status.peers.map(|peers| peers.to_string())),
+			]
+		})
+		.collect();
+	let header =
+		["NODE", "ROLE", "CHAIN", "PARA ID", "RPC PORT", "P2P PORT", "BEST", "FINALIZED", "PEERS"]
+			.map(String::from);
+	let widths: Vec<_> = (0..header.len())
+		.map(|column| {
+			rows.iter()
+				.chain([&header])
+				.map(|row| row[column].len())
+				.max()
+				.unwrap_or_default()
+		})
+		.collect();
+	std::iter::once(&header)
+		.chain(&rows)
+		.map(|row| {
+			row.iter()
+				.zip(&widths)
+				.map(|(cell, width)| format!("{cell:<width$}"))
+				.collect::<Vec<_>>()
+				.join("  ")
+				.trim_end()
+				.to_string()
+		})
+		.collect::<Vec<_>>()
+		.join("\n")
+}
+
+impl LogsArgs {
+	async fn execute(
+		&self,
+		network: &LiveNetwork,
+		cli: &mut impl Cli,
+		output_mode: OutputMode,
+	) -> Result<()> {
+		let path = network.log_path(&self.node)?;
+		let (contents, mut offset) = read_from(&path, 0)?;
+		let lines = tail(&contents, self.lines);
+		if output_mode == OutputMode::Json && !self.follow {
+			CliResponse::ok(LogsOutput { node: self.node.clone(), path, lines }).print_json();
+			return Ok(());
+		}
+		if output_mode == OutputMode::Human {
+			cli.info(format!("Log of {}: {}", self.node, path.display()))?;
+		}
+		self.print(&lines, cli, output_mode)?;
+		if !self.follow {
+			cli.outro(format!("Showing the last {} line(s) of the log.", lines.len()))?;
+			return Ok(());
+		}
+
+		// Print new lines as they are written, until interrupted.
+		let mut partial = String::new();
+		loop {
+			tokio::time::sleep(FOLLOW_INTERVAL).await;
+			let (contents, next) = read_from(&path, offset)?;
+			if next < offset {
+				// The log was truncated, e.g. by a restart of the node.
+				partial.clear();
+			}
+			offset = next;
+			partial.push_str(&contents);
+			if let Some(end) = partial.rfind('\n') {
+				let complete: Vec<_> = partial[..end].lines().map(String::from).collect();
+				partial.drain(..=end);
+				self.print(&complete, cli, output_mode)?;
+			}
+		}
+	}
+
+	fn print(&self, lines: &[String], cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		for line in lines {
+			if output_mode == OutputMode::Json {
+				CliResponse::ok(serde_json::json!({ "node": self.node, "line": line }))
+					.print_json();
+			} else {
+				cli.plain(line)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+// Reads a file from an offset, returning its contents and the offset of its end. The file is read
+// from the start if it is shorter than the offset.
+fn read_from(path: &Path, offset: u64) -> Result<(String, u64)> {
+	let mut file = File::open(path)?;
+	let length = file.metadata()?.len();
+	let offset = if length < offset { 0 } else { offset };
+	file.seek(SeekFrom::Start(offset))?;
+	let mut contents = String::new();
+	file.take(length - offset).read_to_string(&mut contents)?;
+	Ok((contents, length))
+}
+
+// The last lines of the contents.
+fn tail(contents: &str, lines: usize) -> Vec<String> {
+	let all: Vec<_> = contents.lines().collect();
+	all[all.len().saturating_sub(lines)..]
+		.iter()
+		.map(|line| line.to_string())
+		.collect()
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Status => write!(f, "status"),
+			Command::Logs(_) => write!(f, "logs"),
+			Command::Restart(_) => write!(f, "restart"),
+			Command::Pause(_) => write!(f, "pause"),
+			Command::Resume(_) => write!(f, "resume"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use pop_chains::up::{NodeInfo, NodeRole};
+	use std::io::Write;
+
+	fn node(name: &str, para_id: Option<u32>, best: Option<u64>) -> NodeStatus {
+		NodeStatus {
+			node: NodeInfo {
+				name: name.into(),
+				role: if para_id.is_some() { NodeRole::Collator } else { NodeRole::Validator },
+				chain: if para_id.is_some() { "asset-hub".into() } else { "paseo-local".into() },
+				para_id,
+				ws_uri: "ws://127.0.0.1:9944".into(),
+				rpc_port: Some(9944),
+				p2p_port: Some(30333),
+			},
+			reachable: best.is_some(),
+			best,
+			finalized: best.map(|number| number - 1),
+			peers: best.map(|_| 3),
+		}
+	}
+
+	#[test]
+	fn render_status_works() {
+		let output =
+			render_status(&[node("alice", None, Some(10)), node("collator-01", Some(1000), None)]);
+		let lines: Vec<_> = output.lines().collect();
+		assert_eq!(lines.len(), 3);
+		assert!(lines[0].starts_with("NODE         ROLE"));
+		assert!(lines[1].starts_with("alice        validator  paseo-local  -"));
+		assert!(lines[1].ends_with("#10   #9         3"));
+		assert!(lines[2].starts_with("collator-01  collator   asset-hub    1000"));
+		assert!(lines[2].ends_with("-     -          -"));
+	}
+
+	#[test]
+	fn tail_works() {
+		let contents = "one\ntwo\nthree\n";
+		assert_eq!(tail(contents, 2), vec!["two", "three"]);
+		assert_eq!(tail(contents, 5), vec!["one", "two", "three"]);
+		assert!(tail(contents, 0).is_empty());
+	}
+
+	#[test]
+	fn read_from_works() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let path = temp.path().join("alice.log");
+		std::fs::write(&path, "one\n")?;
+		let (contents, offset) = read_from(&path, 0)?;
+		assert_eq!((contents.as_str(), offset), ("one\n", 4));
+		File::options().append(true).open(&path)?.write_all(b"two\n")?;
+		let (contents, offset) = read_from(&path, offset)?;
+		assert_eq!((contents.as_str(), offset), ("two\n", 8));
+		// A truncated file is read from the start.
+		std::fs::write(&path, "three\n")?;
+		assert_eq!(read_from(&path, offset)?.0, "three\n");
+		Ok(())
+	}
+
+	#[test]
+	fn zombie_json_resolves_path() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let path = temp.path().join("zombie.json");
+		std::fs::write(&path, "{}")?;
+		assert_eq!(zombie_json(Some(temp.path()))?, path);
+		assert_eq!(zombie_json(Some(&path))?, path);
+		assert!(zombie_json(Some(&temp.path().join("missing"))).is_err());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn execute_fails_for_invalid_network() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		std::fs::write(temp.path().join("zombie.json"), "{}")?;
+		let args = NetworkArgs { path: Some(temp.path().to_path_buf()), command: Command::Status };
+		let mut cli = MockCli::new().expect_intro("Network status");
+		assert!(args.execute(&mut cli, OutputMode::Human).await.is_err());
+		cli.verify()
+	}
+
+	#[test]
+	fn command_display_works() {
+		let node = || NodeArgs { node: "alice".into() };
+		assert_eq!(Command::Status.to_string(), "status");
+		assert_eq!(
+			Command::Logs(LogsArgs { node: "alice".into(), follow: true, lines: 10 }).to_string(),
+			"logs"
+		);
+		assert_eq!(Command::Restart(node()).to_string(), "restart");
+		assert_eq!(Command::Pause(node()).to_string(), "pause");
+		assert_eq!(Command::Resume(node()).to_string(), "resume");
+	}
+}
@@ -25,6 +25,8 @@ pub mod execute_block;
 #[cfg(feature = "chain")]
 pub mod fast_forward;
 #[cfg(feature = "chain")]
+pub mod network;
+#[cfg(feature = "chain")]
 pub mod on_runtime_upgrade;
 
 /// Arguments for testing.
@@ -65,6 +67,9 @@ pub(crate) enum Command {
 	/// Create a chain state snapshot.
 	#[cfg(feature = "chain")]
 	CreateSnapshot(create_snapshot::TestCreateSnapshotCommand),
+	/// Launch a network and run declarative assertions against its nodes.
+	#[cfg(feature = "chain")]
+	Network(network::TestNetworkCommand),
 }
 
 #[derive(Debug, Serialize)]
@@ -197,6 +202,7 @@ impl Display for Command {
 			Command::ExecuteBlock(_) => write!(f, "execute block"),
 			Command::FastForward(_) => write!(f, "fast forward"),
 			Command::CreateSnapshot(_) => write!(f, "create snapshot"),
+			Command::Network(_) => write!(f, "network"),
 		}
 	}
 }
@@ -256,5 +262,7 @@ mod tests {
 		assert_eq!(Command::FastForward(Default::default()).to_string(), "fast forward");
 		#[cfg(feature = "chain")]
 		assert_eq!(Command::CreateSnapshot(Default::default()).to_string(), "create snapshot");
+		#[cfg(feature = "chain")]
+		assert_eq!(Command::Network(Default::default()).to_string(), "network");
 	}
 }
@@ -0,0 +1,219 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli,
+	commands::up::network::source_binaries,
+	output::{OutputMode, build_error_with_details, deploy_error, invalid_input_error},
+};
+use clap::Args;
+use pop_chains::{
+	Error,
+	up::{NetworkConfiguration, TestReport, TestSuite, Zombienet},
+};
+use serde::Serialize;
+use std::path::PathBuf;
+
+const DEFAULT_JUNIT_PATH: &str = "junit.xml";
+
+/// Launch a network and run declarative assertions against its nodes.
+#[derive(Args, Default, Serialize)]
+pub(crate) struct TestNetworkCommand {
+	/// The network configuration file, e.g. `network.toml`.
+	#[arg(index = 1, value_name = "CONFIG")]
+	pub(crate) config: PathBuf,
+	/// The YAML file defining the assertions to run against the network.
+	#[arg(index = 2, value_name = "TESTS")]
+	pub(crate) tests: PathBuf,
+	/// The path to write the JUnit report to.
+	#[arg(long, value_name = "PATH", default_value = DEFAULT_JUNIT_PATH)]
+	pub(crate) junit: PathBuf,
+	/// Automatically source all necessary binaries required without prompting for confirmation.
+	#[arg(short = 'y', long)]
+	pub(crate) skip_confirm: bool,
+	/// Whether the output should be verbose.
+	#[arg(short, long)]
+	pub(crate) verbose: bool,
+}
+
+/// JSON output for a network test.
+#[derive(Debug, Serialize)]
+pub(crate) struct TestNetworkOutput {
+	pub(crate) junit: String,
+	pub(crate) passed: usize,
+	pub(crate) failed: usize,
+	pub(crate) assertions: Vec<AssertionOutput>,
+}
+
+/// JSON output for an assertion of a network test.
+#[derive(Debug, Serialize)]
+pub(crate) struct AssertionOutput {
+	pub(crate) name: String,
+	pub(crate) node: String,
+	pub(crate) duration_ms: u128,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub(crate) failure: Option<String>,
+}
+
+impl TestNetworkCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(
+		&self,
+		cli: &mut impl cli::traits::Cli,
+		output_mode: OutputMode,
+	) -> anyhow::Result<TestNetworkOutput> {
+		cli.intro("Test a local network")?;
+		let suite = TestSuite::from_path(&self.tests).map_err(|e| {
+			invalid_input_error(format!("Failed to load {}: {e}", self.tests.display()))
+		})?;
+		let config: NetworkConfiguration = self
+			.config
+			.as_path()
+			.try_into()
+			.map_err(|e: Error| invalid_input_error(e.to_string()))?;
+
+		let cache = crate::cache()?;
+		let mut zombienet = Zombienet::new(&cache, config, None, None, None, None, None)
+			.await
+			.map_err(|e| deploy_error(e.to_string()))?;
+		let skip_confirm = self.skip_confirm || output_mode == OutputMode::Json;
+		if source_binaries(&mut zombienet, &cache, self.verbose, skip_confirm, cli).await? {
+			return Err(deploy_error(
+				"Cannot launch the network until all required binaries are available.",
+			));
+		}
+
+		let spinner = cli.spinner();
+		spinner.start("🚀 Launching local network...");
+		let network = zombienet.spawn().await;
+		spinner.clear();
+		let network =
+			network.map_err(|e| deploy_error(format!("Could not launch local network: {e}")))?;
+		// The network is torn down before any error is returned, so no nodes are left running.
+		let results = async {
+			cli.success("Network launched successfully.")?;
+			let mut results = Vec::with_capacity(suite.assertions.len());
+			for case in &suite.assertions {
+				let spinner = cli.spinner();
+				spinner.start(format!("Waiting for {}...", case.name()));
+				let result = case.run(&network, suite.timeout).await;
+				spinner.clear();
+				match &result.failure {
+					None => cli.success(format!(
+						"{} ({:.1}s)",
+						result.name,
+						result.duration.as_secs_f64()
+					))?,
+					Some(failure) => cli.warning(format!("❌ {}: {failure}", result.name))?,
+				}
+				results.push(result);
+			}
+			anyhow::Ok(results)
+		}
+		.await;
+
+		let spinner = cli.spinner();
+		spinner.start("Tearing down the network...");
+		let destroyed = network.destroy().await;
+		spinner.clear();
+		if let Err(e) = destroyed {
+			cli.warning(format!("Failed to tear down the network: {e}"))?;
+		}
+		let results = results?;
+
+		let report = TestReport { name: suite.name().to_string(), results };
+		std::fs::write(&self.junit, report.junit())?;
+		let failed = report.failures();
+		let total = report.results.len();
+		if failed > 0 {
+			let details = report
+				.results
+				.iter()
+				.filter_map(|result| {
+					result.failure.as_ref().map(|failure| format!("{}: {failure}", result.name))
+				})
+				.collect::<Vec<_>>()
+				.join("\n");
+			cli.outro_cancel(format!(
+				"{failed} of {total} assertions failed, see {}",
+				self.junit.display()
+			))?;
+			return Err(build_error_with_details(
+				format!("{failed} of {total} assertions failed"),
+				details,
+			));
+		}
+		cli.outro(format!("All {total} assertions passed, see {}", self.junit.display()))?;
+		Ok(output(&report, &self.junit))
+	}
+}
+
+fn output(report: &TestReport, junit: &std::path::Path) -> TestNetworkOutput {
+	let failed = report.failures();
+	TestNetworkOutput {
+		junit: junit.display().to_string(),
+		passed: report.results.len() - failed,
+		failed,
+		assertions: report
+			.results
+			.iter()
+			.map(|result| AssertionOutput {
+				name: result.name.clone(),
+				node: result.node.clone(),
+				duration_ms: result.duration.as_millis(),
+				failure: result.failure.clone(),
+			})
+			.collect(),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::InvalidInputError};
+	use pop_chains::up::TestResult;
+	use std::time::Duration;
+
+	#[tokio::test]
+	async fn execute_rejects_invalid_tests() -> anyhow::Result<()> {
+		let temp = tempfile::tempdir()?;
+		let tests = temp.path().join("tests.yaml");
+		std::fs::write(&tests, "assertions: []")?;
+		let command = TestNetworkCommand {
+			config: temp.path().join("network.toml"),
+			tests,
+			junit: temp.path().join(DEFAULT_JUNIT_PATH),
+			..Default::default()
+		};
+		let mut cli = MockCli::new().expect_intro("Test a local network");
+		let err = command.execute(&mut cli, OutputMode::Human).await.unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		assert!(err.to_string().contains("no assertions defined"));
+		cli.verify()
+	}
+
+	#[test]
+	fn output_works() {
+		let report = TestReport {
+			name: "network".into(),
+			results: vec![
+				TestResult {
+					name: "alice: best block height >= 5".into(),
+					node: "alice".into(),
+					duration: Duration::from_millis(1500),
+					failure: None,
+				},
+				TestResult {
+					name: "bob: 1 XCM messages delivered".into(),
+					node: "bob".into(),
+					duration: Duration::from_secs(2),
+					failure: Some("timed out".into()),
+				},
+			],
+		};
+		let output = output(&report, "junit.xml".as_ref());
+		assert_eq!(output.junit, "junit.xml");
+		assert_eq!((output.passed, output.failed), (1, 1));
+		assert_eq!(output.assertions[0].duration_ms, 1500);
+		assert_eq!(output.assertions[1].failure.as_deref(), Some("timed out"));
+	}
+}
@@ -0,0 +1,296 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	common::{accounts, urls},
+	output::{CliResponse, OutputMode},
+};
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use pop_chains::{
+	OfflineTransaction, parse_and_format_events, raw_value_to_string, set_up_client,
+	submit_signed_extrinsic,
+};
+use pop_common::{AccountSigner, KeyScheme};
+use serde::Serialize;
+use sp_core::bytes::to_hex;
+use std::{
+	fmt::{Display, Formatter},
+	path::PathBuf,
+};
+use url::Url;
+
+/// Arguments for signing and submitting transactions prepared for offline signing.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true)]
+pub(crate) struct TxArgs {
+	/// Offline transaction subcommand.
+	#[command(subcommand)]
+	pub(crate) command: Command,
+}
+
+/// Sign and submit transactions prepared with `pop call chain --sign-offline`.
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Sign a transaction prepared for offline signing. Does not connect to the chain, so it can
+	/// be used on an air-gapped machine.
+	#[clap(alias = "s")]
+	Sign(SignArgs),
+	/// Submit a transaction signed with `pop tx sign`.
+	Submit(SubmitArgs),
+}
+
+/// Arguments for signing a transaction offline.
+#[derive(Args, Serialize)]
+pub(crate) struct SignArgs {
+	/// File of the transaction to sign, written by `pop call chain --sign-offline`.
+	pub(crate) file: PathBuf,
+	/// Secret key URI of the signer. Prompted for if neither `--suri` nor `--account` is
+	/// provided.
+	#[serde(skip_serializing)]
+	#[arg(short, long, conflicts_with = "account")]
+	pub(crate) suri: Option<String>,
+	/// Key scheme of the secret key URI.
+	#[arg(long, value_enum, default_value_t, conflicts_with = "account")]
+	pub(crate) scheme: KeyScheme,
+	/// Name of a keystore account to sign with. See `pop account`.
+	#[arg(long)]
+	pub(crate) account: Option<String>,
+	/// Write the signed transaction to this file instead of updating the transaction file.
+	#[arg(short, long)]
+	pub(crate) output: Option<PathBuf>,
+	/// Sign without prompting for confirmation.
+	#[arg(short = 'y', long)]
+	pub(crate) skip_confirm: bool,
+}
+
+/// Arguments for submitting a transaction signed offline.
+#[derive(Args, Serialize)]
+pub(crate) struct SubmitArgs {
+	/// File of the signed transaction.
+	pub(crate) file: PathBuf,
+	/// Websocket endpoint of a node of the chain the transaction was prepared for.
+	#[arg(short, long, default_value = urls::LOCAL)]
+	pub(crate) url: Url,
+}
+
+/// Structured output for `tx sign` in JSON mode.
+#[derive(Serialize)]
+struct SignOutput {
+	file: String,
+	signer: String,
+	signature: Option<String>,
+}
+
+/// Structured output for `tx submit` in JSON mode.
+#[derive(Serialize)]
+struct SubmitOutput {
+	tx_hash: String,
+	events: Vec<String>,
+}
+
+impl Command {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		match self {
+			Command::Sign(args) => args.execute(cli, output_mode),
+			Command::Submit(args) => args.execute(cli, output_mode).await,
+		}
+	}
+}
+
+impl SignArgs {
+	fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		let mut transaction = OfflineTransaction::load(&self.file)?;
+		cli.intro("Sign a transaction offline")?;
+		cli.info(describe(&transaction)?)?;
+		if output_mode == OutputMode::Human &&
+			!self.skip_confirm &&
+			!cli.confirm("Do you want to sign the transaction?")
+				.initial_value(true)
+				.interact()?
+		{
+			cli.outro_cancel("The transaction was not signed.")?;
+			return Ok(());
+		}
+
+		let (suri, scheme) = match (&self.account, &self.suri) {
+			(Some(name), _) => accounts::unlock(&accounts::keystore()?, name, cli)?,
+			(None, Some(suri)) => (suri.clone(), self.scheme),
+			(None, None) =>
+				(cli.password("Secret key URI of the signer:").interact()?, self.scheme),
+		};
+		transaction.sign(&AccountSigner::from_uri(&suri, scheme)?)?;
+		let output = self.output.as_ref().unwrap_or(&self.file);
+		transaction.save(output)?;
+
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(SignOutput {
+				file: output.display().to_string(),
+				signer: transaction.signer,
+				signature: transaction.signature,
+			})
+			.print_json();
+			return Ok(());
+		}
+		cli.outro(format!(
+			"Signed transaction written to {0}. Submit it with `pop tx submit {0}`.",
+			output.display()
+		))?;
+		Ok(())
+	}
+}
+
+impl SubmitArgs {
+	async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		let transaction = OfflineTransaction::load(&self.file)?;
+		cli.intro("Submit a signed transaction")?;
+		let client = set_up_client(self.url.as_str()).await?;
+		let extrinsic = transaction.signed_extrinsic(&client)?;
+
+		let spinner = cli.spinner();
+		spinner
+			.start("Submitting the extrinsic and waiting for finalization, please be patient...");
+		let result = submit_signed_extrinsic(client.clone(), to_hex(&extrinsic, false)).await;
+		spinner.clear();
+		let result = result?;
+		let events = parse_and_format_events(&client, &self.url, &result).await?;
+		let tx_hash = format!("{:?}", result.extrinsic_hash());
+
+		if output_mode == OutputMode::Json {
+			let events = events
+				.lines()
+				.map(str::trim)
+				.filter(|line| !line.is_empty())
+				.map(ToOwned::to_owned)
+				.collect();
+			CliResponse::ok(SubmitOutput { tx_hash, events }).print_json();
+			return Ok(());
+		}
+		cli.info(events)?;
+		cli.outro(format!("Extrinsic submitted with hash: {tx_hash}"))?;
+		Ok(())
+	}
+}
+
+/// Summarize what is being signed, so it can be checked before signing. The call is decoded with
+/// the metadata bundled with the transaction.
+fn describe(transaction: &OfflineTransaction) -> Result<String> {
+	let mut description = format!(
+		"Call:\n{}\nCall data: {}\nSigner: {}\nNonce: {}\nGenesis hash: {}\nSpec version: {}",
+		raw_value_to_string(&transaction.decoded_call()?, "  ")?,
+		transaction.call_data,
+		transaction.signer,
+		transaction.nonce,
+		transaction.genesis_hash,
+		transaction.spec_version
+	);
+	if let Some(hash) = &transaction.metadata_hash {
+		description.push_str(&format!("\nMetadata hash: {hash}"));
+	}
+	Ok(description)
+}
+
+impl Display for Command {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Command::Sign(_) => write!(f, "sign"),
+			Command::Submit(_) => write!(f, "submit"),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+	use pop_chains::TxOptions;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use subxt::dynamic::{self, Value};
+
+	// Prepares `System::remark(0x11)` for Alice on the test chain.
+	async fn transaction() -> Result<OfflineTransaction> {
+		let url: Url = shared_substrate_ws_url().await.parse()?;
+		let client = set_up_client(url.as_str()).await?;
+		let xt = dynamic::tx("System", "remark", vec![Value::from_bytes([0x11])]);
+		let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".parse()?;
+		let options = TxOptions { nonce: Some(0), ..Default::default() };
+		Ok(OfflineTransaction::new(&client, &url, &xt, alice, &options).await?)
+	}
+
+	#[tokio::test]
+	async fn sign_adds_signature_to_file() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let file = dir.path().join("tx.json");
+		let transaction = transaction().await?;
+		transaction.save(&file)?;
+
+		let args = SignArgs {
+			file: file.clone(),
+			suri: None,
+			scheme: KeyScheme::Sr25519,
+			account: None,
+			output: None,
+			skip_confirm: false,
+		};
+		let mut cli = MockCli::new()
+			.expect_intro("Sign a transaction offline")
+			.expect_info(describe(&transaction)?)
+			.expect_confirm("Do you want to sign the transaction?", true)
+			.expect_password("Secret key URI of the signer:", "//Alice".into())
+			.expect_outro(format!(
+				"Signed transaction written to {0}. Submit it with `pop tx submit {0}`.",
+				file.display()
+			));
+		args.execute(&mut cli, OutputMode::Human)?;
+		assert!(OfflineTransaction::load(&file)?.signature.is_some());
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn sign_can_be_cancelled() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let file = dir.path().join("tx.json");
+		transaction().await?.save(&file)?;
+
+		let args = SignArgs {
+			file: file.clone(),
+			suri: Some("//Alice".into()),
+			scheme: KeyScheme::Sr25519,
+			account: None,
+			output: None,
+			skip_confirm: false,
+		};
+		let mut cli = MockCli::new()
+			.expect_confirm("Do you want to sign the transaction?", false)
+			.expect_outro_cancel("The transaction was not signed.");
+		args.execute(&mut cli, OutputMode::Human)?;
+		assert!(OfflineTransaction::load(&file)?.signature.is_none());
+		cli.verify()
+	}
+
+	#[tokio::test]
+	async fn sign_writes_to_output_file() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let file = dir.path().join("tx.json");
+		let output = dir.path().join("signed.json");
+		transaction().await?.save(&file)?;
+
+		let args = SignArgs {
+			file: file.clone(),
+			suri: Some("//Bob".into()),
+			scheme: KeyScheme::Sr25519,
+			account: None,
+			output: Some(output.clone()),
+			skip_confirm: true,
+		};
+		// Bob is not the signer the transaction was prepared for.
+		assert!(args.execute(&mut MockCli::new(), OutputMode::Human).is_err());
+
+		let args = SignArgs { suri: Some("//Alice".into()), ..args };
+		args.execute(&mut MockCli::new(), OutputMode::Human)?;
+		assert!(OfflineTransaction::load(&file)?.signature.is_none());
+		assert!(OfflineTransaction::load(&output)?.signature.is_some());
+		Ok(())
+	}
+}
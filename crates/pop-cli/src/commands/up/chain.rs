@@ -5,6 +5,7 @@ use crate::{
 	call::chain::Call,
 	cli::traits::*,
 	common::{
+		accounts,
 		chain::{Chain, configure},
 		urls,
 		wallet::submit_extrinsic,
@@ -13,9 +14,14 @@ use crate::{
 };
 use anyhow::Result;
 use clap::Args;
-use pop_chains::{Action, Payload, Reserved, construct_proxy_extrinsic, find_callable_by_name};
-use pop_common::Profile;
+use pop_chains::{
+	Action, CallData, ExtrinsicEvents, Payload, Reserved, SubstrateConfig, TxOptions,
+	construct_proxy_extrinsic, find_callable_by_name, parse_and_format_events,
+	sign_extrinsic_with_suri, submit_signed_extrinsic,
+};
+use pop_common::{KeyScheme, Profile};
 use serde::Serialize;
+use sp_core::bytes::to_hex;
 use std::{
 	env,
 	path::{Path, PathBuf},
@@ -23,6 +29,8 @@ use std::{
 use url::Url;
 
 type Proxy = Option<String>;
+/// The secret key URI and key scheme of the keystore account signing the transactions, if any.
+type Account = Option<(String, KeyScheme)>;
 
 const HELP_HEADER: &str = "Chain registration options";
 
@@ -55,6 +63,13 @@ pub struct UpCommand {
 	/// account.
 	#[arg(long = "proxy")]
 	pub(crate) proxied_address: Option<String>,
+	/// Name of a keystore account to sign the transactions with, instead of a browser extension
+	/// wallet. The password is prompted for. See `pop account`.
+	// The flag is declared by the contract deployment arguments when both are flattened into
+	// `pop up`, and passed on from there.
+	#[cfg_attr(feature = "contract", arg(skip))]
+	#[cfg_attr(not(feature = "contract"), arg(long))]
+	pub(crate) account: Option<String>,
 	/// Build profile [default: release].
 	#[clap(long, value_enum)]
 	pub(crate) profile: Option<Profile>,
@@ -91,6 +106,7 @@ impl UpCommand {
 
 	// Prepares the chain for registration by setting up its configuration.
 	async fn prepare_for_registration(&self, cli: &mut impl Cli) -> Result<Registration> {
+		let account = self.unlock_account(cli)?;
 		let chain = configure(
 			"Select a chain (type to filter)",
 			"Enter the relay chain node URL",
@@ -101,9 +117,17 @@ impl UpCommand {
 		)
 		.await?;
 		let proxy = self.resolve_proxied_address();
-		let id = self.resolve_id(&chain, &proxy, cli).await?;
+		let id = self.resolve_id(&chain, &proxy, &account, cli).await?;
 		let genesis_artifacts = self.resolve_genesis_files(id, cli).await?;
-		Ok(Registration { id, genesis_artifacts, chain, proxy })
+		Ok(Registration { id, genesis_artifacts, chain, proxy, account })
+	}
+
+	// Unlocks the keystore account provided with `--account`, if any.
+	fn unlock_account(&self, cli: &mut impl Cli) -> Result<Account> {
+		self.account
+			.as_ref()
+			.map(|name| accounts::unlock(&accounts::keystore()?, name, cli))
+			.transpose()
 	}
 
 	// Retrieves the proxied address if specified via CLI flag.
@@ -112,7 +136,13 @@ impl UpCommand {
 	}
 
 	// Resolves the ID, reserving a new one if necessary.
-	async fn resolve_id(&self, chain: &Chain, proxy: &Proxy, cli: &mut impl Cli) -> Result<u32> {
+	async fn resolve_id(
+		&self,
+		chain: &Chain,
+		proxy: &Proxy,
+		account: &Account,
+		cli: &mut impl Cli,
+	) -> Result<u32> {
 		match self.id {
 			Some(id) => Ok(id),
 			None => {
@@ -120,7 +150,7 @@ impl UpCommand {
 					"You will need to sign a transaction to reserve an ID on {} using the `Registrar::reserve` function.",
 					chain.url
 				))?;
-				reserve(chain, proxy, cli).await
+				reserve(chain, proxy, account, cli).await
 			},
 		}
 	}
@@ -158,6 +188,9 @@ impl UpCommand {
 		if let Some(proxy) = &self.proxied_address {
 			full_message.push_str(&format!(" --proxy {}", proxy));
 		}
+		if let Some(account) = &self.account {
+			full_message.push_str(&format!(" --account {}", account));
+		}
 		if let Some(profile) = self.profile {
 			full_message.push_str(&format!(" --profile {}", profile));
 		}
@@ -171,6 +204,7 @@ struct Registration {
 	genesis_artifacts: GenesisArtifacts,
 	chain: Chain,
 	proxy: Proxy,
+	account: Account,
 }
 impl Registration {
 	// Registers by submitting an extrinsic.
@@ -180,7 +214,7 @@ impl Registration {
 			self.chain.url
 		))?;
 		let call_data = self.prepare_register_call_data(cli)?;
-		submit_extrinsic(&self.chain.client, &self.chain.url, call_data, cli)
+		submit(&self.chain, call_data, &self.account, cli)
 			.await
 			.map_err(|e| anyhow::anyhow!("Registration failed: {}", e))?;
 		Ok(())
@@ -211,9 +245,14 @@ impl Registration {
 }
 
 // Reserves an ID by submitting an extrinsic.
-async fn reserve(chain: &Chain, proxy: &Proxy, cli: &mut impl Cli) -> Result<u32> {
+async fn reserve(
+	chain: &Chain,
+	proxy: &Proxy,
+	account: &Account,
+	cli: &mut impl Cli,
+) -> Result<u32> {
 	let call_data = prepare_reserve_call_data(chain, proxy, cli)?;
-	let events = submit_extrinsic(&chain.client, &chain.url, call_data, cli)
+	let events = submit(chain, call_data, account, cli)
 		.await
 		.map_err(|e| anyhow::anyhow!("ID reservation failed: {}", e))?;
 	let id = events
@@ -224,6 +263,36 @@ async fn reserve(chain: &Chain, proxy: &Proxy, cli: &mut impl Cli) -> Result<u32
 	Ok(id)
 }
 
+// Signs and submits an extrinsic with the keystore account, if any, otherwise with a browser
+// extension wallet.
+async fn submit(
+	chain: &Chain,
+	call_data: Vec<u8>,
+	account: &Account,
+	cli: &mut impl Cli,
+) -> Result<ExtrinsicEvents<SubstrateConfig>> {
+	let Some((suri, scheme)) = account else {
+		return submit_extrinsic(&chain.client, &chain.url, call_data, cli).await;
+	};
+	let extrinsic = sign_extrinsic_with_suri(
+		&chain.client,
+		&chain.url,
+		&CallData::new(call_data),
+		suri,
+		*scheme,
+		&TxOptions::default(),
+	)
+	.await?;
+	let spinner = cli.spinner();
+	spinner.start("Submitting the extrinsic and waiting for finalization, please be patient...");
+	let result = submit_signed_extrinsic(chain.client.clone(), to_hex(&extrinsic, false)).await;
+	spinner.clear();
+	let result = result?;
+	let events = parse_and_format_events(&chain.client, &chain.url, &result).await?;
+	cli.success(format!("Extrinsic submitted with hash: {:?}\n{events}", result.extrinsic_hash()))?;
+	Ok(result)
+}
+
 // Prepares and returns the encoded call data for reserving an ID.
 fn prepare_reserve_call_data(chain: &Chain, proxy: &Proxy, cli: &mut impl Cli) -> Result<Vec<u8>> {
 	let dispatchable = find_callable_by_name(
@@ -293,11 +362,12 @@ mod tests {
 			genesis_code: Some(CodePathBuf::from("genesis-code")),
 			relay_chain_url: Some(Url::parse("ws://localhost:9944").unwrap()),
 			proxied_address: Some("5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty".to_string()),
+			account: Some("alice".to_string()),
 			profile: Some(Profile::Release),
 		};
 		assert_eq!(
 			cmd.display(),
-			"pop up rollup --id 2000 --chain-spec chain-spec.json --genesis-state genesis-state --genesis-code genesis-code --relay-chain-url ws://localhost:9944/ --proxy 5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty --profile release"
+			"pop up rollup --id 2000 --chain-spec chain-spec.json --genesis-state genesis-state --genesis-code genesis-code --relay-chain-url ws://localhost:9944/ --proxy 5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty --account alice --profile release"
 		);
 
 		let cmd = UpCommand { path: PathBuf::from("./"), ..Default::default() };
@@ -339,6 +409,21 @@ mod tests {
 		cli.verify()
 	}
 
+	#[tokio::test]
+	async fn prepare_for_registration_fails_with_unknown_account() -> Result<()> {
+		let mut cli = MockCli::new();
+		let error = UpCommand {
+			account: Some("pop-up-chain-unknown-account".to_string()),
+			..Default::default()
+		}
+		.prepare_for_registration(&mut cli)
+		.await
+		.err()
+		.expect("the account does not exist");
+		assert!(error.to_string().contains("no key named"), "{error}");
+		cli.verify()
+	}
+
 	#[test]
 	fn resolve_proxied_address_works() -> Result<()> {
 		let proxied_address = UpCommand {
@@ -406,6 +491,7 @@ mod tests {
 			},
 			chain,
 			proxy: None,
+			account: None,
 		};
 
 		// Encoded call data for a register extrinsic with the above values.
@@ -8,6 +8,7 @@ use crate::{
 	},
 	commands::call::contract::CallContractCommand,
 	common::{
+		accounts,
 		contracts::{
 			build_contract_artifacts, check_ink_node_and_prompt, has_contract_been_built,
 			map_account, normalize_call_args, resolve_function_args, resolve_signer,
@@ -196,6 +197,11 @@ pub struct UpContractCommand {
 		conflicts_with = "suri"
 	)]
 	pub(crate) use_wallet: bool,
+	/// Name of a keystore account to deploy the contract with, instead of a `--suri`, or to
+	/// register a chain with, instead of a browser extension wallet. The password is prompted
+	/// for. See `pop account`.
+	#[clap(long, conflicts_with_all = ["suri", "use-wallet"])]
+	pub(crate) account: Option<String>,
 	/// Actually deploy the contract. Otherwise a dry-run is performed.
 	#[clap(short = 'x', long)]
 	pub(crate) execute: bool,
@@ -233,11 +239,11 @@ impl UpContractCommand {
 			}
 		}
 
-		// Resolve who is deploying the contract. If a `suri` was provided via the command line,
-		// skip the prompt.
-		if let Err(e) =
+		// Resolve who is deploying the contract. If a `suri` or an account was provided via the
+		// command line, skip the prompt.
+		if let Err(e) = self.unlock_account(&mut Cli).and_then(|_| {
 			resolve_signer(self.skip_confirm, &mut self.use_wallet, &mut self.suri, &mut Cli)
-		{
+		}) {
 			Cli.error(e.to_string())?;
 			Cli.outro_cancel(FAILED)?;
 			return Ok(());
@@ -527,6 +533,7 @@ impl UpContractCommand {
 			return Err(deploy_error(e.to_string()));
 		}
 
+		self.unlock_account(&mut json_cli).map_err(|e| deploy_error(e.to_string()))?;
 		resolve_signer(self.skip_confirm, &mut self.use_wallet, &mut self.suri, &mut json_cli)
 			.map_err(|e| deploy_error(e.to_string()))?;
 
@@ -605,6 +612,14 @@ impl UpContractCommand {
 		finalize_json_deploy_result(deploy_result, cleanup_result)
 	}
 
+	// Unlocks the keystore account provided with `--account`, if any, to deploy with its key.
+	fn unlock_account(&mut self, cli: &mut impl crate::cli::traits::Cli) -> anyhow::Result<()> {
+		if let Some(account) = &self.account {
+			self.suri = Some(accounts::unlock_sr25519(account, cli)?);
+		}
+		Ok(())
+	}
+
 	async fn keep_interacting_with_node(
 		&self,
 		cli: &mut Cli,
@@ -623,6 +638,7 @@ impl UpContractCommand {
 			cmd.execute = self.execute;
 			cmd.use_wallet = self.use_wallet;
 			cmd.suri = self.suri.clone();
+			cmd.account = self.account.clone();
 			cmd.skip_confirm = self.skip_confirm;
 			cmd.execute(cli).await?;
 		}
@@ -718,6 +734,8 @@ impl UpContractCommand {
 		}
 		if self.use_wallet {
 			full_message.push_str(" --use-wallet");
+		} else if let Some(account) = &self.account {
+			full_message.push_str(&format!(" --account {}", account));
 		} else if let Some(suri) = &self.suri {
 			full_message.push_str(&format!(" --suri {}", suri));
 		}
@@ -859,6 +877,7 @@ impl Default for UpContractCommand {
 			url: None,
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: true,
 			upload_only: false,
 			skip_confirm: false,
@@ -968,6 +987,7 @@ mod tests {
 			url: None,
 			suri: None,
 			use_wallet: false,
+			account: None,
 			execute: false,
 			upload_only: false,
 			skip_confirm: false,
@@ -985,6 +1005,7 @@ mod tests {
 			url: Some(Url::parse("ws://localhost:9944").unwrap()),
 			suri: Some("//Alice".to_string()),
 			use_wallet: false,
+			account: None,
 			execute: true,
 			upload_only: true,
 			skip_confirm: true,
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	commands::network::zombie_json,
+	output::{deploy_error, invalid_input_error},
+};
+use anyhow::{Result, anyhow};
+use clap::{Args, ValueEnum};
+use pop_chains::{
+	set_up_client,
+	up::{
+		HrmpChannel, LiveNetwork, NodeInfo, NodeRole, close_hrmp_channel, hrmp_channel_exists,
+		open_hrmp_channel, wait_for_hrmp_channel,
+	},
+};
+use serde::Serialize;
+use std::{
+	fmt::{Display, Formatter},
+	path::PathBuf,
+	time::Duration,
+};
+use tokio::time::timeout;
+use url::Url;
+
+/// The change to make to a channel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HrmpAction {
+	/// Open the channel.
+	#[default]
+	Open,
+	/// Close the channel.
+	Close,
+}
+
+impl Display for HrmpAction {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			HrmpAction::Open => write!(f, "open"),
+			HrmpAction::Close => write!(f, "close"),
+		}
+	}
+}
+
+/// Open or close an HRMP channel between two parachains in a running network.
+#[derive(Args, Clone, Default, Serialize)]
+pub(crate) struct HrmpCommand {
+	/// Whether to open or close the channel.
+	#[arg(value_enum)]
+	pub(crate) action: HrmpAction,
+	/// The identifier of the parachain sending messages over the channel.
+	#[arg(long, value_name = "ID")]
+	pub(crate) from: u32,
+	/// The identifier of the parachain receiving messages over the channel.
+	#[arg(long, value_name = "ID")]
+	pub(crate) to: u32,
+	/// The maximum number of messages in the channel, when opening it.
+	#[arg(long, default_value_t = 8)]
+	pub(crate) max_capacity: u32,
+	/// The maximum size of a message in the channel, when opening it.
+	#[arg(long, default_value_t = 512)]
+	pub(crate) max_message_size: u32,
+	/// Path to the network base directory or zombie.json. Defaults to the most recently launched
+	/// network.
+	#[arg(long, value_name = "PATH")]
+	pub(crate) path: Option<PathBuf>,
+	/// Secret key URI of the sudo account of the relay chain.
+	#[serde(skip_serializing)]
+	#[arg(long, default_value = "//Alice")]
+	pub(crate) suri: String,
+	/// The maximum number of seconds to wait for the channel to be opened or closed.
+	#[arg(long, default_value_t = 300)]
+	pub(crate) timeout: u64,
+}
+
+/// JSON output for opening or closing an HRMP channel.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct HrmpOutput {
+	pub(crate) action: HrmpAction,
+	pub(crate) sender: u32,
+	pub(crate) recipient: u32,
+	pub(crate) relay_block: u64,
+}
+
+impl HrmpCommand {
+	/// Executes the command, returning the outcome of the change to the channel.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli) -> Result<HrmpOutput> {
+		let channel = self.channel()?;
+		let open = self.action == HrmpAction::Open;
+		cli.intro(format!("{} the HRMP channel {channel}", if open { "Open" } else { "Close" }))?;
+
+		let zombie_json = zombie_json(self.path.as_deref())?;
+		let network = LiveNetwork::attach(&zombie_json).await?;
+		let relay_chain = relay_chain(network.nodes())?;
+		let url = Url::parse(&relay_chain.ws_uri)?;
+		let client = set_up_client(url.as_str()).await?;
+		if hrmp_channel_exists(&client, channel).await? == open {
+			return Err(invalid_input_error(format!(
+				"The channel {channel} is already {}.",
+				if open { "open" } else { "closed" }
+			)));
+		}
+
+		let spinner = cli.spinner();
+		spinner
+			.start(format!("Submitting the request to {} the channel via {url}...", self.action));
+		let submitted = match self.action {
+			HrmpAction::Open =>
+				open_hrmp_channel(
+					&client,
+					&url,
+					channel,
+					self.max_capacity,
+					self.max_message_size,
+					&self.suri,
+				)
+				.await,
+			HrmpAction::Close => close_hrmp_channel(&client, &url, channel, &self.suri).await,
+		};
+		spinner.clear();
+		submitted.map_err(|e| deploy_error(e.to_string()))?;
+		cli.success("Request submitted.")?;
+
+		let spinner = cli.spinner();
+		spinner.start(format!(
+			"Waiting for the relay chain to {} the channel at the next session change...",
+			self.action
+		));
+		let relay_block = timeout(
+			Duration::from_secs(self.timeout),
+			wait_for_hrmp_channel(&client, channel, open),
+		)
+		.await;
+		spinner.clear();
+		let relay_block = relay_block
+			.map_err(|_| {
+				deploy_error(format!(
+					"The channel was not {} within {} seconds.",
+					if open { "opened" } else { "closed" },
+					self.timeout
+				))
+			})?
+			.map_err(|e| deploy_error(e.to_string()))?;
+
+		cli.outro(format!(
+			"Channel {channel} {} at relay chain block #{relay_block}.",
+			if open { "opened" } else { "closed" }
+		))?;
+		Ok(HrmpOutput {
+			action: self.action,
+			sender: channel.sender,
+			recipient: channel.recipient,
+			relay_block,
+		})
+	}
+
+	// The channel specified by the command.
+	fn channel(&self) -> Result<HrmpChannel> {
+		if self.from == self.to {
+			return Err(invalid_input_error("A channel requires two different parachains."));
+		}
+		Ok(HrmpChannel { sender: self.from, recipient: self.to })
+	}
+}
+
+// The relay chain node used to submit the request and follow the channel.
+fn relay_chain(nodes: Vec<NodeInfo>) -> Result<NodeInfo> {
+	nodes
+		.into_iter()
+		.find(|node| node.role == NodeRole::Validator)
+		.ok_or_else(|| anyhow!("The network has no relay chain nodes."))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::InvalidInputError;
+
+	fn node(name: &str, role: NodeRole) -> NodeInfo {
+		NodeInfo {
+			name: name.into(),
+			role,
+			chain: "paseo-local".into(),
+			para_id: (role == NodeRole::Collator).then_some(1000),
+			ws_uri: "ws://127.0.0.1:9944".into(),
+			rpc_port: Some(9944),
+			p2p_port: None,
+		}
+	}
+
+	#[test]
+	fn channel_works() -> Result<()> {
+		let command = HrmpCommand { from: 4001, to: 1000, ..Default::default() };
+		assert_eq!(command.channel()?, HrmpChannel { sender: 4001, recipient: 1000 });
+
+		let err = HrmpCommand { from: 1000, to: 1000, ..Default::default() }
+			.channel()
+			.unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		Ok(())
+	}
+
+	#[test]
+	fn relay_chain_works() -> Result<()> {
+		let nodes =
+			vec![node("collator-01", NodeRole::Collator), node("alice", NodeRole::Validator)];
+		assert_eq!(relay_chain(nodes)?.name, "alice");
+		assert!(relay_chain(vec![node("collator-01", NodeRole::Collator)]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn hrmp_action_display_works() {
+		assert_eq!(HrmpAction::Open.to_string(), "open");
+		assert_eq!(HrmpAction::Close.to_string(), "close");
+	}
+}
@@ -22,7 +22,11 @@ mod contract;
 /// Utilities for launching a frontend dev server.
 mod frontend;
 #[cfg(feature = "chain")]
+mod hrmp;
+#[cfg(feature = "chain")]
 pub(super) mod network;
+#[cfg(feature = "chain")]
+mod upgrade;
 
 #[cfg(feature = "chain")]
 const KUSAMA: u8 = Relay::Kusama as u8;
@@ -41,6 +45,10 @@ pub(crate) enum UpJsonOutput {
 	Contract(UpContractOutput),
 	#[cfg(feature = "chain")]
 	Network(UpNetworkOutput),
+	#[cfg(feature = "chain")]
+	NetworkUpgrade(upgrade::UpgradeOutput),
+	#[cfg(feature = "chain")]
+	NetworkHrmp(hrmp::HrmpOutput),
 }
 
 /// JSON output for contract deployment.
@@ -146,8 +154,10 @@ impl Command {
 		let project_path = ensure_project_path(args.path.clone(), args.path_pos.clone());
 		#[cfg(feature = "chain")]
 		if project_path.is_file() {
-			let cmd =
-				network::ConfigFileCommand { path: project_path.clone(), ..Default::default() };
+			let cmd = network::ConfigFileCommand {
+				path: Some(project_path.clone()),
+				..Default::default()
+			};
 			cmd.execute(cli).await?;
 			return Ok(());
 		}
@@ -162,6 +172,10 @@ impl Command {
 		#[cfg(feature = "chain")]
 		if pop_chains::is_supported(&project_path) {
 			args.chain.path = project_path.clone();
+			#[cfg(feature = "contract")]
+			{
+				args.chain.account = args.contract.account.clone();
+			}
 			args.chain.execute(cli).await?;
 			return Ok(());
 		}
@@ -177,7 +191,15 @@ pub(crate) async fn execute_json(args: &mut UpArgs) -> anyhow::Result<UpJsonOutp
 			require_detach_for_json(command)?;
 			match command {
 				#[cfg(feature = "chain")]
-				Command::Network(cmd) => Ok(UpJsonOutput::Network(cmd.execute_json().await?)),
+				Command::Network(cmd) => match &cmd.subcommand {
+					Some(network::NetworkCommand::Upgrade(upgrade)) =>
+						Ok(UpJsonOutput::NetworkUpgrade(
+							upgrade.execute(&mut crate::cli::JsonCli).await?,
+						)),
+					Some(network::NetworkCommand::Hrmp(hrmp)) =>
+						Ok(UpJsonOutput::NetworkHrmp(hrmp.execute(&mut crate::cli::JsonCli).await?)),
+					None => Ok(UpJsonOutput::Network(cmd.execute_json().await?)),
+				},
 				#[cfg(feature = "chain")]
 				Command::Paseo(_) => reject_unsupported_json("up paseo").map(|_| unreachable!()),
 				#[cfg(feature = "chain")]
@@ -215,6 +237,7 @@ pub(crate) async fn execute_json(args: &mut UpArgs) -> anyhow::Result<UpJsonOutp
 #[cfg(feature = "chain")]
 fn require_detach_for_json(command: &Command) -> anyhow::Result<()> {
 	if let Command::Network(cmd) = command &&
+		cmd.subcommand.is_none() &&
 		!cmd.detach
 	{
 		return Err(invalid_input_error(
@@ -233,7 +256,10 @@ impl Display for Command {
 	fn fmt(&self, f: &mut Formatter<'_>) -> Result {
 		match self {
 			#[cfg(feature = "chain")]
-			Command::Network(_) => write!(f, "network"),
+			Command::Network(cmd) => match &cmd.subcommand {
+				Some(subcommand) => write!(f, "network {subcommand}"),
+				None => write!(f, "network"),
+			},
 			#[cfg(feature = "chain")]
 			Command::Paseo(_) => write!(f, "paseo"),
 			#[cfg(feature = "chain")]
@@ -281,6 +307,7 @@ mod tests {
 				url: None,
 				suri: Some("//Alice".to_string()),
 				use_wallet: false,
+				account: None,
 				execute: false,
 				upload_only: true,
 				skip_confirm: false,
@@ -342,7 +369,7 @@ mod tests {
 	#[test]
 	fn require_detach_for_json_rejects_network_without_detach() {
 		let command = Command::Network(network::ConfigFileCommand {
-			path: PathBuf::from("network.toml"),
+			path: Some(PathBuf::from("network.toml")),
 			..Default::default()
 		});
 		let err = require_detach_for_json(&command).expect_err("expected detach requirement");
@@ -357,10 +384,43 @@ mod tests {
 	#[test]
 	fn require_detach_for_json_accepts_network_with_detach() {
 		let command = Command::Network(network::ConfigFileCommand {
-			path: PathBuf::from("network.toml"),
+			path: Some(PathBuf::from("network.toml")),
 			detach: true,
 			..Default::default()
 		});
 		require_detach_for_json(&command).expect("detach should be accepted");
 	}
+
+	#[cfg(feature = "chain")]
+	#[test]
+	fn network_upgrade_does_not_require_detach() {
+		let command = Command::Network(network::ConfigFileCommand {
+			subcommand: Some(network::NetworkCommand::Upgrade(upgrade::UpgradeCommand {
+				para_id: 1000,
+				runtime: PathBuf::from("runtime.wasm"),
+				path: None,
+				suri: "//Alice".into(),
+				timeout: 600,
+				force_validation_code: false,
+			})),
+			..Default::default()
+		});
+		require_detach_for_json(&command).expect("upgrade does not launch a network");
+		assert_eq!(command.to_string(), "network upgrade");
+	}
+
+	#[cfg(feature = "chain")]
+	#[test]
+	fn network_hrmp_does_not_require_detach() {
+		let command = Command::Network(network::ConfigFileCommand {
+			subcommand: Some(network::NetworkCommand::Hrmp(hrmp::HrmpCommand {
+				from: 4001,
+				to: 1000,
+				..Default::default()
+			})),
+			..Default::default()
+		});
+		require_detach_for_json(&command).expect("hrmp does not launch a network");
+		assert_eq!(command.to_string(), "network hrmp");
+	}
 }
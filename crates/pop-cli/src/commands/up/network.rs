@@ -1,13 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use super::{NetworkInfo, UpNetworkOutput};
+use super::{NetworkInfo, UpNetworkOutput, hrmp::HrmpCommand, upgrade::UpgradeCommand};
 use crate::{
 	cli::{self, Spinner, traits::Confirm},
 	output::{deploy_error, invalid_input_error},
 	style::{Theme, style},
 };
 use clap::{
-	Arg, Args, Command,
+	Arg, Args, Command, Subcommand,
 	builder::{PossibleValue, PossibleValuesParser, StringValueParser, TypedValueParser},
 	error::ErrorKind,
 };
@@ -25,6 +25,7 @@ use serde_json::Value;
 use std::{
 	collections::HashMap,
 	ffi::OsStr,
+	fmt::{Display, Formatter},
 	path::{Path, PathBuf},
 	time::{Duration, Instant},
 };
@@ -38,11 +39,12 @@ const PID_PARSE_WARNING: &str =
 
 /// Launch a local network by specifying a network configuration file.
 #[derive(Args, Clone, Default, Serialize)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub(crate) struct ConfigFileCommand {
 	/// The Zombienet network configuration file to be used.
 	#[serde(skip_serializing)]
-	#[arg(value_name = "FILE")]
-	pub path: PathBuf,
+	#[arg(value_name = "FILE", required = true)]
+	pub path: Option<PathBuf>,
 	/// The version of the binary to be used for the relay chain, as per the release tag (e.g.
 	/// "stable2512"). See <https://github.com/paritytech/polkadot-sdk/releases> for more details.
 	#[arg(short, long)]
@@ -79,14 +81,46 @@ pub(crate) struct ConfigFileCommand {
 	/// Automatically detach from the terminal and run the network in the background.
 	#[clap(short, long)]
 	pub(crate) detach: bool,
+	/// Manage a running network instead of launching one.
+	#[command(subcommand)]
+	pub(crate) subcommand: Option<NetworkCommand>,
+}
+
+/// Manage a running local network.
+#[derive(Subcommand, Clone, Serialize)]
+pub(crate) enum NetworkCommand {
+	/// Upgrade the runtime of a parachain in the running network.
+	Upgrade(UpgradeCommand),
+	/// Open or close an HRMP channel between two parachains in the running network.
+	Hrmp(HrmpCommand),
+}
+
+impl Display for NetworkCommand {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			NetworkCommand::Upgrade(_) => write!(f, "upgrade"),
+			NetworkCommand::Hrmp(_) => write!(f, "hrmp"),
+		}
+	}
 }
 
 impl ConfigFileCommand {
 	/// Executes the command.
 	pub(crate) async fn execute(&self, cli: &mut impl cli::traits::Cli) -> anyhow::Result<()> {
+		match &self.subcommand {
+			Some(NetworkCommand::Upgrade(cmd)) => {
+				cmd.execute(cli).await?;
+				return Ok(());
+			},
+			Some(NetworkCommand::Hrmp(cmd)) => {
+				cmd.execute(cli).await?;
+				return Ok(());
+			},
+			None => {},
+		}
 		cli.intro("Launch a local network")?;
 
-		let path = self.path.canonicalize()?;
+		let path = self.config_file()?.canonicalize()?;
 		cd_into_chain_base_dir(&path);
 		spawn(
 			path.as_path().try_into()?,
@@ -116,7 +150,7 @@ impl ConfigFileCommand {
 			));
 		}
 		let mut cli = crate::cli::JsonCli;
-		let path = self.path.canonicalize().map_err(|e| deploy_error(e.to_string()))?;
+		let path = self.config_file()?.canonicalize().map_err(|e| deploy_error(e.to_string()))?;
 		cd_into_chain_base_dir(&path);
 		let config: NetworkConfiguration =
 			path.as_path().try_into().map_err(|e: Error| deploy_error(e.to_string()))?;
@@ -139,9 +173,18 @@ impl ConfigFileCommand {
 		output.ok_or_else(|| deploy_error("expected detached network output for JSON mode"))
 	}
 
+	// The network configuration file, required unless a running network is managed.
+	fn config_file(&self) -> anyhow::Result<&PathBuf> {
+		self.path
+			.as_ref()
+			.ok_or_else(|| invalid_input_error("A network configuration file is required"))
+	}
+
 	fn display(&self) -> String {
 		let mut full_message = "pop up network".to_string();
-		full_message.push_str(&format!(" --path {}", self.path.display()));
+		if let Some(path) = &self.path {
+			full_message.push_str(&format!(" --path {}", path.display()));
+		}
 		if let Some(rc) = &self.relay_chain {
 			full_message.push_str(&format!(" --relay-chain {}", rc));
 		}
@@ -198,7 +241,9 @@ pub(crate) struct BuildCommand<const FILTER: u8> {
 	#[arg(short = 'S', long)]
 	system_parachain_runtime: Option<String>,
 	/// The parachain(s) to be included. An optional parachain identifier and/or port can be
-	/// affixed via #id and :port specifiers (e.g. `asset-hub#1000:9944`).
+	/// affixed via #id and :port specifiers (e.g. `asset-hub#1000:9944`). Chains can also be
+	/// defined in `pop-chains.toml` within the project or `chains.toml` within the pop
+	/// configuration directory.
 	#[serde(skip_serializing)]
 	#[arg(short, long, value_delimiter = ',', value_parser = SupportedChains::<FILTER>::new())]
 	parachain: Option<Vec<Box<dyn ChainT>>>,
@@ -240,11 +285,11 @@ impl<const FILTER: u8> BuildCommand<FILTER> {
 
 		// Check for any missing dependencies, auto-adding as required.
 		if let Some(ref mut chains) = chains {
-			let provided: Vec<_> = chains.iter().map(|p| p.as_any().type_id()).collect();
+			let provided: Vec<_> = chains.iter().map(|p| p.chain_type_id()).collect();
 			let dependencies: HashMap<_, _> =
 				chains.iter().filter_map(|p| p.requires()).flatten().collect();
 			let all: HashMap<_, _> =
-				registry::chains(&relay).iter().map(|p| (p.as_any().type_id(), p)).collect();
+				registry::chains(&relay).iter().map(|p| (p.chain_type_id(), p)).collect();
 
 			let missing: Vec<_> = dependencies
 				.keys()
@@ -935,7 +980,7 @@ fn parse_pid_value(value: &Value) -> Option<u32> {
 	}
 }
 
-async fn source_binaries(
+pub(crate) async fn source_binaries(
 	zombienet: &mut Zombienet,
 	cache: &Path,
 	verbose: bool,
@@ -1185,7 +1230,7 @@ mod tests {
 	#[test]
 	fn test_config_file_command_display() {
 		let cmd = ConfigFileCommand {
-			path: PathBuf::from("config.toml"),
+			path: Some(PathBuf::from("config.toml")),
 			relay_chain: Some("stable2503".to_string()),
 			relay_chain_runtime: Some("v1.4.1".to_string()),
 			system_parachain: Some("stable2503".to_string()),
@@ -1196,6 +1241,7 @@ mod tests {
 			skip_confirm: true,
 			auto_remove: true,
 			detach: false,
+			subcommand: None,
 		};
 		assert_eq!(
 			cmd.display(),
@@ -1203,6 +1249,43 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn config_file_command_parses_subcommands() -> anyhow::Result<()> {
+		use crate::commands::up::hrmp::HrmpAction;
+		use clap::Parser;
+		#[derive(Parser)]
+		struct Up {
+			#[command(flatten)]
+			network: ConfigFileCommand,
+		}
+		let up = Up::try_parse_from([
+			"network",
+			"upgrade",
+			"--para",
+			"1000",
+			"--runtime",
+			"runtime.wasm",
+		])?;
+		assert!(up.network.path.is_none());
+		assert!(matches!(
+			up.network.subcommand,
+			Some(NetworkCommand::Upgrade(ref cmd)) if cmd.para_id == 1000 && cmd.suri == "//Alice"
+		));
+		let up = Up::try_parse_from(["network", "hrmp", "open", "--from", "4001", "--to", "1000"])?;
+		assert!(matches!(
+			up.network.subcommand,
+			Some(NetworkCommand::Hrmp(ref cmd))
+				if cmd.action == HrmpAction::Open && cmd.from == 4001 && cmd.to == 1000 &&
+					cmd.max_capacity == 8
+		));
+		let up = Up::try_parse_from(["network", "network.toml", "--detach"])?;
+		assert_eq!(up.network.path, Some(PathBuf::from("network.toml")));
+		assert!(up.network.subcommand.is_none());
+		// The configuration file is required when launching a network.
+		assert!(Up::try_parse_from(["network"]).is_err());
+		Ok(())
+	}
+
 	#[test]
 	fn test_build_command_display() {
 		let cmd = BuildCommand::<0> {
@@ -0,0 +1,297 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	commands::network::zombie_json,
+	output::{deploy_error, invalid_input_error},
+};
+use anyhow::{Result, anyhow};
+use clap::Args;
+use pop_chains::{
+	set_up_client,
+	up::{
+		LiveNetwork, NodeInfo, NodeRole, UpgradeMethod, code_hash, force_set_validation_code,
+		runtime_spec_version, upgrade_parachain_runtime, wait_for_validation_code,
+	},
+};
+use pop_fork::RuntimeExecutor;
+use serde::Serialize;
+use std::{
+	path::PathBuf,
+	time::{Duration, Instant},
+};
+use tokio::time::{error::Elapsed, sleep, timeout_at};
+use url::Url;
+
+/// How often the collators are queried for the spec version of their runtime.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Upgrade the runtime of a parachain in a running network.
+#[derive(Args, Clone, Serialize)]
+pub(crate) struct UpgradeCommand {
+	/// The identifier of the parachain to upgrade.
+	#[arg(long = "para", value_name = "ID")]
+	pub(crate) para_id: u32,
+	/// The runtime to upgrade to, e.g.
+	/// `target/release/wbuild/.../runtime.compact.compressed.wasm`.
+	#[arg(long, value_name = "WASM")]
+	pub(crate) runtime: PathBuf,
+	/// Path to the network base directory or zombie.json. Defaults to the most recently launched
+	/// network.
+	#[arg(long, value_name = "PATH")]
+	pub(crate) path: Option<PathBuf>,
+	/// Secret key URI of the sudo account of the parachain, or of the relay chain when the
+	/// parachain has no `Sudo` pallet.
+	#[serde(skip_serializing)]
+	#[arg(long, default_value = "//Alice")]
+	pub(crate) suri: String,
+	/// The maximum number of seconds to wait for the upgrade to be enacted.
+	#[arg(long, default_value_t = 600)]
+	pub(crate) timeout: u64,
+	/// Set the validation code on the relay chain when the parachain has no `Sudo` pallet. The
+	/// collators keep running their current runtime, so the command fails once the code is
+	/// enacted.
+	#[arg(long)]
+	pub(crate) force_validation_code: bool,
+}
+
+/// JSON output for a parachain runtime upgrade.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct UpgradeOutput {
+	pub(crate) para_id: u32,
+	pub(crate) code_hash: String,
+	pub(crate) spec_name: String,
+	pub(crate) spec_version: u32,
+	pub(crate) method: UpgradeMethod,
+	pub(crate) relay_block: u64,
+	pub(crate) collators: Vec<String>,
+}
+
+impl UpgradeCommand {
+	/// Executes the command, returning the outcome of the upgrade.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli) -> Result<UpgradeOutput> {
+		cli.intro(format!("Upgrade the runtime of parachain {}", self.para_id))?;
+		let code = std::fs::read(&self.runtime).map_err(|e| {
+			invalid_input_error(format!("Failed to read {}: {e}", self.runtime.display()))
+		})?;
+		let version = RuntimeExecutor::new(code.clone(), None)
+			.and_then(|executor| executor.runtime_version())
+			.map_err(|e| invalid_input_error(format!("Invalid runtime: {e}")))?;
+		let code_hash = code_hash(&code);
+
+		let zombie_json = zombie_json(self.path.as_deref())?;
+		let network = LiveNetwork::attach(&zombie_json).await?;
+		let (relay_chain, collators) = self.nodes(network.nodes())?;
+
+		let url = Url::parse(&collators[0].ws_uri)?;
+		let client = set_up_client(url.as_str()).await?;
+		let current = client.runtime_version().spec_version;
+		if version.spec_version <= current {
+			cli.warning(format!(
+				"The spec version of the runtime ({}) is not higher than the current one ({current}).",
+				version.spec_version
+			))?;
+		}
+
+		let relay_url = Url::parse(&relay_chain.ws_uri)?;
+		let relay_chain = set_up_client(relay_url.as_str()).await?;
+		// Parachains without a `Sudo` pallet can only have their validation code set by the relay
+		// chain.
+		let via_relay_chain = client.metadata().pallet_by_name("Sudo").is_none();
+		self.ensure_upgradable(via_relay_chain)?;
+		let spinner = cli.spinner();
+		spinner.start(format!(
+			"Submitting the upgrade to {} v{} via {}...",
+			version.spec_name,
+			version.spec_version,
+			if via_relay_chain { &relay_url } else { &url }
+		));
+		let method = if via_relay_chain {
+			force_set_validation_code(&relay_chain, &relay_url, self.para_id, &code, &self.suri)
+				.await
+		} else {
+			upgrade_parachain_runtime(&client, &url, &code, &self.suri).await
+		};
+		spinner.clear();
+		let method = method.map_err(|e| deploy_error(e.to_string()))?;
+		cli.success(format!("Upgrade submitted via {method}."))?;
+
+		let deadline = Instant::now() + Duration::from_secs(self.timeout);
+		let spinner = cli.spinner();
+		spinner.start("Waiting for the relay chain to enact the new validation code...");
+		let relay_block = timeout_at(
+			deadline.into(),
+			wait_for_validation_code(&relay_chain, self.para_id, code_hash),
+		)
+		.await;
+		spinner.clear();
+		let relay_block = self.within(relay_block)?.map_err(|e| deploy_error(e.to_string()))?;
+		cli.success(format!(
+			"Validation code {code_hash:?} enacted at relay chain block #{relay_block}."
+		))?;
+
+		if method == UpgradeMethod::ForceSetCurrentCode {
+			// The relay chain only sets the validation code, not the code stored by the parachain.
+			return Err(deploy_error(format!(
+				"Parachain {} was not upgraded: only its validation code was set, the collators \
+				 keep running spec version {current} until the code stored by the parachain is \
+				 upgraded.",
+				self.para_id
+			)));
+		}
+		let spinner = cli.spinner();
+		spinner.start(format!(
+			"Waiting for the collators to run spec version {}...",
+			version.spec_version
+		));
+		let upgraded =
+			timeout_at(deadline.into(), wait_for_spec_version(&collators, version.spec_version))
+				.await;
+		spinner.clear();
+		self.within(upgraded)?;
+
+		cli.outro(format!(
+			"Parachain {} upgraded to {} v{}.",
+			self.para_id, version.spec_name, version.spec_version
+		))?;
+		Ok(UpgradeOutput {
+			para_id: self.para_id,
+			code_hash: format!("{code_hash:?}"),
+			spec_name: version.spec_name,
+			spec_version: version.spec_version,
+			method,
+			relay_block,
+			collators: collators.into_iter().map(|node| node.name).collect(),
+		})
+	}
+
+	// Checks that the parachain can be upgraded, which requires an explicit opt-in when only the
+	// validation code can be set through the relay chain.
+	fn ensure_upgradable(&self, via_relay_chain: bool) -> Result<()> {
+		if via_relay_chain && !self.force_validation_code {
+			return Err(invalid_input_error(format!(
+				"Parachain {} has no Sudo pallet, so its runtime cannot be upgraded. Use \
+				 `--force-validation-code` to only set its validation code on the relay chain.",
+				self.para_id
+			)));
+		}
+		Ok(())
+	}
+
+	// The outcome of waiting for the upgrade to be enacted before the timeout.
+	fn within<T>(&self, result: Result<T, Elapsed>) -> Result<T> {
+		result.map_err(|_| {
+			deploy_error(format!("The upgrade was not enacted within {} seconds.", self.timeout))
+		})
+	}
+
+	// The relay chain node used to follow the enactment and the collators of the parachain.
+	fn nodes(&self, nodes: Vec<NodeInfo>) -> Result<(NodeInfo, Vec<NodeInfo>)> {
+		let relay_chain = nodes
+			.iter()
+			.find(|node| node.role == NodeRole::Validator)
+			.cloned()
+			.ok_or_else(|| anyhow!("The network has no relay chain nodes."))?;
+		let collators: Vec<_> =
+			nodes.into_iter().filter(|node| node.para_id == Some(self.para_id)).collect();
+		if collators.is_empty() {
+			return Err(invalid_input_error(format!(
+				"Parachain {} is not part of the network.",
+				self.para_id
+			)));
+		}
+		Ok((relay_chain, collators))
+	}
+}
+
+// Waits for all collators to run the runtime with the spec version.
+async fn wait_for_spec_version(collators: &[NodeInfo], spec_version: u32) {
+	let mut pending: Vec<_> = collators.iter().collect();
+	while !pending.is_empty() {
+		let mut remaining = Vec::new();
+		for collator in pending {
+			if runtime_spec_version(&collator.ws_uri).await.ok() != Some(spec_version) {
+				remaining.push(collator);
+			}
+		}
+		pending = remaining;
+		if !pending.is_empty() {
+			sleep(POLL_INTERVAL).await;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::InvalidInputError};
+
+	fn node(name: &str, role: NodeRole, para_id: Option<u32>) -> NodeInfo {
+		NodeInfo {
+			name: name.into(),
+			role,
+			chain: "paseo-local".into(),
+			para_id,
+			ws_uri: "ws://127.0.0.1:9944".into(),
+			rpc_port: Some(9944),
+			p2p_port: None,
+		}
+	}
+
+	fn command(para_id: u32, runtime: PathBuf) -> UpgradeCommand {
+		UpgradeCommand {
+			para_id,
+			runtime,
+			path: None,
+			suri: "//Alice".into(),
+			timeout: 1,
+			force_validation_code: false,
+		}
+	}
+
+	#[test]
+	fn ensure_upgradable_requires_opt_in_without_sudo() {
+		let mut command = command(1000, PathBuf::new());
+		assert!(command.ensure_upgradable(false).is_ok());
+		let err = command.ensure_upgradable(true).unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		assert!(err.to_string().contains("--force-validation-code"));
+
+		command.force_validation_code = true;
+		assert!(command.ensure_upgradable(true).is_ok());
+	}
+
+	#[test]
+	fn nodes_selects_relay_chain_and_collators() -> Result<()> {
+		let nodes = vec![
+			node("alice", NodeRole::Validator, None),
+			node("bob", NodeRole::Validator, None),
+			node("collator-01", NodeRole::Collator, Some(1000)),
+			node("collator-02", NodeRole::Collator, Some(2000)),
+		];
+		let (relay_chain, collators) = command(2000, PathBuf::new()).nodes(nodes.clone())?;
+		assert_eq!(relay_chain.name, "alice");
+		assert_eq!(collators, vec![nodes[3].clone()]);
+
+		let err = command(3000, PathBuf::new()).nodes(nodes).unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn execute_rejects_invalid_runtime() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let runtime = temp.path().join("runtime.wasm");
+		std::fs::write(&runtime, [0, 1, 2, 3])?;
+		let mut cli = MockCli::new().expect_intro("Upgrade the runtime of parachain 1000");
+		let err = command(1000, runtime).execute(&mut cli).await.unwrap_err();
+		assert!(err.downcast_ref::<InvalidInputError>().is_some());
+
+		let err = command(1000, temp.path().join("missing.wasm"))
+			.execute(&mut MockCli::new())
+			.await
+			.unwrap_err();
+		assert!(err.to_string().contains("Failed to read"));
+		cli.verify()
+	}
+}
@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: GPL-3.0
+
+#[cfg(not(feature = "contract"))]
+use crate::output::invalid_input_error;
+use crate::{cli::traits::Cli, output::OutputMode};
+use anyhow::Result;
+use clap::Args;
+#[cfg(feature = "chain")]
+use clap::Subcommand;
+use serde::Serialize;
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "contract")]
+pub(crate) mod contract;
+#[cfg(feature = "chain")]
+pub(crate) mod runtime;
+
+/// Arguments for verifying a build.
+#[derive(Args, Serialize)]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+pub(crate) struct VerifyArgs {
+	#[cfg(feature = "chain")]
+	#[command(subcommand)]
+	pub(crate) command: Option<Command>,
+	#[cfg(feature = "contract")]
+	#[command(flatten)]
+	pub(crate) contract: contract::VerifyCommand,
+}
+
+/// Verify a build.
+#[cfg(feature = "chain")]
+#[derive(Subcommand, Serialize)]
+pub(crate) enum Command {
+	/// Verify that a deterministic build of a runtime matches the runtime of a live chain, a
+	/// referendum preimage or a published srtool digest.
+	#[clap(alias = "r")]
+	Runtime(runtime::VerifyRuntimeCommand),
+}
+
+impl VerifyArgs {
+	/// Executes the verification selected by the arguments.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		#[cfg(feature = "chain")]
+		if let Some(Command::Runtime(command)) = &self.command {
+			return command.execute(cli, output_mode).await;
+		}
+
+		#[cfg(feature = "contract")]
+		{
+			self.contract.execute(cli, output_mode).await
+		}
+
+		#[cfg(not(feature = "contract"))]
+		{
+			let _ = (cli, output_mode);
+			Err(invalid_input_error("Specify what to verify, e.g. `pop verify runtime`"))
+		}
+	}
+}
+
+impl Display for VerifyArgs {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		#[cfg(feature = "chain")]
+		if let Some(Command::Runtime(_)) = &self.command {
+			return write!(f, "verify runtime");
+		}
+		write!(f, "verify")
+	}
+}
@@ -0,0 +1,293 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::Cli,
+	common::{
+		builds::{ensure_project_path, find_runtime_dir},
+		runtime::build_deterministic_runtime,
+	},
+	output::{CliResponse, OutputMode, build_error_with_details, invalid_input_error},
+};
+use anyhow::Result;
+use clap::{ArgGroup, Args};
+use pop_chains::{
+	H256, RuntimeHashes, SrtoolDigest, fetch_code, fetch_preimage, match_preimage, set_up_client,
+	up::code_hash,
+};
+use pop_common::{Profile, manifest::from_path};
+use serde::Serialize;
+use sp_core::bytes::from_hex;
+use std::path::{Path, PathBuf};
+use url::Url;
+
+/// Rebuild a runtime deterministically and compare it with a reference.
+#[derive(Args, Default, Serialize)]
+#[command(
+	group = ArgGroup::new("reference")
+		.required(true)
+		.multiple(true)
+		.args(["url", "srtool_json"])
+)]
+pub(crate) struct VerifyRuntimeCommand {
+	/// Directory path for your project [default: current directory]
+	#[arg(short, long)]
+	pub(crate) path: Option<PathBuf>,
+	/// Define the directory path where the runtime is located.
+	#[arg(long)]
+	pub(crate) runtime_dir: Option<PathBuf>,
+	/// Specify the runtime package name. If not specified, it will be automatically determined
+	/// based on `runtime-dir`.
+	#[arg(long)]
+	pub(crate) package: Option<String>,
+	/// Build profile of the runtime [default: production].
+	#[arg(long, value_enum)]
+	pub(crate) profile: Option<Profile>,
+	/// The tag of the srtool image used for the deterministic build.
+	#[arg(long)]
+	pub(crate) tag: Option<String>,
+	/// The URL of a live chain, to compare the runtime with its code or a referendum preimage
+	/// noted on it.
+	#[arg(short, long)]
+	pub(crate) url: Option<Url>,
+	/// The number of the block to read the code of the live chain at [default: best block].
+	#[arg(long, value_name = "BLOCK", requires = "url")]
+	pub(crate) at: Option<u64>,
+	/// The hash of a referendum preimage noted on the live chain, proposing the upgrade to the
+	/// runtime.
+	#[arg(long, value_name = "HASH", requires = "url")]
+	pub(crate) preimage: Option<String>,
+	/// The JSON output of `srtool build --json` published for the runtime.
+	#[arg(long, value_name = "PATH")]
+	pub(crate) srtool_json: Option<PathBuf>,
+}
+
+/// JSON output for a runtime verification.
+#[derive(Debug, Serialize)]
+pub(crate) struct VerifyRuntimeOutput {
+	verified: bool,
+	runtime_path: String,
+	code_hash: String,
+	authorize_upgrade_hash: String,
+	checks: Vec<CheckOutput>,
+}
+
+/// JSON output for the comparison of the runtime with a reference.
+#[derive(Debug, Serialize)]
+pub(crate) struct CheckOutput {
+	reference: String,
+	verified: bool,
+	details: String,
+}
+
+impl VerifyRuntimeCommand {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Runtime verification started. This might take a bit⏳")?;
+		// Validate the references before spending time on the build.
+		let preimage = self.preimage_hash()?;
+		let srtool = self
+			.srtool_json
+			.as_deref()
+			.map(|path| {
+				SrtoolDigest::from_path(path).map_err(|e| {
+					invalid_input_error(format!("Failed to load {}: {e}", path.display()))
+				})
+			})
+			.transpose()?;
+
+		let (runtime_path, code) = self.build(cli).await?;
+		cli.info(format!("Runtime built at {}", runtime_path.display()))?;
+		let output = self.verify(&runtime_path, &code, preimage, srtool).await?;
+
+		for check in &output.checks {
+			let message = format!("{}: {}", check.reference, check.details);
+			if check.verified {
+				cli.success(message)?;
+			} else {
+				cli.warning(format!("❌ {message}"))?;
+			}
+		}
+		if !output.verified {
+			let details = output
+				.checks
+				.iter()
+				.filter(|check| !check.verified)
+				.map(|check| format!("{}: {}", check.reference, check.details))
+				.collect::<Vec<_>>()
+				.join("\n");
+			cli.outro_cancel("The runtime could not be verified")?;
+			return Err(build_error_with_details("The runtime could not be verified", details));
+		}
+		cli.outro(format!("The runtime {} is successfully verified ✅", output.code_hash))?;
+		if output_mode == OutputMode::Json {
+			CliResponse::ok(output).print_json();
+		}
+		Ok(())
+	}
+
+	// Builds the runtime deterministically, returning its path and code.
+	async fn build(&self, cli: &mut impl Cli) -> Result<(PathBuf, Vec<u8>)> {
+		let project_path = ensure_project_path(self.path.clone(), None);
+		let runtime_dir = match &self.runtime_dir {
+			Some(runtime_dir) => runtime_dir.clone(),
+			None => find_runtime_dir(&project_path, cli)?,
+		};
+		let package = match &self.package {
+			Some(package) => package.clone(),
+			None => from_path(&runtime_dir)
+				.ok()
+				.and_then(|manifest| manifest.package.map(|pkg| pkg.name))
+				.ok_or_else(|| {
+					invalid_input_error(format!(
+						"Failed to determine the runtime package in {}, specify it with --package",
+						runtime_dir.display()
+					))
+				})?,
+		};
+
+		let spinner = cli.spinner();
+		spinner.start("Building the runtime deterministically with srtool...");
+		let built = build_deterministic_runtime(
+			&package,
+			self.profile.unwrap_or(Profile::Production),
+			runtime_dir,
+			self.tag.clone(),
+		)
+		.await;
+		spinner.clear();
+		built
+	}
+
+	// Compares the runtime with each of the references.
+	async fn verify(
+		&self,
+		runtime_path: &Path,
+		code: &[u8],
+		preimage: Option<H256>,
+		srtool: Option<SrtoolDigest>,
+	) -> Result<VerifyRuntimeOutput> {
+		let mut checks = Vec::new();
+		let hashes = match &self.url {
+			Some(url) => {
+				let client = set_up_client(url.as_str()).await?;
+				// Chains without `System::authorize_upgrade` can still be compared by code hash.
+				RuntimeHashes::for_chain(code, &client).unwrap_or_else(|_| RuntimeHashes::new(code))
+			},
+			None => RuntimeHashes::new(code),
+		};
+
+		if let Some(url) = &self.url {
+			if preimage.is_none() || self.at.is_some() {
+				let (number, onchain) = fetch_code(url.as_str(), self.at).await?;
+				let onchain_hash = code_hash(&onchain);
+				checks.push(CheckOutput {
+					reference: format!("Code of {url} at block #{number}"),
+					verified: onchain_hash == hashes.code_hash,
+					details: format!("code hash {onchain_hash:?}"),
+				});
+			}
+			if let Some(hash) = preimage {
+				let bytes = fetch_preimage(url.as_str(), hash).await?;
+				let matched = match_preimage(&bytes, code, &hashes);
+				checks.push(CheckOutput {
+					reference: format!("Preimage {hash:?}"),
+					verified: matched.is_some(),
+					details: matched.map(|matched| matched.to_string()).unwrap_or_else(|| {
+						"does not reference the code or code hash of the runtime".into()
+					}),
+				});
+			}
+		}
+		if let Some(srtool) = srtool {
+			checks.push(CheckOutput {
+				reference: "srtool digest".into(),
+				verified: srtool.code_hash == hashes.code_hash,
+				details: format!("code hash {:?}", srtool.code_hash),
+			});
+		}
+
+		Ok(VerifyRuntimeOutput {
+			verified: checks.iter().all(|check| check.verified),
+			runtime_path: runtime_path.display().to_string(),
+			code_hash: format!("{:?}", hashes.code_hash),
+			authorize_upgrade_hash: format!("{:?}", hashes.authorize_upgrade_hash),
+			checks,
+		})
+	}
+
+	// The hash of the referendum preimage to compare with, if any.
+	fn preimage_hash(&self) -> Result<Option<H256>> {
+		self.preimage
+			.as_deref()
+			.map(|hash| {
+				from_hex(hash)
+					.ok()
+					.and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+					.map(H256)
+					.ok_or_else(|| invalid_input_error(format!("Invalid preimage hash: {hash}")))
+			})
+			.transpose()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::output::InvalidInputError;
+	use pop_common::test_env::shared_substrate_ws_url;
+
+	const CODE: &[u8] = b"\0asm runtime";
+
+	fn srtool_digest(code: &[u8]) -> SrtoolDigest {
+		SrtoolDigest { code_hash: code_hash(code) }
+	}
+
+	#[test]
+	fn preimage_hash_works() -> Result<()> {
+		let hash = format!("0x{}", "ab".repeat(32));
+		let command = VerifyRuntimeCommand { preimage: Some(hash), ..Default::default() };
+		assert_eq!(command.preimage_hash()?, Some(H256([0xab; 32])));
+		assert_eq!(VerifyRuntimeCommand::default().preimage_hash()?, None);
+
+		for hash in ["0x1234", "not a hash"] {
+			let command =
+				VerifyRuntimeCommand { preimage: Some(hash.into()), ..Default::default() };
+			let err = command.preimage_hash().unwrap_err();
+			assert!(err.downcast_ref::<InvalidInputError>().is_some());
+		}
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn verify_against_srtool_digest_works() -> Result<()> {
+		let command = VerifyRuntimeCommand::default();
+		let path = Path::new("runtime.compact.compressed.wasm");
+
+		let output = command.verify(path, CODE, None, Some(srtool_digest(CODE))).await?;
+		assert!(output.verified);
+		assert_eq!(output.runtime_path, "runtime.compact.compressed.wasm");
+		assert_eq!(output.code_hash, format!("{:?}", code_hash(CODE)));
+		assert_eq!(output.checks.len(), 1);
+
+		let output = command.verify(path, CODE, None, Some(srtool_digest(b"other"))).await?;
+		assert!(!output.verified);
+		assert_eq!(output.checks[0].details, format!("code hash {:?}", code_hash(b"other")));
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn verify_against_live_chain_works() -> Result<()> {
+		let url = Url::parse(&shared_substrate_ws_url().await)?;
+		let (_, genesis_code) = fetch_code(url.as_str(), Some(0)).await?;
+		let command = VerifyRuntimeCommand { url: Some(url), at: Some(0), ..Default::default() };
+		let path = Path::new("runtime.wasm");
+
+		let output = command.verify(path, &genesis_code, None, None).await?;
+		assert!(output.verified);
+		assert!(output.checks[0].reference.ends_with("at block #0"));
+
+		let output = command.verify(path, CODE, None, None).await?;
+		assert!(!output.verified);
+		Ok(())
+	}
+}
@@ -0,0 +1,257 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{
+	cli::traits::*,
+	common::urls,
+	output::{CliResponse, OutputMode, invalid_input_error, prompt_required_error},
+};
+use anyhow::Result;
+use clap::Args;
+use pop_chains::{
+	ChainWatcher, DecodedEvent, EventFilter, Heads, OnlineClient, StorageWatch, SubstrateConfig,
+	WatchUpdate, parse_and_format_events, raw_value_to_string, set_up_client,
+};
+use pop_common::parse_account;
+use serde::Serialize;
+use url::Url;
+
+/// Arguments for following the events and storage of a live chain.
+#[derive(Args, Default, Serialize)]
+pub(crate) struct WatchArgs {
+	/// Websocket endpoint of the node to watch.
+	#[arg(short, long)]
+	pub(crate) url: Option<Url>,
+	/// Only report events of this pallet, e.g. `Balances`.
+	#[arg(long)]
+	pub(crate) pallet: Option<String>,
+	/// Only report events with this name, e.g. `Transfer`.
+	#[arg(long)]
+	pub(crate) event: Option<String>,
+	/// Only report events involving these accounts, separated by commas.
+	#[arg(long, value_delimiter = ',')]
+	pub(crate) account: Vec<String>,
+	/// Watch a storage item for changes, followed by its keys, e.g. `--storage System.Account
+	/// 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY`.
+	#[arg(long, num_args = 1.., value_names = ["PALLET.ITEM", "KEY"])]
+	pub(crate) storage: Vec<String>,
+	/// The heads of the chain to follow.
+	#[arg(long, value_enum, default_value_t = Heads::Finalized)]
+	pub(crate) heads: Heads,
+	/// Stop after this number of blocks instead of watching until interrupted.
+	#[arg(long, value_name = "COUNT")]
+	pub(crate) blocks: Option<u64>,
+}
+
+/// A block reported in JSON mode, printed as one line per block.
+#[derive(Serialize)]
+struct BlockOutput {
+	number: u64,
+	hash: String,
+	events: Vec<serde_json::Value>,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	storage: Option<StorageOutput>,
+}
+
+/// The new value of the watched storage item in JSON mode.
+#[derive(Serialize)]
+struct StorageOutput {
+	item: String,
+	value: serde_json::Value,
+}
+
+impl WatchArgs {
+	/// Executes the command.
+	pub(crate) async fn execute(&self, cli: &mut impl Cli, output_mode: OutputMode) -> Result<()> {
+		cli.intro("Watch a chain")?;
+		let url = match &self.url {
+			Some(url) => url.clone(),
+			None if output_mode == OutputMode::Json =>
+				return Err(prompt_required_error(
+					"Missing required flag for `pop --json watch`: --url",
+				)),
+			None => Url::parse(
+				&cli.input("Which chain would you like to watch?")
+					.placeholder(urls::LOCAL)
+					.default_input(urls::LOCAL)
+					.interact()?,
+			)?,
+		};
+		let filter = self.filter()?;
+
+		let spinner = cli.spinner();
+		spinner.start(format!("Connecting to {url}..."));
+		let client = set_up_client(url.as_str()).await;
+		spinner.clear();
+		let client = client?;
+		let storage = match self.storage.split_first() {
+			Some((item, keys)) => Some(
+				StorageWatch::new(&client, item, keys.to_vec())
+					.map_err(|e| invalid_input_error(e.to_string()))?,
+			),
+			None => None,
+		};
+		let item = storage.as_ref().map(StorageWatch::item);
+		let mut watcher = ChainWatcher::new(client.clone(), self.heads, filter, storage).await?;
+		cli.info(format!("Following the {} blocks of {url}...", self.heads))?;
+
+		let mut blocks = 0;
+		while let Some(update) = watcher.next().await {
+			let update = update?;
+			if output_mode == OutputMode::Json {
+				CliResponse::ok(block_json(&update, item.as_deref())?).print_json();
+			} else {
+				self.display(&update, item.as_deref(), &client, &url, cli).await?;
+			}
+			blocks += 1;
+			if self.blocks.is_some_and(|limit| blocks >= limit) {
+				break;
+			}
+		}
+		cli.outro(format!("Watched {blocks} block(s)."))?;
+		Ok(())
+	}
+
+	// The event filter provided with `--pallet`, `--event` and `--account`.
+	fn filter(&self) -> Result<EventFilter> {
+		let accounts = self
+			.account
+			.iter()
+			.map(|account| {
+				parse_account(account.trim())
+					.map(|account| account.0)
+					.map_err(|e| invalid_input_error(format!("Invalid account `{account}`: {e}")))
+			})
+			.collect::<Result<_>>()?;
+		Ok(EventFilter { pallet: self.pallet.clone(), event: self.event.clone(), accounts })
+	}
+
+	// Displays the events and storage change of a block, skipping blocks without either. The
+	// events of extrinsics are displayed with their token amounts formatted, along with the other
+	// events of the same extrinsics.
+	async fn display(
+		&self,
+		update: &WatchUpdate,
+		item: Option<&str>,
+		client: &OnlineClient<SubstrateConfig>,
+		url: &Url,
+		cli: &mut impl Cli,
+	) -> Result<()> {
+		if update.events.is_empty() && update.storage.is_none() {
+			return Ok(());
+		}
+		let mut output = format!("Block #{} ({:?})", update.number, update.hash);
+		for event in update.events.iter().filter(|event| !event.phase.starts_with("ApplyExtrinsic"))
+		{
+			output.push_str(&format!("\n{}", event.render()?));
+		}
+		for events in &update.extrinsic_events {
+			output.push_str(&format!("\n{}", parse_and_format_events(client, url, events).await?));
+		}
+		if let (Some(item), Some(value)) = (item, &update.storage) {
+			let value = match value {
+				Some(value) => raw_value_to_string(value, "  ")?,
+				None => "  None".to_string(),
+			};
+			output.push_str(&format!("\n{item} changed:\n{value}"));
+		}
+		cli.plain(output)?;
+		Ok(())
+	}
+}
+
+fn event_json(event: &DecodedEvent) -> serde_json::Value {
+	serde_json::json!({
+		"phase": event.phase,
+		"pallet": event.pallet,
+		"name": event.name,
+		"fields": event.fields,
+	})
+}
+
+fn block_json(update: &WatchUpdate, item: Option<&str>) -> Result<BlockOutput> {
+	let storage = match (item, &update.storage) {
+		(Some(item), Some(value)) =>
+			Some(StorageOutput { item: item.to_string(), value: serde_json::to_value(value)? }),
+		_ => None,
+	};
+	Ok(BlockOutput {
+		number: update.number,
+		hash: format!("{:?}", update.hash),
+		events: update.events.iter().map(event_json).collect(),
+		storage,
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{cli::MockCli, output::PromptRequiredError};
+	use pop_chains::DecodedValue;
+	use pop_common::test_env::shared_substrate_ws_url;
+	use scale_value::Value;
+
+	#[tokio::test]
+	async fn json_mode_requires_url() {
+		let args = WatchArgs::default();
+		let err = args.execute(&mut crate::cli::JsonCli, OutputMode::Json).await.unwrap_err();
+		assert!(err.downcast_ref::<PromptRequiredError>().is_some());
+	}
+
+	#[test]
+	fn filter_works() -> Result<()> {
+		let args = WatchArgs {
+			pallet: Some("Balances".into()),
+			event: Some("Transfer".into()),
+			account: vec!["5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into()],
+			..Default::default()
+		};
+		let filter = args.filter()?;
+		assert_eq!(filter.pallet.as_deref(), Some("Balances"));
+		assert_eq!(filter.event.as_deref(), Some("Transfer"));
+		assert_eq!(filter.accounts.len(), 1);
+
+		let args = WatchArgs { account: vec!["invalid".into()], ..Default::default() };
+		assert!(args.filter().is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn block_json_works() -> Result<()> {
+		let fields: DecodedValue = Value::u128(1).map_context(|_| 0);
+		let update = WatchUpdate {
+			number: 1,
+			hash: Default::default(),
+			events: vec![DecodedEvent {
+				phase: "Initialization".into(),
+				pallet: "System".into(),
+				name: "Remarked".into(),
+				fields: fields.clone(),
+			}],
+			extrinsic_events: vec![],
+			storage: Some(Some(fields)),
+		};
+		let json = serde_json::to_value(block_json(&update, Some("System.Number"))?)?;
+		assert_eq!(json["number"], 1);
+		assert_eq!(json["events"][0]["name"], "Remarked");
+		assert_eq!(json["storage"]["item"], "System.Number");
+		// The storage change is omitted without a watched item.
+		let json = serde_json::to_value(block_json(&update, None)?)?;
+		assert!(json.get("storage").is_none());
+		Ok(())
+	}
+
+	#[tokio::test]
+	async fn watch_stops_after_blocks() -> Result<()> {
+		let args = WatchArgs {
+			url: Some(Url::parse(&shared_substrate_ws_url().await)?),
+			storage: vec!["System.Number".into()],
+			heads: Heads::Best,
+			blocks: Some(1),
+			..Default::default()
+		};
+		let mut cli =
+			MockCli::new().expect_intro("Watch a chain").expect_outro("Watched 1 block(s).");
+		args.execute(&mut cli, OutputMode::Human).await?;
+		cli.verify()
+	}
+}
@@ -0,0 +1,98 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::cli::traits::*;
+use anyhow::{Result, anyhow};
+use pop_common::{KeyScheme, Keystore};
+
+/// Environment variable holding the keystore password, used instead of prompting for it.
+pub(crate) const KEYSTORE_PASSWORD_ENV: &str = "POP_KEYSTORE_PASSWORD";
+
+/// The keystore of the user, located in the data directory of pop.
+pub(crate) fn keystore() -> Result<Keystore> {
+	let path = dirs::data_dir()
+		.ok_or(anyhow!("the data directory could not be determined"))?
+		.join("pop")
+		.join("keystore");
+	Ok(Keystore::new(path))
+}
+
+/// Read the keystore password from [`KEYSTORE_PASSWORD_ENV`], or prompt for it.
+pub(crate) fn password(prompt: &str, cli: &mut impl Cli) -> Result<String> {
+	match std::env::var(KEYSTORE_PASSWORD_ENV) {
+		Ok(password) => Ok(password),
+		Err(_) => Ok(cli.password(prompt).interact()?),
+	}
+}
+
+/// Unlock an account of the keystore, prompting for its password.
+///
+/// Returns the secret URI and the key scheme of the account.
+///
+/// # Arguments
+/// * `keystore` - The keystore holding the account.
+/// * `name` - Name of the account.
+/// * `cli` - The CLI used to prompt for the password.
+#[cfg(any(feature = "chain", feature = "contract"))]
+pub(crate) fn unlock(
+	keystore: &Keystore,
+	name: &str,
+	cli: &mut impl Cli,
+) -> Result<(String, KeyScheme)> {
+	// Fail early, before prompting, if the account does not exist.
+	let info = keystore.get(name)?;
+	let password = password(&format!("Password for account `{name}`:"), cli)?;
+	let (_, suri) = keystore.unlock(name, &password)?;
+	Ok((suri, info.scheme))
+}
+
+/// Unlock an sr25519 account of the user's keystore, the only scheme supported to sign contract
+/// calls and deployments.
+///
+/// Returns the secret URI of the account.
+#[cfg(feature = "contract")]
+pub(crate) fn unlock_sr25519(name: &str, cli: &mut impl Cli) -> Result<String> {
+	let (suri, scheme) = unlock(&keystore()?, name, cli)?;
+	if scheme != KeyScheme::Sr25519 {
+		anyhow::bail!(
+			"Account `{name}` is an {scheme} key, but contracts can only be signed with sr25519 keys"
+		);
+	}
+	Ok(suri)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::MockCli;
+
+	#[cfg(any(feature = "chain", feature = "contract"))]
+	#[test]
+	fn unlock_prompts_for_password() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("bob", KeyScheme::Ed25519, "//Bob", "secret")?;
+
+		let mut cli =
+			MockCli::new().expect_password("Password for account `bob`:", "secret".into());
+		let (suri, scheme) =
+			temp_env::with_var_unset(KEYSTORE_PASSWORD_ENV, || unlock(&keystore, "bob", &mut cli))?;
+		assert_eq!(suri, "//Bob");
+		assert_eq!(scheme, KeyScheme::Ed25519);
+		cli.verify()
+	}
+
+	#[cfg(any(feature = "chain", feature = "contract"))]
+	#[test]
+	fn unlock_reads_password_from_env() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("bob", KeyScheme::Sr25519, "//Bob", "secret")?;
+
+		let result = temp_env::with_var(KEYSTORE_PASSWORD_ENV, Some("wrong"), || {
+			unlock(&keystore, "bob", &mut MockCli::new())
+		});
+		assert!(result.unwrap_err().to_string().contains("incorrect password"));
+		assert!(unlock(&keystore, "alice", &mut MockCli::new()).is_err());
+		Ok(())
+	}
+}
@@ -0,0 +1,206 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::{Result, anyhow};
+use pop_chains::{
+	Metadata, OnlineClient, Payload, Signer, SubstrateConfig, sign_extrinsic_with_nonce,
+};
+use pop_common::AccountSigner;
+use pop_fork::{
+	BlockForkPoint, Blockchain, EventPhase, ExecutorConfig, RuntimeEvent, SignatureMockMode,
+};
+use sp_core::{bytes::to_hex, twox_128};
+use url::Url;
+
+/// Runtime API used to read the nonce of the signer from the fork.
+const ACCOUNT_NONCE_API: &str = "AccountNonceApi_account_nonce";
+/// Maximum length of a hex-encoded storage value to display.
+const VALUE_MAX_LEN: usize = 66;
+
+/// Outcome of executing an extrinsic on a local fork of a chain.
+pub(crate) struct ForkPreview {
+	/// Reason the extrinsic was dropped from the block, if it could not be applied.
+	pub dropped: Option<String>,
+	/// Runtime events emitted while applying the extrinsic.
+	pub events: Vec<RuntimeEvent>,
+	/// Storage changes of the block containing the extrinsic, ordered by key.
+	pub changes: Vec<StorageChange>,
+}
+
+/// A storage write of the forked block.
+pub(crate) struct StorageChange {
+	/// Storage item the key belongs to (e.g. `System::Account`), or the hex key if unknown.
+	pub label: String,
+	/// Raw storage key.
+	pub key: Vec<u8>,
+	/// New value, or `None` if the key was deleted.
+	pub value: Option<Vec<u8>>,
+}
+
+impl std::fmt::Display for StorageChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let value = match &self.value {
+			Some(value) => {
+				let value = to_hex(value, false);
+				if value.len() > VALUE_MAX_LEN {
+					format!("{}... ({} bytes)", &value[..VALUE_MAX_LEN], (value.len() - 2) / 2)
+				} else {
+					value
+				}
+			},
+			None => "<deleted>".to_string(),
+		};
+		write!(f, "{}: {value}", self.label)
+	}
+}
+
+/// Execute an extrinsic on an in-process fork of a chain, without touching the chain itself.
+///
+/// Signatures are mocked on the fork, so any signer can be used. The nonce of the signer is
+/// read from the forked state.
+///
+/// # Arguments
+/// * `endpoints` - RPC endpoints of the chain to fork, the first one being preferred.
+/// * `at` - Block number to fork at. Defaults to the latest finalized block.
+/// * `client` - Client of the chain, used to encode and sign the extrinsic.
+/// * `xt` - The extrinsic to execute.
+/// * `signer` - Signer of the extrinsic.
+pub(crate) async fn preview_extrinsic<Xt: Payload>(
+	endpoints: &[Url],
+	at: Option<u32>,
+	client: &OnlineClient<SubstrateConfig>,
+	xt: &Xt,
+	signer: &AccountSigner,
+) -> Result<ForkPreview> {
+	let config =
+		ExecutorConfig { signature_mock: SignatureMockMode::AlwaysValid, ..Default::default() };
+	let blockchain =
+		Blockchain::fork_with_upstreams(endpoints, None, at.map(BlockForkPoint::from), config)
+			.await?;
+
+	let account = Signer::<SubstrateConfig>::account_id(signer).0;
+	let nonce = decode_nonce(&blockchain.call(ACCOUNT_NONCE_API, &account).await?)?;
+	let extrinsic = sign_extrinsic_with_nonce(client, xt, signer, nonce)?;
+
+	let result = blockchain.build_block(vec![extrinsic.clone()]).await?;
+	let block = &result.block;
+	let events = match block.extrinsics.iter().position(|included| *included == extrinsic) {
+		Some(index) => blockchain
+			.block_events(block)
+			.await
+			.into_iter()
+			.filter(|event| event.phase == EventPhase::ApplyExtrinsic(index as u32))
+			.collect(),
+		None => vec![],
+	};
+	let metadata = block.metadata().await?;
+	let mut changes: Vec<_> = result
+		.changes
+		.iter()
+		.map(|(key, value)| StorageChange {
+			label: storage_label(&metadata, key),
+			key: key.clone(),
+			value: value.clone(),
+		})
+		.collect();
+	changes.sort_by(|a, b| a.key.cmp(&b.key));
+	let dropped = result.failed.first().map(|failed| failed.reason.clone());
+
+	if let Err(e) = blockchain.clear_local_storage().await {
+		log::warn!("Failed to clear the local storage of the fork: {e}");
+	}
+	Ok(ForkPreview { dropped, events, changes })
+}
+
+/// Format a runtime event as `Pallet.Variant { field: value, .. }`.
+pub(crate) fn format_event(event: &RuntimeEvent) -> String {
+	if event.fields.is_empty() {
+		return format!("{}.{}", event.pallet, event.variant);
+	}
+	let fields: Vec<_> =
+		event.fields.iter().map(|(name, value)| format!("{name}: {value}")).collect();
+	format!("{}.{} {{ {} }}", event.pallet, event.variant, fields.join(", "))
+}
+
+/// Decode the account nonce returned by the runtime, which is either a `u32` or a `u64`.
+fn decode_nonce(bytes: &[u8]) -> Result<u64> {
+	match bytes.len() {
+		4 => Ok(u32::from_le_bytes(bytes.try_into()?).into()),
+		8 => Ok(u64::from_le_bytes(bytes.try_into()?)),
+		len => Err(anyhow!("Unexpected account nonce length: {len} bytes")),
+	}
+}
+
+/// Name the storage item a key belongs to, falling back to the hex-encoded key.
+fn storage_label(metadata: &Metadata, key: &[u8]) -> String {
+	let hex_key = to_hex(key, false);
+	if key.len() < 32 {
+		return hex_key;
+	}
+	let (pallet_hash, item_hash) = (&key[..16], &key[16..32]);
+	metadata
+		.pallets()
+		.filter_map(|pallet| pallet.storage())
+		.filter(|storage| twox_128(storage.prefix().as_bytes()) == pallet_hash)
+		.filter_map(|storage| {
+			storage
+				.entries()
+				.iter()
+				.find(|entry| twox_128(entry.name().as_bytes()) == item_hash)
+				.map(|entry| format!("{}::{}", storage.prefix(), entry.name()))
+		})
+		.next()
+		.map(|label| match key.len() {
+			32 => label,
+			_ => format!("{label} {}", to_hex(&key[32..], false)),
+		})
+		.unwrap_or(hex_key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn decode_nonce_supports_u32_and_u64() -> Result<()> {
+		assert_eq!(decode_nonce(&7u32.to_le_bytes())?, 7);
+		assert_eq!(decode_nonce(&7u64.to_le_bytes())?, 7);
+		assert!(decode_nonce(&[0u8; 2]).is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn format_event_lists_fields() {
+		let mut event = RuntimeEvent {
+			pallet: "Balances".to_string(),
+			variant: "Transfer".to_string(),
+			phase: EventPhase::ApplyExtrinsic(1),
+			fields: vec![
+				("from".to_string(), "Alice".to_string()),
+				("amount".to_string(), "10".to_string()),
+			],
+			encoded_fields: vec![],
+		};
+		assert_eq!(format_event(&event), "Balances.Transfer { from: Alice, amount: 10 }");
+		event.fields.clear();
+		assert_eq!(format_event(&event), "Balances.Transfer");
+	}
+
+	#[test]
+	fn storage_change_display_truncates_long_values() {
+		let change = StorageChange {
+			label: "System::Number".to_string(),
+			key: vec![],
+			value: Some(vec![1]),
+		};
+		assert_eq!(change.to_string(), "System::Number: 0x01");
+
+		let change = StorageChange { value: Some(vec![0xab; 64]), ..change };
+		assert_eq!(
+			change.to_string(),
+			format!("System::Number: 0x{}... (64 bytes)", "ab".repeat(32))
+		);
+
+		let change = StorageChange { value: None, ..change };
+		assert_eq!(change.to_string(), "System::Number: <deleted>");
+	}
+}
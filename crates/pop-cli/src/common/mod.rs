@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0
 
+/// Contains utilities for unlocking accounts of the keystore.
+pub mod accounts;
 /// Contains benchmarking utilities.
 #[cfg(feature = "chain")]
 pub mod bench;
@@ -10,6 +12,9 @@ pub mod builds;
 pub mod chain;
 #[cfg(feature = "contract")]
 pub mod contracts;
+/// Contains utilities for previewing extrinsics on a local fork.
+#[cfg(feature = "chain")]
+pub mod fork;
 #[cfg(any(feature = "chain", feature = "contract"))]
 pub mod helpers;
 /// Contains omni-node utilities.
@@ -17,6 +22,9 @@ pub mod helpers;
 pub mod omni_node;
 /// Contains utilities for interacting with the CLI prompt.
 pub mod prompt;
+/// Contains utilities for registering chains defined by configuration.
+#[cfg(feature = "chain")]
+pub mod registry;
 /// Contains utilities for interacting with RPC nodes.
 pub mod rpc;
 /// Contains runtime utilities.
@@ -0,0 +1,113 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use anyhow::{Context, Result};
+use pop_chains::registry::{self, ChainDefinition};
+use std::path::{Path, PathBuf};
+
+/// The files defining chains within the user configuration directory (e.g. `~/.config/pop`).
+const USER_FILES: [&str; 2] = ["chains.toml", "chains.json"];
+/// The files defining chains within the current project.
+const PROJECT_FILES: [&str; 2] = ["pop-chains.toml", "pop-chains.json"];
+
+/// Registers the chains defined by the user configuration and the current project, making them
+/// available to `pop up` alongside the built-in chains. A chain defined by the project replaces
+/// one with the same name and relay chain defined by the user configuration.
+pub(crate) fn register_chains() -> Result<()> {
+	let user = dirs::config_dir().map(|dir| dir.join("pop")).unwrap_or_default();
+	let definitions = definitions(
+		USER_FILES
+			.iter()
+			.map(|file| user.join(file))
+			.chain(PROJECT_FILES.iter().map(PathBuf::from)),
+	)?;
+	if !definitions.is_empty() {
+		registry::register(definitions)?;
+	}
+	Ok(())
+}
+
+/// Whether the command line invokes `pop up`, the only command using the chains defined by
+/// configuration. Other commands do not load them, so that an invalid definition cannot break
+/// them.
+///
+/// # Arguments
+/// * `args` - The command line arguments, excluding the binary.
+pub(crate) fn is_required(args: impl IntoIterator<Item = String>) -> bool {
+	args.into_iter()
+		.take_while(|arg| arg != "--")
+		.find(|arg| !arg.starts_with('-'))
+		.is_some_and(|command| ["up", "u", "deploy"].contains(&command.as_str()))
+}
+
+// Loads the chains defined by any of the files which exist, in order.
+fn definitions(paths: impl IntoIterator<Item = PathBuf>) -> Result<Vec<ChainDefinition>> {
+	let mut definitions: Vec<ChainDefinition> = Vec::new();
+	for path in paths.into_iter().filter(|path| path.is_file()) {
+		for definition in load(&path)? {
+			definitions.retain(|d| {
+				d.name != definition.name || !d.relay.eq_ignore_ascii_case(&definition.relay)
+			});
+			definitions.push(definition);
+		}
+	}
+	Ok(definitions)
+}
+
+fn load(path: &Path) -> Result<Vec<ChainDefinition>> {
+	registry::load(path).with_context(|| format!("Failed to load chains from {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::fs::write;
+
+	fn chain(name: &str, relay: &str, id: u32) -> String {
+		format!(
+			"[[chain]]\nname = \"{name}\"\nrelay = \"{relay}\"\nid = {id}\nchain = \"{name}-local\"\nsource.path = \"./{name}-node\"\n"
+		)
+	}
+
+	#[test]
+	fn definitions_prefer_project() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let user = temp.path().join("chains.toml");
+		write(&user, chain("our-chain", "paseo", 2000) + &chain("other-chain", "paseo", 2001))?;
+		let project = temp.path().join("pop-chains.toml");
+		write(&project, chain("our-chain", "Paseo", 3000) + &chain("our-chain", "kusama", 2000))?;
+
+		let definitions = definitions([user, temp.path().join("missing.toml"), project])?;
+		let chains: Vec<_> =
+			definitions.iter().map(|d| (d.name.as_str(), d.relay.as_str(), d.id)).collect();
+		assert_eq!(
+			chains,
+			vec![
+				("other-chain", "paseo", 2001),
+				("our-chain", "Paseo", 3000),
+				("our-chain", "kusama", 2000)
+			]
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn is_required_works() {
+		let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+		assert!(is_required(args(&["up", "network.toml"])));
+		assert!(is_required(args(&["--json", "u", "--parachain", "our-chain"])));
+		assert!(is_required(args(&["deploy"])));
+		assert!(!is_required(args(&["new", "chain", "up"])));
+		assert!(!is_required(args(&["--help"])));
+		assert!(!is_required(args(&[])));
+	}
+
+	#[test]
+	fn definitions_reports_invalid_file() -> Result<()> {
+		let temp = tempfile::tempdir()?;
+		let path = temp.path().join("pop-chains.toml");
+		write(&path, "[[chain]]\nname = 1")?;
+		let error = definitions([path.clone()]).unwrap_err();
+		assert_eq!(error.to_string(), format!("Failed to load chains from {}", path.display()));
+		Ok(())
+	}
+}
@@ -34,6 +34,19 @@ async fn main() -> Result<()> {
 	let json_requested = std::env::args().take_while(|a| a != "--").any(|a| a == "--json");
 	let output_mode = if json_requested { OutputMode::Json } else { OutputMode::Human };
 
+	// Register any chains defined by configuration before parsing, as used by `pop up`.
+	#[cfg(feature = "chain")]
+	if common::registry::is_required(std::env::args().skip(1)) &&
+		let Err(e) = common::registry::register_chains()
+	{
+		if output_mode == OutputMode::Json {
+			CliResponse::err(CliError::new(ErrorCode::InvalidInput, format!("{e:#}")))
+				.print_json_err();
+			std::process::exit(2);
+		}
+		return Err(e);
+	}
+
 	let mut cli = match Cli::try_parse() {
 		Ok(cli) => cli,
 		Err(e) => {
@@ -27,6 +27,9 @@ pub enum Error {
 	/// An error occurred while attempting to create a keypair from the provided URI.
 	#[error("Failed to create keypair from URI: {0}")]
 	KeyPairCreation(String),
+	/// A keystore error occurred.
+	#[error("Keystore error: {0}")]
+	Keystore(String),
 	/// A manifest error occurred.
 	#[error("Manifest error: {0}")]
 	ManifestError(#[from] cargo_toml::Error),
@@ -0,0 +1,407 @@
+// SPDX-License-Identifier: GPL-3.0
+
+use crate::{errors::Error, signer::KeyScheme};
+use chacha20poly1305::{
+	ChaCha20Poly1305, KeyInit, Nonce,
+	aead::{Aead, AeadCore, OsRng, rand_core::RngCore},
+};
+use serde::{Deserialize, Serialize};
+use sp_core::{
+	Pair as _,
+	bytes::{from_hex, to_hex},
+	ed25519,
+};
+use std::{
+	fs::{self, DirBuilder, OpenOptions},
+	io::Write,
+	path::{Path, PathBuf},
+};
+
+/// Name of the key derivation function used to encrypt secrets.
+const KDF: &str = "scrypt";
+/// Name of the cipher used to encrypt secrets.
+const CIPHER: &str = "chacha20poly1305";
+/// Scrypt cost parameter (log2 of N). Kept low in tests to keep them fast.
+const SCRYPT_LOG_N: u8 = if cfg!(test) { 4 } else { 15 };
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+const SALT_LEN: usize = 32;
+const KEY_FILE_EXTENSION: &str = "json";
+
+/// Public information about a key of the keystore. Available without the password.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyInfo {
+	/// Name of the key.
+	pub name: String,
+	/// Signature scheme of the key.
+	pub scheme: KeyScheme,
+	/// Hex-encoded public key.
+	pub public: String,
+	/// Address of the key: SS58 for Substrate schemes, H160 for Ethereum keys.
+	pub address: String,
+}
+
+/// Parameters of the scrypt key derivation.
+#[derive(Debug, Serialize, Deserialize)]
+struct KdfParams {
+	log_n: u8,
+	r: u32,
+	p: u32,
+	salt: String,
+}
+
+/// Encrypted secret of a key.
+#[derive(Debug, Serialize, Deserialize)]
+struct Crypto {
+	kdf: String,
+	kdf_params: KdfParams,
+	cipher: String,
+	nonce: String,
+	ciphertext: String,
+}
+
+/// Contents of a key file.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+	#[serde(flatten)]
+	info: KeyInfo,
+	crypto: Crypto,
+}
+
+/// A password-protected store of keys, with one JSON file per key.
+///
+/// The secret URI of every key is encrypted with ChaCha20-Poly1305, using a key derived from
+/// the password with scrypt. The name, scheme, public key and address are stored in plain text
+/// so keys can be listed without the password.
+#[derive(Clone, Debug)]
+pub struct Keystore {
+	path: PathBuf,
+}
+
+impl Keystore {
+	/// Open the keystore located at `path`. The directory is created when the first key is
+	/// added.
+	pub fn new(path: impl Into<PathBuf>) -> Self {
+		Self { path: path.into() }
+	}
+
+	/// The directory of the keystore.
+	pub fn path(&self) -> &Path {
+		&self.path
+	}
+
+	/// Generate a new key and store it encrypted with `password`.
+	///
+	/// Returns the stored key and its secret mnemonic phrase. Ethereum keys are derived from the
+	/// phrase as Ethereum wallets do, so the phrase can be imported into them.
+	///
+	/// # Arguments
+	/// * `name` - Name of the key.
+	/// * `scheme` - Signature scheme of the key.
+	/// * `password` - Password used to encrypt the secret.
+	pub fn generate(
+		&self,
+		name: &str,
+		scheme: KeyScheme,
+		password: &str,
+	) -> Result<(KeyInfo, String), Error> {
+		let secret = ed25519::Pair::generate_with_phrase(None).1;
+		let info = self.import(name, scheme, &secret, password)?;
+		Ok((info, secret))
+	}
+
+	/// Store an existing secret encrypted with `password`.
+	///
+	/// # Arguments
+	/// * `name` - Name of the key.
+	/// * `scheme` - Signature scheme of the key.
+	/// * `suri` - Secret URI of the key: a mnemonic phrase, a hex-encoded seed or a derivation path
+	///   such as `//Alice`. Ethereum keys take a mnemonic phrase or a hex-encoded private key.
+	/// * `password` - Password used to encrypt the secret.
+	pub fn import(
+		&self,
+		name: &str,
+		scheme: KeyScheme,
+		suri: &str,
+		password: &str,
+	) -> Result<KeyInfo, Error> {
+		validate_name(name)?;
+		let file = self.key_file(name);
+		if file.exists() {
+			return Err(Error::Keystore(format!("a key named `{name}` already exists")));
+		}
+		let public = scheme.public_key(suri)?;
+		let info = KeyInfo {
+			name: name.to_string(),
+			scheme,
+			address: scheme.address(&public)?,
+			public: to_hex(&public, false),
+		};
+		let key_file = KeyFile { info: info.clone(), crypto: encrypt(suri, password)? };
+
+		// Like the key files, the directory is only accessible by the user.
+		let mut builder = DirBuilder::new();
+		builder.recursive(true);
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::DirBuilderExt;
+			builder.mode(0o700);
+		}
+		builder.create(&self.path)?;
+		let contents = serde_json::to_string_pretty(&key_file)
+			.map_err(|e| Error::Keystore(format!("failed to serialize key: {e}")))?;
+		// The file is only ever readable by the user, even while it is being written.
+		let mut options = OpenOptions::new();
+		options.write(true).create_new(true);
+		#[cfg(unix)]
+		{
+			use std::os::unix::fs::OpenOptionsExt;
+			options.mode(0o600);
+		}
+		options.open(&file)?.write_all(contents.as_bytes())?;
+		Ok(info)
+	}
+
+	/// List the keys of the keystore, ordered by name.
+	pub fn list(&self) -> Result<Vec<KeyInfo>, Error> {
+		if !self.path.exists() {
+			return Ok(vec![]);
+		}
+		let mut keys = Vec::new();
+		for entry in fs::read_dir(&self.path)? {
+			let path = entry?.path();
+			if path.extension().is_some_and(|extension| extension == KEY_FILE_EXTENSION) {
+				keys.push(read_key_file(&path)?.info);
+			}
+		}
+		keys.sort_by(|a, b| a.name.cmp(&b.name));
+		Ok(keys)
+	}
+
+	/// Public information about a key.
+	///
+	/// # Arguments
+	/// * `name` - Name of the key.
+	pub fn get(&self, name: &str) -> Result<KeyInfo, Error> {
+		Ok(self.read(name)?.info)
+	}
+
+	/// Decrypt the secret URI of a key.
+	///
+	/// # Arguments
+	/// * `name` - Name of the key.
+	/// * `password` - Password the secret was encrypted with.
+	pub fn unlock(&self, name: &str, password: &str) -> Result<(KeyInfo, String), Error> {
+		let key_file = self.read(name)?;
+		let suri = decrypt(&key_file.crypto, name, password)?;
+		Ok((key_file.info, suri))
+	}
+
+	/// Remove a key from the keystore.
+	///
+	/// # Arguments
+	/// * `name` - Name of the key.
+	pub fn remove(&self, name: &str) -> Result<(), Error> {
+		self.read(name)?;
+		fs::remove_file(self.key_file(name))?;
+		Ok(())
+	}
+
+	fn read(&self, name: &str) -> Result<KeyFile, Error> {
+		validate_name(name)?;
+		let file = self.key_file(name);
+		if !file.exists() {
+			return Err(Error::Keystore(format!("no key named `{name}` in the keystore")));
+		}
+		read_key_file(&file)
+	}
+
+	fn key_file(&self, name: &str) -> PathBuf {
+		self.path.join(name).with_extension(KEY_FILE_EXTENSION)
+	}
+}
+
+/// Key names are used as file names, so only ASCII alphanumerics, `-` and `_` are allowed.
+fn validate_name(name: &str) -> Result<(), Error> {
+	if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+		return Err(Error::Keystore(format!(
+			"invalid key name `{name}`: only letters, digits, `-` and `_` are allowed"
+		)));
+	}
+	Ok(())
+}
+
+fn read_key_file(path: &Path) -> Result<KeyFile, Error> {
+	let contents = fs::read_to_string(path)?;
+	serde_json::from_str(&contents)
+		.map_err(|e| Error::Keystore(format!("invalid key file {}: {e}", path.display())))
+}
+
+fn derive_key(password: &str, salt: &[u8], params: &KdfParams) -> Result<[u8; 32], Error> {
+	let scrypt_params = scrypt::Params::new(params.log_n, params.r, params.p, 32)
+		.map_err(|e| Error::Keystore(format!("invalid scrypt parameters: {e}")))?;
+	let mut key = [0u8; 32];
+	scrypt::scrypt(password.as_bytes(), salt, &scrypt_params, &mut key)
+		.map_err(|e| Error::Keystore(format!("failed to derive key: {e}")))?;
+	Ok(key)
+}
+
+fn encrypt(secret: &str, password: &str) -> Result<Crypto, Error> {
+	let mut salt = [0u8; SALT_LEN];
+	OsRng.fill_bytes(&mut salt);
+	let kdf_params =
+		KdfParams { log_n: SCRYPT_LOG_N, r: SCRYPT_R, p: SCRYPT_P, salt: to_hex(&salt, false) };
+	let cipher = ChaCha20Poly1305::new(&derive_key(password, &salt, &kdf_params)?.into());
+	let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+	let ciphertext = cipher
+		.encrypt(&nonce, secret.as_bytes())
+		.map_err(|e| Error::Keystore(format!("failed to encrypt secret: {e}")))?;
+	Ok(Crypto {
+		kdf: KDF.to_string(),
+		kdf_params,
+		cipher: CIPHER.to_string(),
+		nonce: to_hex(&nonce, false),
+		ciphertext: to_hex(&ciphertext, false),
+	})
+}
+
+// Only a failed authentication of the ciphertext is reported as an incorrect password.
+fn decrypt(crypto: &Crypto, name: &str, password: &str) -> Result<String, Error> {
+	if crypto.kdf != KDF || crypto.cipher != CIPHER {
+		return Err(Error::Keystore(format!(
+			"unsupported encryption `{}` with `{}`",
+			crypto.cipher, crypto.kdf
+		)));
+	}
+	let invalid = |field: &str| Error::Keystore(format!("invalid {field} in key file"));
+	let salt = from_hex(&crypto.kdf_params.salt).map_err(|_| invalid("salt"))?;
+	let nonce = from_hex(&crypto.nonce).map_err(|_| invalid("nonce"))?;
+	if nonce.len() != 12 {
+		return Err(invalid("nonce"));
+	}
+	let ciphertext = from_hex(&crypto.ciphertext).map_err(|_| invalid("ciphertext"))?;
+	let cipher = ChaCha20Poly1305::new(&derive_key(password, &salt, &crypto.kdf_params)?.into());
+	let secret = cipher
+		.decrypt(Nonce::from_slice(&nonce), ciphertext.as_slice())
+		.map_err(|_| Error::Keystore(format!("incorrect password for key `{name}`")))?;
+	String::from_utf8(secret).map_err(|_| invalid("secret"))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn import_and_unlock_works() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		let info = keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		assert_eq!(info.address, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY");
+
+		let contents = fs::read_to_string(dir.path().join("alice.json"))?;
+		assert!(!contents.contains("//Alice"));
+
+		let (unlocked, suri) = keystore.unlock("alice", "secret")?;
+		assert_eq!(unlocked, info);
+		assert_eq!(suri, "//Alice");
+		Ok(())
+	}
+
+	#[test]
+	fn unlock_fails_with_wrong_password() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("alice", KeyScheme::Ed25519, "//Alice", "secret")?;
+		let err = keystore.unlock("alice", "wrong").unwrap_err();
+		assert!(err.to_string().contains("incorrect password"), "{err}");
+		Ok(())
+	}
+
+	#[test]
+	fn unlock_reports_corrupt_key_files() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		let path = dir.path().join("alice.json");
+		let contents = fs::read_to_string(&path)?;
+		let mut key_file: serde_json::Value = serde_json::from_str(&contents)?;
+		key_file["crypto"]["kdf_params"]["salt"] = "0xzz".into();
+		fs::write(&path, key_file.to_string())?;
+		let err = keystore.unlock("alice", "secret").unwrap_err();
+		assert!(err.to_string().contains("invalid salt"), "{err}");
+
+		key_file["crypto"]["cipher"] = "aes-128-ctr".into();
+		fs::write(&path, key_file.to_string())?;
+		let err = keystore.unlock("alice", "secret").unwrap_err();
+		assert!(err.to_string().contains("unsupported encryption"), "{err}");
+		Ok(())
+	}
+
+	#[test]
+	fn generate_returns_importable_secret() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		for (name, scheme) in [("substrate", KeyScheme::Ecdsa), ("evm", KeyScheme::Ethereum)] {
+			let (info, secret) = keystore.generate(name, scheme, "secret")?;
+			assert_eq!(info.address, scheme.address(&scheme.public_key(&secret)?)?);
+			assert_eq!(keystore.unlock(name, "secret")?.1, secret);
+		}
+		assert!(keystore.get("evm")?.address.starts_with("0x"));
+		Ok(())
+	}
+
+	#[test]
+	fn import_derives_ethereum_keys_like_wallets() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		let phrase = "test test test test test test test test test test test junk";
+		let info = keystore.import("evm", KeyScheme::Ethereum, phrase, "secret")?;
+		assert_eq!(info.address, "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+		Ok(())
+	}
+
+	#[cfg(unix)]
+	#[test]
+	fn key_files_are_only_readable_by_the_user() -> Result<()> {
+		use std::os::unix::fs::PermissionsExt;
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		let mode = fs::metadata(dir.path().join("alice.json"))?.permissions().mode();
+		assert_eq!(mode & 0o777, 0o600);
+
+		let keystore = Keystore::new(dir.path().join("keystore"));
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		let mode = fs::metadata(keystore.path())?.permissions().mode();
+		assert_eq!(mode & 0o777, 0o700);
+		Ok(())
+	}
+
+	#[test]
+	fn list_is_sorted_and_remove_works() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path().join("keystore"));
+		assert!(keystore.list()?.is_empty());
+		keystore.import("bob", KeyScheme::Sr25519, "//Bob", "secret")?;
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		let names: Vec<_> = keystore.list()?.into_iter().map(|key| key.name).collect();
+		assert_eq!(names, vec!["alice", "bob"]);
+
+		keystore.remove("bob")?;
+		assert_eq!(keystore.list()?.len(), 1);
+		assert!(keystore.get("bob").is_err());
+		Ok(())
+	}
+
+	#[test]
+	fn import_rejects_duplicates_and_invalid_names() -> Result<()> {
+		let dir = tempfile::tempdir()?;
+		let keystore = Keystore::new(dir.path());
+		keystore.import("alice", KeyScheme::Sr25519, "//Alice", "secret")?;
+		assert!(keystore.import("alice", KeyScheme::Sr25519, "//Bob", "secret").is_err());
+		assert!(keystore.import("../alice", KeyScheme::Sr25519, "//Bob", "secret").is_err());
+		assert!(keystore.import("", KeyScheme::Sr25519, "//Bob", "secret").is_err());
+		Ok(())
+	}
+}
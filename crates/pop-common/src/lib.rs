@@ -14,8 +14,9 @@ pub use helpers::{
 	find_contract_artifact_path, find_workspace_root, get_project_name_from_path,
 	get_relative_or_absolute_path, is_root, replace_in_file,
 };
+pub use keystore::{KeyInfo, Keystore};
 pub use metadata::format_type;
-pub use signer::create_signer;
+pub use signer::{AccountSigner, EthereumConfig, KeyScheme, create_ethereum_signer, create_signer};
 pub use sourcing::set_executable_permission;
 use std::{cmp::Ordering, net::TcpListener, ops::Deref};
 #[cfg(feature = "integration-tests")]
@@ -45,13 +46,15 @@ pub mod errors;
 pub mod git;
 /// Provides general purpose file and path helpers.
 pub mod helpers;
+/// Provides an encrypted on-disk store of keys.
+pub mod keystore;
 /// Provides functionality for resolving and managing Cargo manifests.
 pub mod manifest;
 /// Provides functionality for formatting and resolving metadata types.
 pub mod metadata;
 /// Provides parsers for determining Polkadot SDK versions.
 pub mod polkadot_sdk;
-/// Provides functionality for creating a signer from a secret URI.
+/// Provides functionality for creating signers of the supported key schemes.
 pub mod signer;
 /// Provides functionality for sourcing binaries from a variety of different sources.
 pub mod sourcing;
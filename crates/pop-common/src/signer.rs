@@ -1,7 +1,205 @@
 // SPDX-License-Identifier: GPL-3.0
 
 use crate::errors::Error;
-use subxt_signer::{SecretUri, sr25519::Keypair};
+use serde::{Deserialize, Serialize};
+use sp_core::{
+	Pair as _, blake2_256,
+	bytes::from_hex,
+	crypto::{AccountId32 as SpAccountId32, Ss58Codec},
+	ecdsa, ed25519, keccak_256,
+};
+use strum_macros::{AsRefStr, Display, EnumString, VariantArray};
+use subxt::{
+	Config, SubstrateConfig,
+	config::DefaultExtrinsicParams,
+	ext::subxt_core::utils::AccountId20,
+	tx::Signer,
+	utils::{AccountId32, MultiSignature},
+};
+use subxt_signer::{SecretUri, bip39::Mnemonic, eth, sr25519::Keypair};
+
+/// BIP-44 derivation path of the first account of a mnemonic phrase for Ethereum keys, as used by
+/// MetaMask and most Ethereum wallets.
+const ETHEREUM_DERIVATION_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Signature scheme of a key.
+#[derive(
+	AsRefStr,
+	Clone,
+	Copy,
+	Debug,
+	Default,
+	Deserialize,
+	Display,
+	EnumString,
+	Eq,
+	PartialEq,
+	Serialize,
+	VariantArray,
+	clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum KeyScheme {
+	/// Schnorrkel/Ristretto x25519, the default scheme of Substrate accounts.
+	#[default]
+	Sr25519,
+	/// Edwards-curve ed25519.
+	Ed25519,
+	/// ECDSA over secp256k1, with Substrate account ids (blake2-256 of the public key).
+	Ecdsa,
+	/// ECDSA over secp256k1, with Ethereum (H160) addresses. Mnemonic phrases are derived with
+	/// the BIP-44 path of Ethereum wallets.
+	Ethereum,
+}
+
+impl KeyScheme {
+	/// Derive the public key of a secret URI, as stored in the keystore.
+	///
+	/// Ethereum keys are compressed secp256k1 public keys, like ECDSA keys.
+	pub fn public_key(&self, suri: &str) -> Result<Vec<u8>, Error> {
+		Ok(match self {
+			KeyScheme::Sr25519 => create_signer(suri)?.public_key().0.to_vec(),
+			KeyScheme::Ed25519 => pair::<ed25519::Pair>(suri)?.public().0.to_vec(),
+			KeyScheme::Ecdsa => pair::<ecdsa::Pair>(suri)?.public().0.to_vec(),
+			KeyScheme::Ethereum => create_ethereum_signer(suri)?.public_key().0.to_vec(),
+		})
+	}
+
+	/// Format the address of a public key: SS58 for Substrate schemes and a checksum-free,
+	/// `0x`-prefixed H160 for Ethereum keys.
+	///
+	/// # Arguments
+	/// * `public` - Public key, as returned by [`KeyScheme::public_key`].
+	pub fn address(&self, public: &[u8]) -> Result<String, Error> {
+		let invalid = || Error::AccountAddressParsing(format!("invalid {self} public key"));
+		Ok(match self {
+			KeyScheme::Sr25519 | KeyScheme::Ed25519 => {
+				let public: [u8; 32] = public.try_into().map_err(|_| invalid())?;
+				SpAccountId32::from(public).to_ss58check()
+			},
+			KeyScheme::Ecdsa => SpAccountId32::from(blake2_256(public)).to_ss58check(),
+			KeyScheme::Ethereum => {
+				let public = k256::PublicKey::from_sec1_bytes(public).map_err(|_| invalid())?;
+				let uncompressed =
+					k256::elliptic_curve::sec1::ToEncodedPoint::to_encoded_point(&public, false);
+				// The address is the last 20 bytes of the hash of the uncompressed key, without
+				// its `0x04` tag.
+				let hash = keccak_256(&uncompressed.as_bytes()[1..]);
+				sp_core::bytes::to_hex(&hash[12..], false)
+			},
+		})
+	}
+}
+
+/// A signer of Substrate extrinsics for any scheme of [`KeyScheme`] which uses 32-byte account
+/// ids. Ethereum keys sign with [`create_ethereum_signer`] instead.
+pub enum AccountSigner {
+	/// An sr25519 keypair.
+	Sr25519(Keypair),
+	/// An ed25519 keypair.
+	Ed25519(Box<ed25519::Pair>),
+	/// An ECDSA keypair.
+	Ecdsa(ecdsa::Pair),
+}
+
+impl AccountSigner {
+	/// Create a signer from a secret URI.
+	///
+	/// # Arguments
+	/// * `suri` - Secret URI string used to generate the keypair.
+	/// * `scheme` - Signature scheme of the key.
+	pub fn from_uri(suri: &str, scheme: KeyScheme) -> Result<Self, Error> {
+		Ok(match scheme {
+			KeyScheme::Sr25519 => AccountSigner::Sr25519(create_signer(suri)?),
+			KeyScheme::Ed25519 => AccountSigner::Ed25519(Box::new(pair(suri)?)),
+			KeyScheme::Ecdsa => AccountSigner::Ecdsa(pair(suri)?),
+			KeyScheme::Ethereum =>
+				return Err(Error::KeyPairCreation(
+					"Ethereum keys can only sign extrinsics of chains with Ethereum accounts"
+						.to_string(),
+				)),
+		})
+	}
+
+	/// The signature scheme of the signer.
+	pub fn scheme(&self) -> KeyScheme {
+		match self {
+			AccountSigner::Sr25519(_) => KeyScheme::Sr25519,
+			AccountSigner::Ed25519(_) => KeyScheme::Ed25519,
+			AccountSigner::Ecdsa(_) => KeyScheme::Ecdsa,
+		}
+	}
+}
+
+impl Signer<SubstrateConfig> for AccountSigner {
+	fn account_id(&self) -> AccountId32 {
+		match self {
+			AccountSigner::Sr25519(keypair) => keypair.public_key().to_account_id(),
+			AccountSigner::Ed25519(pair) => AccountId32(pair.public().0),
+			AccountSigner::Ecdsa(pair) => AccountId32(blake2_256(&pair.public().0)),
+		}
+	}
+
+	fn sign(&self, signer_payload: &[u8]) -> MultiSignature {
+		match self {
+			AccountSigner::Sr25519(keypair) =>
+				MultiSignature::Sr25519(keypair.sign(signer_payload).0),
+			AccountSigner::Ed25519(pair) => MultiSignature::Ed25519(pair.sign(signer_payload).0),
+			AccountSigner::Ecdsa(pair) => MultiSignature::Ecdsa(pair.sign(signer_payload).0),
+		}
+	}
+}
+
+/// The configuration of chains with Ethereum accounts: 20-byte account ids and ECDSA signatures
+/// over the keccak-256 hash of the payload, as used by Frontier-based chains.
+pub enum EthereumConfig {}
+
+impl Config for EthereumConfig {
+	type AccountId = AccountId20;
+	type Address = AccountId20;
+	type Signature = eth::Signature;
+	type Hasher = <SubstrateConfig as Config>::Hasher;
+	type Header = <SubstrateConfig as Config>::Header;
+	type ExtrinsicParams = DefaultExtrinsicParams<Self>;
+	type AssetId = u32;
+}
+
+/// Create an Ethereum keypair, which signs for [`EthereumConfig`], from a mnemonic phrase or a
+/// hex-encoded private key.
+///
+/// Mnemonic phrases are derived with the BIP-44 path of the first Ethereum account, so the key
+/// matches the account of the phrase in MetaMask.
+///
+/// # Arguments
+/// * `suri` - The mnemonic phrase or the hex-encoded private key.
+pub fn create_ethereum_signer(suri: &str) -> Result<eth::Keypair, Error> {
+	let keypair = match from_hex(suri.trim()) {
+		Ok(private_key) => {
+			let private_key: [u8; 32] = private_key.try_into().map_err(|_| {
+				Error::KeyPairCreation("an Ethereum private key must be 32 bytes".to_string())
+			})?;
+			eth::Keypair::from_secret_key(private_key)
+		},
+		Err(_) => {
+			let phrase = suri.parse::<Mnemonic>().map_err(|e| {
+				Error::KeyPairCreation(format!(
+					"an Ethereum key must be a mnemonic phrase or a hex-encoded private key: {e}"
+				))
+			})?;
+			let path = ETHEREUM_DERIVATION_PATH
+				.parse()
+				.map_err(|e| Error::KeyPairCreation(format!("{e}")))?;
+			eth::Keypair::from_phrase(&phrase, None, path)
+		},
+	};
+	keypair.map_err(|e| Error::KeyPairCreation(format!("{e}")))
+}
+
+/// Create an `sp_core` keypair from a secret URI.
+fn pair<P: sp_core::Pair>(suri: &str) -> Result<P, Error> {
+	P::from_string(suri, None).map_err(|e| Error::KeyPairCreation(format!("{e:?}")))
+}
 
 /// Create a keypair from a secret URI.
 ///
@@ -29,6 +227,74 @@ mod tests {
 		Ok(())
 	}
 
+	#[test]
+	fn key_scheme_parses_lowercase_names() {
+		use std::str::FromStr;
+		assert_eq!(KeyScheme::from_str("ethereum").unwrap(), KeyScheme::Ethereum);
+		assert_eq!(KeyScheme::Ed25519.to_string(), "ed25519");
+	}
+
+	#[test]
+	fn address_works_for_each_scheme() -> Result<(), Error> {
+		let sr25519 = KeyScheme::Sr25519.public_key("//Alice")?;
+		assert_eq!(
+			KeyScheme::Sr25519.address(&sr25519)?,
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+		);
+		let ed25519 = KeyScheme::Ed25519.public_key("//Alice")?;
+		assert_eq!(
+			KeyScheme::Ed25519.address(&ed25519)?,
+			"5FA9nQDVg267DEd8m1ZypXLBnvN7SFxYwV7ndqSYGiN9TTpu"
+		);
+		// Well-known development key whose Ethereum address is documented by most EVM tooling.
+		let private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+		let ethereum = KeyScheme::Ethereum.public_key(private_key)?;
+		assert_eq!(
+			KeyScheme::Ethereum.address(&ethereum)?,
+			"0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+		);
+		Ok(())
+	}
+
+	#[test]
+	fn account_signer_matches_key_scheme_address() -> Result<(), Error> {
+		for scheme in [KeyScheme::Sr25519, KeyScheme::Ed25519, KeyScheme::Ecdsa] {
+			let signer = AccountSigner::from_uri("//Bob", scheme)?;
+			assert_eq!(signer.scheme(), scheme);
+			let address = scheme.address(&scheme.public_key("//Bob")?)?;
+			assert_eq!(Signer::<SubstrateConfig>::account_id(&signer).to_string(), address);
+		}
+		Ok(())
+	}
+
+	#[test]
+	fn account_signer_rejects_ethereum_keys() {
+		assert!(matches!(
+			AccountSigner::from_uri("//Alice", KeyScheme::Ethereum),
+			Err(Error::KeyPairCreation(..))
+		));
+	}
+
+	#[test]
+	fn create_ethereum_signer_works() -> Result<(), Error> {
+		// The development mnemonic of Hardhat and Foundry, whose first account is documented.
+		let phrase = "test test test test test test test test test test test junk";
+		let address = "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+		let from_phrase = create_ethereum_signer(phrase)?;
+		assert_eq!(KeyScheme::Ethereum.address(&from_phrase.public_key().0)?, address);
+		let private_key = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+		let from_private_key = create_ethereum_signer(private_key)?;
+		assert_eq!(from_private_key.public_key().0, from_phrase.public_key().0);
+		assert_eq!(
+			Signer::<EthereumConfig>::account_id(&from_private_key).0.to_vec(),
+			from_hex(address).unwrap()
+		);
+
+		assert!(matches!(create_ethereum_signer("//Alice"), Err(Error::KeyPairCreation(..))));
+		assert!(matches!(create_ethereum_signer("0x1234"), Err(Error::KeyPairCreation(..))));
+		Ok(())
+	}
+
 	#[test]
 	fn create_signer_fails_wrong_key() -> Result<(), Error> {
 		assert!(matches!(create_signer("11111"), Err(Error::KeyPairCreation(..))));
@@ -43,7 +43,11 @@
 //! fork_block.storage().set(&key, Some(&new_value))?;
 //! ```
 
-use crate::{BlockError, ForkRpcClient, LocalStorageLayer, RemoteStorageLayer, StorageCache};
+use crate::{
+	BlockError, ForkRpcClient, LocalStorageLayer, RawChainSpec, RemoteStorageError,
+	RemoteStorageLayer, RuntimeExecutor, StorageCache, strings::builder::runtime_api,
+};
+use scale::Decode;
 use std::sync::Arc;
 use subxt::{Metadata, config::substrate::H256, ext::codec::Encode};
 use url::Url;
@@ -131,8 +135,27 @@ impl Block {
 		cache: StorageCache,
 		block_fork_point: BlockForkPoint,
 	) -> Result<Self, BlockError> {
-		// Fetch header from remote chain
 		let rpc = ForkRpcClient::connect(endpoint).await?;
+		Self::fork_point_with_client(rpc, cache, block_fork_point).await
+	}
+
+	/// Create a new block at a fork point using an already connected RPC client.
+	///
+	/// Same as [`Block::fork_point`], but reuses `rpc`, which may be connected to a pool of
+	/// upstream endpoints. Every endpoint of the pool is checked against the fork point, and
+	/// endpoints that disagree are excluded from the pool.
+	///
+	/// # Arguments
+	///
+	/// * `rpc` - RPC client connected to the live chain.
+	/// * `cache` - Storage cache for persisting fetched and modified values
+	/// * `block_fork_point` - Hash or number of the block to fork from
+	pub async fn fork_point_with_client(
+		rpc: ForkRpcClient,
+		cache: StorageCache,
+		block_fork_point: BlockForkPoint,
+	) -> Result<Self, BlockError> {
+		// Fetch header from remote chain
 		let (block_hash, header) = match block_fork_point {
 			BlockForkPoint::Number(block_number) => {
 				let (block_hash, block) =
@@ -153,6 +176,9 @@ impl Block {
 		let block_number = header.number;
 		let parent_hash = header.parent_hash;
 
+		// Make sure every upstream serves the same chain before relying on it.
+		rpc.verify_fork_point(block_number, block_hash).await?;
+
 		// Fetch full block to get extrinsics (needed for parachain inherents)
 		let extrinsics = rpc
 			.block_by_hash(block_hash)
@@ -181,6 +207,66 @@ impl Block {
 		})
 	}
 
+	/// Create the genesis block of a raw chain spec, without any upstream node.
+	///
+	/// The genesis storage is written to `cache` under the genesis hash and served by a
+	/// detached [`RemoteStorageLayer`], so the block behaves like a fork point whose remote
+	/// state is fully cached. The metadata is obtained by executing the genesis runtime.
+	///
+	/// # Arguments
+	///
+	/// * `chain_spec` - The raw chain spec to start from.
+	/// * `cache` - Storage cache for the genesis state and subsequent modifications.
+	pub async fn genesis(
+		chain_spec: &RawChainSpec,
+		cache: StorageCache,
+	) -> Result<Self, BlockError> {
+		let runtime_code = chain_spec.runtime_code().ok_or(BlockError::RuntimeCodeNotFound)?;
+		let executor = RuntimeExecutor::new(runtime_code.to_vec(), None)?;
+		let state_version = executor.runtime_version()?.state_version;
+
+		let header = chain_spec.genesis_header(state_version);
+		let block_hash = H256::from(sp_core::blake2_256(&header));
+
+		// Seed the cache with the genesis state, which is the whole remote state.
+		let storage = chain_spec.storage(state_version);
+		let entries: Vec<(&[u8], Option<&[u8]>)> =
+			storage.iter().map(|(k, v)| (k.as_slice(), Some(v.as_slice()))).collect();
+		cache
+			.set_storage_batch(block_hash, &entries)
+			.await
+			.map_err(RemoteStorageError::from)?;
+		cache
+			.cache_block(block_hash, 0, H256::zero(), &header)
+			.await
+			.map_err(RemoteStorageError::from)?;
+
+		let remote = RemoteStorageLayer::detached(cache);
+		let storage = LocalStorageLayer::without_metadata(remote, 0, block_hash);
+
+		// Fetch and decode runtime metadata from the genesis runtime
+		let result = executor.call(runtime_api::METADATA_METADATA, &[], &storage).await?;
+		let metadata = Vec::<u8>::decode(&mut result.output.as_slice())
+			.ok()
+			.and_then(|bytes| Metadata::decode(&mut bytes.as_slice()).ok())
+			.ok_or_else(|| {
+				BlockError::InvalidChainSpec(
+					"failed to decode genesis runtime metadata".to_string(),
+				)
+			})?;
+		storage.register_metadata_version(0, metadata)?;
+
+		Ok(Self {
+			number: 0,
+			hash: block_hash,
+			parent_hash: H256::zero(),
+			header,
+			extrinsics: vec![],
+			storage,
+			parent: None,
+		})
+	}
+
 	/// Create a new child block with the given hash, header, and extrinsics.
 	///
 	/// This commits the parent's storage modifications and creates a new block
@@ -49,16 +49,21 @@
 //! ```
 
 use crate::{
-	Block, BlockBuilder, BlockBuilderError, BlockError, BlockForkPoint, CacheError, ExecutorConfig,
-	ExecutorError, ForkRpcClient, InherentProvider, RuntimeExecutor, StorageCache,
-	builder::ApplyExtrinsicResult,
-	create_next_header_with_slot, default_providers,
+	Block, BlockBuilder, BlockBuilderError, BlockError, BlockForkPoint, BlockLimits, BlockProfile,
+	CacheError, DispatchInfo, EventFilter, EventPhase, ExecutorConfig, ExecutorError,
+	ExtrinsicProfile, FeeInfo, FilteredEvents, ForkRpcClient, InherentProvider, PerDispatchClass,
+	RawChainSpec, RemoteStorageError, RuntimeEvent, RuntimeExecutor, StorageCache, Weight,
+	builder::{self, ApplyExtrinsicResult},
+	create_next_header_with_slot, default_providers, events, profile,
 	strings::{
+		events::system as system_events,
 		inherent::{parachain::storage_keys, timestamp::slot_duration},
+		profile::system as profile_events,
+		rpc_server::runtime_api::QUERY_INFO,
 		txpool::{runtime_api, transaction_source},
 	},
 };
-use scale::Decode;
+use scale::{Decode, Encode};
 use scale_info::{PortableRegistry, TypeDef, TypeDefPrimitive};
 use std::{
 	path::Path,
@@ -269,6 +274,9 @@ pub struct BuildBlockResult {
 	pub included: Vec<Vec<u8>>,
 	/// Extrinsics that failed during apply and were dropped.
 	pub failed: Vec<FailedExtrinsic>,
+	/// Storage changes written by the block, as `(key, new value)` pairs. A `None` value
+	/// means the key was deleted.
+	pub changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
 }
 
 /// An extrinsic that failed during block building.
@@ -281,12 +289,15 @@ pub struct FailedExtrinsic {
 }
 
 /// Capacity for the blockchain event broadcast channel.
-const EVENT_CHANNEL_CAPACITY: usize = 256;
+///
+/// Sized for the runtime events of a busy block on top of the per-block events.
+const EVENT_CHANNEL_CAPACITY: usize = 1024;
 
 /// Events emitted by the blockchain when state changes.
 ///
 /// Subscribe to these events via [`Blockchain::subscribe_events`] to receive
-/// notifications when blocks are built.
+/// notifications when blocks are built. For every block, [`NewBlock`](Self::NewBlock) is
+/// emitted first, followed by the other events of that block.
 #[derive(Debug, Clone)]
 pub enum BlockchainEvent {
 	/// A new block was built and is now the head.
@@ -302,6 +313,59 @@ pub enum BlockchainEvent {
 		/// Storage keys that were modified in this block.
 		modified_keys: Vec<Vec<u8>>,
 	},
+	/// An extrinsic was included in a block and dispatched successfully.
+	ExtrinsicApplied {
+		/// Hash of the block including the extrinsic.
+		hash: H256,
+		/// Number of the block including the extrinsic.
+		number: u32,
+		/// Index of the extrinsic in the block.
+		index: u32,
+		/// Hash of the extrinsic.
+		extrinsic_hash: H256,
+	},
+	/// An extrinsic failed, either during dispatch or before being included.
+	ExtrinsicFailed {
+		/// Hash of the block the extrinsic was applied to.
+		hash: H256,
+		/// Number of the block the extrinsic was applied to.
+		number: u32,
+		/// Index of the extrinsic in the block, or `None` if it was dropped from the block.
+		index: Option<u32>,
+		/// Hash of the extrinsic.
+		extrinsic_hash: H256,
+		/// Reason for the failure.
+		reason: String,
+	},
+	/// A runtime event was emitted in a block.
+	RuntimeEvent {
+		/// Hash of the block emitting the event.
+		hash: H256,
+		/// Number of the block emitting the event.
+		number: u32,
+		/// The decoded event.
+		event: RuntimeEvent,
+	},
+	/// Storage was written in a block.
+	StorageChanged {
+		/// Hash of the block writing the storage.
+		hash: H256,
+		/// Number of the block writing the storage.
+		number: u32,
+		/// Written keys and their new values (`None` if deleted).
+		changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	},
+	/// The runtime was upgraded in a block.
+	RuntimeUpgraded {
+		/// Hash of the block enacting the upgrade.
+		hash: H256,
+		/// Number of the block enacting the upgrade.
+		number: u32,
+		/// Spec name of the new runtime.
+		spec_name: String,
+		/// Spec version of the new runtime.
+		spec_version: u32,
+	},
 }
 
 /// Errors that can occur when working with the blockchain manager.
@@ -350,6 +414,9 @@ pub enum ChainType {
 /// let blockchain = Blockchain::fork(&endpoint, None).await?;
 /// ```
 ///
+/// Use [`Blockchain::fork_from_chain_spec`] to start from the genesis of a raw chain spec
+/// instead, without any upstream node.
+///
 /// # Block Building
 ///
 /// Build blocks using [`build_block`](Blockchain::build_block) or
@@ -563,24 +630,119 @@ impl Blockchain {
 		cache_path: Option<&Path>,
 		fork_point: Option<BlockForkPoint>,
 		executor_config: ExecutorConfig,
+	) -> Result<Arc<Self>, BlockchainError> {
+		Self::fork_with_upstreams(
+			std::slice::from_ref(endpoint),
+			cache_path,
+			fork_point,
+			executor_config,
+		)
+		.await
+	}
+
+	/// Create a new blockchain forked from a live chain served by several RPC endpoints.
+	///
+	/// The endpoints form an upstream pool: requests fail over to the next reachable endpoint
+	/// when the active one drops, and large storage fetches are spread across all healthy
+	/// endpoints. Endpoints that disagree with the first reachable one about the genesis or
+	/// fork point block are excluded from the pool.
+	///
+	/// # Arguments
+	///
+	/// * `endpoints` - RPC endpoint URLs of the live chain, in order of preference
+	/// * `cache_path` - Optional path for persistent SQLite cache
+	/// * `fork_point` - Block number or hash to fork from. If `None`, uses the latest finalized
+	///   block.
+	/// * `executor_config` - Configuration for the runtime executor
+	///
+	/// # Example
+	///
+	/// ```ignore
+	/// let endpoints: Vec<Url> = SupportedChains::POLKADOT
+	///     .rpc_urls()
+	///     .iter()
+	///     .map(|url| url.parse())
+	///     .collect::<Result<_, _>>()?;
+	/// let blockchain =
+	///     Blockchain::fork_with_upstreams(&endpoints, None, None, ExecutorConfig::default()).await?;
+	/// ```
+	pub async fn fork_with_upstreams(
+		endpoints: &[Url],
+		cache_path: Option<&Path>,
+		fork_point: Option<BlockForkPoint>,
+		executor_config: ExecutorConfig,
 	) -> Result<Arc<Self>, BlockchainError> {
 		// Create storage cache
 		let cache = StorageCache::open(cache_path).await?;
 
+		// Connect to the upstream pool once; the connection is shared by all blocks.
+		let rpc = ForkRpcClient::connect_all(endpoints).await.map_err(BlockError::from)?;
+
 		// Determine fork point
 		let fork_point = match fork_point {
 			Some(fp) => fp,
 			None => {
 				// Get latest finalized block from RPC
-				let rpc =
-					crate::ForkRpcClient::connect(endpoint).await.map_err(BlockError::from)?;
 				let finalized = rpc.finalized_head().await.map_err(BlockError::from)?;
 				BlockForkPoint::Hash(finalized)
 			},
 		};
 
 		// Create fork point block
-		let fork_block = Block::fork_point(endpoint, cache, fork_point).await?;
+		let fork_block = Block::fork_point_with_client(rpc, cache, fork_point).await?;
+
+		Self::from_fork_block(fork_block, executor_config, None, OnceCell::new()).await
+	}
+
+	/// Create a new blockchain from the genesis of a raw chain spec, without any upstream node.
+	///
+	/// The genesis storage of the spec becomes the fork point state, so blocks are built on
+	/// top of genesis exactly as for a live fork. Queries about blocks other than the ones
+	/// built locally return nothing, as there is no remote chain.
+	///
+	/// # Arguments
+	///
+	/// * `chain_spec` - Path to a raw chain spec (e.g. generated by `pop build spec --raw`)
+	/// * `cache_path` - Optional path for persistent SQLite cache
+	/// * `executor_config` - Configuration for the runtime executor
+	///
+	/// # Example
+	///
+	/// ```ignore
+	/// let blockchain = Blockchain::fork_from_chain_spec(
+	///     Path::new("raw-chain-spec.json"),
+	///     None,
+	///     ExecutorConfig::default(),
+	/// )
+	/// .await?;
+	/// ```
+	pub async fn fork_from_chain_spec(
+		chain_spec: &Path,
+		cache_path: Option<&Path>,
+		executor_config: ExecutorConfig,
+	) -> Result<Arc<Self>, BlockchainError> {
+		let chain_spec = RawChainSpec::from_file(chain_spec)?;
+		let cache = StorageCache::open(cache_path).await?;
+
+		let genesis = Block::genesis(&chain_spec, cache).await?;
+
+		// Without an upstream, the chain name and properties come from the spec itself.
+		let chain_name = Some(chain_spec.name).filter(|name| !name.is_empty());
+		let chain_properties = OnceCell::new_with(Some(chain_spec.properties));
+
+		Self::from_fork_block(genesis, executor_config, chain_name, chain_properties).await
+	}
+
+	/// Create the blockchain manager on top of an already created fork point block.
+	///
+	/// `chain_name` overrides the name derived from the runtime version, and
+	/// `chain_properties_cache` may be pre-populated when the properties are already known.
+	async fn from_fork_block(
+		fork_block: Block,
+		executor_config: ExecutorConfig,
+		chain_name: Option<String>,
+		chain_properties_cache: OnceCell<Option<serde_json::Value>>,
+	) -> Result<Arc<Self>, BlockchainError> {
 		let fork_point_hash = fork_block.hash;
 		let fork_point_number = fork_block.number;
 
@@ -588,7 +750,10 @@ impl Blockchain {
 		let chain_type = Self::detect_chain_type(&fork_block).await?;
 
 		// Get chain name
-		let chain_name = Self::get_chain_name(&fork_block).await?;
+		let chain_name = match chain_name {
+			Some(name) => name,
+			None => Self::get_chain_name(&fork_block).await?,
+		};
 
 		// Create inherent providers based on chain type
 		let is_parachain = matches!(chain_type, ChainType::Parachain { .. });
@@ -625,7 +790,7 @@ impl Blockchain {
 			remote,
 			event_tx,
 			genesis_hash_cache: OnceCell::new(),
-			chain_properties_cache: OnceCell::new(),
+			chain_properties_cache,
 			last_reconnect_log: AtomicU64::new(0),
 		});
 
@@ -833,11 +998,23 @@ impl Blockchain {
 		self.fork_point_number
 	}
 
-	/// Get the RPC endpoint URL.
-	pub fn endpoint(&self) -> &Url {
+	/// Get the RPC endpoint URL, or `None` when forked from a chain spec.
+	///
+	/// When forked from several endpoints, this is the endpoint currently serving requests.
+	pub fn endpoint(&self) -> Option<&Url> {
 		self.remote.endpoint()
 	}
 
+	/// Get all upstream RPC endpoint URLs, in order of preference.
+	///
+	/// Empty when forked from a chain spec.
+	pub fn endpoints(&self) -> Vec<Url> {
+		self.remote
+			.rpc()
+			.map(|rpc| rpc.endpoints().cloned().collect())
+			.unwrap_or_default()
+	}
+
 	/// Get the genesis hash, formatted as a hex string with "0x" prefix.
 	///
 	/// This method lazily fetches and caches the genesis hash on first call.
@@ -871,11 +1048,9 @@ impl Blockchain {
 	pub async fn chain_properties(&self) -> Option<serde_json::Value> {
 		self.chain_properties_cache
 			.get_or_init(|| async {
-				match ForkRpcClient::connect(self.endpoint()).await {
-					Ok(client) => match client.system_properties().await {
-						Ok(system_props) => serde_json::to_value(system_props).ok(),
-						Err(_) => None,
-					},
+				let rpc = self.remote.rpc()?;
+				match rpc.system_properties().await {
+					Ok(system_props) => serde_json::to_value(system_props).ok(),
 					Err(_) => None,
 				}
 			})
@@ -898,6 +1073,7 @@ impl Blockchain {
 	///             BlockchainEvent::NewBlock { hash, number, .. } => {
 	///                 println!("New block #{} ({:?})", number, hash);
 	///             }
+	///             _ => {}
 	///         }
 	///     }
 	/// });
@@ -906,6 +1082,13 @@ impl Blockchain {
 		self.event_tx.subscribe()
 	}
 
+	/// Subscribe to the blockchain events matching a filter.
+	///
+	/// See [`EventFilter`] for the available criteria.
+	pub fn subscribe_filtered_events(&self, filter: EventFilter) -> FilteredEvents {
+		FilteredEvents::new(self.event_tx.subscribe(), filter)
+	}
+
 	/// Get the current head block.
 	pub async fn head(&self) -> Block {
 		self.head.read().await.clone()
@@ -1302,12 +1485,17 @@ impl Blockchain {
 			*self.warm_prototype.lock().await = returned_prototype;
 		}
 
-		// Get modified keys from storage diff
-		let modified_keys: Vec<Vec<u8>> = new_block
+		// Get modified keys and their new values from storage diff
+		let changes: Vec<(Vec<u8>, Option<Vec<u8>>)> = new_block
 			.storage()
 			.diff()
-			.map(|diff| diff.into_iter().map(|(k, _)| k).collect())
+			.map(|diff| {
+				diff.into_iter()
+					.map(|(k, v)| (k, v.and_then(|shared| shared.value.clone())))
+					.collect()
+			})
 			.unwrap_or_default();
+		let modified_keys: Vec<Vec<u8>> = changes.iter().map(|(k, _)| k.clone()).collect();
 
 		// Emit event AFTER releasing lock (ignore errors - no subscribers is OK)
 		let subscribers = self.event_tx.receiver_count();
@@ -1325,8 +1513,182 @@ impl Blockchain {
 			header: new_block.header.clone(),
 			modified_keys,
 		});
+		if subscribers > 0 {
+			self.emit_block_events(&new_block, &failed, runtime_upgraded, changes.clone())
+				.await;
+		}
+
+		Ok(BuildBlockResult { block: new_block, included, failed, changes })
+	}
+
+	/// Broadcast the detailed events of a newly built block.
+	///
+	/// Emits the runtime upgrade, the outcome of every extrinsic, the decoded runtime
+	/// events and the storage changes of `block`, in that order.
+	async fn emit_block_events(
+		&self,
+		block: &Block,
+		failed: &[FailedExtrinsic],
+		runtime_upgraded: bool,
+		changes: Vec<(Vec<u8>, Option<Vec<u8>>)>,
+	) {
+		let (hash, number) = (block.hash, block.number);
+
+		if runtime_upgraded {
+			match self.executor.read().await.runtime_version() {
+				Ok(version) => {
+					let _ = self.event_tx.send(BlockchainEvent::RuntimeUpgraded {
+						hash,
+						number,
+						spec_name: version.spec_name,
+						spec_version: version.spec_version,
+					});
+				},
+				Err(e) => log::debug!("[Blockchain] Failed to read upgraded runtime version: {e}"),
+			}
+		}
+
+		let runtime_events = self.block_events(block).await;
+
+		for (index, extrinsic) in block.extrinsics.iter().enumerate() {
+			let index = index as u32;
+			let extrinsic_hash = H256::from(sp_core::blake2_256(extrinsic));
+			let dispatch_error = runtime_events.iter().find_map(|event| {
+				(event.phase == EventPhase::ApplyExtrinsic(index) &&
+					event.pallet == system_events::PALLET &&
+					event.variant == system_events::EXTRINSIC_FAILED)
+					.then(|| event.field(system_events::DISPATCH_ERROR_FIELD).unwrap_or_default())
+			});
+			let _ = self.event_tx.send(match dispatch_error {
+				Some(reason) => BlockchainEvent::ExtrinsicFailed {
+					hash,
+					number,
+					index: Some(index),
+					extrinsic_hash,
+					reason: reason.to_string(),
+				},
+				None => BlockchainEvent::ExtrinsicApplied { hash, number, index, extrinsic_hash },
+			});
+		}
+		for failed in failed {
+			let _ = self.event_tx.send(BlockchainEvent::ExtrinsicFailed {
+				hash,
+				number,
+				index: None,
+				extrinsic_hash: H256::from(sp_core::blake2_256(&failed.extrinsic)),
+				reason: failed.reason.clone(),
+			});
+		}
+
+		for event in runtime_events {
+			let _ = self.event_tx.send(BlockchainEvent::RuntimeEvent { hash, number, event });
+		}
+
+		if !changes.is_empty() {
+			let _ = self.event_tx.send(BlockchainEvent::StorageChanged { hash, number, changes });
+		}
+	}
+
+	/// Decode the runtime events emitted in a block.
+	///
+	/// Returns no events if `System::Events` is missing or cannot be decoded.
+	pub async fn block_events(&self, block: &Block) -> Vec<RuntimeEvent> {
+		let bytes = match block.storage().get(block.number, &events::system_events_key()).await {
+			Ok(Some(value)) => match &value.value {
+				Some(bytes) => bytes.clone(),
+				None => return vec![],
+			},
+			Ok(None) => return vec![],
+			Err(e) => {
+				log::debug!("[Blockchain] Failed to read events of block #{}: {e}", block.number);
+				return vec![];
+			},
+		};
+		let metadata = match block.metadata().await {
+			Ok(metadata) => metadata,
+			Err(e) => {
+				log::debug!("[Blockchain] Failed to get metadata of block #{}: {e}", block.number);
+				return vec![];
+			},
+		};
+		events::decode_events(&metadata, &bytes).unwrap_or_else(|| {
+			log::debug!("[Blockchain] Failed to decode events of block #{}", block.number);
+			vec![]
+		})
+	}
+
+	/// Profile the weight, fees and proof size of a built block.
+	///
+	/// Each extrinsic is profiled with the estimate of `TransactionPaymentApi_query_info`
+	/// and the weight reported by its dispatch outcome event. Values that the runtime does
+	/// not expose are left empty rather than failing the whole profile.
+	///
+	/// # Arguments
+	///
+	/// * `result` - The result of [`build_block`](Blockchain::build_block).
+	pub async fn profile_block(
+		&self,
+		result: &BuildBlockResult,
+	) -> Result<BlockProfile, BlockchainError> {
+		let block = &result.block;
+		let metadata = block.metadata().await?;
+		let runtime_events = self.block_events(block).await;
 
-		Ok(BuildBlockResult { block: new_block, included, failed })
+		let mut extrinsics = Vec::with_capacity(block.extrinsics.len());
+		for (index, extrinsic) in block.extrinsics.iter().enumerate() {
+			let index = index as u32;
+			let call = builder::decode_call(&metadata, extrinsic);
+
+			let params = [extrinsic.as_slice(), &(extrinsic.len() as u32).encode()].concat();
+			let fee = match self.call_at_block(block.hash, QUERY_INFO, &params).await {
+				Ok(output) => output.as_deref().and_then(FeeInfo::decode),
+				Err(e) => {
+					log::debug!("[Blockchain] Failed to query info of extrinsic {index}: {e}");
+					None
+				},
+			};
+
+			let outcome = runtime_events.iter().find(|event| {
+				event.phase == EventPhase::ApplyExtrinsic(index) &&
+					event.pallet == system_events::PALLET &&
+					(event.variant == profile_events::EXTRINSIC_SUCCESS ||
+						event.variant == system_events::EXTRINSIC_FAILED)
+			});
+			let actual = outcome
+				.and_then(|event| event.field_bytes(profile_events::DISPATCH_INFO_FIELD))
+				.and_then(DispatchInfo::decode_prefix);
+
+			extrinsics.push(ExtrinsicProfile {
+				index,
+				hash: H256::from(sp_core::blake2_256(extrinsic)),
+				pallet: call.as_ref().map(|call| call.pallet.clone()),
+				call: call.map(|call| call.call),
+				length: extrinsic.len() as u32,
+				class: actual.or(fee.map(|fee| fee.dispatch)).map(|info| info.class),
+				estimated_weight: fee.map(|fee| fee.dispatch.weight),
+				partial_fee: fee.map(|fee| fee.partial_fee),
+				actual_weight: actual.map(|info| info.weight),
+				success: outcome.map(|event| event.variant == profile_events::EXTRINSIC_SUCCESS),
+			});
+		}
+
+		let weight = block
+			.storage()
+			.get(block.number, &profile::block_weight_key())
+			.await
+			.map_err(BlockError::from)?
+			.and_then(|entry| entry.value.clone())
+			.and_then(|bytes| PerDispatchClass::<Weight>::decode(&mut bytes.as_slice()).ok());
+
+		Ok(BlockProfile {
+			hash: block.hash,
+			number: block.number,
+			length: extrinsics.iter().map(|extrinsic| extrinsic.length).sum(),
+			extrinsics,
+			dropped: result.failed.len(),
+			weight,
+			limits: BlockLimits::from_metadata(&metadata),
+		})
 	}
 
 	/// Build an empty block (just inherents, no user extrinsics).
@@ -1439,31 +1801,26 @@ impl Blockchain {
 			log::debug!("storage_keys_paged: returned {} keys (fork-local)", keys.len());
 			Ok(keys)
 		} else {
-			let head = self.head.read().await;
-			let rpc = head.storage().remote().rpc();
-			match rpc.storage_keys_paged(prefix, count, start_key, block_hash).await {
+			match self.remote.storage_keys_paged(prefix, count, start_key, block_hash).await {
 				Ok(keys) => {
 					log::debug!("storage_keys_paged: returned {} keys", keys.len());
 					Ok(keys)
 				},
-				Err(first_err) => {
-					drop(head);
+				Err(first_err) =>
 					if self.reconnect_upstream().await {
-						let head = self.head.read().await;
-						let rpc = head.storage().remote().rpc();
-						let keys = rpc
+						let keys = self
+							.remote
 							.storage_keys_paged(prefix, count, start_key, block_hash)
 							.await
-							.map_err(|e| BlockchainError::Block(BlockError::Rpc(e)))?;
+							.map_err(|e| BlockchainError::Block(BlockError::RemoteStorage(e)))?;
 						log::debug!(
 							"storage_keys_paged: returned {} keys (after reconnect)",
 							keys.len()
 						);
 						Ok(keys)
 					} else {
-						Err(BlockchainError::Block(BlockError::Rpc(first_err)))
-					}
-				},
+						Err(BlockchainError::Block(BlockError::RemoteStorage(first_err)))
+					},
 			}
 		}
 	}
@@ -1677,7 +2034,10 @@ impl Blockchain {
 		args: &[u8],
 		at: H256,
 	) -> Result<Vec<u8>, BlockchainError> {
-		let rpc = self.remote.rpc();
+		let rpc = self
+			.remote
+			.rpc()
+			.ok_or(BlockError::RemoteStorage(RemoteStorageError::NoUpstream))?;
 		match rpc.state_call(method, args, Some(at)).await {
 			Ok(result) => Ok(result),
 			Err(first_err) => {
@@ -1701,6 +2061,11 @@ impl Blockchain {
 	///
 	/// Returns `true` if reconnection succeeded.
 	async fn reconnect_upstream(&self) -> bool {
+		// Nothing to reconnect to when forked from a chain spec.
+		let Some(rpc) = self.remote.rpc() else {
+			return false;
+		};
+
 		let now_ms = std::time::SystemTime::now()
 			.duration_since(std::time::UNIX_EPOCH)
 			.map(|d| d.as_millis() as u64)
@@ -1710,18 +2075,12 @@ impl Blockchain {
 
 		if elapsed_secs >= RECONNECT_LOG_DEBOUNCE_SECS {
 			self.last_reconnect_log.store(now_ms, Ordering::Relaxed);
-			log::debug!(
-				"Upstream connection lost, reconnecting to {}",
-				self.remote.rpc().endpoint()
-			);
+			log::debug!("Upstream connection lost, reconnecting to {}", rpc.endpoint());
 		} else {
-			log::trace!(
-				"Upstream connection lost, reconnecting to {}",
-				self.remote.rpc().endpoint()
-			);
+			log::trace!("Upstream connection lost, reconnecting to {}", rpc.endpoint());
 		}
 
-		self.remote.rpc().reconnect().await.is_ok()
+		rpc.reconnect().await.is_ok()
 	}
 
 	/// Validate an extrinsic before pool submission.
@@ -765,18 +765,7 @@ impl BlockBuilder {
 	/// size of the call's arguments to reject false positives.
 	async fn decode_extrinsic_call(&self, extrinsic: &[u8]) -> Option<DecodedCall> {
 		let metadata = self.parent.metadata().await.ok()?;
-		let remaining = strip_compact_prefix(extrinsic)?;
-
-		let version_byte = *remaining.first()?;
-		let is_signed = version_byte & 0x80 != 0;
-
-		if !is_signed {
-			let pi = *remaining.get(1)?;
-			let ci = *remaining.get(2)?;
-			return try_decode_call(&metadata, pi, ci, remaining.get(3..)?);
-		}
-
-		find_signed_call(&metadata, remaining)
+		decode_call(&metadata, extrinsic)
 	}
 
 	/// Apply storage diff to the parent's storage layer.
@@ -801,10 +790,28 @@ impl BlockBuilder {
 // ---------------------------------------------------------------------------
 
 /// Decoded extrinsic call with pallet, call name, and arguments.
-struct DecodedCall {
-	pallet: String,
-	call: String,
-	args: Vec<(String, String)>,
+pub(crate) struct DecodedCall {
+	pub(crate) pallet: String,
+	pub(crate) call: String,
+	pub(crate) args: Vec<(String, String)>,
+}
+
+/// Decode the call of a length-prefixed extrinsic against `metadata`.
+///
+/// See `BlockBuilder::decode_extrinsic_call` for the supported layout.
+pub(crate) fn decode_call(metadata: &Metadata, extrinsic: &[u8]) -> Option<DecodedCall> {
+	let remaining = strip_compact_prefix(extrinsic)?;
+
+	let version_byte = *remaining.first()?;
+	let is_signed = version_byte & 0x80 != 0;
+
+	if !is_signed {
+		let pi = *remaining.get(1)?;
+		let ci = *remaining.get(2)?;
+		return try_decode_call(metadata, pi, ci, remaining.get(3..)?);
+	}
+
+	find_signed_call(metadata, remaining)
 }
 
 /// Strip the SCALE compact length prefix, returning the remainder.
@@ -919,7 +926,7 @@ fn format_bytes<T, W: std::fmt::Write>(
 
 /// Format a decoded `scale_value::Value` into a human-readable string.
 /// Uses the built-in hex formatter so byte arrays render as `0x...`.
-fn format_scale_value<T>(value: &scale_value::Value<T>) -> Option<String> {
+pub(crate) fn format_scale_value<T>(value: &scale_value::Value<T>) -> Option<String> {
 	let mut buf = String::new();
 	scale_value::stringify::to_writer_custom()
 		.compact()
@@ -985,13 +992,13 @@ pub mod consensus_engine {
 
 /// Internal header struct for encoding.
 #[derive(Encode)]
-struct Header {
-	parent_hash: H256,
+pub(crate) struct Header {
+	pub(crate) parent_hash: H256,
 	#[codec(compact)]
-	number: u32,
-	state_root: H256,
-	extrinsics_root: H256,
-	digest: Vec<DigestItem>,
+	pub(crate) number: u32,
+	pub(crate) state_root: H256,
+	pub(crate) extrinsics_root: H256,
+	pub(crate) digest: Vec<DigestItem>,
 }
 
 /// Create a header for the next block.
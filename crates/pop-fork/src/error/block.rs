@@ -1,6 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0
 
-use crate::error::{LocalStorageError, RemoteStorageError, RpcClientError};
+use crate::error::{ExecutorError, LocalStorageError, RemoteStorageError, RpcClientError};
 use subxt::config::substrate::H256;
 use thiserror::Error;
 
@@ -27,6 +27,14 @@ pub enum BlockError {
 	#[error("Block not found: {0:?}")]
 	BlockNumberNotFound(u32),
 
+	/// Runtime executor error while inspecting the genesis runtime.
+	#[error("Executor error: {0}")]
+	Executor(#[from] ExecutorError),
+
+	/// The chain spec could not be read or has no raw genesis storage.
+	#[error("Invalid chain spec: {0}")]
+	InvalidChainSpec(String),
+
 	/// Runtime code not found in storage.
 	#[error("Runtime code not found in storage")]
 	RuntimeCodeNotFound,
@@ -14,4 +14,7 @@ pub enum RemoteStorageError {
 	/// Cache error when storing/retrieving cached values.
 	#[error("Cache error: {0}")]
 	Cache(#[from] CacheError),
+	/// The layer has no upstream node to fetch from.
+	#[error("No upstream node available: the fork was created from a chain spec")]
+	NoUpstream,
 }
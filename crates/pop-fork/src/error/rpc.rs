@@ -38,4 +38,7 @@ pub enum RpcClientError {
 	/// Failed to decode metadata from remote chain.
 	#[error("Metadata decoding failed: {0}")]
 	MetadataDecodingFailed(String),
+	/// No RPC endpoint was provided to connect to.
+	#[error("No RPC endpoints provided")]
+	NoEndpoints,
 }
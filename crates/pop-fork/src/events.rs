@@ -0,0 +1,351 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Runtime events and filtered event subscriptions for forked chains.
+//!
+//! After every block, [`Blockchain`](crate::Blockchain) decodes the runtime events stored in
+//! `System::Events` and broadcasts them as [`BlockchainEvent`]s, together with the outcome of
+//! each extrinsic, the storage written by the block and any runtime upgrade. This lets tests
+//! react to specific pallet events without polling the chain state.
+//!
+//! An [`EventFilter`] narrows a subscription down to the events of interest.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use pop_fork::{EventFilter, EventKind, BlockchainEvent};
+//!
+//! let filter = EventFilter {
+//!     kinds: vec![EventKind::RuntimeEvent],
+//!     runtime_events: vec!["Balances.Transfer".to_string()],
+//!     ..Default::default()
+//! };
+//! let mut events = blockchain.subscribe_filtered_events(filter);
+//! while let Ok(event) = events.recv().await {
+//!     if let BlockchainEvent::RuntimeEvent { number, event, .. } = event {
+//!         println!("#{number}: {}.{} {:?}", event.pallet, event.variant, event.fields);
+//!     }
+//! }
+//! ```
+
+use crate::{
+	BlockchainEvent,
+	builder::format_scale_value,
+	strings::events::storage_keys::{EVENTS, SYSTEM_PALLET},
+};
+use scale::{Compact, Decode};
+use serde::{Deserialize, Serialize};
+use subxt::Metadata;
+use tokio::sync::broadcast;
+
+/// A runtime event decoded from `System::Events`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RuntimeEvent {
+	/// Name of the pallet emitting the event (e.g. "Balances").
+	pub pallet: String,
+	/// Name of the event variant (e.g. "Transfer").
+	pub variant: String,
+	/// Phase of block execution in which the event was emitted.
+	pub phase: EventPhase,
+	/// Event fields as `(name, value)` pairs, with values in human-readable form.
+	///
+	/// Unnamed fields are named after their position.
+	pub fields: Vec<(String, String)>,
+	/// SCALE-encoded value of each field, in the same order as `fields`.
+	pub encoded_fields: Vec<Vec<u8>>,
+}
+
+impl RuntimeEvent {
+	/// Get the value of a field by name.
+	pub fn field(&self, name: &str) -> Option<&str> {
+		self.fields.iter().find(|(n, _)| n == name).map(|(_, value)| value.as_str())
+	}
+
+	/// Get the SCALE-encoded value of a field by name.
+	pub fn field_bytes(&self, name: &str) -> Option<&[u8]> {
+		let position = self.fields.iter().position(|(n, _)| n == name)?;
+		self.encoded_fields.get(position).map(Vec::as_slice)
+	}
+}
+
+/// Phase of block execution in which a runtime event was emitted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Decode)]
+pub enum EventPhase {
+	/// Emitted while applying the extrinsic at the given index.
+	ApplyExtrinsic(u32),
+	/// Emitted while finalizing the block.
+	Finalization,
+	/// Emitted while initializing the block.
+	Initialization,
+}
+
+/// The kinds of [`BlockchainEvent`] a subscription can select.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EventKind {
+	/// [`BlockchainEvent::NewBlock`].
+	NewBlock,
+	/// [`BlockchainEvent::ExtrinsicApplied`].
+	ExtrinsicApplied,
+	/// [`BlockchainEvent::ExtrinsicFailed`].
+	ExtrinsicFailed,
+	/// [`BlockchainEvent::RuntimeEvent`].
+	RuntimeEvent,
+	/// [`BlockchainEvent::StorageChanged`].
+	StorageChanged,
+	/// [`BlockchainEvent::RuntimeUpgraded`].
+	RuntimeUpgraded,
+}
+
+impl BlockchainEvent {
+	/// Get the kind of this event.
+	pub fn kind(&self) -> EventKind {
+		match self {
+			Self::NewBlock { .. } => EventKind::NewBlock,
+			Self::ExtrinsicApplied { .. } => EventKind::ExtrinsicApplied,
+			Self::ExtrinsicFailed { .. } => EventKind::ExtrinsicFailed,
+			Self::RuntimeEvent { .. } => EventKind::RuntimeEvent,
+			Self::StorageChanged { .. } => EventKind::StorageChanged,
+			Self::RuntimeUpgraded { .. } => EventKind::RuntimeUpgraded,
+		}
+	}
+}
+
+/// Selects the [`BlockchainEvent`]s delivered to a subscription.
+///
+/// Every criterion left empty matches everything, so the default filter lets all events
+/// through.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventFilter {
+	/// Kinds of events to deliver.
+	pub kinds: Vec<EventKind>,
+	/// Runtime events to deliver, either as `Pallet` or `Pallet.Variant`.
+	pub runtime_events: Vec<String>,
+	/// Storage prefixes to watch. Storage changes are narrowed down to the keys under these
+	/// prefixes, and dropped when none matches.
+	pub storage_prefixes: Vec<Vec<u8>>,
+}
+
+impl EventFilter {
+	/// Apply the filter to an event.
+	///
+	/// Returns `None` if the event is filtered out, or the event itself, with its storage
+	/// changes restricted to the watched prefixes.
+	pub fn apply(&self, event: BlockchainEvent) -> Option<BlockchainEvent> {
+		if !self.kinds.is_empty() && !self.kinds.contains(&event.kind()) {
+			return None;
+		}
+		match event {
+			BlockchainEvent::RuntimeEvent { ref event, .. }
+				if !self.runtime_events.is_empty() &&
+					!self.runtime_events.iter().any(|selector| {
+						match selector.split_once('.') {
+							Some((pallet, variant)) =>
+								pallet == event.pallet && variant == event.variant,
+							None => *selector == event.pallet,
+						}
+					}) =>
+				None,
+			BlockchainEvent::StorageChanged { hash, number, changes }
+				if !self.storage_prefixes.is_empty() =>
+			{
+				let changes: Vec<_> = changes
+					.into_iter()
+					.filter(|(key, _)| {
+						self.storage_prefixes.iter().any(|prefix| key.starts_with(prefix))
+					})
+					.collect();
+				(!changes.is_empty()).then_some(BlockchainEvent::StorageChanged {
+					hash,
+					number,
+					changes,
+				})
+			},
+			event => Some(event),
+		}
+	}
+}
+
+/// A subscription to blockchain events that only yields the events matching a filter.
+///
+/// Created with
+/// [`Blockchain::subscribe_filtered_events`](crate::Blockchain::subscribe_filtered_events).
+pub struct FilteredEvents {
+	receiver: broadcast::Receiver<BlockchainEvent>,
+	filter: EventFilter,
+}
+
+impl FilteredEvents {
+	/// Create a filtered subscription on top of a broadcast receiver.
+	pub fn new(receiver: broadcast::Receiver<BlockchainEvent>, filter: EventFilter) -> Self {
+		Self { receiver, filter }
+	}
+
+	/// Receive the next event matching the filter.
+	///
+	/// Fails with [`broadcast::error::RecvError::Lagged`] if the subscriber fell behind and
+	/// events were skipped, and with [`broadcast::error::RecvError::Closed`] once the
+	/// blockchain is dropped.
+	pub async fn recv(&mut self) -> Result<BlockchainEvent, broadcast::error::RecvError> {
+		loop {
+			if let Some(event) = self.filter.apply(self.receiver.recv().await?) {
+				return Ok(event);
+			}
+		}
+	}
+}
+
+/// Compute the storage key for `System::Events`.
+pub fn system_events_key() -> Vec<u8> {
+	[sp_core::twox_128(SYSTEM_PALLET), sp_core::twox_128(EVENTS)].concat()
+}
+
+/// Decode the SCALE-encoded content of `System::Events`.
+///
+/// Each record is an `EventRecord { phase, event, topics }`, where the event is encoded as
+/// the pallet index, the variant index and the variant fields. The fields are decoded
+/// against the type registry of `metadata`.
+///
+/// Returns `None` if any record cannot be decoded, since the records that follow it
+/// cannot be located.
+pub fn decode_events(metadata: &Metadata, bytes: &[u8]) -> Option<Vec<RuntimeEvent>> {
+	let mut cursor = bytes;
+	let Compact(count) = Compact::<u32>::decode(&mut cursor).ok()?;
+	let registry = metadata.types();
+
+	let mut events = Vec::with_capacity(count as usize);
+	for _ in 0..count {
+		let phase = EventPhase::decode(&mut cursor).ok()?;
+		let pallet_index = u8::decode(&mut cursor).ok()?;
+		let variant_index = u8::decode(&mut cursor).ok()?;
+
+		let pallet = metadata.pallets().find(|p| p.index() == pallet_index)?;
+		let variant = pallet.event_variants()?.iter().find(|v| v.index == variant_index)?;
+
+		let mut fields = Vec::with_capacity(variant.fields.len());
+		let mut encoded_fields = Vec::with_capacity(variant.fields.len());
+		for (position, field) in variant.fields.iter().enumerate() {
+			let start = cursor;
+			let value =
+				scale_value::scale::decode_as_type(&mut cursor, field.ty.id, registry).ok()?;
+			let name = field.name.clone().unwrap_or_else(|| position.to_string());
+			fields.push((name, format_scale_value(&value)?));
+			encoded_fields.push(start[..start.len() - cursor.len()].to_vec());
+		}
+
+		// Topics are not exposed, but must be consumed to reach the next record.
+		Vec::<[u8; 32]>::decode(&mut cursor).ok()?;
+
+		events.push(RuntimeEvent {
+			pallet: pallet.name().to_string(),
+			variant: variant.name.clone(),
+			phase,
+			fields,
+			encoded_fields,
+		});
+	}
+
+	Some(events)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale::Encode;
+	use subxt::config::substrate::H256;
+
+	fn runtime_event(pallet: &str, variant: &str) -> BlockchainEvent {
+		BlockchainEvent::RuntimeEvent {
+			hash: H256::zero(),
+			number: 1,
+			event: RuntimeEvent {
+				pallet: pallet.to_string(),
+				variant: variant.to_string(),
+				phase: EventPhase::ApplyExtrinsic(1),
+				fields: vec![("amount".to_string(), "10".to_string())],
+				encoded_fields: vec![10u128.encode()],
+			},
+		}
+	}
+
+	fn storage_changed(keys: &[&[u8]]) -> BlockchainEvent {
+		BlockchainEvent::StorageChanged {
+			hash: H256::zero(),
+			number: 1,
+			changes: keys.iter().map(|key| (key.to_vec(), Some(vec![1]))).collect(),
+		}
+	}
+
+	#[test]
+	fn system_events_key_is_correct() {
+		let key = system_events_key();
+		assert_eq!(
+			hex::encode(key),
+			"26aa394eea5630e07c48ae0c9558cef780d41e5e16056765bc8461851072c9d7"
+		);
+	}
+
+	#[test]
+	fn event_phase_decodes() {
+		assert_eq!(
+			EventPhase::decode(&mut &[0, 2, 0, 0, 0][..]).unwrap(),
+			EventPhase::ApplyExtrinsic(2)
+		);
+		assert_eq!(EventPhase::decode(&mut &[1][..]).unwrap(), EventPhase::Finalization);
+		assert_eq!(EventPhase::decode(&mut &[2][..]).unwrap(), EventPhase::Initialization);
+	}
+
+	#[test]
+	fn runtime_event_field_by_name() {
+		let BlockchainEvent::RuntimeEvent { event, .. } = runtime_event("Balances", "Transfer")
+		else {
+			unreachable!()
+		};
+		assert_eq!(event.field("amount"), Some("10"));
+		assert_eq!(event.field("from"), None);
+		assert_eq!(event.field_bytes("amount"), Some(&10u128.encode()[..]));
+		assert_eq!(event.field_bytes("from"), None);
+	}
+
+	#[test]
+	fn default_filter_lets_everything_through() {
+		let filter = EventFilter::default();
+		assert!(filter.apply(runtime_event("Balances", "Transfer")).is_some());
+		assert!(filter.apply(storage_changed(&[b"key"])).is_some());
+	}
+
+	#[test]
+	fn filter_by_kind() {
+		let filter = EventFilter { kinds: vec![EventKind::StorageChanged], ..Default::default() };
+		assert!(filter.apply(runtime_event("Balances", "Transfer")).is_none());
+		assert!(filter.apply(storage_changed(&[b"key"])).is_some());
+	}
+
+	#[test]
+	fn filter_by_pallet_and_variant() {
+		let filter = EventFilter {
+			runtime_events: vec!["Balances.Transfer".to_string(), "System".to_string()],
+			..Default::default()
+		};
+		assert!(filter.apply(runtime_event("Balances", "Transfer")).is_some());
+		assert!(filter.apply(runtime_event("Balances", "Deposit")).is_none());
+		assert!(filter.apply(runtime_event("System", "ExtrinsicSuccess")).is_some());
+		// Runtime event selectors do not affect other kinds of events.
+		assert!(filter.apply(storage_changed(&[b"key"])).is_some());
+	}
+
+	#[test]
+	fn filter_narrows_storage_changes_to_watched_prefixes() {
+		let filter =
+			EventFilter { storage_prefixes: vec![b"watched".to_vec()], ..Default::default() };
+
+		let Some(BlockchainEvent::StorageChanged { changes, .. }) =
+			filter.apply(storage_changed(&[b"watched_a", b"other", b"watched_b"]))
+		else {
+			panic!("expected storage changes under the watched prefix");
+		};
+		let keys: Vec<_> = changes.into_iter().map(|(key, _)| key).collect();
+		assert_eq!(keys, vec![b"watched_a".to_vec(), b"watched_b".to_vec()]);
+
+		assert!(filter.apply(storage_changed(&[b"other"])).is_none());
+	}
+}
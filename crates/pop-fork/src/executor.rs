@@ -72,11 +72,16 @@
 //! ```
 
 use crate::{
-	LocalStorageLayer,
+	LocalStorageLayer, RemoteStorageLayer, StorageCache,
 	error::ExecutorError,
 	local::LocalSharedValue,
-	strings::executor::{magic_signature, storage_prefixes},
+	rpc::{METADATA_LATEST, METADATA_V14},
+	strings::{
+		builder::runtime_api,
+		executor::{magic_signature, storage_prefixes},
+	},
 };
+use scale::{Decode, Encode};
 use smoldot::{
 	executor::{
 		self,
@@ -601,6 +606,121 @@ impl RuntimeExecutor {
 			state_version: version.state_version.map(|v| v.into()).unwrap_or(0),
 		})
 	}
+
+	/// Get the SCALE-encoded metadata of the runtime, without any chain state.
+	///
+	/// Requests the latest supported metadata version via `Metadata_metadata_at_version`,
+	/// falling back to `Metadata_metadata` for runtimes which do not provide it. This allows
+	/// inspecting a runtime WASM blob which is not deployed on any chain.
+	pub async fn metadata(&self) -> Result<Vec<u8>, ExecutorError> {
+		let storage = Self::detached_storage().await?;
+
+		for version in (METADATA_V14..=METADATA_LATEST).rev() {
+			if let Ok(result) = self
+				.call(runtime_api::METADATA_METADATA_AT_VERSION, &version.encode(), &storage)
+				.await && let Ok(Some(metadata)) =
+				Option::<Vec<u8>>::decode(&mut result.output.as_slice())
+			{
+				return Ok(metadata);
+			}
+		}
+		let result = self.call(runtime_api::METADATA_METADATA, &[], &storage).await?;
+		Vec::<u8>::decode(&mut result.output.as_slice()).map_err(|e| ExecutorError::RuntimeError {
+			method: runtime_api::METADATA_METADATA.to_string(),
+			message: format!("Failed to decode metadata: {e}"),
+		})
+	}
+
+	/// Get the version of the runtime by executing `Core_version`, without any chain state.
+	///
+	/// Unlike [`RuntimeExecutor::runtime_version`], this includes the runtime APIs implemented by
+	/// the runtime.
+	pub async fn core_version(&self) -> Result<CoreVersion, ExecutorError> {
+		let storage = Self::detached_storage().await?;
+		let result = self.call(runtime_api::CORE_VERSION, &[], &storage).await?;
+		CoreVersion::decode(&mut result.output.as_slice()).map_err(|e| {
+			ExecutorError::RuntimeError {
+				method: runtime_api::CORE_VERSION.to_string(),
+				message: format!("Failed to decode runtime version: {e}"),
+			}
+		})
+	}
+
+	/// Get the names of the genesis presets of the runtime by executing
+	/// `GenesisBuilder_preset_names`, without any chain state.
+	pub async fn genesis_preset_names(&self) -> Result<Vec<String>, ExecutorError> {
+		let storage = Self::detached_storage().await?;
+		let result = self.call(runtime_api::GENESIS_BUILDER_PRESET_NAMES, &[], &storage).await?;
+		let names = Vec::<Vec<u8>>::decode(&mut result.output.as_slice()).map_err(|e| {
+			ExecutorError::RuntimeError {
+				method: runtime_api::GENESIS_BUILDER_PRESET_NAMES.to_string(),
+				message: format!("Failed to decode preset names: {e}"),
+			}
+		})?;
+		Ok(names
+			.into_iter()
+			.map(|name| String::from_utf8_lossy(&name).into_owned())
+			.collect())
+	}
+
+	/// Get the JSON of a genesis preset of the runtime by executing `GenesisBuilder_get_preset`,
+	/// without any chain state.
+	///
+	/// # Arguments
+	/// * `name` - The name of the preset, or `None` for the default preset.
+	///
+	/// Returns `None` if the runtime does not provide the preset.
+	pub async fn genesis_preset(
+		&self,
+		name: Option<&str>,
+	) -> Result<Option<Vec<u8>>, ExecutorError> {
+		let storage = Self::detached_storage().await?;
+		let args = name.map(|name| name.as_bytes().to_vec()).encode();
+		let result = self.call(runtime_api::GENESIS_BUILDER_GET_PRESET, &args, &storage).await?;
+		Option::<Vec<u8>>::decode(&mut result.output.as_slice()).map_err(|e| {
+			ExecutorError::RuntimeError {
+				method: runtime_api::GENESIS_BUILDER_GET_PRESET.to_string(),
+				message: format!("Failed to decode preset: {e}"),
+			}
+		})
+	}
+
+	/// Build the genesis state of the runtime from a JSON genesis config by executing
+	/// `GenesisBuilder_build_state`, without any chain state.
+	///
+	/// The outer result fails if the runtime could not be executed, while the inner result holds
+	/// the error reported by the runtime, such as a config which could not be deserialized.
+	///
+	/// # Arguments
+	/// * `config` - The JSON genesis config, either complete or a patch of the default config.
+	pub async fn build_genesis_state(
+		&self,
+		config: &[u8],
+	) -> Result<Result<(), String>, ExecutorError> {
+		let storage = Self::detached_storage().await?;
+		let result = self
+			.call(runtime_api::GENESIS_BUILDER_BUILD_STATE, &config.encode(), &storage)
+			.await?;
+		Result::<(), String>::decode(&mut result.output.as_slice()).map_err(|e| {
+			ExecutorError::RuntimeError {
+				method: runtime_api::GENESIS_BUILDER_BUILD_STATE.to_string(),
+				message: format!("Failed to decode genesis build result: {e}"),
+			}
+		})
+	}
+
+	/// Storage without any chain state, for runtime calls which do not depend on it.
+	async fn detached_storage() -> Result<LocalStorageLayer, ExecutorError> {
+		let cache = StorageCache::in_memory().await.map_err(|e| ExecutorError::StorageError {
+			key: String::new(),
+			message: e.to_string(),
+		})?;
+		Ok(LocalStorageLayer::without_metadata(
+			RemoteStorageLayer::detached(cache),
+			0,
+			Default::default(),
+		))
+	}
 }
 
 /// Runtime version information.
@@ -622,6 +742,27 @@ pub struct RuntimeVersion {
 	pub state_version: u8,
 }
 
+/// Runtime version information as returned by `Core_version`.
+#[derive(Debug, Clone, Decode)]
+pub struct CoreVersion {
+	/// Spec name (e.g., "polkadot", "kusama").
+	pub spec_name: String,
+	/// Implementation name.
+	pub impl_name: String,
+	/// Authoring version.
+	pub authoring_version: u32,
+	/// Spec version.
+	pub spec_version: u32,
+	/// Implementation version.
+	pub impl_version: u32,
+	/// The runtime APIs implemented, as the blake2-64 hash of their name and their version.
+	pub apis: Vec<([u8; 8], u32)>,
+	/// Transaction version.
+	pub transaction_version: u32,
+	/// State version (0 or 1).
+	pub state_version: u8,
+}
+
 /// Create a prefixed key for child storage access.
 fn prefixed_child_key(child: impl Iterator<Item = u8>, key: impl Iterator<Item = u8>) -> Vec<u8> {
 	[storage_prefixes::DEFAULT_CHILD_STORAGE, &child.collect::<Vec<_>>(), &key.collect::<Vec<_>>()]
@@ -0,0 +1,262 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Raw chain spec genesis for forks without an upstream node.
+//!
+//! [`RawChainSpec`] reads the genesis storage of a raw chain spec (as produced by
+//! `pop build spec --raw`) so that a fork can start from the genesis block of a chain that
+//! has never been launched. The genesis header is derived the same way a node does it: the
+//! state root is computed over the genesis storage and the extrinsics root is the root of an
+//! empty trie, so the resulting genesis hash matches the one of a node started from the
+//! same spec.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use pop_fork::RawChainSpec;
+//!
+//! let spec = RawChainSpec::from_file(Path::new("raw.json"))?;
+//! println!("Chain: {} ({})", spec.name, spec.id);
+//! ```
+
+use crate::{BlockError, DigestItem, builder::Header, strings::executor::storage_prefixes};
+use serde_json::Value;
+use sp_core::Blake2Hasher;
+use sp_trie::{LayoutV0, LayoutV1};
+use std::{collections::BTreeMap, path::Path};
+use subxt::{config::substrate::H256, ext::codec::Encode};
+use trie_db::TrieConfiguration;
+
+/// Storage entries ordered by key.
+pub type StorageEntries = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// The parts of a raw chain spec needed to start a fork from its genesis block.
+#[derive(Clone, Debug)]
+pub struct RawChainSpec {
+	/// Human-readable chain name (e.g. "Development").
+	pub name: String,
+	/// Chain identifier (e.g. "dev").
+	pub id: String,
+	/// Chain properties such as the token symbol and decimals, if any.
+	pub properties: Option<Value>,
+	/// Genesis storage of the main trie.
+	top: StorageEntries,
+	/// Genesis storage of the default child tries, keyed by the unprefixed child trie key.
+	children_default: BTreeMap<Vec<u8>, StorageEntries>,
+}
+
+impl RawChainSpec {
+	/// Read a raw chain spec from a file.
+	///
+	/// # Arguments
+	/// * `path` - Path to the chain spec JSON file.
+	pub fn from_file(path: &Path) -> Result<Self, BlockError> {
+		let contents = std::fs::read_to_string(path).map_err(|e| {
+			BlockError::InvalidChainSpec(format!("failed to read {}: {e}", path.display()))
+		})?;
+		Self::from_json(&contents)
+	}
+
+	/// Parse a raw chain spec from its JSON representation.
+	///
+	/// Fails if the spec has no raw genesis storage (`genesis.raw.top`), as is the case for
+	/// plain chain specs, which must be converted to raw first.
+	pub fn from_json(json: &str) -> Result<Self, BlockError> {
+		let spec: Value = serde_json::from_str(json)
+			.map_err(|e| BlockError::InvalidChainSpec(format!("invalid JSON: {e}")))?;
+
+		let raw = spec.pointer("/genesis/raw").ok_or_else(|| {
+			BlockError::InvalidChainSpec(
+				"no raw genesis storage found (`genesis.raw`), convert the chain spec to raw first"
+					.to_string(),
+			)
+		})?;
+		let top = raw
+			.get("top")
+			.ok_or_else(|| {
+				BlockError::InvalidChainSpec("missing `genesis.raw.top` storage".to_string())
+			})
+			.and_then(parse_storage)?;
+
+		let mut children_default = BTreeMap::new();
+		if let Some(children) = raw.get("childrenDefault").and_then(Value::as_object) {
+			for (child_key, storage) in children {
+				children_default.insert(decode_hex(child_key)?, parse_storage(storage)?);
+			}
+		}
+
+		Ok(Self {
+			name: spec.get("name").and_then(Value::as_str).unwrap_or_default().to_string(),
+			id: spec.get("id").and_then(Value::as_str).unwrap_or_default().to_string(),
+			properties: spec.get("properties").filter(|p| !p.is_null()).cloned(),
+			top,
+			children_default,
+		})
+	}
+
+	/// Get the runtime code (`:code`) of the genesis state, if present.
+	pub fn runtime_code(&self) -> Option<&[u8]> {
+		self.top.get(sp_core::storage::well_known_keys::CODE).map(Vec::as_slice)
+	}
+
+	/// Get the genesis storage as a single flat key space.
+	///
+	/// Contains the main trie entries, the root of every default child trie under its
+	/// `:child_storage:default:` key, and the child trie entries prefixed the same way the
+	/// runtime executor addresses them.
+	///
+	/// # Arguments
+	/// * `state_version` - State version of the genesis runtime (0 or 1).
+	pub fn storage(&self, state_version: u8) -> StorageEntries {
+		let mut storage = self.top_with_child_roots(state_version);
+		for (child_key, entries) in &self.children_default {
+			for (key, value) in entries {
+				let prefixed =
+					[storage_prefixes::DEFAULT_CHILD_STORAGE, child_key.as_slice(), key.as_slice()]
+						.concat();
+				storage.insert(prefixed, value.clone());
+			}
+		}
+		storage
+	}
+
+	/// Compute the state root of the genesis storage.
+	///
+	/// # Arguments
+	/// * `state_version` - State version of the genesis runtime (0 or 1).
+	pub fn state_root(&self, state_version: u8) -> H256 {
+		trie_root(&self.top_with_child_roots(state_version), state_version)
+	}
+
+	/// Build the SCALE-encoded genesis header.
+	///
+	/// # Arguments
+	/// * `state_version` - State version of the genesis runtime (0 or 1).
+	pub fn genesis_header(&self, state_version: u8) -> Vec<u8> {
+		Header {
+			parent_hash: H256::zero(),
+			number: 0,
+			state_root: self.state_root(state_version),
+			extrinsics_root: trie_root(&StorageEntries::new(), state_version),
+			digest: Vec::<DigestItem>::new(),
+		}
+		.encode()
+	}
+
+	/// Main trie entries, including the roots of the default child tries.
+	fn top_with_child_roots(&self, state_version: u8) -> StorageEntries {
+		let mut top = self.top.clone();
+		for (child_key, entries) in &self.children_default {
+			let root = trie_root(entries, state_version);
+			top.insert(
+				[storage_prefixes::DEFAULT_CHILD_STORAGE, child_key.as_slice()].concat(),
+				root.as_bytes().to_vec(),
+			);
+		}
+		top
+	}
+}
+
+/// Compute the trie root of `entries` using the layout of `state_version`.
+fn trie_root(entries: &StorageEntries, state_version: u8) -> H256 {
+	let root = match state_version {
+		0 => LayoutV0::<Blake2Hasher>::trie_root(entries),
+		_ => LayoutV1::<Blake2Hasher>::trie_root(entries),
+	};
+	H256::from_slice(root.as_ref())
+}
+
+/// Parse a JSON object of hex-encoded keys and values.
+fn parse_storage(storage: &Value) -> Result<StorageEntries, BlockError> {
+	let storage = storage.as_object().ok_or_else(|| {
+		BlockError::InvalidChainSpec("genesis storage must be a JSON object".to_string())
+	})?;
+	storage
+		.iter()
+		.map(|(key, value)| {
+			let value = value.as_str().ok_or_else(|| {
+				BlockError::InvalidChainSpec(format!("value of {key} must be a hex string"))
+			})?;
+			Ok((decode_hex(key)?, decode_hex(value)?))
+		})
+		.collect()
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, BlockError> {
+	hex::decode(value.trim_start_matches("0x"))
+		.map_err(|e| BlockError::InvalidChainSpec(format!("invalid hex `{value}`: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale::Decode;
+
+	const SPEC: &str = r#"{
+		"name": "Development",
+		"id": "dev",
+		"properties": { "tokenSymbol": "UNIT", "tokenDecimals": 12 },
+		"genesis": {
+			"raw": {
+				"top": { "0x3a636f6465": "0x0061736d", "0x0102": "0x03" },
+				"childrenDefault": { "0x6368696c64": { "0x04": "0x05" } }
+			}
+		}
+	}"#;
+
+	#[derive(Decode)]
+	struct DecodedHeader {
+		parent_hash: H256,
+		#[codec(compact)]
+		number: u32,
+		state_root: H256,
+		extrinsics_root: H256,
+		digest: Vec<DigestItem>,
+	}
+
+	#[test]
+	fn from_json_reads_raw_genesis() {
+		let spec = RawChainSpec::from_json(SPEC).unwrap();
+		assert_eq!(spec.name, "Development");
+		assert_eq!(spec.id, "dev");
+		assert_eq!(spec.properties.as_ref().unwrap()["tokenSymbol"], "UNIT");
+		assert_eq!(spec.runtime_code(), Some(&[0x00, 0x61, 0x73, 0x6d][..]));
+	}
+
+	#[test]
+	fn from_json_rejects_plain_spec() {
+		let plain = r#"{ "name": "Development", "genesis": { "runtimeGenesis": {} } }"#;
+		let err = RawChainSpec::from_json(plain).unwrap_err();
+		assert!(matches!(err, BlockError::InvalidChainSpec(_)));
+		assert!(err.to_string().contains("convert the chain spec to raw"));
+	}
+
+	#[test]
+	fn from_json_rejects_invalid_hex() {
+		let spec = r#"{ "genesis": { "raw": { "top": { "0xzz": "0x00" } } } }"#;
+		assert!(matches!(RawChainSpec::from_json(spec), Err(BlockError::InvalidChainSpec(_))));
+	}
+
+	#[test]
+	fn storage_includes_child_tries() {
+		let spec = RawChainSpec::from_json(SPEC).unwrap();
+		let storage = spec.storage(1);
+		let child_root_key = [storage_prefixes::DEFAULT_CHILD_STORAGE, b"child"].concat();
+		let child_entry_key = [child_root_key.as_slice(), &[0x04]].concat();
+
+		assert_eq!(storage.get(&[0x01, 0x02][..]), Some(&vec![0x03]));
+		assert_eq!(storage.get(&child_entry_key), Some(&vec![0x05]));
+		assert_eq!(storage.get(&child_root_key).map(Vec::len), Some(32));
+	}
+
+	#[test]
+	fn genesis_header_commits_to_state() {
+		let spec = RawChainSpec::from_json(SPEC).unwrap();
+		let header = DecodedHeader::decode(&mut spec.genesis_header(1).as_slice()).unwrap();
+
+		assert_eq!(header.parent_hash, H256::zero());
+		assert_eq!(header.number, 0);
+		assert_eq!(header.state_root, spec.state_root(1));
+		assert_eq!(header.extrinsics_root, H256::from(sp_core::blake2_256(&[0])));
+		assert!(header.digest.is_empty());
+	}
+}
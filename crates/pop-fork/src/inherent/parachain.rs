@@ -17,6 +17,10 @@
 //! 4. Regenerates the storage root to match the updated proof
 //! 5. Re-encodes the extrinsic with all updated data
 //!
+//! A chain started from its genesis block (see [`crate::Blockchain::fork_from_chain_spec`])
+//! has no such extrinsic to start from, so the first one is synthesized from a minimal relay
+//! chain state containing the host configuration, the relay slot and the parachain head.
+//!
 //! # Why Proof Modification is Needed
 //!
 //! The parachain runtime validates that its head appears in the relay chain
@@ -34,9 +38,12 @@
 
 use super::relay_proof;
 use crate::{
-	Block, BlockBuilderError, DigestItem, RuntimeExecutor, consensus_engine,
+	Block, BlockBuilderError, DigestItem, RuntimeExecutor, TimestampInherent, consensus_engine,
 	inherent::InherentProvider,
-	strings::{executor::magic_signature, inherent::parachain as strings},
+	strings::{
+		executor::magic_signature,
+		inherent::{parachain as strings, timestamp::slot_duration},
+	},
 };
 use async_trait::async_trait;
 use log::warn;
@@ -60,6 +67,15 @@ const MIN_EXTRINSIC_BODY_SIZE: usize = 3;
 /// how many relay slots advance per parachain block.
 const RELAY_SLOTS_PER_PARA_BLOCK: u64 = 2;
 
+/// Relay chain slot duration assumed when synthesizing the genesis validation data.
+const RELAY_SLOT_DURATION_MS: u64 = 6_000;
+
+/// Relay parent number used in the synthesized genesis validation data.
+const GENESIS_RELAY_PARENT_NUMBER: u32 = 1;
+
+/// Maximum proof-of-validity size used in the synthesized genesis validation data (5 MiB).
+const GENESIS_MAX_POV_SIZE: u32 = 5 * 1024 * 1024;
+
 // ============================================================================
 // Types for decoding/encoding the inherent data
 // ============================================================================
@@ -77,6 +93,51 @@ struct PersistedValidationData {
 	max_pov_size: u32,
 }
 
+/// Subset of the relay chain host configuration read by `parachain-system`.
+///
+/// Mirrors `cumulus_primitives_core::AbridgedHostConfiguration`.
+#[derive(Debug, Clone, Encode, Decode)]
+struct AbridgedHostConfiguration {
+	max_code_size: u32,
+	max_head_data_size: u32,
+	max_upward_queue_count: u32,
+	max_upward_queue_size: u32,
+	max_upward_message_size: u32,
+	max_upward_message_num_per_candidate: u32,
+	hrmp_max_message_num_per_candidate: u32,
+	validation_upgrade_cooldown: u32,
+	validation_upgrade_delay: u32,
+	async_backing_params: AsyncBackingParams,
+}
+
+/// Async backing parameters of the relay chain host configuration.
+#[derive(Debug, Clone, Encode, Decode)]
+struct AsyncBackingParams {
+	max_candidate_depth: u32,
+	allowed_ancestry_len: u32,
+}
+
+impl Default for AbridgedHostConfiguration {
+	/// Values of a local development relay chain.
+	fn default() -> Self {
+		Self {
+			max_code_size: 3 * 1024 * 1024,
+			max_head_data_size: 32 * 1024,
+			max_upward_queue_count: 8,
+			max_upward_queue_size: 1024 * 1024,
+			max_upward_message_size: 50 * 1024,
+			max_upward_message_num_per_candidate: 10,
+			hrmp_max_message_num_per_candidate: 10,
+			validation_upgrade_cooldown: 2,
+			validation_upgrade_delay: 2,
+			async_backing_params: AsyncBackingParams {
+				max_candidate_depth: 3,
+				allowed_ancestry_len: 2,
+			},
+		}
+	}
+}
+
 /// Storage proof from the relay chain (just a set of trie nodes).
 #[derive(Debug, Clone)]
 struct RelayChainStateProof {
@@ -313,6 +374,57 @@ impl ParachainInherent {
 		descendants
 	}
 
+	/// Build the `setValidationData` extrinsic for the first block after genesis.
+	///
+	/// A chain started from its genesis block has never been backed by a relay chain, so
+	/// there is no inherent in the parent block to update. This builds one from a minimal
+	/// relay chain state: the host configuration, the relay slot matching the next
+	/// parachain slot, and the parachain head. Subsequent blocks update this inherent as
+	/// usual.
+	fn genesis_inherent(
+		para_id: u32,
+		para_head: &[u8],
+		relay_slot: u64,
+		(pallet, call): (u8, u8),
+	) -> Result<Vec<u8>, BlockBuilderError> {
+		let heads_key = relay_proof::paras_heads_key(para_id);
+		let config_key = relay_proof::active_config_key();
+		let entries: Vec<(&[u8], Vec<u8>)> = vec![
+			(&heads_key[..], para_head.to_vec().encode()),
+			(&relay_proof::CURRENT_SLOT_KEY[..], relay_slot.encode()),
+			(&config_key[..], AbridgedHostConfiguration::default().encode()),
+		];
+
+		let (root, proof) =
+			relay_proof::build_proof(entries).map_err(|e| BlockBuilderError::InherentProvider {
+				provider: "ParachainSystem".to_string(),
+				message: format!("Failed to build relay proof: {}", e),
+			})?;
+
+		let validation_data = PersistedValidationData {
+			parent_head: para_head.to_vec(),
+			relay_parent_number: GENESIS_RELAY_PARENT_NUMBER,
+			relay_parent_storage_root: root,
+			max_pov_size: GENESIS_MAX_POV_SIZE,
+		};
+
+		let mut call_data = Vec::new();
+		call_data.extend(validation_data.encode());
+		call_data.extend(RelayChainStateProof::from(proof).encode());
+		call_data.extend(Vec::<RelayHeader>::new().encode());
+		call_data.extend(None::<Vec<u8>>.encode());
+		// InboundMessagesData: empty full and hashed downward and horizontal messages.
+		call_data.extend([0u8; 4]);
+
+		let mut body = vec![EXTRINSIC_FORMAT_VERSION_V4, pallet, call];
+		body.extend(call_data);
+
+		let mut result = Compact(body.len() as u32).encode();
+		result.extend(body);
+
+		Ok(result)
+	}
+
 	/// Process the inherent: update proof, storage root, and relay parent descendants.
 	fn process_inherent(
 		&self,
@@ -457,7 +569,7 @@ impl InherentProvider for ParachainInherent {
 	async fn provide(
 		&self,
 		parent: &Block,
-		_executor: &RuntimeExecutor,
+		executor: &RuntimeExecutor,
 	) -> Result<Vec<Vec<u8>>, BlockBuilderError> {
 		// Check if ParachainSystem pallet exists in metadata
 		let metadata = parent.metadata().await?;
@@ -504,6 +616,31 @@ impl InherentProvider for ParachainInherent {
 
 				Ok(vec![processed])
 			},
+			None if parent.number == 0 => {
+				// Started from genesis: the relay slot must match the slot of the timestamp
+				// set in this block, which advances the current time by one parachain slot.
+				let slot_duration = TimestampInherent::get_slot_duration_from_runtime(
+					executor,
+					parent.storage(),
+					&metadata,
+					slot_duration::PARACHAIN_FALLBACK_MS,
+				)
+				.await;
+				let now = std::time::SystemTime::now()
+					.duration_since(std::time::UNIX_EPOCH)
+					.map(|d| d.as_millis() as u64)
+					.unwrap_or(0);
+				let relay_slot = now.saturating_add(slot_duration) / RELAY_SLOT_DURATION_MS;
+
+				let inherent = Self::genesis_inherent(
+					para_id,
+					&parent.header,
+					relay_slot,
+					(pallet_index, call_index),
+				)?;
+
+				Ok(vec![inherent])
+			},
 			None => {
 				warn!("[ParachainInherent] No setValidationData extrinsic found in parent block");
 				Ok(vec![])
@@ -581,4 +718,30 @@ mod tests {
 		assert_eq!(decoded.relay_parent_storage_root, [0xab; 32]);
 		assert_eq!(decoded.max_pov_size, 5_000_000);
 	}
+
+	#[test]
+	fn genesis_inherent_commits_to_para_head_and_slot() {
+		let para_head = vec![7u8; 40];
+		let ext = ParachainInherent::genesis_inherent(1000, &para_head, 42, (51, 0)).unwrap();
+
+		let extrinsics = [ext.clone()];
+		let found = ParachainInherent::find_validation_data_extrinsic(&extrinsics, 51, 0);
+		assert!(found.is_some());
+
+		let (_len, body) = decode_compact_len(&ext).unwrap();
+		let parsed = ParachainInherent::parse_inherent_data(&body[3..]).unwrap();
+		assert_eq!(parsed.validation_data.parent_head, para_head);
+		assert!(parsed.relay_parent_descendants.is_empty());
+		assert_eq!(parsed.remaining, vec![0u8; 4]);
+
+		let proof: StorageProof = parsed.relay_chain_state.into();
+		let root = parsed.validation_data.relay_parent_storage_root;
+		let slot: Option<u64> =
+			relay_proof::read_from_proof(&proof, &root, &relay_proof::CURRENT_SLOT_KEY).unwrap();
+		let head: Option<Vec<u8>> =
+			relay_proof::read_from_proof(&proof, &root, &relay_proof::paras_heads_key(1000))
+				.unwrap();
+		assert_eq!(slot, Some(42));
+		assert_eq!(head, Some(para_head));
+	}
 }
@@ -30,6 +30,14 @@ pub const CURRENT_SLOT_KEY: [u8; 32] = [
 	0x06, 0x15, 0x5b, 0x3c, 0xd9, 0xa8, 0xc9, 0xe5, 0xe9, 0xa2, 0x3f, 0xd5, 0xdc, 0x13, 0xa5, 0xed,
 ];
 
+/// Construct the storage key for `Configuration::ActiveConfig` on the relay chain.
+///
+/// The key is `twox_128("Configuration") ++ twox_128("ActiveConfig")`. Parachains read the
+/// abridged host configuration from this key, so it must be part of every relay proof.
+pub fn active_config_key() -> Vec<u8> {
+	[sp_core::twox_128(b"Configuration"), sp_core::twox_128(b"ActiveConfig")].concat()
+}
+
 /// Construct the storage key for `Paras::Heads(para_id)`.
 ///
 /// The key format is: prefix ++ twox_64(para_id) ++ para_id
@@ -187,6 +195,35 @@ where
 	Ok((root_hash.into(), new_proof))
 }
 
+/// Build a storage proof for a trie containing exactly the given entries.
+///
+/// Used when there is no relay chain proof to start from (e.g. at genesis).
+///
+/// # Returns
+///
+/// A tuple of (root, proof) or an error.
+pub fn build_proof<'a, I>(entries: I) -> Result<([u8; 32], StorageProof), ProofError>
+where
+	I: IntoIterator<Item = (&'a [u8], Vec<u8>)>,
+{
+	let mut db = MemoryDB::<Blake2Hasher>::default();
+	let mut root_hash = TrieHash::<RelayLayout>::default();
+
+	{
+		use sp_trie::TrieMut;
+		let mut trie = TrieDBMutBuilder::<RelayLayout>::new(&mut db, &mut root_hash).build();
+
+		for (key, value) in entries {
+			trie.insert(key, &value)
+				.map_err(|e| ProofError::TrieError(format!("Failed to insert: {:?}", e)))?;
+		}
+
+		trie.commit();
+	}
+
+	Ok((root_hash.into(), extract_proof_from_db(&db)))
+}
+
 /// Extract a StorageProof from a MemoryDB.
 ///
 /// This collects all trie nodes from the database into a proof.
@@ -234,6 +271,26 @@ mod tests {
 		assert_eq!(CURRENT_SLOT_KEY.to_vec(), expected);
 	}
 
+	#[test]
+	fn active_config_key_is_correct() {
+		let key = active_config_key();
+
+		assert_eq!(key.len(), 32);
+		assert_eq!(&key[..16], &sp_core::twox_128(b"Configuration")[..]);
+	}
+
+	#[test]
+	fn build_proof_contains_entries() {
+		let entries: Vec<(&[u8], Vec<u8>)> =
+			vec![(&CURRENT_SLOT_KEY[..], 42u64.encode()), (b"other", vec![1, 2, 3])];
+
+		let (root, proof) = build_proof(entries).unwrap();
+
+		assert_eq!(read_from_proof::<u64>(&proof, &root, &CURRENT_SLOT_KEY).unwrap(), Some(42));
+		assert_eq!(read_raw_from_proof(&proof, &root, b"other").unwrap(), Some(vec![1, 2, 3]));
+		assert_eq!(read_raw_from_proof(&proof, &root, b"missing").unwrap(), None);
+	}
+
 	#[test]
 	fn paras_heads_key_format_is_correct() {
 		let para_id: u32 = 1000;
@@ -35,18 +35,22 @@
 //!
 //! - [`Blockchain`] - Main entry point for creating and managing forked chains
 //! - [`ChainType`] - Identifies whether the chain is a relay chain or parachain
+//! - [`BlockchainEvent`] - Notifications about new blocks, extrinsics, runtime events and storage
+//! - [`EventFilter`] - Selects the events delivered to a subscription
 //!
 //! ## Block and Block Building
 //!
 //! - [`Block`] - Represents a block in the forked chain with its storage state
 //! - [`BlockBuilder`] - Constructs new blocks by applying inherents and extrinsics
 //! - [`InherentProvider`] - Trait for generating inherent (timestamp, etc.)
+//! - [`BlockProfile`] - Weight, fee and proof size profile of a built block
 //!
 //! ## Storage Layers
 //!
 //! - [`LocalStorageLayer`] - Tracks local modifications to forked state
 //! - [`RemoteStorageLayer`] - Cache-through layer that lazily fetches from RPC
 //! - [`StorageCache`] - SQLite-based persistent cache for storage values
+//! - [`RawChainSpec`] - Genesis storage of a raw chain spec, for forks without an upstream node
 //!
 //! ## Runtime Execution
 //!
@@ -63,10 +67,13 @@ mod builder;
 mod cache;
 pub mod dev;
 pub mod error;
+mod events;
 pub mod executor;
+mod genesis;
 pub mod inherent;
 mod local;
 mod models;
+mod profile;
 mod remote;
 mod rpc;
 pub mod rpc_server;
@@ -92,13 +99,19 @@ pub use error::{
 	BlockBuilderError, BlockError, CacheError, ExecutorError, LocalStorageError,
 	RemoteStorageError, RpcClientError, TxPoolError,
 };
+pub use events::{EventFilter, EventKind, EventPhase, FilteredEvents, RuntimeEvent};
 pub use executor::{
-	ExecutorConfig, RuntimeCallResult, RuntimeExecutor, RuntimeLog, RuntimeVersion,
+	CoreVersion, ExecutorConfig, RuntimeCallResult, RuntimeExecutor, RuntimeLog, RuntimeVersion,
 	SignatureMockMode,
 };
+pub use genesis::RawChainSpec;
 pub use inherent::{InherentProvider, ParachainInherent, TimestampInherent, default_providers};
 pub use local::LocalStorageLayer;
 pub use models::BlockRow;
+pub use profile::{
+	BlockLimits, BlockProfile, DispatchClass, DispatchInfo, ExtrinsicProfile, FeeInfo,
+	PerDispatchClass, Weight,
+};
 pub use remote::RemoteStorageLayer;
 pub use rpc::ForkRpcClient;
 pub use txpool::TxPool;
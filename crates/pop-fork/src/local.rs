@@ -140,6 +140,38 @@ impl LocalStorageLayer {
 		let mut metadata_versions = BTreeMap::new();
 		metadata_versions.insert(first_forked_block_number, Arc::new(metadata));
 
+		Self::with_metadata_versions(
+			parent,
+			first_forked_block_number,
+			first_forked_block_hash,
+			metadata_versions,
+		)
+	}
+
+	/// Create a new local storage layer with no metadata registered yet.
+	///
+	/// Used when the metadata has to be obtained by executing the runtime against the
+	/// layer itself (e.g. at genesis). The metadata must then be registered with
+	/// [`register_metadata_version`](Self::register_metadata_version) before it is queried.
+	pub(crate) fn without_metadata(
+		parent: RemoteStorageLayer,
+		first_forked_block_number: u32,
+		first_forked_block_hash: H256,
+	) -> Self {
+		Self::with_metadata_versions(
+			parent,
+			first_forked_block_number,
+			first_forked_block_hash,
+			BTreeMap::new(),
+		)
+	}
+
+	fn with_metadata_versions(
+		parent: RemoteStorageLayer,
+		first_forked_block_number: u32,
+		first_forked_block_hash: H256,
+		metadata_versions: MetadataVersions,
+	) -> Self {
 		Self {
 			parent,
 			first_forked_block_hash,
@@ -249,15 +281,16 @@ impl LocalStorageLayer {
 		block_number: u32,
 	) -> Result<Arc<Metadata>, LocalStorageError> {
 		// Get block hash for this block number
-		let block_hash = self.parent.rpc().block_hash_at(block_number).await?.ok_or_else(|| {
-			LocalStorageError::MetadataNotFound(format!(
-				"Block {} not found on remote node",
-				block_number
-			))
-		})?;
+		let block_hash =
+			self.parent.block_hash_by_number(block_number).await?.ok_or_else(|| {
+				LocalStorageError::MetadataNotFound(format!(
+					"Block {} not found on remote node",
+					block_number
+				))
+			})?;
 
 		// Fetch and decode metadata from remote
-		let metadata = self.parent.rpc().metadata(block_hash).await?;
+		let metadata = self.parent.metadata(block_hash).await?;
 
 		Ok(Arc::new(metadata))
 	}
@@ -0,0 +1,321 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! Block weight and fee profiling.
+//!
+//! A [`BlockProfile`] describes how much of the block limits a built block consumes. Every
+//! extrinsic is profiled with the weight and fee estimated by
+//! `TransactionPaymentApi_query_info` and the weight actually charged, as reported by the
+//! dispatch info of its `System.ExtrinsicSuccess` or `System.ExtrinsicFailed` event. The block
+//! totals come from `System::BlockWeight` and are compared against the `System.BlockWeights`
+//! and `System.BlockLength` constants of the runtime.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let result = blockchain.build_block(extrinsics).await?;
+//! let profile = blockchain.profile_block(&result).await?;
+//! for extrinsic in &profile.extrinsics {
+//!     println!("#{}: {:?}", extrinsic.index, extrinsic.actual_weight);
+//! }
+//! ```
+
+use crate::strings::profile::{
+	constants,
+	storage_keys::{BLOCK_WEIGHT, SYSTEM_PALLET},
+};
+use scale::{Decode, Encode};
+use subxt::{Metadata, config::substrate::H256};
+
+/// Two-dimensional weight: execution time and proof size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct Weight {
+	/// Computational time, in picoseconds.
+	#[codec(compact)]
+	pub ref_time: u64,
+	/// Size of the storage proof, in bytes.
+	#[codec(compact)]
+	pub proof_size: u64,
+}
+
+impl Weight {
+	/// Add two weights, saturating at the numeric bounds.
+	pub fn saturating_add(self, other: Self) -> Self {
+		Self {
+			ref_time: self.ref_time.saturating_add(other.ref_time),
+			proof_size: self.proof_size.saturating_add(other.proof_size),
+		}
+	}
+}
+
+/// Dispatch class of an extrinsic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode)]
+pub enum DispatchClass {
+	/// A normal dispatch.
+	Normal,
+	/// An operational dispatch.
+	Operational,
+	/// A mandatory dispatch, such as an inherent.
+	Mandatory,
+}
+
+/// A value for each dispatch class.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Encode, Decode)]
+pub struct PerDispatchClass<T> {
+	/// Value for normal dispatches.
+	pub normal: T,
+	/// Value for operational dispatches.
+	pub operational: T,
+	/// Value for mandatory dispatches.
+	pub mandatory: T,
+}
+
+impl PerDispatchClass<Weight> {
+	/// Sum of the weights of all dispatch classes.
+	pub fn total(&self) -> Weight {
+		self.normal.saturating_add(self.operational).saturating_add(self.mandatory)
+	}
+}
+
+/// Block limits configured in the runtime.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BlockLimits {
+	/// Maximum weight of a block (`BlockWeights::max_block`).
+	pub max_weight: Weight,
+	/// Maximum length of a block, in bytes, across all dispatch classes.
+	pub max_length: u32,
+}
+
+impl BlockLimits {
+	/// Read the block limits from the `System` constants of the runtime.
+	///
+	/// Returns `None` if the constants are missing or cannot be decoded.
+	pub fn from_metadata(metadata: &Metadata) -> Option<Self> {
+		let system = metadata.pallet_by_name(constants::SYSTEM_PALLET)?;
+
+		// `BlockWeights { base_block, max_block, per_class }`.
+		let mut weights = system.constant_by_name(constants::BLOCK_WEIGHTS)?.value();
+		let _base_block = Weight::decode(&mut weights).ok()?;
+		let max_weight = Weight::decode(&mut weights).ok()?;
+
+		// `BlockLength { max: PerDispatchClass<u32> }`.
+		let mut length = system.constant_by_name(constants::BLOCK_LENGTH)?.value();
+		let max = PerDispatchClass::<u32>::decode(&mut length).ok()?;
+		let max_length = max.normal.max(max.operational).max(max.mandatory);
+
+		Some(Self { max_weight, max_length })
+	}
+}
+
+/// Weight and class of a dispatched extrinsic.
+///
+/// Decoded from the leading fields shared by `DispatchInfo`, `DispatchEventInfo` and
+/// `RuntimeDispatchInfo`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DispatchInfo {
+	/// Weight of the dispatch.
+	pub weight: Weight,
+	/// Dispatch class.
+	pub class: DispatchClass,
+}
+
+impl DispatchInfo {
+	/// Decode the weight and class from the start of `bytes`, ignoring trailing fields.
+	pub fn decode_prefix(mut bytes: &[u8]) -> Option<Self> {
+		let weight = Weight::decode(&mut bytes).ok()?;
+		let class = DispatchClass::decode(&mut bytes).ok()?;
+		Some(Self { weight, class })
+	}
+}
+
+/// Result of `TransactionPaymentApi_query_info`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeInfo {
+	/// Estimated weight and class of the extrinsic.
+	pub dispatch: DispatchInfo,
+	/// Inclusion fee, excluding any tip.
+	pub partial_fee: u128,
+}
+
+impl FeeInfo {
+	/// Decode a `RuntimeDispatchInfo`.
+	///
+	/// The balance type is runtime specific, so the fee width is taken from the number of
+	/// bytes left after the weight and class.
+	pub fn decode(mut bytes: &[u8]) -> Option<Self> {
+		let weight = Weight::decode(&mut bytes).ok()?;
+		let class = DispatchClass::decode(&mut bytes).ok()?;
+		let partial_fee = match bytes.len() {
+			16 => u128::decode(&mut bytes).ok()?,
+			8 => u64::decode(&mut bytes).ok()?.into(),
+			4 => u32::decode(&mut bytes).ok()?.into(),
+			_ => return None,
+		};
+		Some(Self { dispatch: DispatchInfo { weight, class }, partial_fee })
+	}
+}
+
+/// Profile of a single extrinsic included in a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtrinsicProfile {
+	/// Position of the extrinsic in the block.
+	pub index: u32,
+	/// Blake2-256 hash of the extrinsic.
+	pub hash: H256,
+	/// Pallet of the call, if it could be decoded.
+	pub pallet: Option<String>,
+	/// Name of the call, if it could be decoded.
+	pub call: Option<String>,
+	/// Encoded length of the extrinsic, in bytes.
+	pub length: u32,
+	/// Dispatch class of the extrinsic.
+	pub class: Option<DispatchClass>,
+	/// Weight estimated by `TransactionPaymentApi_query_info`.
+	pub estimated_weight: Option<Weight>,
+	/// Inclusion fee estimated by `TransactionPaymentApi_query_info`.
+	pub partial_fee: Option<u128>,
+	/// Weight reported by the dispatch outcome event.
+	pub actual_weight: Option<Weight>,
+	/// Whether the dispatch succeeded, `None` if no dispatch outcome event was found.
+	pub success: Option<bool>,
+}
+
+/// Weight, length and proof size profile of a built block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockProfile {
+	/// Hash of the profiled block.
+	pub hash: H256,
+	/// Number of the profiled block.
+	pub number: u32,
+	/// Profiles of the extrinsics included in the block, inherents first.
+	pub extrinsics: Vec<ExtrinsicProfile>,
+	/// Number of extrinsics dropped while building the block.
+	pub dropped: usize,
+	/// Weight consumed per dispatch class (`System::BlockWeight`), if available.
+	pub weight: Option<PerDispatchClass<Weight>>,
+	/// Encoded length of all extrinsics, in bytes.
+	pub length: u32,
+	/// Block limits of the runtime, if available.
+	pub limits: Option<BlockLimits>,
+}
+
+impl BlockProfile {
+	/// Total weight consumed by the block.
+	///
+	/// Falls back to the sum of the actual extrinsic weights when `System::BlockWeight` is
+	/// unavailable.
+	pub fn total_weight(&self) -> Weight {
+		match &self.weight {
+			Some(weight) => weight.total(),
+			None => self
+				.extrinsics
+				.iter()
+				.filter_map(|extrinsic| extrinsic.actual_weight)
+				.fold(Weight::default(), Weight::saturating_add),
+		}
+	}
+
+	/// Estimated proof-of-validity size, in bytes.
+	///
+	/// The block body is part of the PoV, so the proof size of the consumed weight is
+	/// extended with the length of the extrinsics.
+	pub fn pov_estimate(&self) -> u64 {
+		self.total_weight().proof_size.saturating_add(self.length as u64)
+	}
+
+	/// Sum of the estimated inclusion fees of all extrinsics.
+	pub fn total_fee(&self) -> u128 {
+		self.extrinsics.iter().filter_map(|extrinsic| extrinsic.partial_fee).sum()
+	}
+}
+
+/// Storage key of `System::BlockWeight`.
+pub fn block_weight_key() -> Vec<u8> {
+	[sp_core::twox_128(SYSTEM_PALLET), sp_core::twox_128(BLOCK_WEIGHT)].concat()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn weight(ref_time: u64, proof_size: u64) -> Weight {
+		Weight { ref_time, proof_size }
+	}
+
+	fn extrinsic(index: u32, actual_weight: Option<Weight>, fee: Option<u128>) -> ExtrinsicProfile {
+		ExtrinsicProfile {
+			index,
+			hash: H256::zero(),
+			pallet: None,
+			call: None,
+			length: 100,
+			class: Some(DispatchClass::Normal),
+			estimated_weight: None,
+			partial_fee: fee,
+			actual_weight,
+			success: Some(true),
+		}
+	}
+
+	#[test]
+	fn block_weight_key_matches_system_block_weight() {
+		assert_eq!(
+			hex::encode(block_weight_key()),
+			"26aa394eea5630e07c48ae0c9558cef734abf5cb34d6244378cddbf18e849d96"
+		);
+	}
+
+	#[test]
+	fn weight_is_compact_encoded() {
+		let encoded = weight(1, 2).encode();
+		assert_eq!(encoded, vec![0x04, 0x08]);
+		assert_eq!(Weight::decode(&mut encoded.as_slice()).unwrap(), weight(1, 2));
+	}
+
+	#[test]
+	fn dispatch_info_ignores_trailing_fields() {
+		// `DispatchEventInfo { weight, class, pays_fee }`.
+		let bytes = (weight(10, 20), DispatchClass::Operational, 0u8).encode();
+		let info = DispatchInfo::decode_prefix(&bytes).unwrap();
+		assert_eq!(info.weight, weight(10, 20));
+		assert_eq!(info.class, DispatchClass::Operational);
+	}
+
+	#[test]
+	fn fee_info_decodes_balance_by_width() {
+		let wide = (weight(1, 2), DispatchClass::Normal, 1_000u128).encode();
+		assert_eq!(FeeInfo::decode(&wide).unwrap().partial_fee, 1_000);
+
+		let narrow = (weight(1, 2), DispatchClass::Normal, 1_000u64).encode();
+		assert_eq!(FeeInfo::decode(&narrow).unwrap().partial_fee, 1_000);
+
+		let invalid = (weight(1, 2), DispatchClass::Normal, 1u8).encode();
+		assert!(FeeInfo::decode(&invalid).is_none());
+	}
+
+	#[test]
+	fn total_weight_prefers_block_weight() {
+		let mut profile = BlockProfile {
+			hash: H256::zero(),
+			number: 1,
+			extrinsics: vec![
+				extrinsic(0, Some(weight(5, 50)), None),
+				extrinsic(1, Some(weight(7, 70)), Some(3)),
+			],
+			dropped: 0,
+			weight: None,
+			length: 200,
+			limits: None,
+		};
+		assert_eq!(profile.total_weight(), weight(12, 120));
+		assert_eq!(profile.pov_estimate(), 320);
+		assert_eq!(profile.total_fee(), 3);
+
+		profile.weight = Some(PerDispatchClass {
+			normal: weight(10, 100),
+			operational: weight(0, 0),
+			mandatory: weight(1, 10),
+		});
+		assert_eq!(profile.total_weight(), weight(11, 110));
+		assert_eq!(profile.pov_estimate(), 310);
+	}
+}
@@ -6,6 +6,10 @@
 //! from a live chain via RPC when values aren't in the local cache. This enables
 //! "lazy forking" where state is fetched on-demand rather than requiring a full sync.
 //!
+//! A layer created with [`RemoteStorageLayer::detached`] has no upstream node: the cache
+//! holds the complete state (e.g. the genesis storage of a raw chain spec) and misses are
+//! answered as empty storage.
+//!
 //! # Architecture
 //!
 //! ```text
@@ -123,6 +127,12 @@ impl std::fmt::Display for StorageStatsSnapshot {
 /// and only fetch from the remote RPC when the value isn't cached. Fetched
 /// values are automatically cached for subsequent reads.
 ///
+/// # Detached Mode
+///
+/// A detached layer (see [`RemoteStorageLayer::detached`]) treats the cache as the full
+/// state: uncached keys are empty, key enumeration is served from the cache and only
+/// cached blocks are known.
+///
 /// # Cloning
 ///
 /// `RemoteStorageLayer` is cheap to clone. Both `ForkRpcClient` and `StorageCache`
@@ -135,7 +145,7 @@ impl std::fmt::Display for StorageStatsSnapshot {
 /// cache handles concurrent access safely.
 #[derive(Clone, Debug)]
 pub struct RemoteStorageLayer {
-	rpc: ForkRpcClient,
+	rpc: Option<ForkRpcClient>,
 	cache: StorageCache,
 	stats: Arc<StorageStats>,
 }
@@ -147,12 +157,33 @@ impl RemoteStorageLayer {
 	/// * `rpc` - RPC client connected to the live chain
 	/// * `cache` - Storage cache for persisting fetched values
 	pub fn new(rpc: ForkRpcClient, cache: StorageCache) -> Self {
-		Self { rpc, cache, stats: Arc::new(StorageStats::default()) }
+		Self { rpc: Some(rpc), cache, stats: Arc::new(StorageStats::default()) }
+	}
+
+	/// Create a remote storage layer without an upstream node.
+	///
+	/// The cache must already hold the complete state of every block queried through this
+	/// layer, as nothing can be fetched on a miss.
+	///
+	/// # Arguments
+	/// * `cache` - Storage cache holding the state
+	pub fn detached(cache: StorageCache) -> Self {
+		Self { rpc: None, cache, stats: Arc::new(StorageStats::default()) }
+	}
+
+	/// Get a reference to the underlying RPC client, or `None` for a detached layer.
+	pub fn rpc(&self) -> Option<&ForkRpcClient> {
+		self.rpc.as_ref()
+	}
+
+	/// Whether this layer has no upstream node.
+	pub fn is_detached(&self) -> bool {
+		self.rpc.is_none()
 	}
 
-	/// Get a reference to the underlying RPC client.
-	pub fn rpc(&self) -> &ForkRpcClient {
-		&self.rpc
+	/// Get the RPC client, failing for a detached layer.
+	fn upstream(&self) -> Result<&ForkRpcClient, RemoteStorageError> {
+		self.rpc.as_ref().ok_or(RemoteStorageError::NoUpstream)
 	}
 
 	/// Get a reference to the underlying cache.
@@ -160,9 +191,9 @@ impl RemoteStorageLayer {
 		&self.cache
 	}
 
-	/// Get the RPC endpoint URL this layer is connected to.
-	pub fn endpoint(&self) -> &url::Url {
-		self.rpc.endpoint()
+	/// Get the RPC endpoint URL this layer is connected to, or `None` for a detached layer.
+	pub fn endpoint(&self) -> Option<&url::Url> {
+		self.rpc.as_ref().map(ForkRpcClient::endpoint)
 	}
 
 	/// Take a snapshot of the current storage access counters.
@@ -212,6 +243,11 @@ impl RemoteStorageLayer {
 			return Ok(cached);
 		}
 
+		// Without an upstream, the cache holds the full state.
+		let Some(rpc) = self.rpc() else {
+			return Ok(None);
+		};
+
 		// Speculative prefix prefetch: if the key is at least 32 bytes (pallet hash +
 		// storage item hash), bulk-fetch the FIRST PAGE of keys sharing that prefix.
 		// Only fetches one page to avoid blocking on large maps (e.g., Account maps
@@ -247,11 +283,11 @@ impl RemoteStorageLayer {
 
 		// Fallback: fetch individual key from RPC (with reconnect-retry)
 		self.stats.rpc_misses.fetch_add(1, Ordering::Relaxed);
-		let value = match self.rpc.storage(key, block_hash).await {
+		let value = match rpc.storage(key, block_hash).await {
 			Ok(v) => v,
 			Err(_) => {
-				self.rpc.reconnect().await?;
-				self.rpc.storage(key, block_hash).await?
+				rpc.reconnect().await?;
+				rpc.storage(key, block_hash).await?
 			},
 		};
 
@@ -298,17 +334,18 @@ impl RemoteStorageLayer {
 			}
 		}
 
-		// If everything was cached, return immediately
-		if uncached_keys.is_empty() {
-			return Ok(cached_results.into_iter().map(|c| c.flatten()).collect());
-		}
+		// If everything was cached (or there is nothing to fetch from), return immediately
+		let rpc = match self.rpc() {
+			Some(rpc) if !uncached_keys.is_empty() => rpc,
+			_ => return Ok(cached_results.into_iter().map(|c| c.flatten()).collect()),
+		};
 
 		// Fetch uncached keys from RPC (with reconnect-retry)
-		let fetched_values = match self.rpc.storage_batch(&uncached_keys, block_hash).await {
+		let fetched_values = match rpc.storage_batch(&uncached_keys, block_hash).await {
 			Ok(v) => v,
 			Err(_) => {
-				self.rpc.reconnect().await?;
-				self.rpc.storage_batch(&uncached_keys, block_hash).await?
+				rpc.reconnect().await?;
+				rpc.storage_batch(&uncached_keys, block_hash).await?
 			},
 		};
 
@@ -356,28 +393,25 @@ impl RemoteStorageLayer {
 		// Check existing progress
 		let progress = self.cache.get_prefix_scan_progress(block_hash, prefix).await?;
 
-		if let Some(ref p) = progress &&
-			p.is_complete
-		{
-			// Already done - return cached count
-			return Ok(self.cache.count_keys_by_prefix(block_hash, prefix).await?);
-		}
+		let rpc = match self.rpc() {
+			Some(rpc) if !progress.as_ref().is_some_and(|p| p.is_complete) => rpc,
+			// Already done (or nothing to scan) - return cached count
+			_ => return Ok(self.cache.count_keys_by_prefix(block_hash, prefix).await?),
+		};
 
 		// Resume from last scanned key if we have progress
 		let mut start_key = progress.and_then(|p| p.last_scanned_key);
 
 		loop {
 			// Get next page of keys (with reconnect-retry)
-			let keys = match self
-				.rpc
+			let keys = match rpc
 				.storage_keys_paged(prefix, page_size, start_key.as_deref(), block_hash)
 				.await
 			{
 				Ok(v) => v,
 				Err(_) => {
-					self.rpc.reconnect().await?;
-					self.rpc
-						.storage_keys_paged(prefix, page_size, start_key.as_deref(), block_hash)
+					rpc.reconnect().await?;
+					rpc.storage_keys_paged(prefix, page_size, start_key.as_deref(), block_hash)
 						.await?
 				},
 			};
@@ -396,11 +430,11 @@ impl RemoteStorageLayer {
 
 			// Fetch values for these keys (with reconnect-retry)
 			let key_refs: Vec<&[u8]> = keys.iter().map(|k| k.as_slice()).collect();
-			let values = match self.rpc.storage_batch(&key_refs, block_hash).await {
+			let values = match rpc.storage_batch(&key_refs, block_hash).await {
 				Ok(v) => v,
 				Err(_) => {
-					self.rpc.reconnect().await?;
-					self.rpc.storage_batch(&key_refs, block_hash).await?
+					rpc.reconnect().await?;
+					rpc.storage_batch(&key_refs, block_hash).await?
 				},
 			};
 
@@ -446,6 +480,10 @@ impl RemoteStorageLayer {
 		// Check existing progress
 		let progress = self.cache.get_prefix_scan_progress(block_hash, prefix).await?;
 
+		let Some(rpc) = self.rpc() else {
+			return Ok(self.cache.count_keys_by_prefix(block_hash, prefix).await?);
+		};
+
 		if let Some(ref p) = progress {
 			if p.is_complete {
 				return Ok(self.cache.count_keys_by_prefix(block_hash, prefix).await?);
@@ -456,11 +494,11 @@ impl RemoteStorageLayer {
 		}
 
 		// Fetch first page of keys (with reconnect-retry)
-		let keys = match self.rpc.storage_keys_paged(prefix, page_size, None, block_hash).await {
+		let keys = match rpc.storage_keys_paged(prefix, page_size, None, block_hash).await {
 			Ok(v) => v,
 			Err(_) => {
-				self.rpc.reconnect().await?;
-				self.rpc.storage_keys_paged(prefix, page_size, None, block_hash).await?
+				rpc.reconnect().await?;
+				rpc.storage_keys_paged(prefix, page_size, None, block_hash).await?
 			},
 		};
 
@@ -473,11 +511,11 @@ impl RemoteStorageLayer {
 
 		// Fetch values for these keys (with reconnect-retry)
 		let key_refs: Vec<&[u8]> = keys.iter().map(|k| k.as_slice()).collect();
-		let values = match self.rpc.storage_batch(&key_refs, block_hash).await {
+		let values = match rpc.storage_batch(&key_refs, block_hash).await {
 			Ok(v) => v,
 			Err(_) => {
-				self.rpc.reconnect().await?;
-				self.rpc.storage_batch(&key_refs, block_hash).await?
+				rpc.reconnect().await?;
+				rpc.storage_batch(&key_refs, block_hash).await?
 			},
 		};
 
@@ -550,8 +588,13 @@ impl RemoteStorageLayer {
 		&self,
 		block_number: u32,
 	) -> Result<Option<BlockRow>, RemoteStorageError> {
+		// Without an upstream, only cached blocks exist
+		let Some(rpc) = self.rpc() else {
+			return Ok(self.cache.get_block_by_number(block_number).await?);
+		};
+
 		// Get block hash and full block data in one call
-		let (block_hash, block) = match self.rpc.block_by_number(block_number).await? {
+		let (block_hash, block) = match rpc.block_by_number(block_number).await? {
 			Some((hash, block)) => (hash, block),
 			None => return Ok(None),
 		};
@@ -600,6 +643,10 @@ impl RemoteStorageLayer {
 		// Check if we have a complete prefix scan that covers this query.
 		// Try the exact prefix first, then common parent lengths (32-byte = pallet+item,
 		// 16-byte = pallet-only).
+		let Some(rpc) = self.rpc() else {
+			self.stats.next_key_cache.fetch_add(1, Ordering::Relaxed);
+			return Ok(self.cache.next_key_from_cache(block_hash, prefix, key).await?);
+		};
 		let candidate_lengths: &[usize] = &[prefix.len(), 32, 16];
 		for &len in candidate_lengths {
 			if len > prefix.len() {
@@ -617,16 +664,39 @@ impl RemoteStorageLayer {
 
 		// Fallback: fetch from RPC (with reconnect-retry)
 		self.stats.next_key_rpc.fetch_add(1, Ordering::Relaxed);
-		let keys = match self.rpc.storage_keys_paged(prefix, 1, Some(key), block_hash).await {
+		let keys = match rpc.storage_keys_paged(prefix, 1, Some(key), block_hash).await {
 			Ok(v) => v,
 			Err(_) => {
-				self.rpc.reconnect().await?;
-				self.rpc.storage_keys_paged(prefix, 1, Some(key), block_hash).await?
+				rpc.reconnect().await?;
+				rpc.storage_keys_paged(prefix, 1, Some(key), block_hash).await?
 			},
 		};
 		Ok(keys.into_iter().next())
 	}
 
+	/// Get a page of keys starting with `prefix`, in lexicographic order.
+	///
+	/// Returns at most `count` keys, all strictly greater than `start_key` when given.
+	/// A detached layer serves the page from the cache.
+	pub async fn storage_keys_paged(
+		&self,
+		prefix: &[u8],
+		count: u32,
+		start_key: Option<&[u8]>,
+		block_hash: H256,
+	) -> Result<Vec<Vec<u8>>, RemoteStorageError> {
+		let Some(rpc) = self.rpc() else {
+			let mut keys = self.cache.get_keys_by_prefix(block_hash, prefix).await?;
+			keys.sort();
+			return Ok(keys
+				.into_iter()
+				.filter(|k| start_key.is_none_or(|sk| k.as_slice() > sk))
+				.take(count as usize)
+				.collect());
+		};
+		Ok(rpc.storage_keys_paged(prefix, count, start_key, block_hash).await?)
+	}
+
 	// ============================================================================
 	// Block and header fetching methods
 	// ============================================================================
@@ -640,7 +710,11 @@ impl RemoteStorageLayer {
 	/// * `Ok(Some(extrinsics))` - Block found, returns list of encoded extrinsics
 	/// * `Ok(None)` - Block not found
 	pub async fn block_body(&self, hash: H256) -> Result<Option<Vec<Vec<u8>>>, RemoteStorageError> {
-		match self.rpc.block_by_hash(hash).await? {
+		let Some(rpc) = self.rpc() else {
+			// Cached blocks of a detached layer are genesis blocks, which have no extrinsics
+			return Ok(self.cache.get_block(hash).await?.map(|_| Vec::new()));
+		};
+		match rpc.block_by_hash(hash).await? {
 			Some(block) => {
 				let extrinsics = block.extrinsics.into_iter().map(|ext| ext.0.to_vec()).collect();
 				Ok(Some(extrinsics))
@@ -656,7 +730,10 @@ impl RemoteStorageLayer {
 	/// * `Ok(None)` - Block not found on the remote chain
 	/// * `Err(..)` - Transport/connection error (caller should retry or reconnect)
 	pub async fn block_header(&self, hash: H256) -> Result<Option<Vec<u8>>, RemoteStorageError> {
-		match self.rpc.header(hash).await {
+		let Some(rpc) = self.rpc() else {
+			return Ok(self.cache.get_block(hash).await?.map(|block| block.header));
+		};
+		match rpc.header(hash).await {
 			Ok(header) => Ok(Some(header.encode())),
 			// Header not found (RPC returned null): legitimate "not found"
 			Err(RpcClientError::InvalidResponse(_)) => Ok(None),
@@ -674,7 +751,14 @@ impl RemoteStorageLayer {
 		&self,
 		block_number: u32,
 	) -> Result<Option<H256>, RemoteStorageError> {
-		Ok(self.rpc.block_hash_at(block_number).await?)
+		let Some(rpc) = self.rpc() else {
+			return Ok(self
+				.cache
+				.get_block_by_number(block_number)
+				.await?
+				.map(|block| H256::from_slice(&block.hash)));
+		};
+		Ok(rpc.block_hash_at(block_number).await?)
 	}
 
 	/// Get block number by hash from the remote chain.
@@ -695,7 +779,10 @@ impl RemoteStorageLayer {
 		}
 
 		// Fetch from RPC
-		match self.rpc.block_by_hash(hash).await? {
+		let Some(rpc) = self.rpc() else {
+			return Ok(None);
+		};
+		match rpc.block_by_hash(hash).await? {
 			Some(block) => {
 				let number = block.header.number;
 				let parent_hash = block.header.parent_hash;
@@ -726,7 +813,10 @@ impl RemoteStorageLayer {
 		}
 
 		// Fetch from RPC
-		match self.rpc.block_by_hash(hash).await? {
+		let Some(rpc) = self.rpc() else {
+			return Ok(None);
+		};
+		match rpc.block_by_hash(hash).await? {
 			Some(block) => {
 				let number = block.header.number;
 				let parent_hash = block.header.parent_hash;
@@ -753,17 +843,20 @@ impl RemoteStorageLayer {
 		Option<(H256, subxt::backend::legacy::rpc_methods::Block<subxt::SubstrateConfig>)>,
 		RemoteStorageError,
 	> {
-		Ok(self.rpc.block_by_number(block_number).await?)
+		match self.rpc() {
+			Some(rpc) => Ok(rpc.block_by_number(block_number).await?),
+			None => Ok(None),
+		}
 	}
 
 	/// Get the latest finalized block hash from the remote chain.
 	pub async fn finalized_head(&self) -> Result<H256, RemoteStorageError> {
-		Ok(self.rpc.finalized_head().await?)
+		Ok(self.upstream()?.finalized_head().await?)
 	}
 
 	/// Get decoded metadata at a specific block from the remote chain.
 	pub async fn metadata(&self, block_hash: H256) -> Result<Metadata, RemoteStorageError> {
-		Ok(self.rpc.metadata(block_hash).await?)
+		Ok(self.upstream()?.metadata(block_hash).await?)
 	}
 }
 
@@ -779,6 +872,41 @@ mod tests {
 		assert!(err.to_string().contains("RPC error"));
 	}
 
+	#[test]
+	fn error_display_no_upstream() {
+		let err = RemoteStorageError::NoUpstream;
+		assert!(err.to_string().contains("No upstream node"));
+	}
+
+	#[tokio::test(flavor = "multi_thread")]
+	async fn detached_layer_serves_state_from_cache() {
+		let cache = StorageCache::in_memory().await.unwrap();
+		let block_hash = H256::from([1u8; 32]);
+		let entries: [(&[u8], Option<&[u8]>); 2] =
+			[(b"key_a", Some(&b"a"[..])), (b"key_b", Some(&b"b"[..]))];
+		cache.set_storage_batch(block_hash, &entries).await.unwrap();
+		let layer = RemoteStorageLayer::detached(cache);
+
+		assert!(layer.is_detached());
+		assert!(layer.endpoint().is_none());
+		assert_eq!(layer.get(block_hash, b"key_a").await.unwrap(), Some(b"a".to_vec()));
+		assert_eq!(layer.get(block_hash, b"missing").await.unwrap(), None);
+		assert_eq!(
+			layer.get_batch(block_hash, &[&b"key_b"[..], b"missing"]).await.unwrap(),
+			vec![Some(b"b".to_vec()), None]
+		);
+		assert_eq!(layer.get_keys(block_hash, b"key").await.unwrap().len(), 2);
+		assert_eq!(
+			layer.next_key(block_hash, b"key", b"key_a").await.unwrap(),
+			Some(b"key_b".to_vec())
+		);
+		assert_eq!(
+			layer.storage_keys_paged(b"key", 1, Some(b"key_a"), block_hash).await.unwrap(),
+			vec![b"key_b".to_vec()]
+		);
+		assert!(matches!(layer.finalized_head().await, Err(RemoteStorageError::NoUpstream)));
+	}
+
 	#[test]
 	fn error_display_cache() {
 		use crate::error::CacheError;
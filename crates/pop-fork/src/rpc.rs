@@ -50,7 +50,14 @@ use crate::{
 	strings::rpc::{methods, storage_keys},
 };
 use scale::{Decode, Encode};
-use std::sync::Arc;
+use std::{
+	future::Future,
+	sync::{
+		Arc, OnceLock,
+		atomic::{AtomicBool, AtomicUsize, Ordering},
+	},
+	time::Duration,
+};
 use subxt::{
 	Metadata, SubstrateConfig,
 	backend::{
@@ -68,6 +75,15 @@ use url::Url;
 /// timeout reduces flaky connection failures.
 const WS_CONNECT_TIMEOUT_SECS: u64 = 30;
 
+/// Timeout for a single upstream RPC request.
+///
+/// Generous enough for large `state_queryStorageAt` batches and metadata downloads, but bounded
+/// so that a stalled endpoint triggers a failover instead of hanging the fork.
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// Timeout for the cheap liveness probe (`system_chain`) used before failing over.
+const LIVENESS_TIMEOUT_SECS: u64 = 10;
+
 /// Maximum number of concurrent upstream RPC calls for heavy storage methods.
 ///
 /// Limits parallelism for `storage_batch()` and `storage_keys_paged()` to prevent
@@ -75,10 +91,97 @@ const WS_CONNECT_TIMEOUT_SECS: u64 = 30;
 /// concurrent `state_queryStorageAt` requests) hit the RPC server at once.
 const MAX_CONCURRENT_UPSTREAM_CALLS: usize = 4;
 
+/// Minimum number of keys sent to each upstream when a storage batch is spread across the pool.
+///
+/// Smaller batches are not worth the extra round-trips and are sent to the active upstream only.
+const MIN_KEYS_PER_UPSTREAM: usize = 64;
+
 /// Oldest metadata version supported.
-const METADATA_V14: u32 = 14;
+pub(crate) const METADATA_V14: u32 = 14;
 /// Most up-to-date metadata version supported.
-const METADATA_LATEST: u32 = 15;
+pub(crate) const METADATA_LATEST: u32 = 16;
+
+/// Block that all upstreams in a pool must agree on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ForkPointReference {
+	genesis_hash: H256,
+	number: u32,
+	hash: H256,
+}
+
+/// Outcome of checking an upstream against the [`ForkPointReference`].
+enum ForkPointCheck {
+	/// The upstream agrees with the fork point.
+	Matches,
+	/// The upstream could not be checked yet, e.g. it has not synced the fork point.
+	Pending(String),
+	/// The upstream serves a different chain.
+	Mismatch(String),
+}
+
+/// A single endpoint of the upstream pool.
+struct Upstream {
+	endpoint: Url,
+	/// Connection to the endpoint, `None` until a connection has been established.
+	legacy: RwLock<Option<LegacyRpcMethods<SubstrateConfig>>>,
+	/// Whether the last request to (or connection attempt with) this endpoint succeeded.
+	healthy: AtomicBool,
+	/// Set once the endpoint disagreed with the pool about the fork point. Excluded endpoints are
+	/// never used again.
+	excluded: AtomicBool,
+	/// Set once the endpoint confirmed the fork point. Only verified endpoints receive batch
+	/// chunks.
+	verified: AtomicBool,
+}
+
+impl Upstream {
+	fn new(endpoint: Url) -> Self {
+		Self {
+			endpoint,
+			legacy: RwLock::new(None),
+			healthy: AtomicBool::new(false),
+			excluded: AtomicBool::new(false),
+			verified: AtomicBool::new(false),
+		}
+	}
+
+	/// Get the current connection, if any.
+	async fn connection(&self) -> Option<LegacyRpcMethods<SubstrateConfig>> {
+		self.legacy.read().await.clone()
+	}
+
+	/// Open a fresh connection to the endpoint, replacing any existing one.
+	async fn connect(&self) -> Result<LegacyRpcMethods<SubstrateConfig>, RpcClientError> {
+		match ForkRpcClient::create_connection(&self.endpoint).await {
+			Ok(legacy) => {
+				*self.legacy.write().await = Some(legacy.clone());
+				self.healthy.store(true, Ordering::Relaxed);
+				Ok(legacy)
+			},
+			Err(e) => {
+				self.healthy.store(false, Ordering::Relaxed);
+				Err(e)
+			},
+		}
+	}
+
+	/// Cheap liveness check of the current connection.
+	async fn is_alive(&self) -> bool {
+		let Some(legacy) = self.connection().await else {
+			return false;
+		};
+		matches!(
+			tokio::time::timeout(Duration::from_secs(LIVENESS_TIMEOUT_SECS), legacy.system_chain())
+				.await,
+			Ok(Ok(_))
+		)
+	}
+
+	/// Whether the endpoint can currently be used to spread work across the pool.
+	fn is_usable(&self) -> bool {
+		self.healthy.load(Ordering::Relaxed) && !self.excluded.load(Ordering::Relaxed)
+	}
+}
 
 /// RPC client wrapper for fork operations.
 ///
@@ -86,6 +189,14 @@ const METADATA_LATEST: u32 = 15;
 /// from live Polkadot-SDK chains. See the module-level documentation for why this
 /// wrapper exists rather than using `LegacyRpcMethods` directly.
 ///
+/// # Upstream Pool
+///
+/// The client can be connected to several endpoints of the same chain via
+/// [`ForkRpcClient::connect_all`]. Requests are sent to the active endpoint; when a request
+/// fails or times out and the endpoint no longer responds, the client fails over to the next
+/// reachable endpoint and retries. Large storage batches are spread across all healthy endpoints.
+/// Use [`ForkRpcClient::verify_fork_point`] to make sure every endpoint serves the same chain.
+///
 /// # Example
 ///
 /// ```ignore
@@ -98,8 +209,12 @@ const METADATA_LATEST: u32 = 15;
 /// ```
 #[derive(Clone)]
 pub struct ForkRpcClient {
-	legacy: Arc<RwLock<LegacyRpcMethods<SubstrateConfig>>>,
-	endpoint: Url,
+	/// Upstream endpoints, in order of preference.
+	upstreams: Arc<[Upstream]>,
+	/// Index of the upstream currently serving requests.
+	active: Arc<AtomicUsize>,
+	/// Block every upstream must agree on, set by [`ForkRpcClient::verify_fork_point`].
+	fork_point: Arc<OnceLock<ForkPointReference>>,
 	/// Semaphore limiting concurrent upstream calls for heavy storage methods.
 	upstream_semaphore: Arc<Semaphore>,
 	/// Lock that serializes reconnection attempts so only one task reconnects
@@ -111,7 +226,10 @@ pub struct ForkRpcClient {
 
 impl std::fmt::Debug for ForkRpcClient {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		f.debug_struct("ForkRpcClient").field("endpoint", &self.endpoint).finish()
+		f.debug_struct("ForkRpcClient")
+			.field("endpoint", self.endpoint())
+			.field("upstreams", &self.upstreams.len())
+			.finish()
 	}
 }
 
@@ -126,10 +244,61 @@ impl ForkRpcClient {
 	/// let client = ForkRpcClient::connect(&"wss://rpc.polkadot.io".parse()?).await?;
 	/// ```
 	pub async fn connect(endpoint: &Url) -> Result<Self, RpcClientError> {
-		let legacy = Self::create_connection(endpoint).await?;
+		Self::connect_all(std::slice::from_ref(endpoint)).await
+	}
+
+	/// Connect to a pool of endpoints serving the same chain.
+	///
+	/// All endpoints are dialed concurrently. Endpoints that cannot be reached are kept in the
+	/// pool and retried during failover. The first reachable endpoint (in the given order)
+	/// becomes the active one.
+	///
+	/// # Arguments
+	/// * `endpoints` - WebSocket URLs of the chain's RPC endpoints, in order of preference
+	///
+	/// # Errors
+	/// Returns [`RpcClientError::NoEndpoints`] if `endpoints` is empty, or the connection error
+	/// of the first endpoint if none of them can be reached.
+	///
+	/// # Example
+	/// ```ignore
+	/// let endpoints = ["wss://rpc.ibp.network/polkadot".parse()?, "wss://polkadot.dotters.network".parse()?];
+	/// let client = ForkRpcClient::connect_all(&endpoints).await?;
+	/// ```
+	pub async fn connect_all(endpoints: &[Url]) -> Result<Self, RpcClientError> {
+		if endpoints.is_empty() {
+			return Err(RpcClientError::NoEndpoints);
+		}
+
+		let upstreams: Arc<[Upstream]> = endpoints.iter().cloned().map(Upstream::new).collect();
+		let results =
+			futures::future::join_all(upstreams.iter().map(|upstream| upstream.connect())).await;
+
+		let mut first_error = None;
+		let mut active = None;
+		for (index, result) in results.into_iter().enumerate() {
+			match result {
+				Ok(_) =>
+					if active.is_none() {
+						active = Some(index);
+					},
+				Err(e) => {
+					log::debug!("Upstream {} unavailable: {e}", upstreams[index].endpoint);
+					if first_error.is_none() {
+						first_error = Some(e);
+					}
+				},
+			}
+		}
+
+		let Some(active) = active else {
+			return Err(first_error.expect("at least one endpoint was dialed; qed"));
+		};
+
 		Ok(Self {
-			legacy: Arc::new(RwLock::new(legacy)),
-			endpoint: endpoint.clone(),
+			upstreams,
+			active: Arc::new(AtomicUsize::new(active)),
+			fork_point: Arc::new(OnceLock::new()),
 			upstream_semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_UPSTREAM_CALLS)),
 			reconnect_lock: Arc::new(Mutex::new(())),
 		})
@@ -147,7 +316,7 @@ impl ForkRpcClient {
 
 		let client = WsClientBuilder::default()
 			.max_response_size(u32::MAX)
-			.connection_timeout(std::time::Duration::from_secs(WS_CONNECT_TIMEOUT_SECS))
+			.connection_timeout(Duration::from_secs(WS_CONNECT_TIMEOUT_SECS))
 			.build(endpoint.as_str())
 			.await
 			.map_err(|e| RpcClientError::ConnectionFailed {
@@ -162,6 +331,8 @@ impl ForkRpcClient {
 	///
 	/// Creates a fresh WebSocket connection, replacing the existing one. All clones
 	/// of this client share the connection, so reconnecting affects all of them.
+	/// When the active endpoint cannot be reached, the client fails over to the next
+	/// reachable endpoint of the pool.
 	///
 	/// Serialized via `reconnect_lock`: only one task performs the actual reconnection.
 	/// Other concurrent callers wait for the lock, then verify the connection is alive
@@ -171,18 +342,232 @@ impl ForkRpcClient {
 
 		// Another task may have already reconnected while we waited for the lock.
 		// Do a cheap liveness check before creating a new connection.
-		if self.legacy.read().await.system_chain().await.is_ok() {
+		let active = self.active.load(Ordering::Acquire);
+		if self.upstreams[active].is_alive().await {
 			return Ok(());
 		}
 
-		let new_legacy = Self::create_connection(&self.endpoint).await?;
-		*self.legacy.write().await = new_legacy;
-		Ok(())
+		self.switch_upstream(active).await
+	}
+
+	/// Fail over from the upstream at index `from`, unless another task already did.
+	async fn fail_over(&self, from: usize) -> Result<(), RpcClientError> {
+		let _guard = self.reconnect_lock.lock().await;
+		if self.active.load(Ordering::Acquire) != from {
+			return Ok(());
+		}
+		self.switch_upstream(from).await
+	}
+
+	/// Activate the next usable upstream after `from`, trying `from` itself last.
+	///
+	/// Must be called with `reconnect_lock` held.
+	async fn switch_upstream(&self, from: usize) -> Result<(), RpcClientError> {
+		self.upstreams[from].healthy.store(false, Ordering::Relaxed);
+
+		let count = self.upstreams.len();
+		let mut last_error = None;
+		for offset in 1..=count {
+			let index = (from + offset) % count;
+			let upstream = &self.upstreams[index];
+			if upstream.excluded.load(Ordering::Relaxed) {
+				continue;
+			}
+			// Reuse a live connection to another endpoint, otherwise dial it again.
+			let legacy = if index != from && upstream.is_alive().await {
+				upstream.connection().await.expect("alive upstreams are connected; qed")
+			} else {
+				match upstream.connect().await {
+					Ok(legacy) => legacy,
+					Err(e) => {
+						last_error = Some(e);
+						continue;
+					},
+				}
+			};
+			// Endpoints joining after the fork point was fixed must agree with it.
+			// Endpoints that cannot confirm it yet are skipped for now, but kept in the pool.
+			if let Some(reference) = self.fork_point.get() &&
+				!Self::confirm_fork_point(upstream, &legacy, reference).await
+			{
+				continue;
+			}
+			upstream.healthy.store(true, Ordering::Relaxed);
+			self.active.store(index, Ordering::Release);
+			if index != from {
+				log::info!(
+					"Upstream {} unavailable, switched to {}",
+					self.upstreams[from].endpoint,
+					upstream.endpoint
+				);
+			}
+			return Ok(());
+		}
+
+		Err(last_error.unwrap_or_else(|| RpcClientError::ConnectionFailed {
+			endpoint: self.upstreams[from].endpoint.to_string(),
+			message: "no usable upstream endpoint left".to_string(),
+		}))
+	}
+
+	/// Remove an upstream from rotation for good.
+	fn exclude(upstream: &Upstream, reason: &str) {
+		log::warn!("Excluding upstream {}: {reason}", upstream.endpoint);
+		upstream.excluded.store(true, Ordering::Relaxed);
+		upstream.healthy.store(false, Ordering::Relaxed);
+	}
+
+	/// Get the connection of the active upstream, reconnecting if it was never established.
+	async fn active_connection(
+		&self,
+	) -> Result<(usize, LegacyRpcMethods<SubstrateConfig>), RpcClientError> {
+		let index = self.active.load(Ordering::Acquire);
+		match self.upstreams[index].connection().await {
+			Some(legacy) => Ok((index, legacy)),
+			None => {
+				self.fail_over(index).await?;
+				let index = self.active.load(Ordering::Acquire);
+				let legacy = self.upstreams[index]
+					.connection()
+					.await
+					.expect("switch_upstream only activates connected upstreams; qed");
+				Ok((index, legacy))
+			},
+		}
+	}
+
+	/// Send a request to the active upstream, failing over to the rest of the pool on errors.
+	///
+	/// A failed request only triggers a failover when the endpoint no longer passes a liveness
+	/// check, so genuine request errors (e.g. an unknown runtime API) are returned directly.
+	/// Timeouts always trigger a failover.
+	async fn request<T, E, F, Fut>(&self, method: &'static str, f: F) -> Result<T, RpcClientError>
+	where
+		F: Fn(LegacyRpcMethods<SubstrateConfig>) -> Fut,
+		Fut: Future<Output = Result<T, E>>,
+		E: std::fmt::Display,
+	{
+		let mut last_error = None;
+		for _ in 0..self.upstreams.len() {
+			let (index, legacy) = self.active_connection().await?;
+			let error =
+				match tokio::time::timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS), f(legacy))
+					.await
+				{
+					Ok(Ok(value)) => return Ok(value),
+					Ok(Err(e)) => {
+						let error =
+							RpcClientError::RequestFailed { method, message: e.to_string() };
+						if self.upstreams.len() == 1 || self.upstreams[index].is_alive().await {
+							return Err(error);
+						}
+						error
+					},
+					Err(_) => RpcClientError::Timeout { method },
+				};
+			last_error = Some(error);
+			if self.fail_over(index).await.is_err() {
+				break;
+			}
+		}
+		Err(last_error.expect("at least one request was sent; qed"))
 	}
 
 	/// Get the endpoint URL this client is connected to.
+	///
+	/// For a pool, this is the endpoint currently serving requests.
 	pub fn endpoint(&self) -> &Url {
-		&self.endpoint
+		&self.upstreams[self.active.load(Ordering::Acquire)].endpoint
+	}
+
+	/// Get all endpoint URLs of the upstream pool, in order of preference.
+	pub fn endpoints(&self) -> impl Iterator<Item = &Url> {
+		self.upstreams.iter().map(|upstream| &upstream.endpoint)
+	}
+
+	/// Check that every connected upstream serves the same chain as the active one.
+	///
+	/// Compares the genesis hash and the hash of block `number` reported by each endpoint
+	/// against `hash` (the fork point as seen by the active endpoint). Endpoints that disagree
+	/// are excluded from the pool with a warning. Endpoints that cannot confirm the fork point
+	/// yet (e.g. they are still syncing) are kept. The reference is remembered, so those and
+	/// endpoints that only become reachable later are checked again before they are used.
+	pub async fn verify_fork_point(&self, number: u32, hash: H256) -> Result<(), RpcClientError> {
+		if self.upstreams.len() == 1 {
+			return Ok(());
+		}
+
+		let genesis_hash = self.block_hash_at(0).await?.ok_or_else(|| {
+			RpcClientError::InvalidResponse("No genesis block hash found".to_string())
+		})?;
+		let reference =
+			*self
+				.fork_point
+				.get_or_init(|| ForkPointReference { genesis_hash, number, hash });
+
+		// The reference is taken from the active upstream.
+		let active = self.active.load(Ordering::Acquire);
+		self.upstreams[active].verified.store(true, Ordering::Relaxed);
+		let checks = self.upstreams.iter().enumerate().filter(|(index, _)| *index != active).map(
+			|(_, upstream)| async move {
+				if let Some(legacy) = upstream.connection().await {
+					Self::confirm_fork_point(upstream, &legacy, &reference).await;
+				}
+			},
+		);
+		futures::future::join_all(checks).await;
+		Ok(())
+	}
+
+	/// Check an upstream against the fork point reference, remembering a match and excluding the
+	/// upstream on a mismatch. Returns whether the upstream can be used.
+	async fn confirm_fork_point(
+		upstream: &Upstream,
+		legacy: &LegacyRpcMethods<SubstrateConfig>,
+		reference: &ForkPointReference,
+	) -> bool {
+		match Self::check_fork_point(legacy, reference).await {
+			ForkPointCheck::Matches => {
+				upstream.verified.store(true, Ordering::Relaxed);
+				true
+			},
+			ForkPointCheck::Pending(reason) => {
+				log::debug!("Upstream {} not verified yet: {reason}", upstream.endpoint);
+				false
+			},
+			ForkPointCheck::Mismatch(reason) => {
+				Self::exclude(upstream, &reason);
+				false
+			},
+		}
+	}
+
+	/// Compare an upstream's view of the chain against the fork point reference.
+	///
+	/// Only a different block hash counts as a mismatch; unknown blocks and request errors
+	/// leave the outcome pending.
+	async fn check_fork_point(
+		legacy: &LegacyRpcMethods<SubstrateConfig>,
+		reference: &ForkPointReference,
+	) -> ForkPointCheck {
+		for (number, expected) in [(0, reference.genesis_hash), (reference.number, reference.hash)]
+		{
+			match legacy.chain_get_block_hash(Some(number.into())).await {
+				Ok(Some(hash)) if hash == expected => {},
+				Ok(Some(hash)) => {
+					return ForkPointCheck::Mismatch(format!(
+						"block #{number} is {hash:?}, expected {expected:?}"
+					));
+				},
+				Ok(None) => {
+					return ForkPointCheck::Pending(format!("block #{number} is unknown"));
+				},
+				Err(e) => {
+					return ForkPointCheck::Pending(format!("failed to fetch block #{number}: {e}"));
+				},
+			}
+		}
+		ForkPointCheck::Matches
 	}
 
 	/// Get the latest finalized block hash.
@@ -190,12 +575,10 @@ impl ForkRpcClient {
 	/// This is typically the starting point for forking - we fork from the latest
 	/// finalized state to ensure consistency.
 	pub async fn finalized_head(&self) -> Result<H256, RpcClientError> {
-		self.legacy.read().await.chain_get_finalized_head().await.map_err(|e| {
-			RpcClientError::RequestFailed {
-				method: methods::CHAIN_GET_FINALIZED_HEAD,
-				message: e.to_string(),
-			}
+		self.request(methods::CHAIN_GET_FINALIZED_HEAD, |legacy| async move {
+			legacy.chain_get_finalized_head().await
 		})
+		.await
 	}
 
 	/// Get block header by hash.
@@ -206,16 +589,11 @@ impl ForkRpcClient {
 		&self,
 		hash: H256,
 	) -> Result<<SubstrateConfig as subxt::Config>::Header, RpcClientError> {
-		self.legacy
-			.read()
-			.await
-			.chain_get_header(Some(hash))
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::CHAIN_GET_HEADER,
-				message: e.to_string(),
-			})?
-			.ok_or_else(|| RpcClientError::InvalidResponse(format!("No header found for {hash:?}")))
+		self.request(methods::CHAIN_GET_HEADER, |legacy| async move {
+			legacy.chain_get_header(Some(hash)).await
+		})
+		.await?
+		.ok_or_else(|| RpcClientError::InvalidResponse(format!("No header found for {hash:?}")))
 	}
 
 	/// Get a block hash by its number.
@@ -228,15 +606,10 @@ impl ForkRpcClient {
 	/// * `Ok(None)` - Block number doesn't exist yet
 	/// * `Err(_)` - RPC error
 	pub async fn block_hash_at(&self, block_number: u32) -> Result<Option<H256>, RpcClientError> {
-		self.legacy
-			.read()
-			.await
-			.chain_get_block_hash(Some(block_number.into()))
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::CHAIN_GET_BLOCK_HASH,
-				message: e.to_string(),
-			})
+		self.request(methods::CHAIN_GET_BLOCK_HASH, |legacy| async move {
+			legacy.chain_get_block_hash(Some(block_number.into())).await
+		})
+		.await
 	}
 
 	/// Get full block data by block number.
@@ -264,15 +637,9 @@ impl ForkRpcClient {
 		};
 
 		// Get full block data
-		let block =
-			self.legacy.read().await.chain_get_block(Some(block_hash)).await.map_err(|e| {
-				RpcClientError::RequestFailed {
-					method: methods::CHAIN_GET_BLOCK,
-					message: e.to_string(),
-				}
-			})?;
+		let block = self.block_by_hash(block_hash).await?;
 
-		Ok(block.map(|block| (block_hash, block.block)))
+		Ok(block.map(|block| (block_hash, block)))
 	}
 
 	/// Get full block data by block hash.
@@ -288,13 +655,11 @@ impl ForkRpcClient {
 		&self,
 		block_hash: H256,
 	) -> Result<Option<Block<SubstrateConfig>>, RpcClientError> {
-		let block =
-			self.legacy.read().await.chain_get_block(Some(block_hash)).await.map_err(|e| {
-				RpcClientError::RequestFailed {
-					method: methods::CHAIN_GET_BLOCK,
-					message: e.to_string(),
-				}
-			})?;
+		let block = self
+			.request(methods::CHAIN_GET_BLOCK, |legacy| async move {
+				legacy.chain_get_block(Some(block_hash)).await
+			})
+			.await?;
 
 		Ok(block.map(|b| b.block))
 	}
@@ -310,18 +675,18 @@ impl ForkRpcClient {
 	/// * `Ok(None)` - Storage key doesn't exist (empty)
 	/// * `Err(_)` - RPC error
 	pub async fn storage(&self, key: &[u8], at: H256) -> Result<Option<Vec<u8>>, RpcClientError> {
-		self.legacy.read().await.state_get_storage(key, Some(at)).await.map_err(|e| {
-			RpcClientError::RequestFailed {
-				method: methods::STATE_GET_STORAGE,
-				message: e.to_string(),
-			}
+		self.request(methods::STATE_GET_STORAGE, |legacy| async move {
+			legacy.state_get_storage(key, Some(at)).await
 		})
+		.await
 	}
 
 	/// Get multiple storage values in a single batch request.
 	///
 	/// More efficient than multiple individual `storage()` calls when fetching
-	/// many keys at once.
+	/// many keys at once. When connected to a pool, large batches are split across
+	/// all healthy endpoints which confirmed the fork point (see
+	/// [`ForkRpcClient::verify_fork_point`]) and fetched concurrently.
 	///
 	/// # Arguments
 	/// * `keys` - Slice of storage keys to fetch
@@ -340,19 +705,86 @@ impl ForkRpcClient {
 
 		let _permit = self.upstream_semaphore.acquire().await.expect("semaphore closed");
 
-		let result = self
-			.legacy
-			.read()
-			.await
-			.state_query_storage_at(keys.iter().copied(), Some(at))
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::STATE_QUERY_STORAGE_AT,
-				message: e.to_string(),
-			})?;
+		// Collect the usable upstreams, active first. Other upstreams only receive chunks once
+		// they confirmed the fork point, so no values are read from another chain.
+		let active = self.active.load(Ordering::Acquire);
+		let reference = self.fork_point.get();
+		let mut connections = Vec::new();
+		for offset in 0..self.upstreams.len() {
+			let index = (active + offset) % self.upstreams.len();
+			let upstream = &self.upstreams[index];
+			if index != active && !upstream.is_usable() {
+				continue;
+			}
+			let Some(legacy) = upstream.connection().await else {
+				continue;
+			};
+			if index != active && !upstream.verified.load(Ordering::Relaxed) {
+				let Some(reference) = reference else {
+					continue;
+				};
+				if !Self::confirm_fork_point(upstream, &legacy, reference).await {
+					continue;
+				}
+			}
+			connections.push((index, legacy));
+		}
 
+		let chunk_size = batch_chunk_size(keys.len(), connections.len());
+		if chunk_size >= keys.len() {
+			return self.query_storage_at(keys, at).await;
+		}
+
+		let fetches = keys.chunks(chunk_size).zip(connections).map(|(chunk, (index, legacy))| {
+			async move {
+				let result = tokio::time::timeout(
+					Duration::from_secs(REQUEST_TIMEOUT_SECS),
+					legacy.state_query_storage_at(chunk.iter().copied(), Some(at)),
+				)
+				.await;
+				match result {
+					Ok(Ok(change_sets)) => Ok(Self::values_from_change_sets(change_sets, chunk)),
+					_ => {
+						// Retry the chunk through the regular failover path.
+						log::debug!(
+							"Batch chunk failed on {}, retrying on active upstream",
+							self.upstreams[index].endpoint
+						);
+						self.upstreams[index].healthy.store(false, Ordering::Relaxed);
+						self.query_storage_at(chunk, at).await
+					},
+				}
+			}
+		});
+
+		let mut values = Vec::with_capacity(keys.len());
+		for chunk_values in futures::future::join_all(fetches).await {
+			values.extend(chunk_values?);
+		}
+		Ok(values)
+	}
+
+	/// Fetch storage values through `state_queryStorageAt` on the active upstream.
+	async fn query_storage_at(
+		&self,
+		keys: &[&[u8]],
+		at: H256,
+	) -> Result<Vec<Option<Vec<u8>>>, RpcClientError> {
+		let change_sets = self
+			.request(methods::STATE_QUERY_STORAGE_AT, |legacy| async move {
+				legacy.state_query_storage_at(keys.iter().copied(), Some(at)).await
+			})
+			.await?;
+		Ok(Self::values_from_change_sets(change_sets, keys))
+	}
+
+	/// Map a `state_queryStorageAt` response back onto the requested keys.
+	fn values_from_change_sets(
+		change_sets: Vec<subxt::backend::legacy::rpc_methods::StorageChangeSet<H256>>,
+		keys: &[&[u8]],
+	) -> Vec<Option<Vec<u8>>> {
 		// Build a map of key -> value from the response
-		let changes: std::collections::HashMap<Vec<u8>, Option<Vec<u8>>> = result
+		let changes: std::collections::HashMap<Vec<u8>, Option<Vec<u8>>> = change_sets
 			.into_iter()
 			.flat_map(|change_set| {
 				change_set.changes.into_iter().map(|(k, v)| {
@@ -364,9 +796,7 @@ impl ForkRpcClient {
 			.collect();
 
 		// Return values in the same order as input keys, preserving duplicates.
-		let values = keys.iter().map(|key| changes.get::<[u8]>(key).cloned().flatten()).collect();
-
-		Ok(values)
+		keys.iter().map(|key| changes.get::<[u8]>(key).cloned().flatten()).collect()
 	}
 
 	/// Get storage keys matching a prefix, with pagination.
@@ -387,15 +817,10 @@ impl ForkRpcClient {
 	) -> Result<Vec<Vec<u8>>, RpcClientError> {
 		let _permit = self.upstream_semaphore.acquire().await.expect("semaphore closed");
 
-		self.legacy
-			.read()
-			.await
-			.state_get_keys_paged(prefix, count, start_key, Some(at))
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::STATE_GET_KEYS_PAGED,
-				message: e.to_string(),
-			})
+		self.request(methods::STATE_GET_KEYS_PAGED, |legacy| async move {
+			legacy.state_get_keys_paged(prefix, count, start_key, Some(at)).await
+		})
+		.await
 	}
 
 	/// Get runtime metadata at a specific block.
@@ -405,12 +830,11 @@ impl ForkRpcClient {
 	/// falls back to requesting specific metadata versions via
 	/// `Metadata_metadata_at_version` runtime API (latest down to V14).
 	pub async fn metadata(&self, at: H256) -> Result<Metadata, RpcClientError> {
-		let raw = self.legacy.read().await.state_get_metadata(Some(at)).await.map_err(|e| {
-			RpcClientError::RequestFailed {
-				method: methods::STATE_GET_METADATA,
-				message: e.to_string(),
-			}
-		})?;
+		let raw = self
+			.request(methods::STATE_GET_METADATA, |legacy| async move {
+				legacy.state_get_metadata(Some(at)).await
+			})
+			.await?;
 
 		let raw_bytes = raw.into_raw();
 		match Metadata::decode(&mut raw_bytes.as_slice()) {
@@ -440,15 +864,8 @@ impl ForkRpcClient {
 		at: H256,
 	) -> Result<Option<Vec<u8>>, RpcClientError> {
 		let result = self
-			.legacy
-			.read()
-			.await
-			.state_call("Metadata_metadata_at_version", Some(&version.encode()), Some(at))
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::STATE_CALL,
-				message: e.to_string(),
-			})?;
+			.state_call("Metadata_metadata_at_version", &version.encode(), Some(at))
+			.await?;
 
 		// The runtime returns SCALE-encoded `Option<OpaqueMetadata>` where
 		// `OpaqueMetadata` is `Vec<u8>`.
@@ -475,15 +892,8 @@ impl ForkRpcClient {
 
 	/// Get the chain name from system properties.
 	pub async fn system_chain(&self) -> Result<String, RpcClientError> {
-		self.legacy
-			.read()
-			.await
-			.system_chain()
+		self.request(methods::SYSTEM_CHAIN, |legacy| async move { legacy.system_chain().await })
 			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::SYSTEM_CHAIN,
-				message: e.to_string(),
-			})
 	}
 
 	/// Execute a runtime API call via `state_call` on the upstream chain.
@@ -497,28 +907,32 @@ impl ForkRpcClient {
 		call_parameters: &[u8],
 		at: Option<H256>,
 	) -> Result<Vec<u8>, RpcClientError> {
-		self.legacy
-			.read()
-			.await
-			.state_call(function, Some(call_parameters), at)
-			.await
-			.map_err(|e| RpcClientError::RequestFailed {
-				method: methods::STATE_CALL,
-				message: e.to_string(),
-			})
+		self.request(methods::STATE_CALL, |legacy| async move {
+			legacy.state_call(function, Some(call_parameters), at).await
+		})
+		.await
 	}
 
 	/// Get system properties (token decimals, symbols, etc.).
 	pub async fn system_properties(
 		&self,
 	) -> Result<subxt::backend::legacy::rpc_methods::SystemProperties, RpcClientError> {
-		self.legacy.read().await.system_properties().await.map_err(|e| {
-			RpcClientError::RequestFailed {
-				method: methods::SYSTEM_PROPERTIES,
-				message: e.to_string(),
-			}
+		self.request(methods::SYSTEM_PROPERTIES, |legacy| async move {
+			legacy.system_properties().await
 		})
+		.await
+	}
+}
+
+/// Number of keys sent to each upstream when spreading a storage batch.
+///
+/// Returns `len` (i.e. no split) when the pool has a single usable upstream or the batch is too
+/// small to be worth splitting.
+fn batch_chunk_size(len: usize, upstreams: usize) -> usize {
+	if upstreams < 2 || len < 2 * MIN_KEYS_PER_UPSTREAM {
+		return len;
 	}
+	len.div_ceil(upstreams).max(MIN_KEYS_PER_UPSTREAM)
 }
 
 #[cfg(test)]
@@ -570,6 +984,52 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn error_display_no_endpoints() {
+		assert_eq!(RpcClientError::NoEndpoints.to_string(), "No RPC endpoints provided");
+	}
+
+	#[test]
+	fn batch_chunk_size_does_not_split_for_single_upstream() {
+		assert_eq!(batch_chunk_size(1_000, 0), 1_000);
+		assert_eq!(batch_chunk_size(1_000, 1), 1_000);
+	}
+
+	#[test]
+	fn batch_chunk_size_does_not_split_small_batches() {
+		assert_eq!(
+			batch_chunk_size(2 * MIN_KEYS_PER_UPSTREAM - 1, 5),
+			2 * MIN_KEYS_PER_UPSTREAM - 1
+		);
+	}
+
+	#[test]
+	fn batch_chunk_size_spreads_across_upstreams() {
+		assert_eq!(batch_chunk_size(1_000, 4), 250);
+		assert_eq!(batch_chunk_size(1_001, 4), 251);
+		// Never more chunks than upstreams, never fewer keys than the minimum per chunk.
+		let chunk = batch_chunk_size(2 * MIN_KEYS_PER_UPSTREAM, 5);
+		assert_eq!(chunk, MIN_KEYS_PER_UPSTREAM);
+		assert!((2 * MIN_KEYS_PER_UPSTREAM).div_ceil(chunk) <= 5);
+	}
+
+	#[tokio::test]
+	async fn connect_all_without_endpoints_fails() {
+		let err = ForkRpcClient::connect_all(&[]).await.unwrap_err();
+		assert!(matches!(err, RpcClientError::NoEndpoints), "Expected NoEndpoints, got: {err:?}");
+	}
+
+	#[tokio::test]
+	async fn connect_all_fails_when_no_endpoint_is_reachable() {
+		let endpoints: Vec<Url> =
+			vec!["ws://127.0.0.1:19998".parse().unwrap(), "ws://127.0.0.1:19999".parse().unwrap()];
+		let err = ForkRpcClient::connect_all(&endpoints).await.unwrap_err();
+		assert!(
+			matches!(&err, RpcClientError::ConnectionFailed { endpoint, .. } if endpoint.contains("19998")),
+			"Expected ConnectionFailed for the first endpoint, got: {err:?}"
+		);
+	}
+
 	#[tokio::test]
 	async fn connect_to_invalid_endpoint_fails() {
 		// Use a port that's unlikely to have anything listening
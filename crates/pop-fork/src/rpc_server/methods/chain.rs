@@ -308,6 +308,7 @@ impl<T: ChainBlockchain + 'static> ChainApiServer for ChainApi<T> {
 									),
 								}
 							}
+							Ok(_) => continue,
 							Err(broadcast::error::RecvError::Lagged(n)) => {
 								warn!("[chain] Subscriber lagged, skipped {n} events");
 								continue;
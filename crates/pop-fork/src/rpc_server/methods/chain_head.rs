@@ -377,6 +377,7 @@ impl ChainHeadApiServer for ChainHeadApi {
 									break;
 								}
 							}
+							Ok(_) => continue,
 							Err(broadcast::error::RecvError::Lagged(_)) => continue,
 							Err(broadcast::error::RecvError::Closed) => break,
 						}
@@ -6,11 +6,21 @@
 //! development and testing purposes.
 
 use crate::{
-	Blockchain, TxPool,
-	rpc_server::{RpcServerError, types::HexString},
+	BlockProfile, Blockchain, DispatchClass, EventFilter, ExtrinsicProfile, TxPool, Weight,
+	rpc_server::{
+		RpcServerError, parse_hex_bytes,
+		types::{DevEvent, DevEventFilter, HexString},
+	},
 };
-use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use jsonrpsee::{
+	PendingSubscriptionSink,
+	core::{RpcResult, SubscriptionResult},
+	proc_macros::rpc,
+};
+use log::{debug, warn};
 use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio_util::sync::CancellationToken;
 
 /// Development RPC methods for manual chain control.
 #[rpc(server, namespace = "dev")]
@@ -21,9 +31,27 @@ pub trait DevApi {
 	/// 1. Inherent extrinsics (timestamp, parachain validation data, etc.)
 	/// 2. Any pending transactions from the transaction pool
 	///
-	/// Returns the hash of the newly created block.
+	/// Returns the hash of the newly created block. With `{"profile": true}`, the result also
+	/// contains the weight, fee and proof size profile of the block.
 	#[method(name = "newBlock")]
-	async fn new_block(&self) -> RpcResult<NewBlockResult>;
+	async fn new_block(&self, params: Option<NewBlockParams>) -> RpcResult<NewBlockResult>;
+
+	/// Subscribe to the events of the blocks built on the fork.
+	///
+	/// Notifies new blocks, extrinsic outcomes, decoded runtime events, storage writes and
+	/// runtime upgrades, optionally narrowed down by a filter. For example,
+	/// `{"kinds": ["runtimeEvent"], "runtimeEvents": ["Balances.Transfer"]}` only notifies
+	/// balance transfers.
+	#[subscription(name = "subscribeEvents" => "event", unsubscribe = "unsubscribeEvents", item = DevEvent)]
+	async fn subscribe_events(&self, filter: Option<DevEventFilter>) -> SubscriptionResult;
+}
+
+/// Options for producing a new block.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct NewBlockParams {
+	/// Profile the weight, fees and proof size of the new block.
+	pub profile: bool,
 }
 
 /// Result of producing a new block.
@@ -36,24 +64,150 @@ pub struct NewBlockResult {
 	pub number: u32,
 	/// Number of extrinsics included (inherents + user transactions).
 	pub extrinsics_count: usize,
+	/// Profile of the new block, if requested.
+	#[serde(default, skip_serializing_if = "Option::is_none")]
+	pub profile: Option<BlockProfileResult>,
+}
+
+/// Two-dimensional weight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WeightResult {
+	/// Computational time, in picoseconds.
+	pub ref_time: u64,
+	/// Size of the storage proof, in bytes.
+	pub proof_size: u64,
+}
+
+impl From<Weight> for WeightResult {
+	fn from(weight: Weight) -> Self {
+		Self { ref_time: weight.ref_time, proof_size: weight.proof_size }
+	}
+}
+
+/// Profile of an extrinsic included in a new block.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtrinsicProfileResult {
+	/// Position of the extrinsic in the block.
+	pub index: u32,
+	/// Hex-encoded hash of the extrinsic.
+	pub hash: String,
+	/// Pallet and call name (e.g. `Balances.transfer_keep_alive`), if decodable.
+	pub call: Option<String>,
+	/// Encoded length, in bytes.
+	pub length: u32,
+	/// Dispatch class (`normal`, `operational` or `mandatory`).
+	pub class: Option<String>,
+	/// Weight estimated by `TransactionPaymentApi_query_info`.
+	pub estimated_weight: Option<WeightResult>,
+	/// Weight reported by the dispatch outcome event.
+	pub actual_weight: Option<WeightResult>,
+	/// Estimated inclusion fee, as a decimal string.
+	pub partial_fee: Option<String>,
+	/// Whether the dispatch succeeded, `null` if the outcome is unknown.
+	pub success: Option<bool>,
+}
+
+impl From<ExtrinsicProfile> for ExtrinsicProfileResult {
+	fn from(extrinsic: ExtrinsicProfile) -> Self {
+		Self {
+			index: extrinsic.index,
+			hash: HexString::from_bytes(extrinsic.hash.as_bytes()).into(),
+			call: extrinsic
+				.pallet
+				.zip(extrinsic.call)
+				.map(|(pallet, call)| format!("{pallet}.{call}")),
+			length: extrinsic.length,
+			class: extrinsic.class.map(|class| {
+				match class {
+					DispatchClass::Normal => "normal",
+					DispatchClass::Operational => "operational",
+					DispatchClass::Mandatory => "mandatory",
+				}
+				.to_string()
+			}),
+			estimated_weight: extrinsic.estimated_weight.map(Into::into),
+			actual_weight: extrinsic.actual_weight.map(Into::into),
+			partial_fee: extrinsic.partial_fee.map(|fee| fee.to_string()),
+			success: extrinsic.success,
+		}
+	}
+}
+
+/// Weight, fee and proof size profile of a new block.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockProfileResult {
+	/// Profiles of the included extrinsics, inherents first.
+	pub extrinsics: Vec<ExtrinsicProfileResult>,
+	/// Number of extrinsics dropped while building the block.
+	pub dropped: usize,
+	/// Total weight consumed by the block.
+	pub weight: WeightResult,
+	/// Maximum block weight, if known.
+	pub max_weight: Option<WeightResult>,
+	/// Encoded length of all extrinsics, in bytes.
+	pub length: u32,
+	/// Maximum block length, in bytes, if known.
+	pub max_length: Option<u32>,
+	/// Estimated proof-of-validity size, in bytes.
+	pub pov_estimate: u64,
+	/// Sum of the estimated inclusion fees, as a decimal string.
+	pub total_fee: String,
+}
+
+impl From<BlockProfile> for BlockProfileResult {
+	fn from(profile: BlockProfile) -> Self {
+		Self {
+			weight: profile.total_weight().into(),
+			pov_estimate: profile.pov_estimate(),
+			total_fee: profile.total_fee().to_string(),
+			max_weight: profile.limits.map(|limits| limits.max_weight.into()),
+			max_length: profile.limits.map(|limits| limits.max_length),
+			length: profile.length,
+			dropped: profile.dropped,
+			extrinsics: profile.extrinsics.into_iter().map(Into::into).collect(),
+		}
+	}
 }
 
 /// Implementation of development RPC methods.
 pub struct DevApi {
 	blockchain: Arc<Blockchain>,
 	txpool: Arc<TxPool>,
+	shutdown_token: CancellationToken,
 }
 
 impl DevApi {
 	/// Create a new DevApi instance.
-	pub fn new(blockchain: Arc<Blockchain>, txpool: Arc<TxPool>) -> Self {
-		Self { blockchain, txpool }
+	pub fn new(
+		blockchain: Arc<Blockchain>,
+		txpool: Arc<TxPool>,
+		shutdown_token: CancellationToken,
+	) -> Self {
+		Self { blockchain, txpool, shutdown_token }
 	}
 }
 
+/// Convert the subscription filter parameters into an [`EventFilter`].
+fn event_filter(params: DevEventFilter) -> Result<EventFilter, RpcServerError> {
+	Ok(EventFilter {
+		kinds: params.kinds,
+		runtime_events: params.runtime_events,
+		storage_prefixes: params
+			.storage_prefixes
+			.iter()
+			.map(|prefix| parse_hex_bytes(prefix, "storage prefix"))
+			.collect::<Result<_, _>>()?,
+	})
+}
+
 #[async_trait::async_trait]
 impl DevApiServer for DevApi {
-	async fn new_block(&self) -> RpcResult<NewBlockResult> {
+	async fn new_block(&self, params: Option<NewBlockParams>) -> RpcResult<NewBlockResult> {
+		let params = params.unwrap_or_default();
+
 		// Drain pending transactions from the pool
 		let pending_txs = self.txpool.drain().map_err(|e| {
 			RpcServerError::Internal(format!("Failed to drain transaction pool: {e}"))
@@ -66,10 +220,81 @@ impl DevApiServer for DevApi {
 			.await
 			.map_err(|e| RpcServerError::Internal(format!("Failed to build block: {e}")))?;
 
+		let profile = if params.profile {
+			let profile =
+				self.blockchain.profile_block(&result).await.map_err(|e| {
+					RpcServerError::Internal(format!("Failed to profile block: {e}"))
+				})?;
+			Some(profile.into())
+		} else {
+			None
+		};
+
 		Ok(NewBlockResult {
 			hash: HexString::from_bytes(result.block.hash.as_bytes()).into(),
 			number: result.block.number,
 			extrinsics_count: result.block.extrinsics.len(),
+			profile,
 		})
 	}
+
+	async fn subscribe_events(
+		&self,
+		pending: PendingSubscriptionSink,
+		filter: Option<DevEventFilter>,
+	) -> SubscriptionResult {
+		let filter = match event_filter(filter.unwrap_or_default()) {
+			Ok(filter) => filter,
+			Err(e) => {
+				pending.reject(e).await;
+				return Ok(());
+			},
+		};
+		let sink = pending.accept().await?;
+		let mut events = self.blockchain.subscribe_filtered_events(filter);
+		let token = self.shutdown_token.clone();
+
+		tokio::spawn(async move {
+			loop {
+				tokio::select! {
+					biased;
+
+					_ = token.cancelled() => break,
+
+					_ = sink.closed() => {
+						debug!("[dev] Event subscriber disconnected");
+						break;
+					},
+
+					event = events.recv() => {
+						match event {
+							Ok(event) => {
+								let msg = match jsonrpsee::SubscriptionMessage::from_json(&DevEvent::from(event)) {
+									Ok(m) => m,
+									Err(e) => {
+										warn!("[dev] Failed to serialize event: {e}");
+										continue;
+									},
+								};
+								if sink.send(msg).await.is_err() {
+									debug!("[dev] Event subscriber disconnected during send");
+									break;
+								}
+							}
+							Err(broadcast::error::RecvError::Lagged(n)) => {
+								warn!("[dev] Event subscriber lagged, skipped {n} events");
+								continue;
+							}
+							Err(broadcast::error::RecvError::Closed) => {
+								debug!("[dev] Broadcast channel closed");
+								break;
+							}
+						}
+					}
+				}
+			}
+		});
+
+		Ok(())
+	}
 }
@@ -35,7 +35,10 @@ pub use author::{AuthorApi, AuthorApiServer};
 pub use chain::{ChainApi, ChainApiServer};
 pub use chain_head::{ChainHeadApi, ChainHeadApiServer, ChainHeadState};
 pub use chain_spec::{ChainSpecApi, ChainSpecApiServer};
-pub use dev::{DevApi, DevApiServer};
+pub use dev::{
+	BlockProfileResult, DevApi, DevApiServer, ExtrinsicProfileResult, NewBlockParams,
+	NewBlockResult, WeightResult,
+};
 pub use payment::{PaymentApi, PaymentApiServer};
 pub use state::{StateApi, StateApiServer};
 pub use system::{SystemApi, SystemApiServer};
@@ -63,11 +66,12 @@ pub fn create_rpc_module(
 	let author_impl = AuthorApi::new(blockchain.clone(), txpool.clone());
 	let archive_impl = ArchiveApi::new(blockchain.clone());
 	let chain_head_state = Arc::new(ChainHeadState::new());
-	let chain_head_impl = ChainHeadApi::new(blockchain.clone(), chain_head_state, shutdown_token);
+	let chain_head_impl =
+		ChainHeadApi::new(blockchain.clone(), chain_head_state, shutdown_token.clone());
 	let chain_spec_impl = ChainSpecApi::new(blockchain.clone());
 	let payment_impl = PaymentApi::new(blockchain.clone());
 	let transaction_impl = TransactionApi::new(blockchain.clone(), txpool.clone());
-	let dev_impl = DevApi::new(blockchain, txpool);
+	let dev_impl = DevApi::new(blockchain, txpool, shutdown_token);
 
 	// Merge all methods into the module
 	module
@@ -590,6 +590,7 @@ impl<T: StateBlockchain + 'static> StateApiServer for StateApi<T> {
 									}
 								}
 							}
+							Ok(_) => continue,
 							Err(broadcast::error::RecvError::Lagged(_)) => continue,
 							Err(broadcast::error::RecvError::Closed) => break,
 						}
@@ -709,6 +710,7 @@ impl<T: StateBlockchain + 'static> StateApiServer for StateApi<T> {
 									break;
 								}
 							}
+							Ok(_) => continue,
 							Err(broadcast::error::RecvError::Lagged(n)) => {
 								warn!("[state] Storage subscriber lagged, skipped {n} events");
 								continue;
@@ -65,9 +65,13 @@ pub async fn fork_at_creates_blockchain_at_specific_block() {
 	let fork_number = blockchain.fork_point_number();
 
 	// Fork at a specific block number (same as current for test node)
-	let blockchain2 = Blockchain::fork_at(blockchain.endpoint(), None, Some(fork_number.into()))
-		.await
-		.expect("Failed to fork at specific block");
+	let blockchain2 = Blockchain::fork_at(
+		blockchain.endpoint().expect("forked from an upstream"),
+		None,
+		Some(fork_number.into()),
+	)
+	.await
+	.expect("Failed to fork at specific block");
 
 	assert_eq!(blockchain2.fork_point_number(), fork_number);
 }
@@ -83,7 +87,12 @@ pub async fn fork_with_invalid_endpoint_fails() {
 pub async fn fork_at_with_invalid_block_number_fails() {
 	let blockchain = readonly_blockchain().await;
 
-	let result = Blockchain::fork_at(blockchain.endpoint(), None, Some(u32::MAX.into())).await;
+	let result = Blockchain::fork_at(
+		blockchain.endpoint().expect("forked from an upstream"),
+		None,
+		Some(u32::MAX.into()),
+	)
+	.await;
 
 	assert!(result.is_err());
 }
@@ -1004,3 +1013,133 @@ pub async fn build_block_result_tracks_failed_extrinsics() {
 	assert!(result.included.is_empty(), "Failed extrinsic should not be in included list");
 	assert_eq!(result.failed[0].extrinsic, extrinsic);
 }
+
+pub async fn build_block_emits_extrinsic_and_runtime_events() {
+	use crate::{BlockchainEvent, EventFilter, EventKind, ExecutorConfig, SignatureMockMode};
+	use scale::{Compact, Encode};
+	use std::time::Duration;
+
+	let ctx = TestContext::minimal().await;
+	let config =
+		ExecutorConfig { signature_mock: SignatureMockMode::AlwaysValid, ..Default::default() };
+	let blockchain = Blockchain::fork_with_config(&ctx.endpoint, None, None, config)
+		.await
+		.expect("Failed to fork");
+	blockchain
+		.initialize_dev_accounts()
+		.await
+		.expect("Failed to initialize dev accounts");
+
+	let head = blockchain.head().await;
+	let metadata = head.metadata().await.expect("Failed to get metadata");
+	let balances_pallet = metadata.pallet_by_name("Balances").expect("Balances pallet");
+	let transfer_call = balances_pallet
+		.call_variant_by_name("transfer_keep_alive")
+		.expect("transfer_keep_alive");
+
+	let mut call_data = vec![balances_pallet.index(), transfer_call.index];
+	call_data.push(0x00); // MultiAddress::Id
+	call_data.extend(BOB);
+	call_data.extend(Compact(TRANSFER_AMOUNT).encode());
+
+	let alice_nonce = blockchain
+		.storage(&account_storage_key(&ALICE))
+		.await
+		.expect("Failed to get Alice account data")
+		.map(|v| decode_account_nonce(&v))
+		.expect("Alice account should exist");
+	let extrinsic = build_mock_signed_extrinsic_v4_with_nonce(&call_data, u64::from(alice_nonce));
+	let extrinsic_hash = H256::from(sp_core::blake2_256(&extrinsic));
+
+	let mut events = blockchain.subscribe_filtered_events(EventFilter {
+		kinds: vec![EventKind::ExtrinsicApplied, EventKind::RuntimeEvent],
+		runtime_events: vec!["Balances.Transfer".to_string()],
+		..Default::default()
+	});
+
+	let result = blockchain.build_block(vec![extrinsic]).await.expect("Failed to build block");
+
+	let mut applied = None;
+	let mut transfer = None;
+	while let Ok(Ok(event)) = tokio::time::timeout(Duration::from_secs(1), events.recv()).await {
+		match event {
+			BlockchainEvent::ExtrinsicApplied { extrinsic_hash: hash, index, .. }
+				if hash == extrinsic_hash =>
+				applied = Some(index),
+			BlockchainEvent::RuntimeEvent { number, event, .. } => {
+				assert_eq!(number, result.block.number);
+				transfer = Some(event);
+			},
+			BlockchainEvent::ExtrinsicApplied { .. } => {},
+			other => panic!("Unexpected event passed the filter: {other:?}"),
+		}
+	}
+
+	let index = applied.expect("Transfer extrinsic should be reported as applied");
+	assert_eq!(result.block.extrinsics[index as usize], result.included[0]);
+	let transfer = transfer.expect("Balances.Transfer event should be emitted");
+	assert_eq!(transfer.phase, crate::EventPhase::ApplyExtrinsic(index));
+	assert_eq!(transfer.field("amount"), Some(TRANSFER_AMOUNT.to_string().as_str()));
+}
+
+pub async fn profile_block_reports_extrinsic_weights() {
+	use crate::{DispatchClass, ExecutorConfig, SignatureMockMode};
+	use scale::{Compact, Encode};
+
+	let ctx = TestContext::minimal().await;
+	let config =
+		ExecutorConfig { signature_mock: SignatureMockMode::AlwaysValid, ..Default::default() };
+	let blockchain = Blockchain::fork_with_config(&ctx.endpoint, None, None, config)
+		.await
+		.expect("Failed to fork");
+	blockchain
+		.initialize_dev_accounts()
+		.await
+		.expect("Failed to initialize dev accounts");
+
+	let head = blockchain.head().await;
+	let metadata = head.metadata().await.expect("Failed to get metadata");
+	let balances_pallet = metadata.pallet_by_name("Balances").expect("Balances pallet");
+	let transfer_call = balances_pallet
+		.call_variant_by_name("transfer_keep_alive")
+		.expect("transfer_keep_alive");
+
+	let mut call_data = vec![balances_pallet.index(), transfer_call.index];
+	call_data.push(0x00); // MultiAddress::Id
+	call_data.extend(BOB);
+	call_data.extend(Compact(TRANSFER_AMOUNT).encode());
+
+	let alice_nonce = blockchain
+		.storage(&account_storage_key(&ALICE))
+		.await
+		.expect("Failed to get Alice account data")
+		.map(|v| decode_account_nonce(&v))
+		.expect("Alice account should exist");
+	let extrinsic = build_mock_signed_extrinsic_v4_with_nonce(&call_data, u64::from(alice_nonce));
+
+	let result = blockchain.build_block(vec![extrinsic.clone()]).await.expect("Failed to build");
+	let profile = blockchain.profile_block(&result).await.expect("Failed to profile block");
+
+	assert_eq!(profile.hash, result.block.hash);
+	assert_eq!(profile.extrinsics.len(), result.block.extrinsics.len());
+	assert_eq!(profile.dropped, 0);
+
+	let transfer = profile
+		.extrinsics
+		.iter()
+		.find(|profiled| profiled.hash == H256::from(sp_core::blake2_256(&extrinsic)))
+		.expect("Transfer should be profiled");
+	assert_eq!(transfer.success, Some(true));
+	assert_eq!(transfer.pallet.as_deref(), Some("Balances"));
+	assert_eq!(transfer.call.as_deref(), Some("transfer_keep_alive"));
+	assert_eq!(transfer.class, Some(DispatchClass::Normal));
+	assert!(transfer.partial_fee.is_some_and(|fee| fee > 0));
+	let actual = transfer.actual_weight.expect("Dispatch info should be reported");
+	assert!(actual.ref_time > 0);
+
+	let weight = profile.weight.expect("System::BlockWeight should be readable");
+	assert!(weight.normal.ref_time >= actual.ref_time);
+	let limits = profile.limits.expect("Block limits should be readable");
+	assert!(limits.max_weight.ref_time > profile.total_weight().ref_time);
+	assert!(profile.pov_estimate() > 0);
+}
@@ -111,15 +111,16 @@ pub async fn accessor_methods() {
 	let block_hash = ctx.block_hash();
 
 	assert!(!block_hash.is_zero());
-	assert!(layer.rpc().endpoint().as_str().starts_with("ws://"));
+	assert!(!layer.is_detached());
+	assert!(layer.endpoint().expect("layer has an upstream").as_str().starts_with("ws://"));
 }
 
 pub async fn fetch_and_cache_block_by_number_caches_block() {
 	let ctx = TestContext::for_remote().await;
 	let layer = ctx.remote();
 
-	let finalized_hash = layer.rpc().finalized_head().await.unwrap();
-	let finalized_header = layer.rpc().header(finalized_hash).await.unwrap();
+	let finalized_hash = ctx.rpc().finalized_head().await.unwrap();
+	let finalized_header = ctx.rpc().header(finalized_hash).await.unwrap();
 	let finalized_number = finalized_header.number;
 
 	let cached = layer.cache().get_block_by_number(finalized_number).await.unwrap();
@@ -159,8 +160,8 @@ pub async fn fetch_and_cache_block_by_number_multiple_blocks() {
 	let ctx = TestContext::for_remote().await;
 	let layer = ctx.remote();
 
-	let finalized_hash = layer.rpc().finalized_head().await.unwrap();
-	let finalized_header = layer.rpc().header(finalized_hash).await.unwrap();
+	let finalized_hash = ctx.rpc().finalized_head().await.unwrap();
+	let finalized_header = ctx.rpc().header(finalized_hash).await.unwrap();
 	let finalized_number = finalized_header.number;
 
 	let max_blocks = finalized_number.min(3);
@@ -192,8 +193,8 @@ pub async fn fetch_and_cache_block_by_number_verifies_parent_chain() {
 	let ctx = TestContext::for_remote().await;
 	let layer = ctx.remote();
 
-	let finalized_hash = layer.rpc().finalized_head().await.unwrap();
-	let finalized_header = layer.rpc().header(finalized_hash).await.unwrap();
+	let finalized_hash = ctx.rpc().finalized_head().await.unwrap();
+	let finalized_header = ctx.rpc().header(finalized_hash).await.unwrap();
 	let finalized_number = finalized_header.number;
 
 	let max_blocks = finalized_number.min(3);
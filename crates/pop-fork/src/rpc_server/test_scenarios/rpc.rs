@@ -211,3 +211,58 @@ pub async fn fetch_storage_batch_empty_keys() {
 	let values = client.storage_batch(&[], hash).await.unwrap();
 	assert!(values.is_empty());
 }
+
+// =============================================================================
+// Upstream pool tests
+// =============================================================================
+
+pub async fn connect_all_skips_unreachable_endpoints() {
+	let unreachable: Url = "ws://127.0.0.1:19999".parse().unwrap();
+	let endpoint = local_endpoint();
+	let client = ForkRpcClient::connect_all(&[unreachable.clone(), endpoint.clone()])
+		.await
+		.unwrap();
+
+	// The first reachable endpoint becomes active, the unreachable one stays in the pool.
+	assert_eq!(client.endpoint(), &endpoint);
+	assert_eq!(client.endpoints().cloned().collect::<Vec<_>>(), vec![unreachable, endpoint]);
+	assert!(client.finalized_head().await.is_ok());
+}
+
+pub async fn verify_fork_point_accepts_agreeing_upstreams() {
+	let endpoint = local_endpoint();
+	let client = ForkRpcClient::connect_all(&[endpoint.clone(), endpoint.clone()]).await.unwrap();
+	let hash = client.finalized_head().await.unwrap();
+	let number = client.header(hash).await.unwrap().number;
+
+	client.verify_fork_point(number, hash).await.unwrap();
+
+	// Both upstreams stay usable, so requests are still served after a reconnect.
+	client.reconnect().await.unwrap();
+	assert_eq!(client.block_hash_at(number).await.unwrap(), Some(hash));
+}
+
+pub async fn storage_batch_spreads_across_upstreams() {
+	let endpoint = local_endpoint();
+	let client = ForkRpcClient::connect_all(&[endpoint.clone(), endpoint.clone(), endpoint])
+		.await
+		.unwrap();
+	let hash = client.finalized_head().await.unwrap();
+	let number = client.header(hash).await.unwrap().number;
+	// Only upstreams which confirmed the fork point receive chunks.
+	client.verify_fork_point(number, hash).await.unwrap();
+
+	// Large enough to be split across all three upstreams.
+	let number_key = hex::decode(SYSTEM_NUMBER_KEY).unwrap();
+	let missing_key = vec![0xff; 32];
+	let keys: Vec<&[u8]> = (0..300)
+		.map(|i| if i % 2 == 0 { number_key.as_slice() } else { missing_key.as_slice() })
+		.collect();
+	let values = client.storage_batch(&keys, hash).await.unwrap();
+
+	// Values are returned in input order, regardless of which upstream served them.
+	assert_eq!(values.len(), keys.len());
+	for (i, value) in values.iter().enumerate() {
+		assert_eq!(value.is_some(), i % 2 == 0, "Unexpected value at index {i}");
+	}
+}
@@ -400,3 +400,151 @@ pub struct SyncState {
 	/// Highest known block number.
 	pub highest_block: u32,
 }
+
+/// Filter parameters for the `dev_subscribeEvents` subscription.
+///
+/// Every field left out matches everything.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DevEventFilter {
+	/// Kinds of events to receive (e.g. `["runtimeEvent", "storageChanged"]`).
+	#[serde(default)]
+	pub kinds: Vec<crate::EventKind>,
+	/// Runtime events to receive, as `Pallet` or `Pallet.Variant`.
+	#[serde(default)]
+	pub runtime_events: Vec<String>,
+	/// Hex-encoded storage prefixes to watch.
+	#[serde(default)]
+	pub storage_prefixes: Vec<String>,
+}
+
+/// Event sent via the `dev_subscribeEvents` subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DevEvent {
+	/// A new block was built.
+	#[serde(rename_all = "camelCase")]
+	NewBlock {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+	},
+	/// An extrinsic was included and dispatched successfully.
+	#[serde(rename_all = "camelCase")]
+	ExtrinsicApplied {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+		/// Index of the extrinsic in the block.
+		index: u32,
+		/// Extrinsic hash.
+		extrinsic_hash: String,
+	},
+	/// An extrinsic failed to dispatch or was dropped from the block.
+	#[serde(rename_all = "camelCase")]
+	ExtrinsicFailed {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+		/// Index of the extrinsic in the block, if it was included.
+		index: Option<u32>,
+		/// Extrinsic hash.
+		extrinsic_hash: String,
+		/// Reason for the failure.
+		reason: String,
+	},
+	/// A runtime event was emitted.
+	#[serde(rename_all = "camelCase")]
+	RuntimeEvent {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+		/// Pallet name.
+		pallet: String,
+		/// Event variant name.
+		variant: String,
+		/// Index of the extrinsic emitting the event, if emitted while applying one.
+		extrinsic_index: Option<u32>,
+		/// Event fields by name, with values in human-readable form.
+		fields: Vec<(String, String)>,
+	},
+	/// Storage was written.
+	#[serde(rename_all = "camelCase")]
+	StorageChanged {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+		/// Hex-encoded keys and new values (`null` if deleted).
+		changes: Vec<(String, Option<String>)>,
+	},
+	/// The runtime was upgraded.
+	#[serde(rename_all = "camelCase")]
+	RuntimeUpgraded {
+		/// Block hash.
+		block_hash: String,
+		/// Block number.
+		block_number: u32,
+		/// Spec name of the new runtime.
+		spec_name: String,
+		/// Spec version of the new runtime.
+		spec_version: u32,
+	},
+}
+
+impl From<crate::BlockchainEvent> for DevEvent {
+	fn from(event: crate::BlockchainEvent) -> Self {
+		use crate::BlockchainEvent as Event;
+
+		let hex = |bytes: &[u8]| String::from(HexString::from_bytes(bytes));
+		match event {
+			Event::NewBlock { hash, number, .. } =>
+				Self::NewBlock { block_hash: hex(hash.as_bytes()), block_number: number },
+			Event::ExtrinsicApplied { hash, number, index, extrinsic_hash } =>
+				Self::ExtrinsicApplied {
+					block_hash: hex(hash.as_bytes()),
+					block_number: number,
+					index,
+					extrinsic_hash: hex(extrinsic_hash.as_bytes()),
+				},
+			Event::ExtrinsicFailed { hash, number, index, extrinsic_hash, reason } =>
+				Self::ExtrinsicFailed {
+					block_hash: hex(hash.as_bytes()),
+					block_number: number,
+					index,
+					extrinsic_hash: hex(extrinsic_hash.as_bytes()),
+					reason,
+				},
+			Event::RuntimeEvent { hash, number, event } => Self::RuntimeEvent {
+				block_hash: hex(hash.as_bytes()),
+				block_number: number,
+				extrinsic_index: match event.phase {
+					crate::EventPhase::ApplyExtrinsic(index) => Some(index),
+					_ => None,
+				},
+				pallet: event.pallet,
+				variant: event.variant,
+				fields: event.fields,
+			},
+			Event::StorageChanged { hash, number, changes } => Self::StorageChanged {
+				block_hash: hex(hash.as_bytes()),
+				block_number: number,
+				changes: changes
+					.into_iter()
+					.map(|(key, value)| (hex(&key), value.as_deref().map(hex)))
+					.collect(),
+			},
+			Event::RuntimeUpgraded { hash, number, spec_name, spec_version } =>
+				Self::RuntimeUpgraded {
+					block_hash: hex(hash.as_bytes()),
+					block_number: number,
+					spec_name,
+					spec_version,
+				},
+		}
+	}
+}
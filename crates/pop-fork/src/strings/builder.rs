@@ -34,4 +34,32 @@ pub mod runtime_api {
 	/// Called to fetch the metadata of a runtime, typically after a runtime upgrade.
 	/// Returns SCALE-encoded metadata bytes.
 	pub const METADATA_METADATA: &str = "Metadata_metadata";
+
+	/// Runtime method to retrieve runtime metadata at a specific version.
+	///
+	/// Returns SCALE-encoded `Option<OpaqueMetadata>`, which is `None` if the runtime does not
+	/// support the requested version.
+	pub const METADATA_METADATA_AT_VERSION: &str = "Metadata_metadata_at_version";
+
+	/// Runtime method to retrieve the runtime version.
+	///
+	/// Returns the SCALE-encoded `RuntimeVersion`, including the runtime APIs implemented.
+	pub const CORE_VERSION: &str = "Core_version";
+
+	/// Runtime method to retrieve the names of the genesis presets of a runtime.
+	///
+	/// Returns a SCALE-encoded `Vec<PresetId>`.
+	pub const GENESIS_BUILDER_PRESET_NAMES: &str = "GenesisBuilder_preset_names";
+
+	/// Runtime method to retrieve the JSON of a genesis preset of a runtime.
+	///
+	/// Called with a SCALE-encoded `Option<PresetId>`, `None` requesting the default preset.
+	/// Returns a SCALE-encoded `Option<Vec<u8>>`.
+	pub const GENESIS_BUILDER_GET_PRESET: &str = "GenesisBuilder_get_preset";
+
+	/// Runtime method to build the genesis state from a JSON genesis config.
+	///
+	/// Called with the SCALE-encoded JSON. Returns a SCALE-encoded `Result<(), String>`, holding
+	/// the error if the config could not be deserialized.
+	pub const GENESIS_BUILDER_BUILD_STATE: &str = "GenesisBuilder_build_state";
 }
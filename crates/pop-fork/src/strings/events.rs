@@ -0,0 +1,24 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! String constants for runtime event decoding.
+
+/// Storage location of the runtime events of the current block.
+pub mod storage_keys {
+	/// Pallet name for `System::Events`.
+	pub const SYSTEM_PALLET: &[u8] = b"System";
+
+	/// Storage item name for `System::Events`.
+	pub const EVENTS: &[u8] = b"Events";
+}
+
+/// Names used to identify extrinsic outcomes among the runtime events.
+pub mod system {
+	/// Pallet emitting the extrinsic outcome events.
+	pub const PALLET: &str = "System";
+
+	/// Event emitted when an extrinsic dispatch fails.
+	pub const EXTRINSIC_FAILED: &str = "ExtrinsicFailed";
+
+	/// Field of `ExtrinsicFailed` holding the dispatch error.
+	pub const DISPATCH_ERROR_FIELD: &str = "dispatch_error";
+}
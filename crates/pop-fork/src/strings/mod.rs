@@ -7,8 +7,10 @@
 
 pub mod builder;
 pub mod cache;
+pub mod events;
 pub mod executor;
 pub mod inherent;
+pub mod profile;
 pub mod rpc;
 pub mod rpc_server;
 pub mod txpool;
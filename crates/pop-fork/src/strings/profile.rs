@@ -0,0 +1,33 @@
+// SPDX-License-Identifier: GPL-3.0
+
+//! String constants for block weight and fee profiling.
+
+/// Storage location of the weight consumed by the current block.
+pub mod storage_keys {
+	/// Pallet name for `System::BlockWeight`.
+	pub const SYSTEM_PALLET: &[u8] = b"System";
+
+	/// Storage item name for `System::BlockWeight`.
+	pub const BLOCK_WEIGHT: &[u8] = b"BlockWeight";
+}
+
+/// Runtime constants describing the block limits.
+pub mod constants {
+	/// Pallet exposing the block limits.
+	pub const SYSTEM_PALLET: &str = "System";
+
+	/// Constant holding the `BlockWeights` limits.
+	pub const BLOCK_WEIGHTS: &str = "BlockWeights";
+
+	/// Constant holding the `BlockLength` limits.
+	pub const BLOCK_LENGTH: &str = "BlockLength";
+}
+
+/// Names used to find the dispatch info of an extrinsic among the runtime events.
+pub mod system {
+	/// Event emitted when an extrinsic dispatch succeeds.
+	pub const EXTRINSIC_SUCCESS: &str = "ExtrinsicSuccess";
+
+	/// Field of `ExtrinsicSuccess` and `ExtrinsicFailed` holding the dispatch info.
+	pub const DISPATCH_INFO_FIELD: &str = "dispatch_info";
+}
@@ -63,6 +63,7 @@ module_scenarios!(
 		block_number_by_hash_returns_number_for_head,
 		block_number_by_hash_returns_number_for_historical_block,
 		block_number_by_hash_returns_number_for_parent,
+		build_block_emits_extrinsic_and_runtime_events,
 		build_block_result_tracks_failed_extrinsics,
 		build_block_result_tracks_included_extrinsics,
 		build_block_with_signed_transfer_updates_balances,
@@ -83,6 +84,7 @@ module_scenarios!(
 		fork_with_invalid_endpoint_fails,
 		head_returns_current_block,
 		head_updates_after_building_block,
+		profile_block_reports_extrinsic_weights,
 		storage_at_queries_specific_block,
 		storage_returns_none_for_nonexistent_key,
 		storage_returns_value_for_existing_key,
@@ -211,6 +213,7 @@ module_scenarios!(
 		prefetch_prefix,
 	],
 	rpc => [
+		connect_all_skips_unreachable_endpoints,
 		connect_to_invalid_endpoint_fails,
 		connect_to_node,
 		fetch_finalized_head,
@@ -226,6 +229,8 @@ module_scenarios!(
 		fetch_storage_non_existent_key_returns_none,
 		fetch_system_chain,
 		fetch_system_properties,
+		storage_batch_spreads_across_upstreams,
+		verify_fork_point_accepts_agreeing_upstreams,
 	],
 	rpc_server_archive => [
 		archive_body_is_idempotent_over_finalized_blocks,